@@ -0,0 +1,124 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        program::invoke_signed,
+    },
+};
+
+use crate::AuctionError;
+
+pub const BUBBLEGUM_PROGRAM_ID: Pubkey = pubkey!("BGUMAp9Gq7iTEuizy4pqaxsTyUCBK68MDfK752saRPUY");
+pub const SPL_NOOP_PROGRAM_ID: Pubkey = pubkey!("noopb9bkMVfRPU8AsbpTUg8AQkHtKwMYZiFUjNRtMmV");
+pub const SPL_ACCOUNT_COMPRESSION_PROGRAM_ID: Pubkey =
+    pubkey!("cmtDvXumGCrqC1Age74AVPhSRVXJMd8PJS91L8KbNCK");
+
+/// Anchor discriminator of Bubblegum's `transfer` instruction.
+const TRANSFER_DISCRIMINATOR: [u8; 8] = [163, 52, 200, 231, 140, 3, 69, 186];
+
+/// Leaf fields that stay fixed across ownership changes and identify a
+/// compressed NFT inside its tree.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct LeafArgs {
+    pub data_hash: [u8; 32],
+    pub creator_hash: [u8; 32],
+    pub nonce: u64,
+    pub index: u32,
+}
+
+impl LeafArgs {
+    pub const LEN: usize = 32 + 32 + 8 + 4;
+}
+
+/// Accounts Bubblegum's `transfer` needs, minus the proof path which is
+/// passed separately.
+pub struct TransferAccounts<'a, 'info> {
+    pub bubblegum_program: &'a AccountInfo<'info>,
+    pub tree_authority: &'a AccountInfo<'info>,
+    pub leaf_owner: &'a AccountInfo<'info>,
+    pub new_leaf_owner: &'a AccountInfo<'info>,
+    pub merkle_tree: &'a AccountInfo<'info>,
+    pub log_wrapper: &'a AccountInfo<'info>,
+    pub compression_program: &'a AccountInfo<'info>,
+    pub system_program: &'a AccountInfo<'info>,
+}
+
+/// Derives the asset id Bubblegum assigns to the leaf minted at `nonce`.
+pub fn asset_id(merkle_tree: &Pubkey, nonce: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"asset", merkle_tree.as_ref(), &nonce.to_le_bytes()],
+        &BUBBLEGUM_PROGRAM_ID,
+    )
+    .0
+}
+
+/// CPIs into Bubblegum to move a compressed NFT to `new_leaf_owner`. The
+/// current owner signs (directly, or through `signer_seeds` when it is a PDA)
+/// and also acts as delegate. `proof` is the Merkle path from the leaf to
+/// `root`, typically forwarded from `remaining_accounts`.
+pub fn transfer<'info>(
+    accounts: TransferAccounts<'_, 'info>,
+    proof: &[AccountInfo<'info>],
+    root: [u8; 32],
+    leaf: &LeafArgs,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let mut data = TRANSFER_DISCRIMINATOR.to_vec();
+    data.extend_from_slice(&root);
+    leaf.serialize(&mut data)?;
+
+    let mut metas = vec![
+        AccountMeta::new_readonly(accounts.tree_authority.key(), false),
+        AccountMeta::new_readonly(accounts.leaf_owner.key(), true),
+        AccountMeta::new_readonly(accounts.leaf_owner.key(), false),
+        AccountMeta::new_readonly(accounts.new_leaf_owner.key(), false),
+        AccountMeta::new(accounts.merkle_tree.key(), false),
+        AccountMeta::new_readonly(accounts.log_wrapper.key(), false),
+        AccountMeta::new_readonly(accounts.compression_program.key(), false),
+        AccountMeta::new_readonly(accounts.system_program.key(), false),
+    ];
+    metas.extend(
+        proof
+            .iter()
+            .map(|node| AccountMeta::new_readonly(node.key(), false)),
+    );
+
+    let mut infos = vec![
+        accounts.tree_authority.clone(),
+        accounts.leaf_owner.clone(),
+        accounts.leaf_owner.clone(),
+        accounts.new_leaf_owner.clone(),
+        accounts.merkle_tree.clone(),
+        accounts.log_wrapper.clone(),
+        accounts.compression_program.clone(),
+        accounts.system_program.clone(),
+    ];
+    infos.extend(proof.iter().cloned());
+
+    let ix = Instruction {
+        program_id: BUBBLEGUM_PROGRAM_ID,
+        accounts: metas,
+        data,
+    };
+    invoke_signed(&ix, &infos, signer_seeds).map_err(Into::into)
+}
+
+/// Splits proof paths passed back to back (e.g. in `remaining_accounts`)
+/// into one path per leaf, `lens[i]` nodes long. Every node must belong to
+/// exactly one path.
+pub fn split_proofs<'a, 'info>(
+    nodes: &'a [AccountInfo<'info>],
+    lens: &[u8],
+) -> Result<Vec<&'a [AccountInfo<'info>]>> {
+    let total: usize = lens.iter().map(|&len| len as usize).sum();
+    require!(total == nodes.len(), AuctionError::InvalidProofs);
+
+    let mut rest = nodes;
+    let mut proofs = Vec::with_capacity(lens.len());
+    for &len in lens {
+        let (proof, tail) = rest.split_at(len as usize);
+        proofs.push(proof);
+        rest = tail;
+    }
+    Ok(proofs)
+}
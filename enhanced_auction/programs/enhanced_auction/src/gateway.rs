@@ -0,0 +1,79 @@
+use anchor_lang::prelude::*;
+
+use crate::AuctionError;
+
+/// Identity.com gateway program, owner of Civic pass gateway tokens.
+pub const GATEWAY_PROGRAM_ID: Pubkey = pubkey!("gatem74V238djXdzWnJf94Wo1DcnuGkfijbf3AuBhfs");
+
+/// Middle seed of gateway token PDAs.
+const GATEWAY_SEED: &[u8] = b"gateway";
+/// `GatewayTokenState::Active`.
+const STATE_ACTIVE: u8 = 0;
+
+/// Checks that `gateway_token` is the active, unexpired gateway token the
+/// gatekeeper `network` issued to `wallet`, at the wallet's default token
+/// address (seed index 0).
+pub fn verify_pass(
+    gateway_token: &AccountInfo,
+    wallet: &Pubkey,
+    network: &Pubkey,
+    now: i64,
+) -> Result<()> {
+    require_keys_eq!(
+        *gateway_token.owner,
+        GATEWAY_PROGRAM_ID,
+        AuctionError::InvalidAttestation
+    );
+    let (address, _) = Pubkey::find_program_address(
+        &[wallet.as_ref(), GATEWAY_SEED, &[0; 8], network.as_ref()],
+        &GATEWAY_PROGRAM_ID,
+    );
+    require_keys_eq!(
+        gateway_token.key(),
+        address,
+        AuctionError::InvalidAttestation
+    );
+    let data = gateway_token.try_borrow_data()?;
+
+    // features: u8, then parent_gateway_token: Option<Pubkey>
+    let mut offset = 1;
+    offset = skip_option(&data, offset, 32)?;
+    let owner_wallet = Pubkey::try_from(read(&data, offset, 32)?).unwrap();
+    offset += 32;
+    // owner_identity: Option<Pubkey>
+    offset = skip_option(&data, offset, 32)?;
+    let gatekeeper_network = Pubkey::try_from(read(&data, offset, 32)?).unwrap();
+    // gatekeeper_network, then issuing_gatekeeper
+    offset += 64;
+    let state = read(&data, offset, 1)?[0];
+    offset += 1;
+    let expire_time = match read(&data, offset, 1)?[0] {
+        0 => None,
+        _ => {
+            let expiry = read(&data, offset + 1, 8)?;
+            Some(i64::from_le_bytes(expiry.try_into().unwrap()))
+        }
+    };
+
+    require!(
+        owner_wallet == *wallet && gatekeeper_network == *network && state == STATE_ACTIVE,
+        AuctionError::InvalidAttestation
+    );
+    if let Some(expiry) = expire_time {
+        require!(now < expiry, AuctionError::AttestationExpired);
+    }
+    Ok(())
+}
+
+/// Steps over a Borsh `Option` of a `len`-byte value at `offset`.
+fn skip_option(data: &[u8], offset: usize, len: usize) -> Result<usize> {
+    match read(data, offset, 1)?[0] {
+        0 => Ok(offset + 1),
+        _ => Ok(offset + 1 + len),
+    }
+}
+
+fn read(data: &[u8], offset: usize, len: usize) -> Result<&[u8]> {
+    data.get(offset..offset + len)
+        .ok_or_else(|| error!(AuctionError::InvalidAttestation))
+}
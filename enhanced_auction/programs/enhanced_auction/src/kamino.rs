@@ -0,0 +1,143 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        program::invoke_signed,
+    },
+};
+
+pub const KLEND_PROGRAM_ID: Pubkey = pubkey!("KLend2g3cP87fffoy8q1mQqGKjrxjC8boSyAYavgmjD");
+
+/// Anchor discriminator of klend's `deposit_reserve_liquidity`.
+const DEPOSIT_RESERVE_LIQUIDITY: [u8; 8] = [169, 201, 30, 126, 6, 205, 102, 68];
+/// Anchor discriminator of klend's `redeem_reserve_collateral`.
+const REDEEM_RESERVE_COLLATERAL: [u8; 8] = [234, 117, 181, 125, 185, 142, 220, 29];
+
+/// Accounts shared by klend's reserve deposit and redeem instructions. The
+/// reserve must have been refreshed earlier in the same transaction with
+/// `refresh_reserve`; klend rejects stale reserves.
+pub struct ReserveAccounts<'a, 'info> {
+    pub klend_program: &'a AccountInfo<'info>,
+    pub owner: &'a AccountInfo<'info>,
+    pub reserve: &'a AccountInfo<'info>,
+    pub lending_market: &'a AccountInfo<'info>,
+    pub lending_market_authority: &'a AccountInfo<'info>,
+    pub liquidity_mint: &'a AccountInfo<'info>,
+    pub liquidity_supply: &'a AccountInfo<'info>,
+    pub collateral_mint: &'a AccountInfo<'info>,
+    /// Owner's token account for the liquidity mint
+    pub liquidity: &'a AccountInfo<'info>,
+    /// Owner's token account for the collateral mint
+    pub collateral: &'a AccountInfo<'info>,
+    pub collateral_token_program: &'a AccountInfo<'info>,
+    pub liquidity_token_program: &'a AccountInfo<'info>,
+    pub sysvar_instructions: &'a AccountInfo<'info>,
+}
+
+/// CPIs `deposit_reserve_liquidity`, lending `amount` of the owner's
+/// liquidity to the reserve in exchange for collateral tokens. `owner` signs
+/// through `signer_seeds`.
+pub fn deposit_reserve_liquidity<'info>(
+    accounts: ReserveAccounts<'_, 'info>,
+    amount: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let metas = vec![
+        AccountMeta::new_readonly(accounts.owner.key(), true),
+        AccountMeta::new(accounts.reserve.key(), false),
+        AccountMeta::new_readonly(accounts.lending_market.key(), false),
+        AccountMeta::new_readonly(accounts.lending_market_authority.key(), false),
+        AccountMeta::new_readonly(accounts.liquidity_mint.key(), false),
+        AccountMeta::new(accounts.liquidity_supply.key(), false),
+        AccountMeta::new(accounts.collateral_mint.key(), false),
+        AccountMeta::new(accounts.liquidity.key(), false),
+        AccountMeta::new(accounts.collateral.key(), false),
+        AccountMeta::new_readonly(accounts.collateral_token_program.key(), false),
+        AccountMeta::new_readonly(accounts.liquidity_token_program.key(), false),
+        AccountMeta::new_readonly(accounts.sysvar_instructions.key(), false),
+    ];
+    invoke(DEPOSIT_RESERVE_LIQUIDITY, metas, accounts, amount, signer_seeds)
+}
+
+/// CPIs `redeem_reserve_collateral`, burning `amount` of the owner's
+/// collateral tokens for the liquidity they are worth, interest included.
+/// `owner` signs through `signer_seeds`.
+pub fn redeem_reserve_collateral<'info>(
+    accounts: ReserveAccounts<'_, 'info>,
+    amount: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let metas = vec![
+        AccountMeta::new_readonly(accounts.owner.key(), true),
+        AccountMeta::new_readonly(accounts.lending_market.key(), false),
+        AccountMeta::new(accounts.reserve.key(), false),
+        AccountMeta::new_readonly(accounts.lending_market_authority.key(), false),
+        AccountMeta::new_readonly(accounts.liquidity_mint.key(), false),
+        AccountMeta::new(accounts.collateral_mint.key(), false),
+        AccountMeta::new(accounts.liquidity_supply.key(), false),
+        AccountMeta::new(accounts.collateral.key(), false),
+        AccountMeta::new(accounts.liquidity.key(), false),
+        AccountMeta::new_readonly(accounts.collateral_token_program.key(), false),
+        AccountMeta::new_readonly(accounts.liquidity_token_program.key(), false),
+        AccountMeta::new_readonly(accounts.sysvar_instructions.key(), false),
+    ];
+    invoke(REDEEM_RESERVE_COLLATERAL, metas, accounts, amount, signer_seeds)
+}
+
+fn invoke<'info>(
+    discriminator: [u8; 8],
+    metas: Vec<AccountMeta>,
+    accounts: ReserveAccounts<'_, 'info>,
+    amount: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let infos = [
+        accounts.owner.clone(),
+        accounts.reserve.clone(),
+        accounts.lending_market.clone(),
+        accounts.lending_market_authority.clone(),
+        accounts.liquidity_mint.clone(),
+        accounts.liquidity_supply.clone(),
+        accounts.collateral_mint.clone(),
+        accounts.liquidity.clone(),
+        accounts.collateral.clone(),
+        accounts.collateral_token_program.clone(),
+        accounts.liquidity_token_program.clone(),
+        accounts.sysvar_instructions.clone(),
+        accounts.klend_program.clone(),
+    ];
+
+    let mut data = discriminator.to_vec();
+    data.extend_from_slice(&amount.to_le_bytes());
+
+    let ix = Instruction {
+        program_id: KLEND_PROGRAM_ID,
+        accounts: metas,
+        data,
+    };
+    invoke_signed(&ix, &infos, signer_seeds).map_err(Into::into)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::solana_program::hash::hash;
+
+    fn sighash(name: &str) -> [u8; 8] {
+        hash(format!("global:{name}").as_bytes()).to_bytes()[..8]
+            .try_into()
+            .unwrap()
+    }
+
+    #[test]
+    fn discriminators_match_klend_instructions() {
+        assert_eq!(
+            DEPOSIT_RESERVE_LIQUIDITY,
+            sighash("deposit_reserve_liquidity")
+        );
+        assert_eq!(
+            REDEEM_RESERVE_COLLATERAL,
+            sighash("redeem_reserve_collateral")
+        );
+    }
+}
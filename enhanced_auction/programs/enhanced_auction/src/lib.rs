@@ -1,48 +1,178 @@
-use anchor_lang::prelude::*;
+use anchor_lang::{
+    prelude::*,
+    solana_program::hash::{hash, hashv},
+    Discriminator,
+};
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{Mint, Token, TokenAccount, Transfer},
+    token_2022::spl_token_2022::{
+        self,
+        extension::{
+            transfer_fee::TransferFeeConfig, BaseStateWithExtensions, StateWithExtensions,
+        },
+    },
+    token::Token,
+    token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked},
 };
 
+pub mod bubblegum;
+pub mod gateway;
+pub mod kamino;
+pub mod mpl_core;
+pub mod pyth;
+pub mod sns;
+pub mod stake_pool;
+#[cfg(feature = "test-vectors")]
+pub mod test_vectors;
+pub mod token_metadata;
+
 declare_id!("41ggUgk3yL79W8Ue3c79gUzYSsZLpL6GDCsHt6UFYCQj");
 
 #[program]
 pub mod enhanced_auction {
     use super::*;
 
-    pub fn initialize_auction(
-        ctx: Context<InitializeAuction>,
+    pub fn initialize_auction<'info>(
+        ctx: Context<'_, '_, '_, 'info, InitializeAuction<'info>>,
         starting_bid: u64,
         min_bid_increment: u64,
         duration: i64,
+        buy_now_price: Option<u64>,
+        quantity: Option<u64>,
+        required_collection: Option<Pubkey>,
     ) -> Result<()> {
         ctx.accounts.validate()?;
+        check_not_blocked(&ctx.accounts.creator_blocklist_entry)?;
+        if let Some(collection) = required_collection {
+            let metadata = ctx
+                .accounts
+                .nft_metadata
+                .as_ref()
+                .ok_or(AuctionError::InvalidMetadata)?;
+            let listed = token_metadata::load_collection(metadata, &ctx.accounts.nft_mint.key())?;
+            require!(
+                listed.is_some_and(|c| c.verified && c.key == collection),
+                AuctionError::CollectionMismatch
+            );
+        }
+        if ctx.accounts.config.curated {
+            let metadata = ctx
+                .accounts
+                .nft_metadata
+                .as_ref()
+                .ok_or(AuctionError::InvalidMetadata)?;
+            let approved = ctx
+                .accounts
+                .approved_collection
+                .as_ref()
+                .ok_or(AuctionError::CollectionNotApproved)?;
+            let listed = token_metadata::load_collection(metadata, &ctx.accounts.nft_mint.key())?;
+            require!(
+                listed.is_some_and(|c| c.verified && c.key == approved.collection),
+                AuctionError::CollectionNotApproved
+            );
+        }
+        if let Some(snapshot) = ctx.accounts.metadata_snapshot.as_mut() {
+            let metadata = ctx
+                .accounts
+                .nft_metadata
+                .as_ref()
+                .ok_or(AuctionError::InvalidMetadata)?;
+            let (descriptor, royalties) =
+                token_metadata::load_descriptor(metadata, &ctx.accounts.nft_mint.key())?;
+            snapshot.auction = ctx.accounts.auction.key();
+            snapshot.capture(&descriptor, &royalties);
+            snapshot.bump = ctx.bumps.metadata_snapshot.unwrap();
+        }
         let auction = &mut ctx.accounts.auction;
         let clock = Clock::get()?;
+        let settings = ctx.accounts.creator_settings.as_deref();
 
-        require!(duration > 0, AuctionError::InvalidDuration);
-        require!(starting_bid > 0, AuctionError::InvalidStartingBid);
-        require!(min_bid_increment > 0, AuctionError::InvalidBidIncrement);
+        let params = AuctionParams {
+            starting_bid,
+            min_bid_increment: match settings {
+                Some(settings) if min_bid_increment == 0 => settings.defaults.min_bid_increment,
+                _ => min_bid_increment,
+            },
+            duration,
+            buy_now_price,
+        };
+        params.check()?;
+        check_relist_cooldown(&ctx.accounts.mint_cooldown, clock.unix_timestamp)?;
+        let quantity = quantity.unwrap_or(1);
+        require!(quantity > 0, AuctionError::InvalidQuantity);
 
-        auction.creator = ctx.accounts.creator.key();
-        auction.nft_mint = ctx.accounts.nft_mint.key();
-        auction.starting_bid = starting_bid;
-        auction.min_bid_increment = min_bid_increment;
-        auction.end_time = clock.unix_timestamp + duration;
-        auction.highest_bid = 0;
-        auction.highest_bidder = Pubkey::default();
-        auction.status = AuctionStatus::Active;
+        auction.open(
+            ctx.accounts.creator.key(),
+            ctx.accounts.nft_mint.key(),
+            &params,
+            clock.unix_timestamp,
+        );
+
+        ctx.accounts.config.apply_terms(auction, params.duration)?;
+        if let Some(settings) = settings {
+            settings.apply_anti_snipe(auction);
+        }
+        auction.quantity = quantity;
+        auction.collection = required_collection.unwrap_or_default();
+        auction.bid_mint = ctx
+            .accounts
+            .bid_mint
+            .as_ref()
+            .map(|mint| mint.key())
+            .unwrap_or_default();
+        if !auction.is_native() {
+            require!(
+                ctx.accounts.config.accepts_bid_mint(&auction.bid_mint),
+                AuctionError::InvalidBidCurrency
+            );
+        }
+        if let Some(experiment) = &ctx.accounts.experiment {
+            let auction_key = auction.key();
+            auction.join_experiment(&auction_key, experiment);
+        }
+        if auction.is_native() {
+            fund_escrow_reserve(
+                &ctx.accounts.system_program,
+                &ctx.accounts.creator.to_account_info(),
+                &ctx.accounts.auction_escrow,
+                auction,
+            )?;
+        }
 
         // Transfer NFT to auction vault
-        let transfer_ctx = CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.creator_nft_account.to_account_info(),
-                to: ctx.accounts.vault_nft_account.to_account_info(),
-                authority: ctx.accounts.creator.to_account_info(),
-            },
-        );
-        anchor_spl::token::transfer(transfer_ctx, 1)?;
+        if ctx.accounts.pnft.token_metadata_program.is_some() {
+            require!(quantity == 1, AuctionError::InvalidQuantity);
+            auction.is_programmable = true;
+            let creator = ctx.accounts.creator.to_account_info();
+            ctx.accounts.pnft.transfer(
+                &ctx.accounts.token_program,
+                &ctx.accounts.creator_nft_account,
+                &creator,
+                &ctx.accounts.vault_nft_account,
+                &ctx.accounts.auction_authority,
+                &ctx.accounts.nft_mint,
+                &creator,
+                &creator,
+                &[],
+            )?;
+        } else {
+            let transfer_ctx = CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.creator_nft_account.to_account_info(),
+                    mint: ctx.accounts.nft_mint.to_account_info(),
+                    to: ctx.accounts.vault_nft_account.to_account_info(),
+                    authority: ctx.accounts.creator.to_account_info(),
+                },
+            )
+            .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+            anchor_spl::token_interface::transfer_checked(
+                transfer_ctx,
+                quantity,
+                ctx.accounts.nft_mint.decimals,
+            )?;
+        }
 
         emit!(AuctionCreated {
             auction_id: auction.key(),
@@ -50,500 +180,17062 @@ pub mod enhanced_auction {
             nft_mint: auction.nft_mint,
             starting_bid,
             end_time: auction.end_time,
+            quantity,
         });
 
         Ok(())
     }
 
-    pub fn place_bid(ctx: Context<PlaceBid>, bid_amount: u64) -> Result<()> {
-        ctx.accounts.validate()?;
-        let auction = &mut ctx.accounts.auction;
-        let clock = Clock::get()?;
-
-        require!(
-            clock.unix_timestamp < auction.end_time,
-            AuctionError::AuctionEnded
-        );
+    /// Creates an auction priced in a 6-decimal USD stablecoin. Starting bid,
+    /// increment and buy-now price are given in the stable mint's base units
+    /// and must be whole cents; bids are held to the same granularity.
+    pub fn initialize_stable_auction<'info>(
+        ctx: Context<'_, '_, '_, 'info, InitializeAuction<'info>>,
+        starting_bid: u64,
+        min_bid_increment: u64,
+        duration: i64,
+        buy_now_price: Option<u64>,
+        quantity: Option<u64>,
+        required_collection: Option<Pubkey>,
+    ) -> Result<()> {
+        let bid_mint = ctx
+            .accounts
+            .bid_mint
+            .as_ref()
+            .ok_or(AuctionError::InvalidBidCurrency)?;
         require!(
-            auction.status == AuctionStatus::Active,
-            AuctionError::AuctionNotActive
+            bid_mint.decimals == STABLE_DECIMALS,
+            AuctionError::InvalidStableMint
         );
+        AuctionParams {
+            starting_bid,
+            min_bid_increment,
+            duration,
+            buy_now_price,
+        }
+        .check_cent_aligned()?;
+
+        ctx.accounts.auction.stable_preset = true;
+        initialize_auction(
+            ctx,
+            starting_bid,
+            min_bid_increment,
+            duration,
+            buy_now_price,
+            quantity,
+            required_collection,
+        )
+    }
+
+    /// Creates an auction that ends `duration_slots` slots from now rather
+    /// than at a unix time, for clusters whose clock may drift. `end_time`
+    /// is kept as an estimate at `SLOT_DURATION_MS` per slot for the windows
+    /// measured in seconds; extensions and pauses are converted to slots at
+    /// the same rate.
+    pub fn initialize_slot_auction<'info>(
+        ctx: Context<'_, '_, '_, 'info, InitializeAuction<'info>>,
+        starting_bid: u64,
+        min_bid_increment: u64,
+        duration_slots: u64,
+        buy_now_price: Option<u64>,
+        quantity: Option<u64>,
+        required_collection: Option<Pubkey>,
+    ) -> Result<()> {
+        require!(duration_slots > 0, AuctionError::InvalidDuration);
+        let clock = Clock::get()?;
+        ctx.accounts.auction.end_slot = clock
+            .slot
+            .checked_add(duration_slots)
+            .ok_or(AuctionError::MathOverflow)?;
+        initialize_auction(
+            ctx,
+            starting_bid,
+            min_bid_increment,
+            slots_to_seconds(duration_slots),
+            buy_now_price,
+            quantity,
+            required_collection,
+        )
+    }
+
+    /// Creates a SOL auction only wallets under the Merkle `allowlist_root`
+    /// may bid in, for private sales and holder-only auctions. Each wallet
+    /// proves its place with its first `place_bid`, as with
+    /// `set_bidder_allowlist`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn initialize_private_auction<'info>(
+        ctx: Context<'_, '_, '_, 'info, InitializeAuction<'info>>,
+        starting_bid: u64,
+        min_bid_increment: u64,
+        duration: i64,
+        buy_now_price: Option<u64>,
+        quantity: Option<u64>,
+        required_collection: Option<Pubkey>,
+        allowlist_root: [u8; 32],
+    ) -> Result<()> {
         require!(
-            bid_amount >= auction.starting_bid,
-            AuctionError::BidTooLow
+            ctx.accounts.bid_mint.is_none(),
+            AuctionError::InvalidBidCurrency
         );
 
-        if auction.highest_bid > 0 {
-            require!(
-                bid_amount >= auction.highest_bid + auction.min_bid_increment,
-                AuctionError::BidIncrementTooLow
-            );
+        let auction_id = ctx.accounts.auction.key();
+        ctx.accounts.auction.allowlist_root = allowlist_root;
+        initialize_auction(
+            ctx,
+            starting_bid,
+            min_bid_increment,
+            duration,
+            buy_now_price,
+            quantity,
+            required_collection,
+        )?;
 
-            // Refund previous highest bidder
-            let refund_ctx = CpiContext::new(
-                ctx.accounts.system_program.to_account_info(),
-                anchor_lang::system_program::Transfer {
-                    from: ctx.accounts.auction_escrow.to_account_info(),
-                    to: ctx.accounts.previous_bidder.to_account_info(),
-                },
-            );
-            anchor_lang::system_program::transfer(refund_ctx, auction.highest_bid)?;
+        emit!(BidderAllowlistSet {
+            auction_id,
+            root: allowlist_root,
+        });
+
+        Ok(())
+    }
+
+    /// Stores a preset of auction parameters under `template_id` for the
+    /// creator to list from with `init_from_template`.
+    pub fn create_template(
+        ctx: Context<CreateTemplate>,
+        template_id: u32,
+        params: AuctionTemplateParams,
+    ) -> Result<()> {
+        ctx.accounts.validate()?;
+        params.check()?;
+
+        let template = &mut ctx.accounts.template;
+        template.creator = ctx.accounts.creator.key();
+        template.template_id = template_id;
+        template.params = params;
+        template.bump = ctx.bumps.template;
+
+        emit!(TemplateCreated {
+            template: template.key(),
+            creator: template.creator,
+            template_id,
+        });
+
+        Ok(())
+    }
+
+    /// Creates an auction from one of the creator's templates: its starting
+    /// bid, increment, duration and buy-now price, plus its anti-snipe rule
+    /// with the cap counted from the new auction's end time. An active
+    /// experiment's variant still takes precedence, as at any listing.
+    pub fn init_from_template<'info>(
+        ctx: Context<'_, '_, '_, 'info, InitializeAuction<'info>>,
+        quantity: Option<u64>,
+        required_collection: Option<Pubkey>,
+    ) -> Result<()> {
+        let params = ctx
+            .accounts
+            .template
+            .as_ref()
+            .ok_or(AuctionError::MissingTemplate)?
+            .params
+            .clone();
+        let clock = Clock::get()?;
+
+        let auction = &mut ctx.accounts.auction;
+        auction.extension_window = params.extension_window;
+        auction.extension_amount = params.extension_amount;
+        if params.max_extension > 0 {
+            auction.max_end_time = clock.unix_timestamp + params.duration + params.max_extension;
         }
+        initialize_auction(
+            ctx,
+            params.starting_bid,
+            params.min_bid_increment,
+            params.duration,
+            params.buy_now_price,
+            quantity,
+            required_collection,
+        )
+    }
 
-        // Transfer new bid amount to escrow
-        let transfer_ctx = CpiContext::new(
-            ctx.accounts.system_program.to_account_info(),
-            anchor_lang::system_program::Transfer {
-                from: ctx.accounts.bidder.to_account_info(),
-                to: ctx.accounts.auction_escrow.to_account_info(),
-            },
-        );
-        anchor_lang::system_program::transfer(transfer_ctx, bid_amount)?;
+    /// Stores the creator's listing defaults: a bid increment for listings
+    /// that pass `0`, and an anti-snipe rule for listings that set none.
+    /// `initialize_auction` applies them when given the settings account;
+    /// a template's rule or an active experiment's variant still takes
+    /// precedence.
+    pub fn set_creator_settings(
+        ctx: Context<SetCreatorSettings>,
+        defaults: CreatorDefaults,
+    ) -> Result<()> {
+        ctx.accounts.validate()?;
+        defaults.check()?;
 
-        auction.highest_bid = bid_amount;
-        auction.highest_bidder = ctx.accounts.bidder.key();
+        let settings = &mut ctx.accounts.creator_settings;
+        settings.creator = ctx.accounts.creator.key();
+        settings.defaults = defaults;
+        settings.bump = ctx.bumps.creator_settings;
 
-        emit!(BidPlaced {
-            auction_id: auction.key(),
-            bidder: ctx.accounts.bidder.key(),
-            bid_amount,
+        emit!(CreatorSettingsSet {
+            creator: settings.creator,
+            defaults: settings.defaults.clone(),
         });
 
         Ok(())
     }
 
-    pub fn finalize_auction(ctx: Context<FinalizeAuction>) -> Result<()> {
+    /// Places a SOL bid. With a `session`, the signer is a session key
+    /// bidding for its owner out of the session's funds, and the owner
+    /// holds the bid. The highest bidder may raise their own bid, paying
+    /// only the difference. With a first refusal window, a bid that would
+    /// take the lead is held pending until the outbid leader matches it or
+    /// the window passes. A bidder who shows a token account holding the
+    /// config's holder mint wins with the holder discount. The creator and
+    /// wallets the admin has linked to them may not bid. On an auction with
+    /// a bidder allowlist, a wallet's first bid carries its Merkle proof.
+    pub fn place_bid(
+        ctx: Context<PlaceBid>,
+        bid_amount: u64,
+        allowlist_proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
         ctx.accounts.validate()?;
+        check_not_blocked(&ctx.accounts.bidder_blocklist_entry)?;
+        let bidder = bidding_wallet(&ctx.accounts.bidder, &ctx.accounts.session);
         let auction = &mut ctx.accounts.auction;
         let clock = Clock::get()?;
 
-        require!(
-            clock.unix_timestamp >= auction.end_time,
-            AuctionError::AuctionNotEnded
-        );
-        require!(
-            auction.status == AuctionStatus::Active,
-            AuctionError::AuctionNotActive
-        );
+        require!(!auction.has_ended(&clock), AuctionError::AuctionEnded);
+        require!(auction.is_native(), AuctionError::InvalidBidCurrency);
+        require_independent_bidder(auction, &ctx.accounts.creator_links, &bidder)?;
+        auction.check_bid(bid_amount, ctx.accounts.increment_tiers.as_deref())?;
+        // A leader raising their own bid only owes the difference
+        let top_up = auction.highest_bid > 0 && bidder == auction.highest_bidder;
+        let owed = if top_up {
+            bid_amount - auction.highest_bid
+        } else {
+            bid_amount
+        };
+        if let Some(session) = &ctx.accounts.session {
+            session.check_spend(owed, clock.unix_timestamp)?;
+        }
+        if let Some(bid_deposit) = &ctx.accounts.bid_deposit {
+            require!(
+                bid_deposit.balance >= owed,
+                AuctionError::InsufficientDeposit
+            );
+        }
+        require_registration(auction, &ctx.accounts.registration)?;
+        if auction.has_allowlist() {
+            let entry = ctx
+                .accounts
+                .allowlist_entry
+                .as_mut()
+                .ok_or(AuctionError::MissingAllowlistEntry)?;
+            if entry.bidder == Pubkey::default() {
+                // First bid from this wallet: check the proof and cache it
+                require!(
+                    verify_allowlist_proof(&allowlist_proof, auction.allowlist_root, &bidder),
+                    AuctionError::NotAllowlisted
+                );
+                entry.auction = auction.key();
+                entry.bidder = bidder;
+                entry.bump = ctx.bumps.allowlist_entry.unwrap_or_default();
+            }
+        }
+        if auction.is_token_gated() {
+            let gate_account = ctx
+                .accounts
+                .gate_token_account
+                .as_ref()
+                .ok_or(AuctionError::MissingGateAccount)?;
+            require_keys_eq!(gate_account.owner, bidder, AuctionError::MissingGateAccount);
+        }
+        if auction.requires_attestation() {
+            let gateway_token = ctx
+                .accounts
+                .gateway_token
+                .as_ref()
+                .ok_or(AuctionError::MissingAttestation)?;
+            gateway::verify_pass(
+                gateway_token,
+                &bidder,
+                &auction.attestation_network,
+                clock.unix_timestamp,
+            )?;
+        }
+        let holder_discount_bps = match &ctx.accounts.holder_token_account {
+            Some(token_account) => holder_discount(&ctx.accounts.config, token_account, bidder)?,
+            None => 0,
+        };
+        track_bidder(
+            auction,
+            ctx.accounts.bidder_stats.as_mut(),
+            bidder,
+            ctx.bumps.bidder_stats,
+            clock.unix_timestamp,
+        )?;
 
-        auction.status = AuctionStatus::Completed;
+        if auction.highest_bid > 0 && !top_up {
+            auction.contested = true;
+            if let Some(bid_escrow) = ctx.accounts.previous_bid_escrow.as_mut() {
+                bid_escrow.open(
+                    auction.key(),
+                    auction.highest_bidder,
+                    ctx.accounts.payer.key(),
+                    ctx.bumps.previous_bid_escrow.unwrap_or_default(),
+                );
+            }
+        }
 
-        if auction.highest_bid > 0 {
-            // Calculate platform fee (2.5%)
-            let platform_fee = (auction.highest_bid * 25) / 1000;
-            let seller_amount = auction.highest_bid - platform_fee;
+        let history = &mut ctx.accounts.bid_history;
+        history.open(auction.key(), ctx.bumps.bid_history);
+        history.record(bidder, bid_amount, clock.unix_timestamp);
+        let top_bids = &mut ctx.accounts.top_bids;
+        top_bids.open(auction.key(), ctx.bumps.top_bids);
+        top_bids.insert(bidder, bid_amount, clock.unix_timestamp);
+        let receipt = &mut ctx.accounts.bid_receipt;
+        receipt.auction = auction.key();
+        receipt.bidder = bidder;
+        receipt.amount = bid_amount;
+        receipt.slot = clock.slot;
+        receipt.rent_payer = ctx.accounts.payer.key();
+        receipt.bump = ctx.bumps.bid_receipt;
 
-            // Transfer funds to seller
-            let seller_transfer_ctx = CpiContext::new(
-                ctx.accounts.system_program.to_account_info(),
-                anchor_lang::system_program::Transfer {
-                    from: ctx.accounts.auction_escrow.to_account_info(),
-                    to: ctx.accounts.creator.to_account_info(),
-                },
+        if auction.proxy_bid_active {
+            require_keys_neq!(
+                bidder,
+                auction.highest_bidder,
+                AuctionError::ProxyBidActive
             );
-            anchor_lang::system_program::transfer(seller_transfer_ctx, seller_amount)?;
+            let leader_proxy = ctx
+                .accounts
+                .leader_proxy_bid
+                .as_mut()
+                .ok_or(AuctionError::MissingProxyBid)?;
+            let answers = bid_amount <= auction.highest_bid + leader_proxy.reserve;
+            if answers && !is_blocked(&ctx.accounts.leader_blocklist_entry) {
+                // The leader's proxy covers the bid and answers it
+                raise_proxy_bid(
+                    auction,
+                    leader_proxy,
+                    &ctx.accounts.auction_escrow,
+                    bid_amount,
+                    ctx.accounts.increment_tiers.as_deref(),
+                )?;
+                auction.apply_extension(clock.unix_timestamp);
+                history.record(
+                    auction.highest_bidder,
+                    auction.highest_bid,
+                    clock.unix_timestamp,
+                );
+                top_bids.insert(
+                    auction.highest_bidder,
+                    auction.highest_bid,
+                    clock.unix_timestamp,
+                );
 
-            // Transfer NFT to winner
-            let nft_transfer_ctx = CpiContext::new(
-                ctx.accounts.token_program.to_account_info(),
-                Transfer {
-                    from: ctx.accounts.vault_nft_account.to_account_info(),
-                    to: ctx.accounts.winner_nft_account.to_account_info(),
-                    authority: ctx.accounts.auction_authority.to_account_info(),
-                },
-            );
-            anchor_spl::token::transfer(nft_transfer_ctx, 1)?;
+                emit!(BidPlaced {
+                    auction_id: auction.key(),
+                    bidder: auction.highest_bidder,
+                    bid_amount: auction.highest_bid,
+                });
 
-            // Transfer platform fee
-            let fee_transfer_ctx = CpiContext::new(
-                ctx.accounts.system_program.to_account_info(),
-                anchor_lang::system_program::Transfer {
-                    from: ctx.accounts.auction_escrow.to_account_info(),
-                    to: ctx.accounts.platform_fee_account.to_account_info(),
-                },
-            );
-            anchor_lang::system_program::transfer(fee_transfer_ctx, platform_fee)?;
+                return Ok(());
+            }
         }
 
-        emit!(AuctionFinalized {
+        let pending = auction.first_refusal_window > 0 && auction.highest_bid > 0 && !top_up;
+        if auction.highest_bid > 0 && !top_up && !pending {
+            // Credit the previous highest bidder's escrow; they pull it out
+            // with `claim_refund`
+            let bid_escrow = ctx
+                .accounts
+                .previous_bid_escrow
+                .as_mut()
+                .ok_or(AuctionError::MissingBidEscrow)?;
+            credit_bid_escrow(
+                &ctx.accounts.system_program,
+                &ctx.accounts.auction_escrow,
+                ctx.bumps.auction_escrow,
+                auction.key(),
+                bid_escrow,
+                auction.highest_bid,
+            )?;
+        }
+
+        // Transfer what the bid owes to escrow
+        match (
+            ctx.accounts.bid_deposit.as_mut(),
+            ctx.accounts.session.as_mut(),
+        ) {
+            (Some(bid_deposit), session) => {
+                spend_bid_deposit(bid_deposit, &ctx.accounts.auction_escrow, owed)?;
+                if let Some(session) = session {
+                    session.spent += owed;
+                }
+            }
+            (None, Some(session)) => spend_session(session, &ctx.accounts.auction_escrow, owed)?,
+            (None, None) => {
+                let transfer_ctx = CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    anchor_lang::system_program::Transfer {
+                        from: ctx.accounts.bidder.to_account_info(),
+                        to: ctx.accounts.auction_escrow.to_account_info(),
+                    },
+                );
+                anchor_lang::system_program::transfer(transfer_ctx, owed)?;
+            }
+        }
+
+        if pending {
+            auction.pending_bid = bid_amount;
+            auction.pending_bidder = bidder;
+            auction.pending_until = clock.unix_timestamp + auction.first_refusal_window;
+            auction.apply_extension(clock.unix_timestamp);
+
+            emit!(BidPendingFirstRefusal {
+                auction_id: auction.key(),
+                bidder,
+                bid_amount,
+                pending_until: auction.pending_until,
+            });
+
+            return Ok(());
+        }
+
+        auction.highest_bid = bid_amount;
+        auction.highest_bidder = bidder;
+        auction.apply_extension(clock.unix_timestamp);
+        auction.highest_bid_wrapped = false;
+        auction.proxy_bid_active = false;
+        if holder_discount_bps > 0 {
+            auction.discount_holder = bidder;
+            auction.holder_discount_bps = holder_discount_bps;
+        }
+
+        emit!(BidPlaced {
             auction_id: auction.key(),
-            winner: auction.highest_bidder,
-            winning_bid: auction.highest_bid,
+            bidder,
+            bid_amount,
         });
 
         Ok(())
     }
 
-    pub fn withdraw_unsold_nft(ctx: Context<WithdrawUnsoldNFT>) -> Result<()> {
+    /// Places a SOL bid `premium_bps` above the standing bid as it is when
+    /// the transaction executes, or at the starting bid when there is none,
+    /// so a bid raced by another does not fail on a stale amount. Fails if
+    /// that comes to more than `max_amount`.
+    pub fn place_relative_bid(
+        ctx: Context<PlaceBid>,
+        premium_bps: u16,
+        max_amount: u64,
+        allowlist_proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        let bid_amount = ctx
+            .accounts
+            .auction
+            .relative_bid(premium_bps)
+            .ok_or(AuctionError::MathOverflow)?;
+        require!(bid_amount <= max_amount, AuctionError::RelativeBidAboveMax);
+        place_bid(ctx, bid_amount, allowlist_proof)
+    }
+
+    /// Places a SOL bid that only counts if the auction's reserve price is
+    /// met. If the auction ends below the reserve with this bid standing, it
+    /// is left out of settlement and `refund_conditional_bid` returns it.
+    pub fn place_conditional_bid(
+        ctx: Context<PlaceBid>,
+        bid_amount: u64,
+        allowlist_proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        let bidder = bidding_wallet(&ctx.accounts.bidder, &ctx.accounts.session);
+        let auction = &mut ctx.accounts.auction;
+        require!(auction.reserve_price > 0, AuctionError::NoReservePrice);
+
+        // The flag only holds while this exact bid is the standing one, so
+        // a bid that ends up pending or answered by a proxy never counts.
+        auction.conditional_bidder = bidder;
+        auction.conditional_amount = bid_amount;
+        place_bid(ctx, bid_amount, allowlist_proof)
+    }
+
+    /// Places a native-SOL bid funded from a wrapped SOL token account. The
+    /// wSOL is moved into a transient PDA token account and unwrapped straight
+    /// into the escrow. If outbid, the bid is credited to the bidder's
+    /// `BidEscrow` as native SOL, like any other outbid bid.
+    pub fn place_wsol_bid(ctx: Context<PlaceWsolBid>, bid_amount: u64) -> Result<()> {
         ctx.accounts.validate()?;
+        check_not_blocked(&ctx.accounts.bidder_blocklist_entry)?;
         let auction = &mut ctx.accounts.auction;
         let clock = Clock::get()?;
 
+        require_independent_bidder(
+            auction,
+            &ctx.accounts.creator_links,
+            &ctx.accounts.bidder.key(),
+        )?;
+        require!(!auction.has_ended(&clock), AuctionError::AuctionEnded);
+        require!(auction.is_native(), AuctionError::InvalidBidCurrency);
+        require!(!auction.proxy_bid_active, AuctionError::ProxyBidActive);
         require!(
-            clock.unix_timestamp >= auction.end_time,
-            AuctionError::AuctionNotEnded
-        );
-        require!(auction.highest_bid == 0, AuctionError::AuctionHasBids);
-        require!(
-            auction.status == AuctionStatus::Active,
-            AuctionError::AuctionNotActive
+            auction.first_refusal_window == 0,
+            AuctionError::FirstRefusalAuction
         );
+        auction.check_bid(bid_amount, None)?;
+        require_registration(auction, &ctx.accounts.registration)?;
+        require_allowlisted(auction, &ctx.accounts.allowlist_entry)?;
+        require!(!auction.has_bid_gate(), AuctionError::BidGated);
+        track_bidder(
+            auction,
+            ctx.accounts.bidder_stats.as_mut(),
+            ctx.accounts.bidder.key(),
+            ctx.bumps.bidder_stats,
+            clock.unix_timestamp,
+        )?;
 
-        // Transfer NFT back to creator
+        if auction.highest_bid > 0 {
+            auction.contested = true;
+            // Credit the previous highest bidder's escrow, as native SOL
+            // even if they bid wSOL; they pull it out with `claim_refund`
+            let bid_escrow = ctx
+                .accounts
+                .previous_bid_escrow
+                .as_mut()
+                .ok_or(AuctionError::MissingBidEscrow)?;
+            bid_escrow.open(
+                auction.key(),
+                auction.highest_bidder,
+                ctx.accounts.bidder.key(),
+                ctx.bumps.previous_bid_escrow.unwrap_or_default(),
+            );
+            credit_bid_escrow(
+                &ctx.accounts.system_program,
+                &ctx.accounts.auction_escrow,
+                ctx.bumps.auction_escrow,
+                auction.key(),
+                bid_escrow,
+                auction.highest_bid,
+            )?;
+        }
+
+        // Move the bid into the transient unwrap account
         let transfer_ctx = CpiContext::new(
             ctx.accounts.token_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.vault_nft_account.to_account_info(),
-                to: ctx.accounts.creator_nft_account.to_account_info(),
+            anchor_spl::token::TransferChecked {
+                from: ctx.accounts.bidder_wsol_account.to_account_info(),
+                mint: ctx.accounts.native_mint.to_account_info(),
+                to: ctx.accounts.wsol_unwrap_account.to_account_info(),
+                authority: ctx.accounts.bidder.to_account_info(),
+            },
+        );
+        anchor_spl::token::transfer_checked(
+            transfer_ctx,
+            bid_amount,
+            ctx.accounts.native_mint.decimals,
+        )?;
+
+        // Unwrap into the escrow. Closing hands over the bid plus the unwrap
+        // account's rent, so the rent portion is returned to the bidder.
+        let unwrap_rent = ctx
+            .accounts
+            .wsol_unwrap_account
+            .to_account_info()
+            .lamports()
+            .checked_sub(bid_amount)
+            .ok_or(AuctionError::MathOverflow)?;
+        let auction_key = auction.key();
+        let authority_seeds: &[&[&[u8]]] = &[&[
+            AUCTION_SEED,
+            auction_key.as_ref(),
+            &[ctx.bumps.auction_authority],
+        ]];
+        let close_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            anchor_spl::token::CloseAccount {
+                account: ctx.accounts.wsol_unwrap_account.to_account_info(),
+                destination: ctx.accounts.auction_escrow.to_account_info(),
                 authority: ctx.accounts.auction_authority.to_account_info(),
             },
+            authority_seeds,
+        );
+        anchor_spl::token::close_account(close_ctx)?;
+
+        let escrow_seeds: &[&[&[u8]]] = &[&[
+            ESCROW_SEED,
+            auction_key.as_ref(),
+            &[ctx.bumps.auction_escrow],
+        ]];
+        let rent_return_ctx = CpiContext::new_with_signer(
+            ctx.accounts.system_program.to_account_info(),
+            anchor_lang::system_program::Transfer {
+                from: ctx.accounts.auction_escrow.to_account_info(),
+                to: ctx.accounts.bidder.to_account_info(),
+            },
+            escrow_seeds,
         );
-        anchor_spl::token::transfer(transfer_ctx, 1)?;
+        anchor_lang::system_program::transfer(rent_return_ctx, unwrap_rent)?;
 
-        auction.status = AuctionStatus::Cancelled;
+        auction.highest_bid = bid_amount;
+        auction.highest_bidder = ctx.accounts.bidder.key();
+        auction.apply_extension(clock.unix_timestamp);
+        auction.highest_bid_wrapped = true;
 
-        emit!(AuctionCancelled {
+        emit!(BidPlaced {
             auction_id: auction.key(),
-            reason: "No bids placed".to_string(),
+            bidder: ctx.accounts.bidder.key(),
+            bid_amount,
         });
 
         Ok(())
     }
 
-    pub fn cancel_auction(ctx: Context<CancelAuction>) -> Result<()> {
+    /// Lets the creator accept bids in several SPL mints, each priced through
+    /// a Pyth feed. Must be configured before the first bid.
+    pub fn set_accepted_currencies(
+        ctx: Context<SetAcceptedCurrencies>,
+        currencies: Vec<AcceptedCurrency>,
+    ) -> Result<()> {
         ctx.accounts.validate()?;
         let auction = &mut ctx.accounts.auction;
 
         require!(
-            auction.status == AuctionStatus::Active,
+            auction.status == AuctionStatus::Live,
             AuctionError::AuctionNotActive
         );
-        require!(auction.highest_bid == 0, AuctionError::AuctionHasBids);
+        require!(!auction.has_bids(), AuctionError::AuctionHasBids);
         require_keys_eq!(
             auction.creator,
             ctx.accounts.creator.key(),
-            AuctionError::UnauthorizedCancellation
+            AuctionError::UnauthorizedUpdate
         );
-
-        // Transfer NFT back to creator
-        let transfer_ctx = CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.vault_nft_account.to_account_info(),
-                to: ctx.accounts.creator_nft_account.to_account_info(),
-                authority: ctx.accounts.auction_authority.to_account_info(),
-            },
+        require!(
+            auction.bid_mint == Pubkey::default(),
+            AuctionError::InvalidBidCurrency
+        );
+        require!(
+            !currencies.is_empty() && currencies.len() <= MAX_ACCEPTED_CURRENCIES,
+            AuctionError::TooManyCurrencies
         );
-        anchor_spl::token::transfer(transfer_ctx, 1)?;
 
-        auction.status = AuctionStatus::Cancelled;
+        // Bids are escrowed per mint from here on, so the SOL escrow's rent
+        // reserve goes back to the creator
+        release_escrow_reserve(
+            &ctx.accounts.system_program,
+            &ctx.accounts.auction_escrow,
+            ctx.bumps.auction_escrow,
+            auction.key(),
+            auction,
+            &ctx.accounts.creator.to_account_info(),
+        )?;
 
-        emit!(AuctionCancelled {
+        auction.multi_currency = true;
+        let accepted = &mut ctx.accounts.accepted_currencies;
+        accepted.auction = auction.key();
+        accepted.currencies = currencies;
+        accepted.bump = ctx.bumps.accepted_currencies;
+
+        emit!(AcceptedCurrenciesSet {
             auction_id: auction.key(),
-            reason: "Cancelled by creator".to_string(),
+            mints: accepted.currencies.iter().map(|c| c.mint).collect(),
         });
 
         Ok(())
     }
 
-    pub fn update_auction_settings(
-        ctx: Context<UpdateAuctionSettings>,
-        new_duration: Option<i64>,
-        new_min_increment: Option<u64>,
+    /// Places a bid in any of the auction's accepted currencies. The bid is
+    /// valued in micro-USD from a fresh Pyth price and compared on that common
+    /// unit; escrow is held per mint in the auction authority's ATA.
+    pub fn place_multi_currency_bid(
+        ctx: Context<PlaceMultiCurrencyBid>,
+        bid_amount: u64,
     ) -> Result<()> {
         ctx.accounts.validate()?;
+        check_not_blocked(&ctx.accounts.bidder_blocklist_entry)?;
         let auction = &mut ctx.accounts.auction;
+        let clock = Clock::get()?;
 
+        require_independent_bidder(
+            auction,
+            &ctx.accounts.creator_links,
+            &ctx.accounts.bidder.key(),
+        )?;
+        require!(!auction.has_ended(&clock), AuctionError::AuctionEnded);
+        require!(auction.multi_currency, AuctionError::InvalidBidCurrency);
+
+        let bid_mint = &ctx.accounts.bid_mint;
+        let currency = ctx
+            .accounts
+            .accepted_currencies
+            .find(&bid_mint.key())
+            .ok_or(AuctionError::InvalidBidCurrency)?;
+        let price = pyth::load_price(
+            &ctx.accounts.price_feed,
+            clock.unix_timestamp,
+            MAX_PRICE_AGE,
+        )?;
         require!(
-            auction.status == AuctionStatus::Active,
-            AuctionError::AuctionNotActive
-        );
-        require!(auction.highest_bid == 0, AuctionError::AuctionHasBids);
-        require_keys_eq!(
-            auction.creator,
-            ctx.accounts.creator.key(),
-            AuctionError::UnauthorizedUpdate
+            price.feed_id == currency.feed_id,
+            AuctionError::InvalidPriceFeed
         );
 
-        if let Some(duration) = new_duration {
-            require!(duration > 0, AuctionError::InvalidDuration);
-            let clock = Clock::get()?;
-            auction.end_time = clock.unix_timestamp + duration;
-        }
+        let credited = amount_after_transfer_fee(
+            &bid_mint.to_account_info(),
+            bid_amount,
+            clock.epoch,
+        )?;
+        let value = pyth::normalize(credited, bid_mint.decimals, &price, USD_DECIMALS)?;
+        auction.check_bid(value, None)?;
 
-        if let Some(min_increment) = new_min_increment {
-            require!(min_increment > 0, AuctionError::InvalidBidIncrement);
-            auction.min_bid_increment = min_increment;
+        if auction.highest_bid > 0 {
+            auction.contested = true;
+            let previous_mint = ctx
+                .accounts
+                .previous_bid_mint
+                .as_ref()
+                .ok_or(AuctionError::InvalidPreviousBidder)?;
+            let previous_escrow = ctx
+                .accounts
+                .previous_escrow_token_account
+                .as_ref()
+                .ok_or(AuctionError::InvalidPreviousBidder)?;
+            let previous_bidder_token_account = ctx
+                .accounts
+                .previous_bidder_token_account
+                .as_ref()
+                .ok_or(AuctionError::InvalidPreviousBidder)?;
+            let previous_token_program = ctx
+                .accounts
+                .previous_token_program
+                .as_ref()
+                .ok_or(AuctionError::MissingTokenProgram)?;
+            require_keys_eq!(
+                previous_mint.key(),
+                auction.highest_bid_mint,
+                AuctionError::InvalidPreviousBidder
+            );
+            require_keys_eq!(
+                previous_bidder_token_account.owner,
+                auction.highest_bidder,
+                AuctionError::InvalidPreviousBidder
+            );
+            require_keys_eq!(
+                previous_escrow.key(),
+                anchor_spl::associated_token::get_associated_token_address_with_program_id(
+                    &ctx.accounts.auction_authority.key(),
+                    &previous_mint.key(),
+                    &previous_token_program.key(),
+                ),
+                AuctionError::InvalidPreviousBidder
+            );
+
+            // Refund previous highest bidder in the currency they bid with
+            transfer_from_escrow(
+                previous_token_program,
+                previous_escrow,
+                previous_mint,
+                previous_bidder_token_account,
+                &ctx.accounts.auction_authority,
+                auction.key(),
+                ctx.bumps.auction_authority,
+                auction.highest_bid,
+            )?;
         }
 
-        emit!(AuctionUpdated {
+        // Transfer new bid amount to this mint's escrow
+        let transfer_ctx = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.bidder_token_account.to_account_info(),
+                mint: bid_mint.to_account_info(),
+                to: ctx.accounts.escrow_token_account.to_account_info(),
+                authority: ctx.accounts.bidder.to_account_info(),
+            },
+        );
+        anchor_spl::token_interface::transfer_checked(transfer_ctx, bid_amount, bid_mint.decimals)?;
+
+        auction.highest_bid = credited;
+        auction.highest_bid_value = value;
+        auction.highest_bid_mint = bid_mint.key();
+        auction.highest_bidder = ctx.accounts.bidder.key();
+        auction.apply_extension(clock.unix_timestamp);
+
+        emit!(MultiCurrencyBidPlaced {
             auction_id: auction.key(),
-            new_duration,
-            new_min_increment,
+            bidder: ctx.accounts.bidder.key(),
+            bid_mint: bid_mint.key(),
+            bid_amount: credited,
+            normalized_value: value,
         });
 
         Ok(())
     }
-}
 
-#[derive(Accounts)]
-pub struct InitializeAuction<'info> {
-    #[account(init, payer = creator, space = Auction::LEN)]
-    pub auction: Account<'info, Auction>,
-    #[account(mut)]
-    pub creator: Signer<'info>,
-    pub nft_mint: Account<'info, Mint>,
-    #[account(
-        mut,
-        associated_token::mint = nft_mint,
-        associated_token::authority = creator
-    )]
-    pub creator_nft_account: Account<'info, TokenAccount>,
-    #[account(
-        init_if_needed,
-        payer = creator,
-        associated_token::mint = nft_mint,
-        associated_token::authority = auction
-    )]
-    pub vault_nft_account: Account<'info, TokenAccount>,
-    pub system_program: Program<'info, System>,
+    /// Places a bid in the auction's SPL bid mint. When the mint carries the
+    /// Token-2022 TransferFee extension, the bid is credited at the amount the
+    /// escrow actually receives, and that credited amount is what gets
+    /// compared against the current highest bid and refunded on outbid.
+    pub fn place_token_bid(ctx: Context<PlaceTokenBid>, bid_amount: u64) -> Result<()> {
+        ctx.accounts.validate()?;
+        check_not_blocked(&ctx.accounts.bidder_blocklist_entry)?;
+        let auction = &mut ctx.accounts.auction;
+        let clock = Clock::get()?;
+
+        require_independent_bidder(
+            auction,
+            &ctx.accounts.creator_links,
+            &ctx.accounts.bidder.key(),
+        )?;
+        require!(!auction.has_ended(&clock), AuctionError::AuctionEnded);
+        require!(!auction.is_native(), AuctionError::InvalidBidCurrency);
+        require!(auction.yield_principal == 0, AuctionError::EscrowDeployed);
+        if auction.stable_preset {
+            require!(
+                is_cent_aligned(bid_amount),
+                AuctionError::AmountNotCentAligned
+            );
+        }
+
+        let credited = amount_after_transfer_fee(
+            &ctx.accounts.bid_mint.to_account_info(),
+            bid_amount,
+            clock.epoch,
+        )?;
+        auction.check_bid(credited, None)?;
+
+        if auction.highest_bid > 0 {
+            auction.contested = true;
+            let previous_bidder_token_account = ctx
+                .accounts
+                .previous_bidder_token_account
+                .as_ref()
+                .ok_or(AuctionError::InvalidPreviousBidder)?;
+            require_keys_eq!(
+                previous_bidder_token_account.owner,
+                auction.highest_bidder,
+                AuctionError::InvalidPreviousBidder
+            );
+
+            // Refund previous highest bidder the amount they were credited
+            transfer_from_escrow(
+                &ctx.accounts.token_program,
+                &ctx.accounts.escrow_token_account,
+                &ctx.accounts.bid_mint,
+                previous_bidder_token_account,
+                &ctx.accounts.auction_authority,
+                auction.key(),
+                ctx.bumps.auction_authority,
+                auction.highest_bid,
+            )?;
+        }
+
+        // Transfer new bid amount to escrow
+        let transfer_ctx = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.bidder_token_account.to_account_info(),
+                mint: ctx.accounts.bid_mint.to_account_info(),
+                to: ctx.accounts.escrow_token_account.to_account_info(),
+                authority: ctx.accounts.bidder.to_account_info(),
+            },
+        );
+        anchor_spl::token_interface::transfer_checked(
+            transfer_ctx,
+            bid_amount,
+            ctx.accounts.bid_mint.decimals,
+        )?;
+
+        auction.highest_bid = credited;
+        auction.highest_bidder = ctx.accounts.bidder.key();
+        auction.apply_extension(clock.unix_timestamp);
+
+        emit!(BidPlaced {
+            auction_id: auction.key(),
+            bidder: ctx.accounts.bidder.key(),
+            bid_amount: credited,
+        });
+
+        Ok(())
+    }
+
+    /// Purchases the lot outright at `buy_now_price`, also once an unsold
+    /// auction has become a fixed-price listing. The first buy-now to land
+    /// wins: every check runs before any lamports move, so a competing
+    /// buy-now in the same slot fails without transferring anything.
+    pub fn buy_now(ctx: Context<BuyNow>) -> Result<()> {
+        ctx.accounts.validate()?;
+        check_not_blocked(&ctx.accounts.buyer_blocklist_entry)?;
+        let auction = &mut ctx.accounts.auction;
+        let clock = Clock::get()?;
+
+        require_independent_bidder(
+            auction,
+            &ctx.accounts.creator_links,
+            &ctx.accounts.buyer.key(),
+        )?;
+        require_registration(auction, &ctx.accounts.registration)?;
+        require_allowlisted(auction, &ctx.accounts.allowlist_entry)?;
+        require!(!auction.has_bid_gate(), AuctionError::BidGated);
+        let listed = auction.status == AuctionStatus::Listed;
+        require!(
+            auction.status == AuctionStatus::Live || listed,
+            AuctionError::AuctionNotActive
+        );
+        require!(
+            !auction.buy_now_executed,
+            AuctionError::BuyNowAlreadyExecuted
+        );
+        require!(
+            listed || !auction.has_ended(&clock),
+            AuctionError::AuctionEnded
+        );
+        require!(!auction.is_paused(), AuctionError::AuctionPaused);
+        require!(auction.buy_now_price > 0, AuctionError::BuyNowNotAvailable);
+        require!(auction.is_native(), AuctionError::InvalidBidCurrency);
+        require!(!auction.partial_fills, AuctionError::PartialFillAuction);
+        require!(
+            auction.deposit_mode == DepositMode::Off,
+            AuctionError::DepositAuction
+        );
+        require!(!auction.has_pending_bid(), AuctionError::BidPending);
+
+        let price = auction.buy_now_price;
+        let previous_bid = auction.highest_bid;
+        let previous_bidder = auction.highest_bidder;
+        auction.buy_now_executed = true;
+        auction.highest_bid = price;
+        auction.highest_bidder = ctx.accounts.buyer.key();
+        auction.highest_bid_wrapped = false;
+        auction.proxy_bid_active = false;
+        auction.set_deadline(&clock, 0);
+        if listed {
+            set_auction_status(auction, AuctionStatus::Ended)?;
+        }
+
+        if previous_bid > 0 {
+            // Credit the previous highest bidder's escrow, as native SOL
+            // even if they bid wSOL; they pull it out with `claim_refund`
+            let bid_escrow = ctx
+                .accounts
+                .previous_bid_escrow
+                .as_mut()
+                .ok_or(AuctionError::MissingBidEscrow)?;
+            bid_escrow.open(
+                auction.key(),
+                previous_bidder,
+                ctx.accounts.buyer.key(),
+                ctx.bumps.previous_bid_escrow.unwrap_or_default(),
+            );
+            credit_bid_escrow(
+                &ctx.accounts.system_program,
+                &ctx.accounts.auction_escrow,
+                ctx.bumps.auction_escrow,
+                auction.key(),
+                bid_escrow,
+                previous_bid,
+            )?;
+        }
+
+        // Transfer the buy-now price to escrow
+        let transfer_ctx = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            anchor_lang::system_program::Transfer {
+                from: ctx.accounts.buyer.to_account_info(),
+                to: ctx.accounts.auction_escrow.to_account_info(),
+            },
+        );
+        anchor_lang::system_program::transfer(transfer_ctx, price)?;
+
+        emit!(BuyNowExecuted {
+            auction_id: auction.key(),
+            buyer: ctx.accounts.buyer.key(),
+            price,
+        });
+
+        Ok(())
+    }
+
+    /// Closes bidding on an auction past its end time, moving it from `Live`
+    /// to `Ended`. Settlement works either way; this only makes the state
+    /// explicit for indexers, except for fair-end auctions, whose cutoff it
+    /// records from the SlotHashes sysvar and which settle only after it.
+    /// Anyone may call it.
+    pub fn end_auction(ctx: Context<EndAuction>) -> Result<()> {
+        ctx.accounts.validate()?;
+        let auction = &mut ctx.accounts.auction;
+        let clock = Clock::get()?;
+
+        require!(auction.has_ended(&clock), AuctionError::AuctionNotEnded);
+        require!(!auction.is_paused(), AuctionError::AuctionPaused);
+        require!(!auction.has_pending_bid(), AuctionError::BidPending);
+        if auction.fair_end {
+            let slot_hashes = ctx
+                .accounts
+                .slot_hashes
+                .as_ref()
+                .ok_or(AuctionError::SlotHashesUnavailable)?;
+            let (slot, hash) = latest_slot_hash(slot_hashes)?;
+            auction.cutoff_slot = slot;
+            auction.cutoff_hash = hash;
+
+            emit!(FairCutoffRecorded {
+                auction_id: auction.key(),
+                slot,
+                hash,
+            });
+        }
+        set_auction_status(auction, AuctionStatus::Ended)
+    }
+
+    /// Settles the auction and returns a [`SettlementSummary`] as return data
+    /// so CPI callers can verify each payout leg in the same transaction.
+    /// Anyone may call it once the auction has ended; a `settler` who signs
+    /// is paid `FINALIZE_BOUNTY_BPS` of the platform fee. When
+    /// `winner_nft_account` is omitted or frozen, settlement still completes
+    /// and the NFT stays in the vault for `claim_won_nft`.
+    pub fn finalize_auction<'info>(
+        ctx: Context<'_, '_, '_, 'info, FinalizeAuction<'info>>,
+    ) -> Result<SettlementSummary> {
+        ctx.accounts.validate()?;
+        let auction = &mut ctx.accounts.auction;
+        let clock = Clock::get()?;
+
+        require!(auction.is_closed(&clock), AuctionError::AuctionNotEnded);
+        require!(!auction.is_paused(), AuctionError::AuctionPaused);
+        require!(auction.status.can_settle(), AuctionError::AuctionNotActive);
+        require!(auction.is_native(), AuctionError::InvalidBidCurrency);
+        require!(!auction.partial_fills, AuctionError::PartialFillAuction);
+        require!(
+            auction.highest_bid == 0 || auction.deposit_mode != DepositMode::Unsettled,
+            AuctionError::SettlementPending
+        );
+        require!(!auction.has_pending_bid(), AuctionError::BidPending);
+        if auction.closing_queue {
+            let queue = ctx
+                .accounts
+                .closing_queue
+                .as_mut()
+                .ok_or(AuctionError::MissingClosingQueue)?;
+            resolve_closing_queue(
+                &ctx.accounts.system_program,
+                &ctx.accounts.auction_escrow,
+                ctx.bumps.auction_escrow,
+                auction,
+                queue,
+            )?;
+        }
+        require!(
+            !auction.conditional_bid_unmet(),
+            AuctionError::ReserveNotMet
+        );
+
+        let unsold_to_list =
+            auction.fixed_price_fallback && !auction.has_bids() && auction.reserve_price > 0;
+        let status = if unsold_to_list {
+            AuctionStatus::Listed
+        } else if auction.split_settlement && auction.has_bids() {
+            AuctionStatus::Settling
+        } else {
+            AuctionStatus::Settled
+        };
+        set_auction_status(auction, status)?;
+
+        let mut summary = SettlementSummary {
+            auction_id: auction.key(),
+            winner: auction.highest_bidder,
+            winning_bid: auction.highest_bid,
+            seller: ctx.accounts.creator.key(),
+            seller_amount: 0,
+            platform_fee_account: ctx.accounts.platform_fee_account.key(),
+            platform_fee: 0,
+            nft_recipient_account: None,
+            keeper_rebate: 0,
+            settler_bounty: 0,
+            royalties: 0,
+        };
+
+        if status == AuctionStatus::Listed {
+            // Unsold: keep the lot in the vault for sale at the reserve
+            auction.buy_now_price = auction.reserve_price;
+
+            emit!(ConvertedToFixedPrice {
+                auction_id: auction.key(),
+                price: auction.buy_now_price,
+            });
+
+            return Ok(summary);
+        }
+
+        let mut remaining_accounts = ctx.remaining_accounts;
+        if auction.highest_bid > 0 {
+            let (platform_fee, mut seller_amount) =
+                split_proceeds(auction.highest_bid, auction.platform_fee_bps());
+
+            // Pay verified creators their royalty share before the seller,
+            // per the listing-time snapshot when one was taken
+            let royalties = match &ctx.accounts.metadata_snapshot {
+                Some(snapshot) => {
+                    if !snapshot.matches(&ctx.accounts.nft_metadata, &ctx.accounts.nft_mint.key()) {
+                        emit!(MetadataDrifted {
+                            auction_id: auction.key(),
+                            nft_mint: auction.nft_mint,
+                        });
+                    }
+                    snapshot.royalties()
+                }
+                None => token_metadata::load_royalties(
+                    &ctx.accounts.nft_metadata,
+                    &ctx.accounts.nft_mint.key(),
+                )?,
+            };
+            let (payments, rest) = pay_royalties(
+                &ctx.accounts.system_program,
+                &ctx.accounts.auction_escrow,
+                ctx.bumps.auction_escrow,
+                auction.key(),
+                seller_amount,
+                &royalties,
+                remaining_accounts,
+            )?;
+            remaining_accounts = rest;
+            let royalty_total: u64 = payments.iter().map(|p| p.amount).sum();
+            seller_amount = seller_amount
+                .checked_sub(royalty_total)
+                .ok_or(AuctionError::MathOverflow)?;
+            if !payments.is_empty() {
+                emit!(RoyaltiesPaid {
+                    auction_id: auction.key(),
+                    seller_fee_basis_points: royalties.seller_fee_basis_points,
+                    payments,
+                });
+            }
+
+            // Transfer funds to seller, staked into their LST if they chose
+            // one, or hold them until the dispute window closes
+            if auction.delivery_escrow {
+                let dispute = ctx
+                    .accounts
+                    .dispute
+                    .as_mut()
+                    .ok_or(AuctionError::MissingDispute)?;
+                dispute.held = seller_amount;
+                dispute.release_at = clock
+                    .unix_timestamp
+                    .checked_add(dispute.window)
+                    .ok_or(AuctionError::MathOverflow)?;
+            } else if auction.split_settlement {
+                auction.proceeds_owed = seller_amount;
+            } else if auction.lst_payout_pool == Pubkey::default() {
+                pay_from_native_escrow(
+                    &ctx.accounts.system_program,
+                    &ctx.accounts.auction_escrow,
+                    ctx.bumps.auction_escrow,
+                    auction.key(),
+                    &ctx.accounts.creator,
+                    seller_amount,
+                )?;
+            } else {
+                ctx.accounts.lst.deposit(
+                    auction.lst_payout_pool,
+                    &ctx.accounts.creator.key(),
+                    &ctx.accounts.system_program,
+                    &ctx.accounts.auction_escrow,
+                    ctx.bumps.auction_escrow,
+                    auction.key(),
+                    seller_amount,
+                )?;
+            }
+
+            // Transfer NFT to winner, unless they claim it themselves. With
+            // no token account that can take it, it waits in the vault
+            if !auction.split_settlement {
+                let deliverable = ctx
+                    .accounts
+                    .winner_nft_account
+                    .as_ref()
+                    .filter(|account| !account.is_frozen());
+                if let Some(winner_nft_account) = deliverable {
+                    release_from_vault(
+                        auction.is_programmable,
+                        auction.lot_size(),
+                        &ctx.accounts.pnft,
+                        &ctx.accounts.token_program,
+                        &ctx.accounts.vault_nft_account,
+                        &ctx.accounts.nft_mint,
+                        winner_nft_account,
+                        None,
+                        None,
+                        &ctx.accounts.auction_authority,
+                        auction.key(),
+                        ctx.bumps.auction_authority,
+                        remaining_accounts,
+                    )?;
+                    summary.nft_recipient_account = Some(winner_nft_account.key());
+                } else {
+                    auction.nft_parked = true;
+
+                    emit!(WonNftParked {
+                        auction_id: auction.key(),
+                        winner: auction.highest_bidder,
+                    });
+                }
+            }
+
+            // Return the holder discount to a winner who earned it, out of
+            // the platform fee
+            let mut platform_fee = platform_fee;
+            if auction.winner_has_holder_discount() {
+                let winner = ctx
+                    .accounts
+                    .winner
+                    .as_ref()
+                    .ok_or(AuctionError::MissingWinner)?;
+                let (discount, _) =
+                    split_proceeds(auction.highest_bid, auction.holder_discount_bps);
+                let discount = discount.min(platform_fee);
+                pay_from_native_escrow(
+                    &ctx.accounts.system_program,
+                    &ctx.accounts.auction_escrow,
+                    ctx.bumps.auction_escrow,
+                    auction.key(),
+                    winner,
+                    discount,
+                )?;
+                platform_fee -= discount;
+
+                emit!(HolderDiscountPaid {
+                    auction_id: auction.key(),
+                    winner: auction.highest_bidder,
+                    amount: discount,
+                });
+            }
+
+            // Set the losing bidders' share of the fee aside for them to
+            // claim
+            if auction.loser_rebate_bps > 0 {
+                let loser_rebate = ctx
+                    .accounts
+                    .loser_rebate
+                    .as_mut()
+                    .ok_or(AuctionError::MissingLoserRebate)?;
+                let (rebate, _) = split_proceeds(platform_fee, auction.loser_rebate_bps);
+                pay_from_native_escrow(
+                    &ctx.accounts.system_program,
+                    &ctx.accounts.auction_escrow,
+                    ctx.bumps.auction_escrow,
+                    auction.key(),
+                    &loser_rebate.to_account_info(),
+                    rebate,
+                )?;
+                loser_rebate.amount = rebate;
+                platform_fee -= rebate;
+            }
+
+            // Pay the settler's bounty out of the fee
+            if let Some(settler) = &ctx.accounts.settler {
+                let (bounty, _) = split_proceeds(platform_fee, FINALIZE_BOUNTY_BPS);
+                pay_from_native_escrow(
+                    &ctx.accounts.system_program,
+                    &ctx.accounts.auction_escrow,
+                    ctx.bumps.auction_escrow,
+                    auction.key(),
+                    &settler.to_account_info(),
+                    bounty,
+                )?;
+                platform_fee -= bounty;
+                summary.settler_bounty = bounty;
+            }
+
+            // Route the rebate pool's share of the fee
+            if let Some(pool) = ctx.accounts.rebate_pool.as_mut() {
+                let pool_share = pool.share_of(platform_fee);
+                pay_from_native_escrow(
+                    &ctx.accounts.system_program,
+                    &ctx.accounts.auction_escrow,
+                    ctx.bumps.auction_escrow,
+                    auction.key(),
+                    &pool.to_account_info(),
+                    pool_share,
+                )?;
+                pool.available += pool_share;
+                platform_fee -= pool_share;
+            }
+
+            // Transfer platform fee
+            pay_from_native_escrow(
+                &ctx.accounts.system_program,
+                &ctx.accounts.auction_escrow,
+                ctx.bumps.auction_escrow,
+                auction.key(),
+                &ctx.accounts.platform_fee_account,
+                platform_fee,
+            )?;
+
+            summary.seller_amount = seller_amount;
+            summary.platform_fee = platform_fee;
+            summary.royalties = royalty_total;
+        }
+
+        // Return the escrow's rent reserve to the creator, unless it still
+        // backs held or unclaimed proceeds
+        let held = ctx.accounts.dispute.as_ref().map_or(0, |d| d.held);
+        if held == 0 && auction.proceeds_owed == 0 {
+            release_escrow_reserve(
+                &ctx.accounts.system_program,
+                &ctx.accounts.auction_escrow,
+                ctx.bumps.auction_escrow,
+                auction.key(),
+                auction,
+                &ctx.accounts.creator,
+            )?;
+        }
+
+        if let (Some(pool), Some(keeper)) = (
+            ctx.accounts.rebate_pool.as_mut(),
+            ctx.accounts.keeper_account.as_mut(),
+        ) {
+            summary.keeper_rebate = credit_keeper_rebate(pool, keeper, auction.key())?;
+        }
+
+        emit!(AuctionFinalized {
+            auction_id: auction.key(),
+            winner: auction.highest_bidder,
+            winning_bid: auction.highest_bid,
+            experiment_id: auction.experiment_id,
+            variant: auction.experiment_variant,
+        });
+
+        Ok(summary)
+    }
+
+    /// Settles a SOL auction as `finalize_auction` does, but leaves the
+    /// seller's proceeds and the NFT in escrow to be pulled with
+    /// `claim_proceeds` and `claim_item`, so a winner token account that
+    /// cannot take the NFT never holds up the seller's payout.
+    pub fn settle_auction<'info>(
+        ctx: Context<'_, '_, '_, 'info, FinalizeAuction<'info>>,
+    ) -> Result<SettlementSummary> {
+        ctx.accounts.auction.split_settlement = true;
+        finalize_auction(ctx)
+    }
+
+    /// Pays the seller the proceeds `settle_auction` left in escrow, staked
+    /// into their LST if they chose one, and returns the escrow's rent
+    /// reserve. Anyone may call it.
+    pub fn claim_proceeds(ctx: Context<ClaimProceeds>) -> Result<()> {
+        ctx.accounts.validate()?;
+        let auction = &mut ctx.accounts.auction;
+
+        require!(
+            auction.status == AuctionStatus::Settling,
+            AuctionError::InvalidStateTransition
+        );
+        let amount = auction.proceeds_owed;
+        require!(amount > 0, AuctionError::NothingToClaim);
+
+        if auction.lst_payout_pool == Pubkey::default() {
+            pay_from_native_escrow(
+                &ctx.accounts.system_program,
+                &ctx.accounts.auction_escrow,
+                ctx.bumps.auction_escrow,
+                auction.key(),
+                &ctx.accounts.creator,
+                amount,
+            )?;
+        } else {
+            ctx.accounts.lst.deposit(
+                auction.lst_payout_pool,
+                &ctx.accounts.creator.key(),
+                &ctx.accounts.system_program,
+                &ctx.accounts.auction_escrow,
+                ctx.bumps.auction_escrow,
+                auction.key(),
+                amount,
+            )?;
+        }
+        auction.proceeds_owed = 0;
+
+        // Return the escrow's rent reserve to the creator
+        release_escrow_reserve(
+            &ctx.accounts.system_program,
+            &ctx.accounts.auction_escrow,
+            ctx.bumps.auction_escrow,
+            auction.key(),
+            auction,
+            &ctx.accounts.creator,
+        )?;
+
+        emit!(ProceedsClaimed {
+            auction_id: auction.key(),
+            seller: auction.creator,
+            amount,
+        });
+
+        if !auction.claims_outstanding() {
+            set_auction_status(auction, AuctionStatus::Settled)?;
+        }
+
+        Ok(())
+    }
+
+    /// Delivers the NFT `settle_auction` left in the vault to a token account
+    /// of the winner. Anyone may call it.
+    pub fn claim_item<'info>(ctx: Context<'_, '_, '_, 'info, ClaimItem<'info>>) -> Result<()> {
+        ctx.accounts.validate()?;
+        let auction = &mut ctx.accounts.auction;
+
+        require!(
+            matches!(
+                auction.status,
+                AuctionStatus::Settling | AuctionStatus::Disputed
+            ),
+            AuctionError::InvalidStateTransition
+        );
+        require!(
+            auction.has_bids() && !auction.item_claimed,
+            AuctionError::NothingToClaim
+        );
+
+        release_from_vault(
+            auction.is_programmable,
+            auction.lot_size(),
+            &ctx.accounts.pnft,
+            &ctx.accounts.token_program,
+            &ctx.accounts.vault_nft_account,
+            &ctx.accounts.nft_mint,
+            &ctx.accounts.winner_nft_account,
+            None,
+            None,
+            &ctx.accounts.auction_authority,
+            auction.key(),
+            ctx.bumps.auction_authority,
+            ctx.remaining_accounts,
+        )?;
+        auction.item_claimed = true;
+
+        emit!(ItemClaimed {
+            auction_id: auction.key(),
+            winner: auction.highest_bidder,
+            nft_recipient_account: ctx.accounts.winner_nft_account.key(),
+        });
+
+        if auction.status == AuctionStatus::Settling && !auction.claims_outstanding() {
+            set_auction_status(auction, AuctionStatus::Settled)?;
+        }
+
+        Ok(())
+    }
+
+    /// Delivers an NFT that `finalize_auction` left in the vault, because the
+    /// winner had no token account that could take it, to a token account
+    /// of the winner. Anyone may call it.
+    pub fn claim_won_nft<'info>(ctx: Context<'_, '_, '_, 'info, ClaimItem<'info>>) -> Result<()> {
+        ctx.accounts.validate()?;
+        let auction = &mut ctx.accounts.auction;
+
+        require!(auction.nft_parked, AuctionError::NothingToClaim);
+
+        release_from_vault(
+            auction.is_programmable,
+            auction.lot_size(),
+            &ctx.accounts.pnft,
+            &ctx.accounts.token_program,
+            &ctx.accounts.vault_nft_account,
+            &ctx.accounts.nft_mint,
+            &ctx.accounts.winner_nft_account,
+            None,
+            None,
+            &ctx.accounts.auction_authority,
+            auction.key(),
+            ctx.bumps.auction_authority,
+            ctx.remaining_accounts,
+        )?;
+        auction.nft_parked = false;
+
+        emit!(ItemClaimed {
+            auction_id: auction.key(),
+            winner: auction.highest_bidder,
+            nft_recipient_account: ctx.accounts.winner_nft_account.key(),
+        });
+
+        Ok(())
+    }
+
+    /// Refunds the standing bid of a SOL auction nobody finalized within
+    /// `DEADMAN_WINDOW` of its end time, to the highest bidder only. Anyone
+    /// may call it; the creator takes the NFT back with `recover_item`.
+    pub fn recover_bid(ctx: Context<RecoverBid>) -> Result<()> {
+        ctx.accounts.validate()?;
+        let auction = &mut ctx.accounts.auction;
+        let clock = Clock::get()?;
+
+        begin_recovery(auction, &clock)?;
+        require!(
+            auction.highest_bid > 0 && !auction.bid_recovered,
+            AuctionError::NothingToClaim
+        );
+
+        let amount = auction.highest_bid;
+        refund_native_bid(
+            &ctx.accounts.system_program,
+            &ctx.accounts.auction_escrow,
+            ctx.bumps.auction_escrow,
+            auction.key(),
+            &ctx.accounts.bidder,
+            auction.highest_bidder,
+            amount,
+            auction.highest_bid_wrapped,
+            ctx.accounts.token_program.as_ref(),
+        )?;
+        auction.bid_recovered = true;
+
+        emit!(BidRecovered {
+            auction_id: auction.key(),
+            bidder: auction.highest_bidder,
+            amount,
+        });
+
+        finish_recovery(auction)
+    }
+
+    /// Returns the NFT of a SOL auction nobody finalized within
+    /// `DEADMAN_WINDOW` of its end time to its creator, whether or not the
+    /// bidder has taken back their bid with `recover_bid`. Only the creator.
+    pub fn recover_item<'info>(ctx: Context<'_, '_, '_, 'info, RecoverItem<'info>>) -> Result<()> {
+        ctx.accounts.validate()?;
+        let auction = &mut ctx.accounts.auction;
+        let clock = Clock::get()?;
+
+        begin_recovery(auction, &clock)?;
+        require!(!auction.item_claimed, AuctionError::NothingToClaim);
+
+        release_from_vault(
+            auction.is_programmable,
+            auction.lot_size(),
+            &ctx.accounts.pnft,
+            &ctx.accounts.token_program,
+            &ctx.accounts.vault_nft_account,
+            &ctx.accounts.nft_mint,
+            &ctx.accounts.creator_nft_account,
+            Some(&ctx.accounts.creator.to_account_info()),
+            Some(&ctx.accounts.creator.to_account_info()),
+            &ctx.accounts.auction_authority,
+            auction.key(),
+            ctx.bumps.auction_authority,
+            ctx.remaining_accounts,
+        )?;
+        auction.item_claimed = true;
+
+        emit!(ItemRecovered {
+            auction_id: auction.key(),
+            creator: auction.creator,
+            nft_recipient_account: ctx.accounts.creator_nft_account.key(),
+        });
+
+        finish_recovery(auction)
+    }
+
+    /// Settles an auction whose bids were escrowed in an SPL bid mint.
+    pub fn finalize_token_auction<'info>(
+        ctx: Context<'_, '_, '_, 'info, FinalizeTokenAuction<'info>>,
+    ) -> Result<SettlementSummary> {
+        ctx.accounts.validate()?;
+        let auction = &mut ctx.accounts.auction;
+        let clock = Clock::get()?;
+
+        require!(auction.is_closed(&clock), AuctionError::AuctionNotEnded);
+        require!(!auction.is_paused(), AuctionError::AuctionPaused);
+        require!(auction.status.can_settle(), AuctionError::AuctionNotActive);
+        require!(!auction.is_native(), AuctionError::InvalidBidCurrency);
+        require!(auction.yield_principal == 0, AuctionError::EscrowDeployed);
+
+        set_auction_status(auction, AuctionStatus::Settled)?;
+
+        let mut summary = SettlementSummary {
+            auction_id: auction.key(),
+            winner: auction.highest_bidder,
+            winning_bid: auction.highest_bid,
+            seller: auction.creator,
+            seller_amount: 0,
+            platform_fee_account: ctx.accounts.platform_fee_token_account.key(),
+            platform_fee: 0,
+            nft_recipient_account: None,
+            keeper_rebate: 0,
+            settler_bounty: 0,
+            royalties: 0,
+        };
+
+        if auction.highest_bid > 0 {
+            let (platform_fee, mut seller_amount) =
+                split_proceeds(auction.highest_bid, auction.platform_fee_bps());
+            if auction.yield_reserve != Pubkey::default() {
+                // Interest earned while the escrow was lent goes to the seller
+                seller_amount += ctx
+                    .accounts
+                    .escrow_token_account
+                    .amount
+                    .saturating_sub(auction.highest_bid);
+            }
+
+            // Transfer funds to seller
+            transfer_from_escrow(
+                &ctx.accounts.bid_token_program,
+                &ctx.accounts.escrow_token_account,
+                &ctx.accounts.bid_mint,
+                &ctx.accounts.creator_token_account,
+                &ctx.accounts.auction_authority,
+                auction.key(),
+                ctx.bumps.auction_authority,
+                seller_amount,
+            )?;
+
+            // Transfer NFT to winner
+            release_from_vault(
+                auction.is_programmable,
+                auction.lot_size(),
+                &ctx.accounts.pnft,
+                &ctx.accounts.token_program,
+                &ctx.accounts.vault_nft_account,
+                &ctx.accounts.nft_mint,
+                &ctx.accounts.winner_nft_account,
+                None,
+                None,
+                &ctx.accounts.auction_authority,
+                auction.key(),
+                ctx.bumps.auction_authority,
+                ctx.remaining_accounts,
+            )?;
+
+            // Transfer platform fee
+            transfer_from_escrow(
+                &ctx.accounts.bid_token_program,
+                &ctx.accounts.escrow_token_account,
+                &ctx.accounts.bid_mint,
+                &ctx.accounts.platform_fee_token_account,
+                &ctx.accounts.auction_authority,
+                auction.key(),
+                ctx.bumps.auction_authority,
+                platform_fee,
+            )?;
+
+            summary.seller_amount = seller_amount;
+            summary.platform_fee = platform_fee;
+            summary.nft_recipient_account = Some(ctx.accounts.winner_nft_account.key());
+        }
+
+        emit!(AuctionFinalized {
+            auction_id: auction.key(),
+            winner: auction.highest_bidder,
+            winning_bid: auction.highest_bid,
+            experiment_id: auction.experiment_id,
+            variant: auction.experiment_variant,
+        });
+
+        Ok(summary)
+    }
+
+    pub fn withdraw_unsold_nft<'info>(
+        ctx: Context<'_, '_, '_, 'info, WithdrawUnsoldNFT<'info>>,
+    ) -> Result<()> {
+        ctx.accounts.validate()?;
+        let auction = &mut ctx.accounts.auction;
+        let clock = Clock::get()?;
+
+        require!(auction.is_closed(&clock), AuctionError::AuctionNotEnded);
+        require!(!auction.is_paused(), AuctionError::AuctionPaused);
+        require!(!auction.has_bids(), AuctionError::AuctionHasBids);
+        require!(auction.status.can_settle(), AuctionError::AuctionNotActive);
+
+        // Transfer NFT back to creator
+        release_from_vault(
+            auction.is_programmable,
+            auction.lot_size(),
+            &ctx.accounts.pnft,
+            &ctx.accounts.token_program,
+            &ctx.accounts.vault_nft_account,
+            &ctx.accounts.nft_mint,
+            &ctx.accounts.creator_nft_account,
+            Some(&ctx.accounts.creator.to_account_info()),
+            Some(&ctx.accounts.creator.to_account_info()),
+            &ctx.accounts.auction_authority,
+            auction.key(),
+            ctx.bumps.auction_authority,
+            ctx.remaining_accounts,
+        )?;
+
+        // Return the escrow's rent reserve to the creator
+        release_escrow_reserve(
+            &ctx.accounts.system_program,
+            &ctx.accounts.auction_escrow,
+            ctx.bumps.auction_escrow,
+            auction.key(),
+            auction,
+            &ctx.accounts.creator.to_account_info(),
+        )?;
+
+        set_auction_status(auction, AuctionStatus::Cancelled)?;
+
+        emit!(AuctionCancelled {
+            auction_id: auction.key(),
+            reason: "No bids placed".to_string(),
+        });
+
+        Ok(())
+    }
+
+    /// Puts an ended auction that drew no bids back up with fresh prices and
+    /// duration. The auction account is reused, so the NFT stays in its vault
+    /// and the SOL escrow keeps its rent reserve; the bid currency,
+    /// collection, quantity and other listing settings carry over.
+    pub fn relist_auction(
+        ctx: Context<RelistAuction>,
+        starting_bid: u64,
+        min_bid_increment: u64,
+        duration: i64,
+        buy_now_price: Option<u64>,
+    ) -> Result<()> {
+        ctx.accounts.validate()?;
+        let auction = &mut ctx.accounts.auction;
+        let clock = Clock::get()?;
+
+        require!(auction.is_closed(&clock), AuctionError::AuctionNotEnded);
+        require!(!auction.is_paused(), AuctionError::AuctionPaused);
+        require!(!auction.has_bids(), AuctionError::AuctionHasBids);
+        require!(auction.status.can_settle(), AuctionError::AuctionNotActive);
+        require_keys_eq!(
+            auction.creator,
+            ctx.accounts.creator.key(),
+            AuctionError::UnauthorizedUpdate
+        );
+
+        let params = AuctionParams {
+            starting_bid,
+            min_bid_increment,
+            duration,
+            buy_now_price,
+        };
+        params.check()?;
+        if auction.stable_preset {
+            params.check_cent_aligned()?;
+        }
+
+        let previous_end_time = auction.end_time;
+        auction.relist(&params, &clock);
+        if auction.status == AuctionStatus::Ended {
+            set_auction_status(auction, AuctionStatus::Live)?;
+        }
+
+        emit!(AuctionRelisted {
+            auction_id: auction.key(),
+            previous_end_time,
+            starting_bid,
+            end_time: auction.end_time,
+        });
+
+        Ok(())
+    }
+
+    /// Cancels a batch of ended auctions that drew no bids, returning each
+    /// NFT and escrow reserve to its creator as `withdraw_unsold_nft` would.
+    /// Open to anyone so marketplaces can clear stale listings. Remaining
+    /// accounts are `[auction, auction_authority, auction_escrow, nft_mint,
+    /// vault_nft_account, creator_nft_account, creator]` groups; only
+    /// non-programmable SPL auctions can be swept.
+    pub fn expire_unsold_auctions<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ExpireUnsoldAuctions<'info>>,
+    ) -> Result<()> {
+        ctx.accounts.validate()?;
+        let clock = Clock::get()?;
+        require!(
+            ctx.remaining_accounts
+                .chunks_exact(7)
+                .remainder()
+                .is_empty(),
+            AuctionError::InvalidExpiryAccounts
+        );
+
+        for accounts in ctx.remaining_accounts.chunks_exact(7) {
+            let mut auction = Account::<Auction>::try_from(&accounts[0])?;
+            let auction_authority = &accounts[1];
+            let auction_escrow = SystemAccount::try_from(&accounts[2])?;
+            let nft_mint = InterfaceAccount::<Mint>::try_from(&accounts[3])?;
+            let vault_nft_account = InterfaceAccount::<TokenAccount>::try_from(&accounts[4])?;
+            let creator_nft_account = InterfaceAccount::<TokenAccount>::try_from(&accounts[5])?;
+            let creator = &accounts[6];
+
+            require!(auction.is_closed(&clock), AuctionError::AuctionNotEnded);
+            require!(!auction.is_paused(), AuctionError::AuctionPaused);
+            require!(!auction.has_bids(), AuctionError::AuctionHasBids);
+            require!(auction.status.can_settle(), AuctionError::AuctionNotActive);
+            require!(
+                auction.asset_kind == AssetKind::Spl && !auction.is_programmable,
+                AuctionError::InvalidAsset
+            );
+
+            let auction_key = auction.key();
+            let (authority_key, authority_bump) =
+                Pubkey::find_program_address(&[AUCTION_SEED, auction_key.as_ref()], ctx.program_id);
+            let (escrow_key, escrow_bump) =
+                Pubkey::find_program_address(&[ESCROW_SEED, auction_key.as_ref()], ctx.program_id);
+            require!(
+                auction_authority.key() == authority_key
+                    && auction_escrow.key() == escrow_key
+                    && nft_mint.key() == auction.nft_mint
+                    && vault_nft_account.owner == authority_key
+                    && creator_nft_account.owner == auction.creator
+                    && creator.key() == auction.creator,
+                AuctionError::InvalidExpiryAccounts
+            );
+
+            transfer_from_vault(
+                &ctx.accounts.token_program,
+                &vault_nft_account,
+                &nft_mint,
+                auction.lot_size(),
+                &creator_nft_account,
+                auction_authority,
+                auction_key,
+                authority_bump,
+                &[],
+            )?;
+            release_escrow_reserve(
+                &ctx.accounts.system_program,
+                &auction_escrow,
+                escrow_bump,
+                auction_key,
+                &mut auction,
+                creator,
+            )?;
+
+            set_auction_status(&mut auction, AuctionStatus::Cancelled)?;
+            auction.exit(ctx.program_id)?;
+
+            emit!(AuctionCancelled {
+                auction_id: auction_key,
+                reason: "Expired unsold".to_string(),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Instead of withdrawing an unsold NFT, locks it in the vault for good
+    /// and mints `shares` fungible fractions of it to the creator. The
+    /// fraction mint's authority is dropped afterwards, so the supply is
+    /// fixed. Whoever gathers every share can redeem the NFT with
+    /// `redeem_fractions`.
+    pub fn fractionalize_unsold_nft(ctx: Context<FractionalizeUnsoldNFT>, shares: u64) -> Result<()> {
+        ctx.accounts.validate()?;
+        let auction = &mut ctx.accounts.auction;
+        let clock = Clock::get()?;
+
+        require!(auction.is_closed(&clock), AuctionError::AuctionNotEnded);
+        require!(!auction.is_paused(), AuctionError::AuctionPaused);
+        require!(!auction.has_bids(), AuctionError::AuctionHasBids);
+        require!(auction.status.can_settle(), AuctionError::AuctionNotActive);
+        require_keys_eq!(
+            auction.creator,
+            ctx.accounts.creator.key(),
+            AuctionError::Unauthorized
+        );
+        require!(auction.asset_kind == AssetKind::Spl, AuctionError::InvalidAsset);
+        require!(shares > 0, AuctionError::InvalidQuantity);
+
+        let auction_key = auction.key();
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            AUCTION_SEED,
+            auction_key.as_ref(),
+            &[ctx.bumps.auction_authority],
+        ]];
+        let mint_ctx = CpiContext::new_with_signer(
+            ctx.accounts.fraction_token_program.to_account_info(),
+            anchor_spl::token::MintTo {
+                mint: ctx.accounts.fraction_mint.to_account_info(),
+                to: ctx.accounts.creator_fraction_account.to_account_info(),
+                authority: ctx.accounts.auction_authority.to_account_info(),
+            },
+            signer_seeds,
+        );
+        anchor_spl::token::mint_to(mint_ctx, shares)?;
+
+        // Fix the supply so the shares can't be diluted
+        let set_authority_ctx = CpiContext::new_with_signer(
+            ctx.accounts.fraction_token_program.to_account_info(),
+            anchor_spl::token::SetAuthority {
+                current_authority: ctx.accounts.auction_authority.to_account_info(),
+                account_or_mint: ctx.accounts.fraction_mint.to_account_info(),
+            },
+            signer_seeds,
+        );
+        anchor_spl::token::set_authority(
+            set_authority_ctx,
+            anchor_spl::token::spl_token::instruction::AuthorityType::MintTokens,
+            None,
+        )?;
+
+        // Return the escrow's rent reserve to the creator
+        release_escrow_reserve(
+            &ctx.accounts.system_program,
+            &ctx.accounts.auction_escrow,
+            ctx.bumps.auction_escrow,
+            auction.key(),
+            auction,
+            &ctx.accounts.creator.to_account_info(),
+        )?;
+
+        set_auction_status(auction, AuctionStatus::Fractionalized)?;
+
+        emit!(AuctionFractionalized {
+            auction_id: auction.key(),
+            fraction_mint: ctx.accounts.fraction_mint.key(),
+            shares,
+        });
+
+        Ok(())
+    }
+
+    /// Burns the entire supply of a fractionalized NFT's shares, which the
+    /// redeemer must hold in one account, and releases the NFT to them.
+    pub fn redeem_fractions<'info>(
+        ctx: Context<'_, '_, '_, 'info, RedeemFractions<'info>>,
+    ) -> Result<()> {
+        ctx.accounts.validate()?;
+        let auction = &mut ctx.accounts.auction;
+
+        require!(
+            auction.status == AuctionStatus::Fractionalized,
+            AuctionError::AuctionNotFractionalized
+        );
+        let shares = ctx.accounts.fraction_mint.supply;
+        require!(
+            ctx.accounts.redeemer_fraction_account.amount == shares,
+            AuctionError::IncompleteFractions
+        );
+
+        let burn_ctx = CpiContext::new(
+            ctx.accounts.fraction_token_program.to_account_info(),
+            anchor_spl::token::Burn {
+                mint: ctx.accounts.fraction_mint.to_account_info(),
+                from: ctx.accounts.redeemer_fraction_account.to_account_info(),
+                authority: ctx.accounts.redeemer.to_account_info(),
+            },
+        );
+        anchor_spl::token::burn(burn_ctx, shares)?;
+
+        // Transfer NFT to the redeemer
+        let redeemer = ctx.accounts.redeemer.to_account_info();
+        release_from_vault(
+            auction.is_programmable,
+            auction.lot_size(),
+            &ctx.accounts.pnft,
+            &ctx.accounts.token_program,
+            &ctx.accounts.vault_nft_account,
+            &ctx.accounts.nft_mint,
+            &ctx.accounts.redeemer_nft_account,
+            Some(&redeemer),
+            Some(&redeemer),
+            &ctx.accounts.auction_authority,
+            auction.key(),
+            ctx.bumps.auction_authority,
+            ctx.remaining_accounts,
+        )?;
+
+        set_auction_status(auction, AuctionStatus::Settled)?;
+
+        emit!(FractionsRedeemed {
+            auction_id: auction.key(),
+            redeemer: redeemer.key(),
+            shares,
+        });
+
+        Ok(())
+    }
+
+    pub fn cancel_auction<'info>(
+        ctx: Context<'_, '_, '_, 'info, CancelAuction<'info>>,
+    ) -> Result<()> {
+        ctx.accounts.validate()?;
+        let auction = &mut ctx.accounts.auction;
+
+        require!(
+            auction.status.is_open() || auction.status == AuctionStatus::Listed,
+            AuctionError::AuctionNotActive
+        );
+        require!(!auction.has_bids(), AuctionError::AuctionHasBids);
+        require_keys_eq!(
+            auction.creator,
+            ctx.accounts.creator.key(),
+            AuctionError::UnauthorizedCancellation
+        );
+
+        // Transfer NFT back to creator
+        release_from_vault(
+            auction.is_programmable,
+            auction.lot_size(),
+            &ctx.accounts.pnft,
+            &ctx.accounts.token_program,
+            &ctx.accounts.vault_nft_account,
+            &ctx.accounts.nft_mint,
+            &ctx.accounts.creator_nft_account,
+            Some(&ctx.accounts.creator.to_account_info()),
+            Some(&ctx.accounts.creator.to_account_info()),
+            &ctx.accounts.auction_authority,
+            auction.key(),
+            ctx.bumps.auction_authority,
+            ctx.remaining_accounts,
+        )?;
+
+        // Return the escrow's rent reserve to the creator
+        release_escrow_reserve(
+            &ctx.accounts.system_program,
+            &ctx.accounts.auction_escrow,
+            ctx.bumps.auction_escrow,
+            auction.key(),
+            auction,
+            &ctx.accounts.creator.to_account_info(),
+        )?;
+
+        set_auction_status(auction, AuctionStatus::Cancelled)?;
+
+        let cooldown = ctx.accounts.config.relist_cooldown;
+        if cooldown > 0 {
+            let mint_cooldown = ctx
+                .accounts
+                .mint_cooldown
+                .as_mut()
+                .ok_or(AuctionError::MissingMintCooldown)?;
+            mint_cooldown.mint = auction.nft_mint;
+            mint_cooldown.relist_after = Clock::get()?.unix_timestamp + cooldown;
+            mint_cooldown.bump = ctx.bumps.mint_cooldown.unwrap();
+
+            emit!(RelistCooldownStarted {
+                mint: mint_cooldown.mint,
+                relist_after: mint_cooldown.relist_after,
+            });
+        }
+
+        emit!(AuctionCancelled {
+            auction_id: auction.key(),
+            reason: "Cancelled by creator".to_string(),
+        });
+
+        Ok(())
+    }
+
+    pub fn update_auction_settings(
+        ctx: Context<UpdateAuctionSettings>,
+        new_duration: Option<i64>,
+        new_min_increment: Option<u64>,
+        new_min_increment_bps: Option<u16>,
+    ) -> Result<()> {
+        ctx.accounts.validate()?;
+        let auction = &mut ctx.accounts.auction;
+
+        require!(
+            auction.status == AuctionStatus::Live,
+            AuctionError::AuctionNotActive
+        );
+        require!(!auction.has_bids(), AuctionError::AuctionHasBids);
+        require_keys_eq!(
+            auction.creator,
+            ctx.accounts.creator.key(),
+            AuctionError::UnauthorizedUpdate
+        );
+
+        if let Some(duration) = new_duration {
+            require!(duration > 0, AuctionError::InvalidDuration);
+            let clock = Clock::get()?;
+            auction.set_deadline(&clock, duration);
+        }
+
+        if let Some(min_increment) = new_min_increment {
+            require!(min_increment > 0, AuctionError::InvalidBidIncrement);
+            auction.min_bid_increment = min_increment;
+        }
+
+        if let Some(min_increment_bps) = new_min_increment_bps {
+            require!(
+                min_increment_bps <= BPS_DENOMINATOR,
+                AuctionError::InvalidBidIncrement
+            );
+            auction.min_bid_increment_bps = min_increment_bps;
+        }
+
+        emit!(AuctionUpdated {
+            auction_id: auction.key(),
+            new_duration,
+            new_min_increment,
+            new_min_increment_bps,
+        });
+
+        Ok(())
+    }
+
+    /// Announces the auction ahead of its drop: it is shown from
+    /// `preview_start` and opens for bids at `bid_start`, staying
+    /// `Scheduled` until `open_bidding` is called. Only the creator, and only
+    /// before the first bid.
+    pub fn schedule_bidding(
+        ctx: Context<ScheduleBidding>,
+        preview_start: i64,
+        bid_start: i64,
+    ) -> Result<()> {
+        ctx.accounts.validate()?;
+        let auction = &mut ctx.accounts.auction;
+        let clock = Clock::get()?;
+
+        require!(
+            auction.status == AuctionStatus::Live,
+            AuctionError::AuctionNotActive
+        );
+        require!(!auction.has_bids(), AuctionError::AuctionHasBids);
+        require!(!auction.is_paused(), AuctionError::AuctionPaused);
+        require_keys_eq!(
+            auction.creator,
+            ctx.accounts.creator.key(),
+            AuctionError::UnauthorizedUpdate
+        );
+        require!(
+            preview_start <= bid_start
+                && bid_start > clock.unix_timestamp
+                && bid_start < auction.end_time,
+            AuctionError::InvalidBidStart
+        );
+
+        auction.preview_start = preview_start;
+        auction.bid_start = bid_start;
+        set_auction_status(auction, AuctionStatus::Scheduled)?;
+
+        emit!(BiddingScheduled {
+            auction_id: auction.key(),
+            preview_start,
+            bid_start,
+        });
+
+        Ok(())
+    }
+
+    /// Opens a scheduled auction for bids once `bid_start` has passed.
+    /// Anyone may call it, typically ahead of the first bid.
+    pub fn open_bidding(ctx: Context<OpenBidding>) -> Result<()> {
+        ctx.accounts.validate()?;
+        let auction = &mut ctx.accounts.auction;
+        let clock = Clock::get()?;
+
+        require!(
+            auction.status == AuctionStatus::Scheduled,
+            AuctionError::AuctionNotActive
+        );
+        require!(
+            clock.unix_timestamp >= auction.bid_start,
+            AuctionError::BiddingNotOpen
+        );
+        set_auction_status(auction, AuctionStatus::Live)
+    }
+
+    /// Pushes a running auction's `end_time` out by `extension` seconds,
+    /// bids or not. Extensions by the creator add up to at most the config's
+    /// `max_creator_extension` over the auction's life.
+    pub fn extend_end_time(ctx: Context<ExtendEndTime>, extension: i64) -> Result<()> {
+        ctx.accounts.validate()?;
+        let auction = &mut ctx.accounts.auction;
+        let clock = Clock::get()?;
+
+        require!(
+            auction.status == AuctionStatus::Live,
+            AuctionError::AuctionNotActive
+        );
+        require!(!auction.has_ended(&clock), AuctionError::AuctionEnded);
+        require!(!auction.is_paused(), AuctionError::AuctionPaused);
+        require_keys_eq!(
+            auction.creator,
+            ctx.accounts.creator.key(),
+            AuctionError::UnauthorizedUpdate
+        );
+        require!(extension > 0, AuctionError::InvalidDuration);
+        let extended = auction
+            .creator_extension
+            .checked_add(extension)
+            .ok_or(AuctionError::MathOverflow)?;
+        require!(
+            extended <= ctx.accounts.config.max_creator_extension,
+            AuctionError::ExtensionTooLong
+        );
+
+        let previous_end_time = auction.end_time;
+        auction.push_deadline(extension);
+        auction.creator_extension = extended;
+
+        emit!(EndTimeExtended {
+            auction_id: auction.key(),
+            previous_end_time,
+            end_time: auction.end_time,
+        });
+
+        Ok(())
+    }
+
+    /// One-time setup of the program-wide config. Only the program's upgrade
+    /// authority may call this; it becomes the config admin.
+    pub fn initialize_config(ctx: Context<InitializeConfig>) -> Result<()> {
+        ctx.accounts.validate()?;
+        let config = &mut ctx.accounts.config;
+        config.admin = ctx.accounts.admin.key();
+        config.bump = ctx.bumps.config;
+        config.fee_bps = DEFAULT_FEE_BPS;
+        config.fee_destination = config.admin;
+        config.pauser = config.admin;
+        config.fee_manager = config.admin;
+        config.curator = config.admin;
+
+        emit!(ConfigInitialized {
+            admin: config.admin,
+        });
+
+        Ok(())
+    }
+
+    /// Sets the platform fee new auctions are listed at, the account
+    /// finalize pays it to, and the bounds on auction duration. Auctions
+    /// already listed keep their fee. Fee manager only.
+    pub fn set_platform_params(
+        ctx: Context<SetPlatformParams>,
+        fee_bps: u16,
+        fee_destination: Pubkey,
+        min_duration: i64,
+        max_duration: i64,
+    ) -> Result<()> {
+        ctx.accounts.validate()?;
+        require!(fee_bps <= BPS_DENOMINATOR, AuctionError::InvalidPlatformFee);
+        require!(
+            min_duration >= 0 && (max_duration == 0 || max_duration >= min_duration),
+            AuctionError::InvalidDuration
+        );
+
+        let config = &mut ctx.accounts.config;
+        config.fee_bps = fee_bps;
+        config.fee_destination = fee_destination;
+        config.min_duration = min_duration;
+        config.max_duration = max_duration;
+
+        emit!(PlatformParamsSet {
+            fee_bps,
+            fee_destination,
+            min_duration,
+            max_duration,
+        });
+
+        Ok(())
+    }
+
+    /// Hands the pauser, fee manager and curator roles to separate keys, so
+    /// operational duties need not all sit with the admin. Admin only.
+    pub fn set_roles(
+        ctx: Context<SetRoles>,
+        pauser: Pubkey,
+        fee_manager: Pubkey,
+        curator: Pubkey,
+    ) -> Result<()> {
+        ctx.accounts.validate()?;
+
+        let config = &mut ctx.accounts.config;
+        config.pauser = pauser;
+        config.fee_manager = fee_manager;
+        config.curator = curator;
+
+        emit!(RolesSet {
+            pauser,
+            fee_manager,
+            curator,
+        });
+
+        Ok(())
+    }
+
+    /// Halts or resumes new listings, bids and buy-now purchases across the
+    /// program, for incident response. Refunds, withdrawals, cancellation
+    /// and settlement keep working while halted. Pauser only.
+    pub fn set_halted(ctx: Context<SetHalted>, halted: bool) -> Result<()> {
+        ctx.accounts.validate()?;
+
+        let config = &mut ctx.accounts.config;
+        config.halted = halted;
+
+        emit!(ProgramHaltSet { halted });
+
+        Ok(())
+    }
+
+    /// Turns curated mode on or off. While on, `initialize_auction` only
+    /// lists NFTs whose verified collection the curator has approved.
+    /// Curator only.
+    pub fn set_curated(ctx: Context<SetCurated>, curated: bool) -> Result<()> {
+        ctx.accounts.validate()?;
+
+        let config = &mut ctx.accounts.config;
+        config.curated = curated;
+
+        emit!(CuratedModeSet { curated });
+
+        Ok(())
+    }
+
+    /// Adds a collection to the registry curated mode lists from. Curator
+    /// only.
+    pub fn approve_collection(ctx: Context<ApproveCollection>) -> Result<()> {
+        ctx.accounts.validate()?;
+        let entry = &mut ctx.accounts.approved_collection;
+        entry.collection = ctx.accounts.collection.key();
+        entry.bump = ctx.bumps.approved_collection;
+
+        emit!(CollectionApproved {
+            collection: entry.collection,
+        });
+
+        Ok(())
+    }
+
+    /// Removes a collection from the curated registry, returning the
+    /// entry's rent to the curator. Auctions already listed are unaffected.
+    pub fn revoke_collection(ctx: Context<RevokeCollection>) -> Result<()> {
+        ctx.accounts.validate()?;
+
+        emit!(CollectionRevoked {
+            collection: ctx.accounts.approved_collection.collection,
+        });
+
+        Ok(())
+    }
+
+    /// Adds a wallet to the blocklist, barring it from `initialize_auction`
+    /// and from placing or raising bids on any path; a proxy it leads with
+    /// stops answering. Bids it already holds are left to settle or be
+    /// refunded as usual. Admin only.
+    pub fn block_wallet(ctx: Context<BlockWallet>) -> Result<()> {
+        ctx.accounts.validate()?;
+        let entry = &mut ctx.accounts.blocklist_entry;
+        entry.wallet = ctx.accounts.wallet.key();
+        entry.blocked_at = Clock::get()?.unix_timestamp;
+        entry.bump = ctx.bumps.blocklist_entry;
+
+        emit!(WalletBlocked {
+            wallet: entry.wallet,
+        });
+
+        Ok(())
+    }
+
+    /// Takes a wallet off the blocklist, returning the entry's rent to the
+    /// admin.
+    pub fn unblock_wallet(ctx: Context<UnblockWallet>) -> Result<()> {
+        ctx.accounts.validate()?;
+
+        emit!(WalletUnblocked {
+            wallet: ctx.accounts.blocklist_entry.wallet,
+        });
+
+        Ok(())
+    }
+
+    /// Replaces the registry of SPL mints auctions may take bids in. Bids in
+    /// a registered mint are escrowed in the auction authority's ATA for that
+    /// mint, and refunds and payouts go to the recipients' ATAs.
+    pub fn set_bid_mints(ctx: Context<SetBidMints>, mints: Vec<Pubkey>) -> Result<()> {
+        ctx.accounts.validate()?;
+        require!(mints.len() <= MAX_BID_MINTS, AuctionError::TooManyCurrencies);
+
+        let config = &mut ctx.accounts.config;
+        config.bid_mints = mints;
+
+        emit!(BidMintsSet {
+            mints: config.bid_mints.clone(),
+        });
+
+        Ok(())
+    }
+
+    /// Replaces the wallets linked to `creator` as known sybils; none of
+    /// them may bid on the creator's auctions. Admin only.
+    pub fn set_creator_links(
+        ctx: Context<SetCreatorLinks>,
+        creator: Pubkey,
+        linked: Vec<Pubkey>,
+    ) -> Result<()> {
+        ctx.accounts.validate()?;
+        require!(
+            linked.len() <= MAX_CREATOR_LINKS,
+            AuctionError::TooManyCreatorLinks
+        );
+
+        let creator_links = &mut ctx.accounts.creator_links;
+        creator_links.creator = creator;
+        creator_links.linked = linked;
+        creator_links.bump = ctx.bumps.creator_links;
+
+        emit!(CreatorLinksSet {
+            creator,
+            linked: creator_links.linked.clone(),
+        });
+
+        Ok(())
+    }
+
+    /// Moves platform fees collected in an SPL mint out of the treasury's
+    /// ATA. Admin only.
+    pub fn withdraw_treasury_fees(ctx: Context<WithdrawTreasuryFees>, amount: u64) -> Result<()> {
+        ctx.accounts.validate()?;
+
+        let signer_seeds: &[&[&[u8]]] = &[&[TREASURY_SEED, &[ctx.bumps.treasury]]];
+        let transfer_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.treasury_token_account.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.destination.to_account_info(),
+                authority: ctx.accounts.treasury.to_account_info(),
+            },
+            signer_seeds,
+        );
+        anchor_spl::token_interface::transfer_checked(transfer_ctx, amount, ctx.accounts.mint.decimals)?;
+
+        emit!(TreasuryFeesWithdrawn {
+            mint: ctx.accounts.mint.key(),
+            destination: ctx.accounts.destination.key(),
+            amount,
+        });
+
+        Ok(())
+    }
+
+    /// Recreates a live auction from a legacy fork's account under the current
+    /// layout. The NFT and any escrowed bid must already sit in this program's
+    /// vault and escrow; the import only activates state once custody checks
+    /// out. A provenance record keyed by the legacy account makes each import
+    /// one-way and non-repeatable.
+    pub fn import_auction(ctx: Context<ImportAuction>) -> Result<()> {
+        ctx.accounts.validate()?;
+        let legacy_info = &ctx.accounts.legacy_auction;
+        require_keys_eq!(
+            *legacy_info.owner,
+            ctx.accounts.legacy_program.key(),
+            AuctionError::InvalidLegacyAccount
+        );
+        let legacy = {
+            let data = legacy_info.try_borrow_data()?;
+            require!(data.len() > 8, AuctionError::InvalidLegacyAccount);
+            LegacyAuction::deserialize(&mut &data[8..])
+                .map_err(|_| error!(AuctionError::InvalidLegacyAccount))?
+        };
+        require_keys_eq!(
+            legacy.nft_mint,
+            ctx.accounts.nft_mint.key(),
+            AuctionError::InvalidLegacyAccount
+        );
+        require!(
+            legacy.status == AuctionStatus::Live,
+            AuctionError::AuctionNotActive
+        );
+        require!(
+            ctx.accounts.vault_nft_account.amount >= 1,
+            AuctionError::LegacyCustodyMissing
+        );
+        require!(
+            ctx.accounts.auction_escrow.lamports() >= legacy.highest_bid,
+            AuctionError::LegacyCustodyMissing
+        );
+
+        let auction = &mut ctx.accounts.auction;
+        auction.creator = legacy.creator;
+        auction.nft_mint = legacy.nft_mint;
+        auction.starting_bid = legacy.starting_bid;
+        auction.min_bid_increment = legacy.min_bid_increment;
+        auction.end_time = legacy.end_time;
+        auction.highest_bid = legacy.highest_bid;
+        auction.highest_bidder = legacy.highest_bidder;
+        auction.status = AuctionStatus::Live;
+        auction.buy_now_price = 0;
+        auction.buy_now_executed = false;
+        auction.bid_mint = Pubkey::default();
+        auction.highest_bid_wrapped = false;
+        auction.stable_preset = false;
+        auction.multi_currency = false;
+        auction.fee_bps = ctx.accounts.config.fee_bps;
+        auction.version = AUCTION_VERSION;
+
+        let clock = Clock::get()?;
+        let provenance = &mut ctx.accounts.provenance;
+        provenance.auction = auction.key();
+        provenance.legacy_program = ctx.accounts.legacy_program.key();
+        provenance.legacy_account = legacy_info.key();
+        provenance.imported_by = ctx.accounts.admin.key();
+        provenance.imported_at = clock.unix_timestamp;
+        provenance.bump = ctx.bumps.provenance;
+
+        emit!(AuctionImported {
+            auction_id: auction.key(),
+            legacy_program: provenance.legacy_program,
+            legacy_account: provenance.legacy_account,
+            imported_by: provenance.imported_by,
+        });
+
+        Ok(())
+    }
+
+    /// Brings an auction created under an older layout up to
+    /// `AUCTION_VERSION`. Accounts shorter than `Auction::LEN` are grown,
+    /// with the payer topping up rent; fields added since read as zero.
+    /// Anyone may call it.
+    pub fn migrate_auction(ctx: Context<MigrateAuction>) -> Result<()> {
+        ctx.accounts.validate()?;
+        let info = ctx.accounts.auction.to_account_info();
+        {
+            let data = info.try_borrow_data()?;
+            require!(
+                data.get(..8) == Some(&Auction::DISCRIMINATOR[..]),
+                AuctionError::InvalidAuctionAccount
+            );
+        }
+
+        if info.data_len() < Auction::LEN {
+            let shortfall = Rent::get()?
+                .minimum_balance(Auction::LEN)
+                .saturating_sub(info.lamports());
+            if shortfall > 0 {
+                let transfer_ctx = CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    anchor_lang::system_program::Transfer {
+                        from: ctx.accounts.payer.to_account_info(),
+                        to: info.clone(),
+                    },
+                );
+                anchor_lang::system_program::transfer(transfer_ctx, shortfall)?;
+            }
+            info.realloc(Auction::LEN, true)?;
+        }
+
+        let mut auction = Auction::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        let previous_version = auction.version;
+        require!(
+            previous_version < AUCTION_VERSION,
+            AuctionError::AuctionUpToDate
+        );
+        auction.backfill(previous_version);
+        auction.version = AUCTION_VERSION;
+        auction.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+
+        emit!(AuctionMigrated {
+            auction_id: info.key(),
+            previous_version,
+            version: AUCTION_VERSION,
+        });
+
+        Ok(())
+    }
+
+    /// Dry-runs the checks `initialize_auction` would apply to `params`, plus
+    /// advisory warnings, without creating or touching any accounts. Every
+    /// finding is reported (not just the first) via return data.
+    pub fn validate_auction_params(
+        ctx: Context<ValidateAuctionParams>,
+        params: AuctionParams,
+    ) -> Result<ParamValidation> {
+        ctx.accounts.validate()?;
+        let bid_mint = ctx.accounts.bid_mint.as_ref().map(|mint| mint.to_account_info());
+        let diagnostics = params.diagnose(bid_mint.as_ref())?;
+        let valid = diagnostics
+            .iter()
+            .all(|diagnostic| diagnostic.severity != Severity::Error);
+        Ok(ParamValidation { valid, diagnostics })
+    }
+
+    /// Freezes the auction's result into an `AuctionResult` PDA once bidding
+    /// has closed, ahead of and independent from settlement: the winner, the
+    /// winning bid and whether it met the reserve. It can be taken only once.
+    /// Anyone may call it.
+    pub fn snapshot_auction(ctx: Context<SnapshotAuction>) -> Result<()> {
+        ctx.accounts.validate()?;
+        let auction = &ctx.accounts.auction;
+        let clock = Clock::get()?;
+
+        require!(auction.is_closed(&clock), AuctionError::AuctionNotEnded);
+        require!(!auction.is_paused(), AuctionError::AuctionPaused);
+        require!(
+            auction.status.can_settle() || auction.status.is_settled(),
+            AuctionError::AuctionNotActive
+        );
+        require!(!auction.has_pending_bid(), AuctionError::BidPending);
+        // Queued closing bids may still change the winner until finalize
+        require!(
+            !auction.closing_queue || auction.status.is_settled(),
+            AuctionError::ResultUndetermined
+        );
+
+        let result = &mut ctx.accounts.result;
+        result.auction = auction.key();
+        result.nft_mint = auction.nft_mint;
+        result.winner = auction.highest_bidder;
+        result.winning_bid = auction.highest_bid;
+        result.bid_mint = auction.settlement_mint();
+        result.reserve_met = !auction.conditional_bid_unmet();
+        result.end_time = auction.end_time;
+        result.taken_at = clock.unix_timestamp;
+        result.slot = clock.slot;
+        result.bump = ctx.bumps.result;
+
+        emit!(AuctionSnapshotted {
+            auction_id: auction.key(),
+            winner: result.winner,
+            winning_bid: result.winning_bid,
+            reserve_met: result.reserve_met,
+        });
+
+        Ok(())
+    }
+
+    /// Final disposition for lots nobody has settled or reclaimed within
+    /// `ESCHEAT_WINDOW` of the end time. The NFT and any escrowed winning bid
+    /// move into platform custody and an `EscheatRecord` captures the full
+    /// state at the time, so operators can run unclaimed-property processes.
+    pub fn escheat_auction<'info>(
+        ctx: Context<'_, '_, '_, 'info, EscheatAuction<'info>>,
+    ) -> Result<()> {
+        ctx.accounts.validate()?;
+        let auction = &mut ctx.accounts.auction;
+        let clock = Clock::get()?;
+
+        require!(auction.status.can_settle(), AuctionError::AuctionNotActive);
+        require!(
+            clock.unix_timestamp >= auction.end_time.saturating_add(ESCHEAT_WINDOW),
+            AuctionError::EscheatWindowNotReached
+        );
+        require!(auction.is_native(), AuctionError::InvalidBidCurrency);
+        require!(!auction.partial_fills, AuctionError::PartialFillAuction);
+
+        release_from_vault(
+            auction.is_programmable,
+            auction.lot_size(),
+            &ctx.accounts.pnft,
+            &ctx.accounts.token_program,
+            &ctx.accounts.vault_nft_account,
+            &ctx.accounts.nft_mint,
+            &ctx.accounts.custody_nft_account,
+            Some(&ctx.accounts.custody_authority),
+            Some(&ctx.accounts.admin.to_account_info()),
+            &ctx.accounts.auction_authority,
+            auction.key(),
+            ctx.bumps.auction_authority,
+            ctx.remaining_accounts,
+        )?;
+
+        if auction.highest_bid > 0 {
+            let auction_key = auction.key();
+            let signer_seeds: &[&[&[u8]]] = &[&[
+                ESCROW_SEED,
+                auction_key.as_ref(),
+                &[ctx.bumps.auction_escrow],
+            ]];
+            let escrow_ctx = CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                anchor_lang::system_program::Transfer {
+                    from: ctx.accounts.auction_escrow.to_account_info(),
+                    to: ctx.accounts.custody_authority.to_account_info(),
+                },
+                signer_seeds,
+            );
+            anchor_lang::system_program::transfer(escrow_ctx, auction.highest_bid)?;
+        }
+
+        set_auction_status(auction, AuctionStatus::Escheated)?;
+
+        let record = &mut ctx.accounts.escheat_record;
+        record.auction = auction.key();
+        record.nft_mint = auction.nft_mint;
+        record.creator = auction.creator;
+        record.highest_bidder = auction.highest_bidder;
+        record.escrowed_amount = auction.highest_bid;
+        record.end_time = auction.end_time;
+        record.escheated_at = clock.unix_timestamp;
+        record.custody_nft_account = ctx.accounts.custody_nft_account.key();
+        record.operator = ctx.accounts.admin.key();
+        record.bump = ctx.bumps.escheat_record;
+
+        emit!(AuctionEscheated {
+            auction_id: auction.key(),
+            nft_mint: record.nft_mint,
+            creator: record.creator,
+            highest_bidder: record.highest_bidder,
+            escrowed_amount: record.escrowed_amount,
+            operator: record.operator,
+        });
+
+        Ok(())
+    }
+
+    /// Lists a compressed NFT. The leaf is moved into the auction authority's
+    /// custody through Bubblegum; the Merkle proof for `root` is passed in
+    /// `remaining_accounts`.
+    pub fn initialize_compressed_auction<'info>(
+        ctx: Context<'_, '_, '_, 'info, InitializeCompressedAuction<'info>>,
+        params: AuctionParams,
+        root: [u8; 32],
+        leaf: bubblegum::LeafArgs,
+    ) -> Result<()> {
+        ctx.accounts.validate()?;
+        params.check()?;
+        let clock = Clock::get()?;
+        let asset_id = bubblegum::asset_id(&ctx.accounts.merkle_tree.key(), leaf.nonce);
+
+        bubblegum::transfer(
+            bubblegum::TransferAccounts {
+                bubblegum_program: &ctx.accounts.bubblegum_program,
+                tree_authority: &ctx.accounts.tree_authority,
+                leaf_owner: &ctx.accounts.creator.to_account_info(),
+                new_leaf_owner: &ctx.accounts.auction_authority,
+                merkle_tree: &ctx.accounts.merkle_tree,
+                log_wrapper: &ctx.accounts.log_wrapper,
+                compression_program: &ctx.accounts.compression_program,
+                system_program: &ctx.accounts.system_program.to_account_info(),
+            },
+            ctx.remaining_accounts,
+            root,
+            &leaf,
+            &[],
+        )?;
+
+        let auction = &mut ctx.accounts.auction;
+        auction.open(
+            ctx.accounts.creator.key(),
+            asset_id,
+            &params,
+            clock.unix_timestamp,
+        );
+        ctx.accounts.config.apply_terms(auction, params.duration)?;
+        auction.is_compressed = true;
+        if let Some(experiment) = &ctx.accounts.experiment {
+            let auction_key = auction.key();
+            auction.join_experiment(&auction_key, experiment);
+        }
+        fund_escrow_reserve(
+            &ctx.accounts.system_program,
+            &ctx.accounts.creator.to_account_info(),
+            &ctx.accounts.auction_escrow,
+            auction,
+        )?;
+
+        let compressed_asset = &mut ctx.accounts.compressed_asset;
+        compressed_asset.merkle_tree = ctx.accounts.merkle_tree.key();
+        compressed_asset.leaf = leaf;
+        compressed_asset.bump = ctx.bumps.compressed_asset;
+
+        emit!(AuctionCreated {
+            auction_id: auction.key(),
+            creator: auction.creator,
+            nft_mint: asset_id,
+            starting_bid: params.starting_bid,
+            end_time: auction.end_time,
+            quantity: 1,
+        });
+
+        Ok(())
+    }
+
+    /// Settles a compressed-NFT auction in SOL. The leaf goes to the winner,
+    /// or back to the creator if nobody bid. The current tree `root` and the
+    /// leaf's proof (in `remaining_accounts`) must be supplied.
+    pub fn finalize_compressed_auction<'info>(
+        ctx: Context<'_, '_, '_, 'info, FinalizeCompressedAuction<'info>>,
+        root: [u8; 32],
+    ) -> Result<SettlementSummary> {
+        ctx.accounts.validate()?;
+        let auction = &mut ctx.accounts.auction;
+        let clock = Clock::get()?;
+
+        require!(auction.is_closed(&clock), AuctionError::AuctionNotEnded);
+        require!(!auction.is_paused(), AuctionError::AuctionPaused);
+        require!(auction.status.can_settle(), AuctionError::AuctionNotActive);
+        require!(auction.is_native(), AuctionError::InvalidBidCurrency);
+
+        let mut summary = SettlementSummary {
+            auction_id: auction.key(),
+            winner: auction.highest_bidder,
+            winning_bid: auction.highest_bid,
+            seller: ctx.accounts.creator.key(),
+            seller_amount: 0,
+            platform_fee_account: ctx.accounts.platform_fee_account.key(),
+            platform_fee: 0,
+            nft_recipient_account: None,
+            keeper_rebate: 0,
+            settler_bounty: 0,
+            royalties: 0,
+        };
+
+        let recipient = if auction.highest_bid > 0 {
+            require_keys_eq!(
+                ctx.accounts.recipient.key(),
+                auction.highest_bidder,
+                AuctionError::InvalidRecipient
+            );
+            let (platform_fee, seller_amount) = split_proceeds(auction.highest_bid, auction.platform_fee_bps());
+
+            // Transfer funds to seller
+            pay_from_native_escrow(
+                &ctx.accounts.system_program,
+                &ctx.accounts.auction_escrow,
+                ctx.bumps.auction_escrow,
+                auction.key(),
+                &ctx.accounts.creator,
+                seller_amount,
+            )?;
+
+            // Transfer platform fee
+            pay_from_native_escrow(
+                &ctx.accounts.system_program,
+                &ctx.accounts.auction_escrow,
+                ctx.bumps.auction_escrow,
+                auction.key(),
+                &ctx.accounts.platform_fee_account,
+                platform_fee,
+            )?;
+
+            summary.seller_amount = seller_amount;
+            summary.platform_fee = platform_fee;
+            set_auction_status(auction, AuctionStatus::Settled)?;
+            auction.highest_bidder
+        } else {
+            require_keys_eq!(
+                ctx.accounts.recipient.key(),
+                auction.creator,
+                AuctionError::InvalidRecipient
+            );
+            set_auction_status(auction, AuctionStatus::Cancelled)?;
+            auction.creator
+        };
+
+        let auction_key = auction.key();
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            AUCTION_SEED,
+            auction_key.as_ref(),
+            &[ctx.bumps.auction_authority],
+        ]];
+        bubblegum::transfer(
+            bubblegum::TransferAccounts {
+                bubblegum_program: &ctx.accounts.bubblegum_program,
+                tree_authority: &ctx.accounts.tree_authority,
+                leaf_owner: &ctx.accounts.auction_authority,
+                new_leaf_owner: &ctx.accounts.recipient,
+                merkle_tree: &ctx.accounts.merkle_tree,
+                log_wrapper: &ctx.accounts.log_wrapper,
+                compression_program: &ctx.accounts.compression_program,
+                system_program: &ctx.accounts.system_program.to_account_info(),
+            },
+            ctx.remaining_accounts,
+            root,
+            &ctx.accounts.compressed_asset.leaf,
+            signer_seeds,
+        )?;
+        summary.nft_recipient_account = Some(recipient);
+
+        // Return the escrow's rent reserve to the creator
+        release_escrow_reserve(
+            &ctx.accounts.system_program,
+            &ctx.accounts.auction_escrow,
+            ctx.bumps.auction_escrow,
+            auction.key(),
+            auction,
+            &ctx.accounts.creator,
+        )?;
+
+        emit!(AuctionFinalized {
+            auction_id: auction.key(),
+            winner: auction.highest_bidder,
+            winning_bid: auction.highest_bid,
+            experiment_id: auction.experiment_id,
+            variant: auction.experiment_variant,
+        });
+
+        Ok(summary)
+    }
+
+    /// Defines (or redefines) the running A/B experiment. New auctions that
+    /// pass the experiment account are split between the two variants by a
+    /// hash of their key; `split_bps` is the share assigned to variant 1.
+    pub fn configure_experiment(
+        ctx: Context<ConfigureExperiment>,
+        id: u32,
+        variants: [ExperimentVariant; 2],
+        split_bps: u16,
+        active: bool,
+    ) -> Result<()> {
+        ctx.accounts.validate()?;
+        require!(id > 0, AuctionError::InvalidExperiment);
+        require!(split_bps <= BPS_DENOMINATOR, AuctionError::InvalidExperiment);
+        for variant in &variants {
+            require!(
+                variant.fee_bps <= BPS_DENOMINATOR
+                    && variant.extension_window >= 0
+                    && variant.extension_amount >= 0,
+                AuctionError::InvalidExperiment
+            );
+        }
+
+        let experiment = &mut ctx.accounts.experiment;
+        experiment.id = id;
+        experiment.variants = variants;
+        experiment.split_bps = split_bps;
+        experiment.active = active;
+        experiment.bump = ctx.bumps.experiment;
+
+        emit!(ExperimentConfigured {
+            id,
+            variants: experiment.variants.clone(),
+            split_bps,
+            active,
+        });
+
+        Ok(())
+    }
+
+    /// Creates or updates the keeper rebate pool. `fee_share_bps` of each
+    /// native platform fee is routed into the pool at settlement, and each
+    /// successful finalize/expire by a registered keeper accrues up to
+    /// `max_rebate` lamports from it.
+    pub fn configure_rebate_pool(
+        ctx: Context<ConfigureRebatePool>,
+        max_rebate: u64,
+        fee_share_bps: u16,
+    ) -> Result<()> {
+        ctx.accounts.validate()?;
+        require!(fee_share_bps <= BPS_DENOMINATOR, AuctionError::InvalidRebateConfig);
+
+        let pool = &mut ctx.accounts.rebate_pool;
+        pool.max_rebate = max_rebate;
+        pool.fee_share_bps = fee_share_bps;
+        pool.bump = ctx.bumps.rebate_pool;
+
+        Ok(())
+    }
+
+    /// Approves a keeper wallet for rebates.
+    pub fn register_keeper(ctx: Context<RegisterKeeper>) -> Result<()> {
+        ctx.accounts.validate()?;
+        let keeper = &mut ctx.accounts.keeper_account;
+        keeper.keeper = ctx.accounts.keeper.key();
+        keeper.bump = ctx.bumps.keeper_account;
+
+        emit!(KeeperRegistered {
+            keeper: keeper.keeper,
+        });
+
+        Ok(())
+    }
+
+    /// Pays out everything a keeper has accrued in one transfer.
+    pub fn claim_keeper_rebates(ctx: Context<ClaimKeeperRebates>) -> Result<()> {
+        ctx.accounts.validate()?;
+        let keeper = &mut ctx.accounts.keeper_account;
+        let amount = keeper.accrued;
+        require!(amount > 0, AuctionError::NothingToClaim);
+
+        let pool_info = ctx.accounts.rebate_pool.to_account_info();
+        let rent_floor = Rent::get()?.minimum_balance(pool_info.data_len());
+        require!(
+            pool_info.lamports().saturating_sub(amount) >= rent_floor,
+            AuctionError::InsufficientPoolFunds
+        );
+        **pool_info.try_borrow_mut_lamports()? -= amount;
+        **ctx.accounts.keeper.to_account_info().try_borrow_mut_lamports()? += amount;
+
+        keeper.accrued = 0;
+        keeper.claimed += amount;
+
+        emit!(KeeperRebatesClaimed {
+            keeper: keeper.keeper,
+            amount,
+        });
+
+        Ok(())
+    }
+
+    /// Permissionless cleanup for an ended auction that drew no bids: the NFT
+    /// is returned to the creator's ATA and the auction is cancelled. A
+    /// registered keeper calling this earns a rebate.
+    pub fn expire_auction<'info>(
+        ctx: Context<'_, '_, '_, 'info, ExpireAuction<'info>>,
+    ) -> Result<()> {
+        ctx.accounts.validate()?;
+        let auction = &mut ctx.accounts.auction;
+        let clock = Clock::get()?;
+
+        require!(auction.is_closed(&clock), AuctionError::AuctionNotEnded);
+        require!(!auction.is_paused(), AuctionError::AuctionPaused);
+        require!(!auction.has_bids(), AuctionError::AuctionHasBids);
+        require!(auction.status.can_settle(), AuctionError::AuctionNotActive);
+
+        // Transfer NFT back to creator
+        release_from_vault(
+            auction.is_programmable,
+            auction.lot_size(),
+            &ctx.accounts.pnft,
+            &ctx.accounts.token_program,
+            &ctx.accounts.vault_nft_account,
+            &ctx.accounts.nft_mint,
+            &ctx.accounts.creator_nft_account,
+            None,
+            None,
+            &ctx.accounts.auction_authority,
+            auction.key(),
+            ctx.bumps.auction_authority,
+            ctx.remaining_accounts,
+        )?;
+
+        set_auction_status(auction, AuctionStatus::Cancelled)?;
+
+        // Return the escrow's rent reserve to the creator
+        release_escrow_reserve(
+            &ctx.accounts.system_program,
+            &ctx.accounts.auction_escrow,
+            ctx.bumps.auction_escrow,
+            auction.key(),
+            auction,
+            &ctx.accounts.creator,
+        )?;
+
+        if let (Some(pool), Some(keeper)) = (
+            ctx.accounts.rebate_pool.as_mut(),
+            ctx.accounts.keeper_account.as_mut(),
+        ) {
+            credit_keeper_rebate(pool, keeper, auction.key())?;
+        }
+
+        emit!(AuctionCancelled {
+            auction_id: auction.key(),
+            reason: "Expired with no bids".to_string(),
+        });
+
+        Ok(())
+    }
+
+    /// Single CPI surface for aggregators. `action` selects the underlying
+    /// instruction, and `remaining_accounts` must hold exactly that
+    /// instruction's accounts in the order of its accounts struct
+    /// (`InitializeAuction`, `PlaceBid`, `BuyNow` or `FinalizeAuction`),
+    /// followed by any extra accounts it forwards (e.g. transfer-hook
+    /// accounts). Settlement summaries from `Finalize` are passed through.
+    pub fn route<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Route>,
+        action: RouteAction,
+    ) -> Result<Option<SettlementSummary>> {
+        let program_id = ctx.program_id;
+        let accounts = ctx.remaining_accounts;
+        match action {
+            RouteAction::Create {
+                starting_bid,
+                min_bid_increment,
+                duration,
+                buy_now_price,
+                quantity,
+                required_collection,
+            } => dispatch_route(program_id, accounts, |ctx: Context<InitializeAuction>| {
+                initialize_auction(
+                    ctx,
+                    starting_bid,
+                    min_bid_increment,
+                    duration,
+                    buy_now_price,
+                    quantity,
+                    required_collection,
+                )
+            })
+            .map(|_| None),
+            RouteAction::Bid {
+                amount,
+                allowlist_proof,
+            } => dispatch_route(program_id, accounts, |ctx: Context<PlaceBid>| {
+                place_bid(ctx, amount, allowlist_proof)
+            })
+            .map(|_| None),
+            RouteAction::BuyNow => {
+                dispatch_route(program_id, accounts, |ctx: Context<BuyNow>| buy_now(ctx)).map(|_| None)
+            }
+            RouteAction::Finalize => dispatch_route(program_id, accounts, |ctx: Context<FinalizeAuction>| {
+                finalize_auction(ctx)
+            })
+            .map(Some),
+        }
+    }
+
+    /// Lists a Metaplex Core asset. The asset's owner becomes the auction
+    /// authority PDA; bids are taken in SOL as for any other auction.
+    pub fn initialize_core_auction(
+        ctx: Context<InitializeCoreAuction>,
+        params: AuctionParams,
+    ) -> Result<()> {
+        ctx.accounts.validate()?;
+        params.check()?;
+        let clock = Clock::get()?;
+        require_keys_eq!(
+            mpl_core::owner(&ctx.accounts.asset)?,
+            ctx.accounts.creator.key(),
+            AuctionError::Unauthorized
+        );
+
+        let creator = ctx.accounts.creator.to_account_info();
+        mpl_core::transfer(
+            mpl_core::TransferAccounts {
+                core_program: &ctx.accounts.core_program,
+                asset: &ctx.accounts.asset,
+                collection: ctx.accounts.collection.as_deref(),
+                payer: &creator,
+                authority: &creator,
+                new_owner: &ctx.accounts.auction_authority,
+                system_program: &ctx.accounts.system_program.to_account_info(),
+            },
+            &[],
+        )?;
+
+        let auction = &mut ctx.accounts.auction;
+        auction.open(
+            ctx.accounts.creator.key(),
+            ctx.accounts.asset.key(),
+            &params,
+            clock.unix_timestamp,
+        );
+        ctx.accounts.config.apply_terms(auction, params.duration)?;
+        auction.asset_kind = AssetKind::Core;
+        if let Some(experiment) = &ctx.accounts.experiment {
+            let auction_key = auction.key();
+            auction.join_experiment(&auction_key, experiment);
+        }
+        fund_escrow_reserve(
+            &ctx.accounts.system_program,
+            &ctx.accounts.creator.to_account_info(),
+            &ctx.accounts.auction_escrow,
+            auction,
+        )?;
+
+        emit!(AuctionCreated {
+            auction_id: auction.key(),
+            creator: auction.creator,
+            nft_mint: auction.nft_mint,
+            starting_bid: params.starting_bid,
+            end_time: auction.end_time,
+            quantity: 1,
+        });
+
+        Ok(())
+    }
+
+    /// Settles a Core-asset auction in SOL. The asset goes to the winner, or
+    /// back to the creator if nobody bid.
+    pub fn finalize_core_auction(ctx: Context<FinalizeCoreAuction>) -> Result<SettlementSummary> {
+        ctx.accounts.validate()?;
+        let auction = &mut ctx.accounts.auction;
+        let clock = Clock::get()?;
+
+        require!(auction.is_closed(&clock), AuctionError::AuctionNotEnded);
+        require!(!auction.is_paused(), AuctionError::AuctionPaused);
+        require!(auction.status.can_settle(), AuctionError::AuctionNotActive);
+        require!(
+            auction.asset_kind == AssetKind::Core,
+            AuctionError::InvalidAsset
+        );
+        require!(auction.is_native(), AuctionError::InvalidBidCurrency);
+
+        let mut summary = SettlementSummary {
+            auction_id: auction.key(),
+            winner: auction.highest_bidder,
+            winning_bid: auction.highest_bid,
+            seller: ctx.accounts.creator.key(),
+            seller_amount: 0,
+            platform_fee_account: ctx.accounts.platform_fee_account.key(),
+            platform_fee: 0,
+            nft_recipient_account: None,
+            keeper_rebate: 0,
+            settler_bounty: 0,
+            royalties: 0,
+        };
+
+        let recipient = if auction.highest_bid > 0 {
+            require_keys_eq!(
+                ctx.accounts.recipient.key(),
+                auction.highest_bidder,
+                AuctionError::InvalidRecipient
+            );
+            let (platform_fee, seller_amount) = split_proceeds(auction.highest_bid, auction.platform_fee_bps());
+
+            // Transfer funds to seller
+            pay_from_native_escrow(
+                &ctx.accounts.system_program,
+                &ctx.accounts.auction_escrow,
+                ctx.bumps.auction_escrow,
+                auction.key(),
+                &ctx.accounts.creator,
+                seller_amount,
+            )?;
+
+            // Transfer platform fee
+            pay_from_native_escrow(
+                &ctx.accounts.system_program,
+                &ctx.accounts.auction_escrow,
+                ctx.bumps.auction_escrow,
+                auction.key(),
+                &ctx.accounts.platform_fee_account,
+                platform_fee,
+            )?;
+
+            summary.seller_amount = seller_amount;
+            summary.platform_fee = platform_fee;
+            set_auction_status(auction, AuctionStatus::Settled)?;
+            auction.highest_bidder
+        } else {
+            require_keys_eq!(
+                ctx.accounts.recipient.key(),
+                auction.creator,
+                AuctionError::InvalidRecipient
+            );
+            set_auction_status(auction, AuctionStatus::Cancelled)?;
+            auction.creator
+        };
+
+        let auction_key = auction.key();
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            AUCTION_SEED,
+            auction_key.as_ref(),
+            &[ctx.bumps.auction_authority],
+        ]];
+        mpl_core::transfer(
+            mpl_core::TransferAccounts {
+                core_program: &ctx.accounts.core_program,
+                asset: &ctx.accounts.asset,
+                collection: ctx.accounts.collection.as_deref(),
+                payer: &ctx.accounts.auction_authority,
+                authority: &ctx.accounts.auction_authority,
+                new_owner: &ctx.accounts.recipient,
+                system_program: &ctx.accounts.system_program.to_account_info(),
+            },
+            signer_seeds,
+        )?;
+        summary.nft_recipient_account = Some(recipient);
+
+        // Return the escrow's rent reserve to the creator
+        release_escrow_reserve(
+            &ctx.accounts.system_program,
+            &ctx.accounts.auction_escrow,
+            ctx.bumps.auction_escrow,
+            auction.key(),
+            auction,
+            &ctx.accounts.creator,
+        )?;
+
+        emit!(AuctionFinalized {
+            auction_id: auction.key(),
+            winner: auction.highest_bidder,
+            winning_bid: auction.highest_bid,
+            experiment_id: auction.experiment_id,
+            variant: auction.experiment_variant,
+        });
+
+        Ok(summary)
+    }
+
+    /// Lists a `.sol` domain. Ownership of the name record is handed to the
+    /// auction authority through the name service; bids are in SOL.
+    pub fn initialize_domain_auction(
+        ctx: Context<InitializeDomainAuction>,
+        params: AuctionParams,
+    ) -> Result<()> {
+        ctx.accounts.validate()?;
+        params.check()?;
+        let clock = Clock::get()?;
+        require_keys_eq!(
+            sns::domain_owner(&ctx.accounts.name_account)?,
+            ctx.accounts.creator.key(),
+            AuctionError::Unauthorized
+        );
+
+        sns::transfer(
+            &ctx.accounts.name_service_program,
+            &ctx.accounts.name_account,
+            &ctx.accounts.creator.to_account_info(),
+            ctx.accounts.auction_authority.key(),
+            &[],
+        )?;
+
+        let auction = &mut ctx.accounts.auction;
+        auction.open(
+            ctx.accounts.creator.key(),
+            ctx.accounts.name_account.key(),
+            &params,
+            clock.unix_timestamp,
+        );
+        ctx.accounts.config.apply_terms(auction, params.duration)?;
+        auction.asset_kind = AssetKind::Domain;
+        if let Some(experiment) = &ctx.accounts.experiment {
+            let auction_key = auction.key();
+            auction.join_experiment(&auction_key, experiment);
+        }
+        fund_escrow_reserve(
+            &ctx.accounts.system_program,
+            &ctx.accounts.creator.to_account_info(),
+            &ctx.accounts.auction_escrow,
+            auction,
+        )?;
+
+        emit!(AuctionCreated {
+            auction_id: auction.key(),
+            creator: auction.creator,
+            nft_mint: auction.nft_mint,
+            starting_bid: params.starting_bid,
+            end_time: auction.end_time,
+            quantity: 1,
+        });
+
+        Ok(())
+    }
+
+    /// Settles a domain auction in SOL. The name record goes to the winner,
+    /// or back to the creator if nobody bid.
+    pub fn finalize_domain_auction(
+        ctx: Context<FinalizeDomainAuction>,
+    ) -> Result<SettlementSummary> {
+        ctx.accounts.validate()?;
+        let auction = &mut ctx.accounts.auction;
+        let clock = Clock::get()?;
+
+        require!(auction.is_closed(&clock), AuctionError::AuctionNotEnded);
+        require!(!auction.is_paused(), AuctionError::AuctionPaused);
+        require!(auction.status.can_settle(), AuctionError::AuctionNotActive);
+        require!(
+            auction.asset_kind == AssetKind::Domain,
+            AuctionError::InvalidAsset
+        );
+        require!(auction.is_native(), AuctionError::InvalidBidCurrency);
+
+        let mut summary = SettlementSummary {
+            auction_id: auction.key(),
+            winner: auction.highest_bidder,
+            winning_bid: auction.highest_bid,
+            seller: ctx.accounts.creator.key(),
+            seller_amount: 0,
+            platform_fee_account: ctx.accounts.platform_fee_account.key(),
+            platform_fee: 0,
+            nft_recipient_account: None,
+            keeper_rebate: 0,
+            settler_bounty: 0,
+            royalties: 0,
+        };
+
+        let recipient = if auction.highest_bid > 0 {
+            let (platform_fee, seller_amount) = split_proceeds(auction.highest_bid, auction.platform_fee_bps());
+
+            // Transfer funds to seller
+            pay_from_native_escrow(
+                &ctx.accounts.system_program,
+                &ctx.accounts.auction_escrow,
+                ctx.bumps.auction_escrow,
+                auction.key(),
+                &ctx.accounts.creator,
+                seller_amount,
+            )?;
+
+            // Transfer platform fee
+            pay_from_native_escrow(
+                &ctx.accounts.system_program,
+                &ctx.accounts.auction_escrow,
+                ctx.bumps.auction_escrow,
+                auction.key(),
+                &ctx.accounts.platform_fee_account,
+                platform_fee,
+            )?;
+
+            summary.seller_amount = seller_amount;
+            summary.platform_fee = platform_fee;
+            set_auction_status(auction, AuctionStatus::Settled)?;
+            auction.highest_bidder
+        } else {
+            set_auction_status(auction, AuctionStatus::Cancelled)?;
+            auction.creator
+        };
+
+        let auction_key = auction.key();
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            AUCTION_SEED,
+            auction_key.as_ref(),
+            &[ctx.bumps.auction_authority],
+        ]];
+        sns::transfer(
+            &ctx.accounts.name_service_program,
+            &ctx.accounts.name_account,
+            &ctx.accounts.auction_authority,
+            recipient,
+            signer_seeds,
+        )?;
+        summary.nft_recipient_account = Some(recipient);
+
+        // Return the escrow's rent reserve to the creator
+        release_escrow_reserve(
+            &ctx.accounts.system_program,
+            &ctx.accounts.auction_escrow,
+            ctx.bumps.auction_escrow,
+            auction.key(),
+            auction,
+            &ctx.accounts.creator,
+        )?;
+
+        emit!(AuctionFinalized {
+            auction_id: auction.key(),
+            winner: auction.highest_bidder,
+            winning_bid: auction.highest_bid,
+            experiment_id: auction.experiment_id,
+            variant: auction.experiment_variant,
+        });
+
+        Ok(summary)
+    }
+
+    /// Lists a whole token account, e.g. an LP position. Ownership of the
+    /// account is handed to the auction authority instead of moving its
+    /// tokens, so illiquid positions can be sold as-is; bids are in SOL.
+    pub fn initialize_token_account_auction(
+        ctx: Context<InitializeTokenAccountAuction>,
+        params: AuctionParams,
+    ) -> Result<()> {
+        ctx.accounts.validate()?;
+        params.check()?;
+        let clock = Clock::get()?;
+
+        set_account_owner(
+            &ctx.accounts.token_program,
+            &ctx.accounts.position,
+            &ctx.accounts.creator.to_account_info(),
+            ctx.accounts.auction_authority.key(),
+            &[],
+        )?;
+
+        let auction = &mut ctx.accounts.auction;
+        auction.open(
+            ctx.accounts.creator.key(),
+            ctx.accounts.position.key(),
+            &params,
+            clock.unix_timestamp,
+        );
+        ctx.accounts.config.apply_terms(auction, params.duration)?;
+        auction.asset_kind = AssetKind::TokenAccount;
+        if let Some(experiment) = &ctx.accounts.experiment {
+            let auction_key = auction.key();
+            auction.join_experiment(&auction_key, experiment);
+        }
+        fund_escrow_reserve(
+            &ctx.accounts.system_program,
+            &ctx.accounts.creator.to_account_info(),
+            &ctx.accounts.auction_escrow,
+            auction,
+        )?;
+
+        emit!(AuctionCreated {
+            auction_id: auction.key(),
+            creator: auction.creator,
+            nft_mint: auction.nft_mint,
+            starting_bid: params.starting_bid,
+            end_time: auction.end_time,
+            quantity: 1,
+        });
+
+        Ok(())
+    }
+
+    /// Settles a token-account auction in SOL. Ownership of the account goes
+    /// to the winner, or back to the creator if nobody bid.
+    pub fn finalize_token_account_auction(
+        ctx: Context<FinalizeTokenAccountAuction>,
+    ) -> Result<SettlementSummary> {
+        ctx.accounts.validate()?;
+        let auction = &mut ctx.accounts.auction;
+        let clock = Clock::get()?;
+
+        require!(auction.is_closed(&clock), AuctionError::AuctionNotEnded);
+        require!(!auction.is_paused(), AuctionError::AuctionPaused);
+        require!(auction.status.can_settle(), AuctionError::AuctionNotActive);
+        require!(
+            auction.asset_kind == AssetKind::TokenAccount,
+            AuctionError::InvalidAsset
+        );
+        require!(auction.is_native(), AuctionError::InvalidBidCurrency);
+
+        let mut summary = SettlementSummary {
+            auction_id: auction.key(),
+            winner: auction.highest_bidder,
+            winning_bid: auction.highest_bid,
+            seller: ctx.accounts.creator.key(),
+            seller_amount: 0,
+            platform_fee_account: ctx.accounts.platform_fee_account.key(),
+            platform_fee: 0,
+            nft_recipient_account: None,
+            keeper_rebate: 0,
+            settler_bounty: 0,
+            royalties: 0,
+        };
+
+        let recipient = if auction.highest_bid > 0 {
+            require_keys_eq!(
+                ctx.accounts.recipient.key(),
+                auction.highest_bidder,
+                AuctionError::InvalidRecipient
+            );
+            let (platform_fee, seller_amount) = split_proceeds(auction.highest_bid, auction.platform_fee_bps());
+
+            // Transfer funds to seller
+            pay_from_native_escrow(
+                &ctx.accounts.system_program,
+                &ctx.accounts.auction_escrow,
+                ctx.bumps.auction_escrow,
+                auction.key(),
+                &ctx.accounts.creator,
+                seller_amount,
+            )?;
+
+            // Transfer platform fee
+            pay_from_native_escrow(
+                &ctx.accounts.system_program,
+                &ctx.accounts.auction_escrow,
+                ctx.bumps.auction_escrow,
+                auction.key(),
+                &ctx.accounts.platform_fee_account,
+                platform_fee,
+            )?;
+
+            summary.seller_amount = seller_amount;
+            summary.platform_fee = platform_fee;
+            set_auction_status(auction, AuctionStatus::Settled)?;
+            auction.highest_bidder
+        } else {
+            require_keys_eq!(
+                ctx.accounts.recipient.key(),
+                auction.creator,
+                AuctionError::InvalidRecipient
+            );
+            set_auction_status(auction, AuctionStatus::Cancelled)?;
+            auction.creator
+        };
+
+        let auction_key = auction.key();
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            AUCTION_SEED,
+            auction_key.as_ref(),
+            &[ctx.bumps.auction_authority],
+        ]];
+        set_account_owner(
+            &ctx.accounts.token_program,
+            &ctx.accounts.position,
+            &ctx.accounts.auction_authority,
+            recipient,
+            signer_seeds,
+        )?;
+        summary.nft_recipient_account = Some(ctx.accounts.position.key());
+
+        // Return the escrow's rent reserve to the creator
+        release_escrow_reserve(
+            &ctx.accounts.system_program,
+            &ctx.accounts.auction_escrow,
+            ctx.bumps.auction_escrow,
+            auction.key(),
+            auction,
+            &ctx.accounts.creator,
+        )?;
+
+        emit!(AuctionFinalized {
+            auction_id: auction.key(),
+            winner: auction.highest_bidder,
+            winning_bid: auction.highest_bid,
+            experiment_id: auction.experiment_id,
+            variant: auction.experiment_variant,
+        });
+
+        Ok(summary)
+    }
+
+    /// Lists an NFT without moving it. The auction authority is approved as
+    /// delegate of the seller's token account and freezes it in place through
+    /// Token Metadata, as staking locks do; the NFT is only pulled at
+    /// settlement. Bids are in SOL.
+    pub fn initialize_delegated_auction(
+        ctx: Context<InitializeDelegatedAuction>,
+        params: AuctionParams,
+    ) -> Result<()> {
+        ctx.accounts.validate()?;
+        params.check()?;
+        let clock = Clock::get()?;
+        check_relist_cooldown(&ctx.accounts.mint_cooldown, clock.unix_timestamp)?;
+
+        let approve_ctx = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            anchor_spl::token::Approve {
+                to: ctx.accounts.creator_nft_account.to_account_info(),
+                delegate: ctx.accounts.auction_authority.to_account_info(),
+                authority: ctx.accounts.creator.to_account_info(),
+            },
+        );
+        anchor_spl::token::approve(approve_ctx, 1)?;
+
+        let auction_key = ctx.accounts.auction.key();
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            AUCTION_SEED,
+            auction_key.as_ref(),
+            &[ctx.bumps.auction_authority],
+        ]];
+        token_metadata::freeze_delegated_account(
+            ctx.accounts.delegated_freeze_accounts(),
+            signer_seeds,
+        )?;
+
+        let auction = &mut ctx.accounts.auction;
+        auction.open(
+            ctx.accounts.creator.key(),
+            ctx.accounts.nft_mint.key(),
+            &params,
+            clock.unix_timestamp,
+        );
+        ctx.accounts.config.apply_terms(auction, params.duration)?;
+        auction.asset_kind = AssetKind::Delegated;
+        auction.seller_token_account = ctx.accounts.creator_nft_account.key();
+        if let Some(experiment) = &ctx.accounts.experiment {
+            auction.join_experiment(&auction_key, experiment);
+        }
+        fund_escrow_reserve(
+            &ctx.accounts.system_program,
+            &ctx.accounts.creator.to_account_info(),
+            &ctx.accounts.auction_escrow,
+            auction,
+        )?;
+
+        emit!(AuctionCreated {
+            auction_id: auction.key(),
+            creator: auction.creator,
+            nft_mint: auction.nft_mint,
+            starting_bid: params.starting_bid,
+            end_time: auction.end_time,
+            quantity: 1,
+        });
+
+        Ok(())
+    }
+
+    /// Settles a delegated-custody or escrowless auction in SOL. The seller's
+    /// token account is thawed if it was frozen and, if there was a winner,
+    /// the NFT is pulled from it with the auction authority's delegation.
+    pub fn finalize_delegated_auction(
+        ctx: Context<FinalizeDelegatedAuction>,
+    ) -> Result<SettlementSummary> {
+        ctx.accounts.validate()?;
+        let clock = Clock::get()?;
+        let auction_key = ctx.accounts.auction.key();
+        {
+            let auction = &ctx.accounts.auction;
+            require!(auction.is_closed(&clock), AuctionError::AuctionNotEnded);
+            require!(!auction.is_paused(), AuctionError::AuctionPaused);
+            require!(auction.status.can_settle(), AuctionError::AuctionNotActive);
+            require!(
+                matches!(auction.asset_kind, AssetKind::Delegated | AssetKind::Escrowless),
+                AuctionError::InvalidAsset
+            );
+            require!(auction.is_native(), AuctionError::InvalidBidCurrency);
+        }
+
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            AUCTION_SEED,
+            auction_key.as_ref(),
+            &[ctx.bumps.auction_authority],
+        ]];
+        if ctx.accounts.auction.asset_kind == AssetKind::Delegated {
+            token_metadata::thaw_delegated_account(
+                ctx.accounts.delegated_freeze_accounts(),
+                signer_seeds,
+            )?;
+        }
+
+        let auction = &mut ctx.accounts.auction;
+        let mut summary = SettlementSummary {
+            auction_id: auction_key,
+            winner: auction.highest_bidder,
+            winning_bid: auction.highest_bid,
+            seller: ctx.accounts.creator.key(),
+            seller_amount: 0,
+            platform_fee_account: ctx.accounts.platform_fee_account.key(),
+            platform_fee: 0,
+            nft_recipient_account: None,
+            keeper_rebate: 0,
+            settler_bounty: 0,
+            royalties: 0,
+        };
+
+        if auction.highest_bid > 0 {
+            let winner_nft_account = ctx
+                .accounts
+                .winner_nft_account
+                .as_ref()
+                .ok_or(AuctionError::InvalidRecipient)?;
+            require_keys_eq!(
+                winner_nft_account.owner,
+                auction.highest_bidder,
+                AuctionError::InvalidRecipient
+            );
+            let (platform_fee, seller_amount) = split_proceeds(auction.highest_bid, auction.platform_fee_bps());
+
+            // Transfer funds to seller
+            pay_from_native_escrow(
+                &ctx.accounts.system_program,
+                &ctx.accounts.auction_escrow,
+                ctx.bumps.auction_escrow,
+                auction_key,
+                &ctx.accounts.creator,
+                seller_amount,
+            )?;
+
+            // Pull the NFT from the seller to the winner
+            let transfer_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                anchor_spl::token::TransferChecked {
+                    from: ctx.accounts.creator_nft_account.to_account_info(),
+                    mint: ctx.accounts.nft_mint.to_account_info(),
+                    to: winner_nft_account.to_account_info(),
+                    authority: ctx.accounts.auction_authority.to_account_info(),
+                },
+                signer_seeds,
+            );
+            anchor_spl::token::transfer_checked(transfer_ctx, 1, ctx.accounts.nft_mint.decimals)?;
+
+            // Transfer platform fee
+            pay_from_native_escrow(
+                &ctx.accounts.system_program,
+                &ctx.accounts.auction_escrow,
+                ctx.bumps.auction_escrow,
+                auction_key,
+                &ctx.accounts.platform_fee_account,
+                platform_fee,
+            )?;
+
+            summary.seller_amount = seller_amount;
+            summary.platform_fee = platform_fee;
+            summary.nft_recipient_account = Some(winner_nft_account.key());
+            set_auction_status(auction, AuctionStatus::Settled)?;
+        } else {
+            set_auction_status(auction, AuctionStatus::Cancelled)?;
+        }
+
+        // Return the escrow's rent reserve to the creator
+        release_escrow_reserve(
+            &ctx.accounts.system_program,
+            &ctx.accounts.auction_escrow,
+            ctx.bumps.auction_escrow,
+            auction_key,
+            auction,
+            &ctx.accounts.creator,
+        )?;
+
+        emit!(AuctionFinalized {
+            auction_id: auction_key,
+            winner: auction.highest_bidder,
+            winning_bid: auction.highest_bid,
+            experiment_id: auction.experiment_id,
+            variant: auction.experiment_variant,
+        });
+
+        Ok(summary)
+    }
+
+    /// Lists several compressed NFTs from one tree as a single lot. Each leaf
+    /// is moved into custody with its own proof; the proofs are passed back
+    /// to back in `remaining_accounts`, `proof_lens[i]` nodes for `leaves[i]`.
+    /// The tree's changelog buffer must be able to absorb the bundle, since
+    /// every proof is checked against the same `root`.
+    pub fn initialize_compressed_bundle_auction<'info>(
+        ctx: Context<'_, '_, '_, 'info, InitializeCompressedBundleAuction<'info>>,
+        params: AuctionParams,
+        root: [u8; 32],
+        leaves: Vec<bubblegum::LeafArgs>,
+        proof_lens: Vec<u8>,
+    ) -> Result<()> {
+        ctx.accounts.validate()?;
+        params.check()?;
+        let clock = Clock::get()?;
+        require!(
+            !leaves.is_empty() && leaves.len() <= MAX_BUNDLE_LEAVES,
+            AuctionError::InvalidQuantity
+        );
+        require!(leaves.len() == proof_lens.len(), AuctionError::InvalidProofs);
+
+        let proofs = bubblegum::split_proofs(ctx.remaining_accounts, &proof_lens)?;
+        for (leaf, proof) in leaves.iter().zip(proofs) {
+            bubblegum::transfer(
+                bubblegum::TransferAccounts {
+                    bubblegum_program: &ctx.accounts.bubblegum_program,
+                    tree_authority: &ctx.accounts.tree_authority,
+                    leaf_owner: &ctx.accounts.creator.to_account_info(),
+                    new_leaf_owner: &ctx.accounts.auction_authority,
+                    merkle_tree: &ctx.accounts.merkle_tree,
+                    log_wrapper: &ctx.accounts.log_wrapper,
+                    compression_program: &ctx.accounts.compression_program,
+                    system_program: &ctx.accounts.system_program.to_account_info(),
+                },
+                proof,
+                root,
+                leaf,
+                &[],
+            )?;
+        }
+
+        let auction = &mut ctx.accounts.auction;
+        auction.open(
+            ctx.accounts.creator.key(),
+            ctx.accounts.bundle.key(),
+            &params,
+            clock.unix_timestamp,
+        );
+        ctx.accounts.config.apply_terms(auction, params.duration)?;
+        auction.is_compressed = true;
+        auction.quantity = leaves.len() as u64;
+        if let Some(experiment) = &ctx.accounts.experiment {
+            let auction_key = auction.key();
+            auction.join_experiment(&auction_key, experiment);
+        }
+        fund_escrow_reserve(
+            &ctx.accounts.system_program,
+            &ctx.accounts.creator.to_account_info(),
+            &ctx.accounts.auction_escrow,
+            auction,
+        )?;
+
+        let bundle = &mut ctx.accounts.bundle;
+        bundle.merkle_tree = ctx.accounts.merkle_tree.key();
+        bundle.leaves = leaves;
+        bundle.bump = ctx.bumps.bundle;
+
+        emit!(AuctionCreated {
+            auction_id: auction.key(),
+            creator: auction.creator,
+            nft_mint: auction.nft_mint,
+            starting_bid: params.starting_bid,
+            end_time: auction.end_time,
+            quantity: auction.quantity,
+        });
+
+        Ok(())
+    }
+
+    /// Settles a compressed bundle in SOL. Every leaf goes to the winner, or
+    /// back to the creator if nobody bid; proofs against the current `root`
+    /// are passed as in [`initialize_compressed_bundle_auction`].
+    pub fn finalize_compressed_bundle_auction<'info>(
+        ctx: Context<'_, '_, '_, 'info, FinalizeCompressedBundleAuction<'info>>,
+        root: [u8; 32],
+        proof_lens: Vec<u8>,
+    ) -> Result<SettlementSummary> {
+        ctx.accounts.validate()?;
+        let auction = &mut ctx.accounts.auction;
+        let clock = Clock::get()?;
+
+        require!(auction.is_closed(&clock), AuctionError::AuctionNotEnded);
+        require!(!auction.is_paused(), AuctionError::AuctionPaused);
+        require!(auction.status.can_settle(), AuctionError::AuctionNotActive);
+        require!(auction.is_native(), AuctionError::InvalidBidCurrency);
+        let leaves = &ctx.accounts.bundle.leaves;
+        require!(leaves.len() == proof_lens.len(), AuctionError::InvalidProofs);
+        let proofs = bubblegum::split_proofs(ctx.remaining_accounts, &proof_lens)?;
+
+        let mut summary = SettlementSummary {
+            auction_id: auction.key(),
+            winner: auction.highest_bidder,
+            winning_bid: auction.highest_bid,
+            seller: ctx.accounts.creator.key(),
+            seller_amount: 0,
+            platform_fee_account: ctx.accounts.platform_fee_account.key(),
+            platform_fee: 0,
+            nft_recipient_account: None,
+            keeper_rebate: 0,
+            settler_bounty: 0,
+            royalties: 0,
+        };
+
+        let recipient = if auction.highest_bid > 0 {
+            require_keys_eq!(
+                ctx.accounts.recipient.key(),
+                auction.highest_bidder,
+                AuctionError::InvalidRecipient
+            );
+            let (platform_fee, seller_amount) = split_proceeds(auction.highest_bid, auction.platform_fee_bps());
+
+            // Transfer funds to seller
+            pay_from_native_escrow(
+                &ctx.accounts.system_program,
+                &ctx.accounts.auction_escrow,
+                ctx.bumps.auction_escrow,
+                auction.key(),
+                &ctx.accounts.creator,
+                seller_amount,
+            )?;
+
+            // Transfer platform fee
+            pay_from_native_escrow(
+                &ctx.accounts.system_program,
+                &ctx.accounts.auction_escrow,
+                ctx.bumps.auction_escrow,
+                auction.key(),
+                &ctx.accounts.platform_fee_account,
+                platform_fee,
+            )?;
+
+            summary.seller_amount = seller_amount;
+            summary.platform_fee = platform_fee;
+            set_auction_status(auction, AuctionStatus::Settled)?;
+            auction.highest_bidder
+        } else {
+            require_keys_eq!(
+                ctx.accounts.recipient.key(),
+                auction.creator,
+                AuctionError::InvalidRecipient
+            );
+            set_auction_status(auction, AuctionStatus::Cancelled)?;
+            auction.creator
+        };
+
+        let auction_key = auction.key();
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            AUCTION_SEED,
+            auction_key.as_ref(),
+            &[ctx.bumps.auction_authority],
+        ]];
+        for (leaf, proof) in leaves.iter().zip(proofs) {
+            bubblegum::transfer(
+                bubblegum::TransferAccounts {
+                    bubblegum_program: &ctx.accounts.bubblegum_program,
+                    tree_authority: &ctx.accounts.tree_authority,
+                    leaf_owner: &ctx.accounts.auction_authority,
+                    new_leaf_owner: &ctx.accounts.recipient,
+                    merkle_tree: &ctx.accounts.merkle_tree,
+                    log_wrapper: &ctx.accounts.log_wrapper,
+                    compression_program: &ctx.accounts.compression_program,
+                    system_program: &ctx.accounts.system_program.to_account_info(),
+                },
+                proof,
+                root,
+                leaf,
+                signer_seeds,
+            )?;
+        }
+        summary.nft_recipient_account = Some(recipient);
+
+        // Return the escrow's rent reserve to the creator
+        release_escrow_reserve(
+            &ctx.accounts.system_program,
+            &ctx.accounts.auction_escrow,
+            ctx.bumps.auction_escrow,
+            auction.key(),
+            auction,
+            &ctx.accounts.creator,
+        )?;
+
+        emit!(AuctionFinalized {
+            auction_id: auction.key(),
+            winner: auction.highest_bidder,
+            winning_bid: auction.highest_bid,
+            experiment_id: auction.experiment_id,
+            variant: auction.experiment_variant,
+        });
+
+        Ok(summary)
+    }
+
+    /// Turns a quantity auction into a partial-fill auction: bidders ask for
+    /// part of the lot at a price per unit, and settlement fills the highest
+    /// unit prices first until the lot runs out. Must be set before any bid.
+    pub fn enable_partial_fills(ctx: Context<EnablePartialFills>) -> Result<()> {
+        ctx.accounts.validate()?;
+        let auction = &mut ctx.accounts.auction;
+
+        require!(
+            auction.status == AuctionStatus::Live,
+            AuctionError::AuctionNotActive
+        );
+        require!(!auction.has_bids(), AuctionError::AuctionHasBids);
+        require_keys_eq!(
+            auction.creator,
+            ctx.accounts.creator.key(),
+            AuctionError::UnauthorizedUpdate
+        );
+        require!(auction.is_native(), AuctionError::InvalidBidCurrency);
+        require!(
+            auction.asset_kind == AssetKind::Spl
+                && !auction.is_compressed
+                && !auction.is_programmable
+                && auction.lot_size() > 1,
+            AuctionError::InvalidQuantity
+        );
+
+        auction.partial_fills = true;
+        let book = &mut ctx.accounts.book;
+        book.auction = auction.key();
+        book.entries = Vec::new();
+        book.bump = ctx.bumps.book;
+
+        Ok(())
+    }
+
+    /// Bids `unit_price` lamports per unit for `quantity` units of a
+    /// partial-fill lot, escrowing the full amount. When the book is full the
+    /// bid must beat the lowest unit price, whose bidder is refunded.
+    pub fn place_partial_bid(
+        ctx: Context<PlacePartialBid>,
+        unit_price: u64,
+        quantity: u64,
+    ) -> Result<()> {
+        ctx.accounts.validate()?;
+        check_not_blocked(&ctx.accounts.bidder_blocklist_entry)?;
+        let auction = &mut ctx.accounts.auction;
+        let book = &mut ctx.accounts.book;
+        let clock = Clock::get()?;
+
+        require_independent_bidder(
+            auction,
+            &ctx.accounts.creator_links,
+            &ctx.accounts.bidder.key(),
+        )?;
+        require!(
+            auction.status == AuctionStatus::Live,
+            AuctionError::AuctionNotActive
+        );
+        require!(!auction.has_ended(&clock), AuctionError::AuctionEnded);
+        require!(auction.partial_fills, AuctionError::PartialFillAuction);
+        require!(unit_price >= auction.starting_bid, AuctionError::BidTooLow);
+        require!(
+            quantity > 0 && quantity <= auction.lot_size(),
+            AuctionError::InvalidQuantity
+        );
+        let amount = unit_price
+            .checked_mul(quantity)
+            .ok_or(AuctionError::MathOverflow)?;
+
+        if book.entries.len() >= MAX_PARTIAL_BIDS {
+            let (lowest, entry) = book
+                .entries
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, entry)| entry.unit_price)
+                .map(|(i, entry)| (i, *entry))
+                .unwrap();
+            require!(
+                unit_price >= entry.unit_price + auction.min_bid_increment,
+                AuctionError::BidIncrementTooLow
+            );
+            let evicted = ctx
+                .accounts
+                .evicted_bidder
+                .as_ref()
+                .ok_or(AuctionError::InvalidPreviousBidder)?;
+            require_keys_eq!(
+                evicted.key(),
+                entry.bidder,
+                AuctionError::InvalidPreviousBidder
+            );
+
+            // Refund the outbid entry in full
+            let refund = entry.escrowed()?;
+            pay_from_native_escrow(
+                &ctx.accounts.system_program,
+                &ctx.accounts.auction_escrow,
+                ctx.bumps.auction_escrow,
+                auction.key(),
+                evicted,
+                refund,
+            )?;
+            auction.partial_escrowed -= refund;
+            book.entries.remove(lowest);
+        }
+
+        let transfer_ctx = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            anchor_lang::system_program::Transfer {
+                from: ctx.accounts.bidder.to_account_info(),
+                to: ctx.accounts.auction_escrow.to_account_info(),
+            },
+        );
+        anchor_lang::system_program::transfer(transfer_ctx, amount)?;
+
+        auction.partial_escrowed = auction
+            .partial_escrowed
+            .checked_add(amount)
+            .ok_or(AuctionError::MathOverflow)?;
+        auction.apply_extension(clock.unix_timestamp);
+        book.entries.push(PartialFillEntry {
+            bidder: ctx.accounts.bidder.key(),
+            unit_price,
+            quantity,
+            filled: 0,
+            claimed: false,
+        });
+
+        emit!(PartialBidPlaced {
+            auction_id: auction.key(),
+            bidder: ctx.accounts.bidder.key(),
+            unit_price,
+            quantity,
+        });
+
+        Ok(())
+    }
+
+    /// Clears a partial-fill auction. Entries are filled by unit price, then
+    /// by arrival, until the lot is exhausted; each winner pays their own unit
+    /// price. Proceeds are paid out now and unsold units go back to the
+    /// creator; bidders collect their units and refunds with
+    /// `claim_partial_fill`.
+    pub fn finalize_partial_fill_auction<'info>(
+        ctx: Context<'_, '_, '_, 'info, FinalizePartialFillAuction<'info>>,
+    ) -> Result<SettlementSummary> {
+        ctx.accounts.validate()?;
+        let auction = &mut ctx.accounts.auction;
+        let book = &mut ctx.accounts.book;
+        let clock = Clock::get()?;
+
+        require!(auction.is_closed(&clock), AuctionError::AuctionNotEnded);
+        require!(!auction.is_paused(), AuctionError::AuctionPaused);
+        require!(auction.status.can_settle(), AuctionError::AuctionNotActive);
+        require!(auction.partial_fills, AuctionError::PartialFillAuction);
+
+        let unsold = book.fill(auction.lot_size());
+        let proceeds = book.proceeds()?;
+
+        let mut summary = SettlementSummary {
+            auction_id: auction.key(),
+            winner: Pubkey::default(),
+            winning_bid: proceeds,
+            seller: ctx.accounts.creator.key(),
+            seller_amount: 0,
+            platform_fee_account: ctx.accounts.platform_fee_account.key(),
+            platform_fee: 0,
+            nft_recipient_account: None,
+            keeper_rebate: 0,
+            settler_bounty: 0,
+            royalties: 0,
+        };
+
+        if proceeds > 0 {
+            let (platform_fee, seller_amount) =
+                split_proceeds(proceeds, auction.platform_fee_bps());
+
+            // Transfer funds to seller
+            pay_from_native_escrow(
+                &ctx.accounts.system_program,
+                &ctx.accounts.auction_escrow,
+                ctx.bumps.auction_escrow,
+                auction.key(),
+                &ctx.accounts.creator,
+                seller_amount,
+            )?;
+
+            // Transfer platform fee
+            pay_from_native_escrow(
+                &ctx.accounts.system_program,
+                &ctx.accounts.auction_escrow,
+                ctx.bumps.auction_escrow,
+                auction.key(),
+                &ctx.accounts.platform_fee_account,
+                platform_fee,
+            )?;
+
+            auction.partial_escrowed -= proceeds;
+            summary.seller_amount = seller_amount;
+            summary.platform_fee = platform_fee;
+        }
+
+        if unsold > 0 {
+            // Return unsold units to the creator
+            transfer_from_vault(
+                &ctx.accounts.token_program,
+                &ctx.accounts.vault_nft_account,
+                &ctx.accounts.nft_mint,
+                unsold,
+                &ctx.accounts.creator_nft_account,
+                &ctx.accounts.auction_authority,
+                auction.key(),
+                ctx.bumps.auction_authority,
+                ctx.remaining_accounts,
+            )?;
+        }
+
+        auction.highest_bid = proceeds;
+        let status = if book.entries.is_empty() {
+            AuctionStatus::Cancelled
+        } else {
+            AuctionStatus::Settled
+        };
+        set_auction_status(auction, status)?;
+        if book.entries.is_empty() {
+            // Return the escrow's rent reserve to the creator
+            release_escrow_reserve(
+                &ctx.accounts.system_program,
+                &ctx.accounts.auction_escrow,
+                ctx.bumps.auction_escrow,
+                auction.key(),
+                auction,
+                &ctx.accounts.creator,
+            )?;
+        }
+
+        emit!(AuctionFinalized {
+            auction_id: auction.key(),
+            winner: Pubkey::default(),
+            winning_bid: proceeds,
+            experiment_id: auction.experiment_id,
+            variant: auction.experiment_variant,
+        });
+
+        Ok(summary)
+    }
+
+    /// Delivers one book entry after clearing: its filled units go to the
+    /// bidder and the unfilled portion of its escrow is refunded. Anyone may
+    /// crank this; the last claim returns the escrow's rent reserve.
+    pub fn claim_partial_fill<'info>(
+        ctx: Context<'_, '_, '_, 'info, ClaimPartialFill<'info>>,
+        index: u8,
+    ) -> Result<()> {
+        ctx.accounts.validate()?;
+        let auction = &mut ctx.accounts.auction;
+        let book = &mut ctx.accounts.book;
+
+        require!(
+            auction.partial_fills && auction.status == AuctionStatus::Settled,
+            AuctionError::AuctionNotEnded
+        );
+        let entry = book
+            .entries
+            .get_mut(index as usize)
+            .ok_or(AuctionError::InvalidPreviousBidder)?;
+        require!(!entry.claimed, AuctionError::NothingToClaim);
+        require_keys_eq!(
+            ctx.accounts.bidder.key(),
+            entry.bidder,
+            AuctionError::InvalidRecipient
+        );
+
+        if entry.filled > 0 {
+            transfer_from_vault(
+                &ctx.accounts.token_program,
+                &ctx.accounts.vault_nft_account,
+                &ctx.accounts.nft_mint,
+                entry.filled,
+                &ctx.accounts.bidder_nft_account,
+                &ctx.accounts.auction_authority,
+                auction.key(),
+                ctx.bumps.auction_authority,
+                ctx.remaining_accounts,
+            )?;
+        }
+
+        let refund = entry
+            .unit_price
+            .checked_mul(entry.quantity - entry.filled)
+            .ok_or(AuctionError::MathOverflow)?;
+        if refund > 0 {
+            pay_from_native_escrow(
+                &ctx.accounts.system_program,
+                &ctx.accounts.auction_escrow,
+                ctx.bumps.auction_escrow,
+                auction.key(),
+                &ctx.accounts.bidder,
+                refund,
+            )?;
+            auction.partial_escrowed -= refund;
+        }
+        entry.claimed = true;
+        let filled = entry.filled;
+
+        if book.entries.iter().all(|entry| entry.claimed) {
+            // Return the escrow's rent reserve to the creator
+            release_escrow_reserve(
+                &ctx.accounts.system_program,
+                &ctx.accounts.auction_escrow,
+                ctx.bumps.auction_escrow,
+                auction.key(),
+                auction,
+                &ctx.accounts.creator,
+            )?;
+        }
+
+        emit!(PartialFillClaimed {
+            auction_id: auction.key(),
+            bidder: ctx.accounts.bidder.key(),
+            filled,
+            refund,
+        });
+
+        Ok(())
+    }
+
+    /// Chooses a stake pool whose LST the seller's SOL proceeds are paid in,
+    /// or clears it to take plain lamports. Only the creator, and only
+    /// before the first bid.
+    pub fn set_lst_payout(ctx: Context<SetLstPayout>) -> Result<()> {
+        ctx.accounts.validate()?;
+        let auction = &mut ctx.accounts.auction;
+
+        require!(
+            auction.status == AuctionStatus::Live,
+            AuctionError::AuctionNotActive
+        );
+        require!(!auction.has_bids(), AuctionError::AuctionHasBids);
+        require_keys_eq!(
+            auction.creator,
+            ctx.accounts.creator.key(),
+            AuctionError::UnauthorizedUpdate
+        );
+        require!(auction.is_native(), AuctionError::InvalidBidCurrency);
+        require!(!auction.delivery_escrow, AuctionError::InvalidStateTransition);
+
+        let stake_pool = match &ctx.accounts.stake_pool {
+            Some(pool) => {
+                stake_pool::check_pool(pool)?;
+                Some(pool.key())
+            }
+            None => None,
+        };
+        auction.lst_payout_pool = stake_pool.unwrap_or_default();
+
+        emit!(LstPayoutSet {
+            auction_id: auction.key(),
+            stake_pool,
+        });
+
+        Ok(())
+    }
+
+    /// Lists an NFT escrowlessly: it stays in the seller's wallet, delegated
+    /// to the auction authority but not frozen, and is pulled only when a
+    /// winner settles through `finalize_delegated_auction`. If the seller
+    /// moves the NFT or revokes the delegation, anyone may cancel the auction
+    /// with `cancel_broken_listing`.
+    pub fn initialize_escrowless_auction(
+        ctx: Context<InitializeEscrowlessAuction>,
+        params: AuctionParams,
+    ) -> Result<()> {
+        ctx.accounts.validate()?;
+        params.check()?;
+        let clock = Clock::get()?;
+        check_relist_cooldown(&ctx.accounts.mint_cooldown, clock.unix_timestamp)?;
+
+        let approve_ctx = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            anchor_spl::token::Approve {
+                to: ctx.accounts.creator_nft_account.to_account_info(),
+                delegate: ctx.accounts.auction_authority.to_account_info(),
+                authority: ctx.accounts.creator.to_account_info(),
+            },
+        );
+        anchor_spl::token::approve(approve_ctx, 1)?;
+
+        let auction = &mut ctx.accounts.auction;
+        auction.open(
+            ctx.accounts.creator.key(),
+            ctx.accounts.nft_mint.key(),
+            &params,
+            clock.unix_timestamp,
+        );
+        ctx.accounts.config.apply_terms(auction, params.duration)?;
+        auction.asset_kind = AssetKind::Escrowless;
+        auction.seller_token_account = ctx.accounts.creator_nft_account.key();
+        if let Some(experiment) = &ctx.accounts.experiment {
+            let auction_key = auction.key();
+            auction.join_experiment(&auction_key, experiment);
+        }
+        fund_escrow_reserve(
+            &ctx.accounts.system_program,
+            &ctx.accounts.creator.to_account_info(),
+            &ctx.accounts.auction_escrow,
+            auction,
+        )?;
+
+        emit!(AuctionCreated {
+            auction_id: auction.key(),
+            creator: auction.creator,
+            nft_mint: auction.nft_mint,
+            starting_bid: params.starting_bid,
+            end_time: auction.end_time,
+            quantity: 1,
+        });
+
+        Ok(())
+    }
+
+    /// Cancels an escrowless auction whose NFT is no longer deliverable: the
+    /// seller's token account was closed, emptied, or lost the auction
+    /// authority's delegation. The standing bid is refunded in full.
+    /// Permissionless, so bidders and keepers can unwind a pulled listing.
+    pub fn cancel_broken_listing(ctx: Context<CancelBrokenListing>) -> Result<()> {
+        ctx.accounts.validate()?;
+        let auction = &mut ctx.accounts.auction;
+
+        require!(auction.status.is_open(), AuctionError::AuctionNotActive);
+        require!(
+            auction.asset_kind == AssetKind::Escrowless,
+            AuctionError::InvalidAsset
+        );
+        require!(
+            !listing_intact(
+                &ctx.accounts.seller_token_account,
+                auction,
+                &ctx.accounts.auction_authority.key(),
+            ),
+            AuctionError::ListingIntact
+        );
+
+        if auction.highest_bid > 0 {
+            // Refund the standing bid
+            refund_native_bid(
+                &ctx.accounts.system_program,
+                &ctx.accounts.auction_escrow,
+                ctx.bumps.auction_escrow,
+                auction.key(),
+                &ctx.accounts.previous_bidder,
+                auction.highest_bidder,
+                auction.highest_bid,
+                auction.highest_bid_wrapped,
+                ctx.accounts.token_program.as_ref(),
+            )?;
+        }
+
+        // Return the escrow's rent reserve to the creator
+        release_escrow_reserve(
+            &ctx.accounts.system_program,
+            &ctx.accounts.auction_escrow,
+            ctx.bumps.auction_escrow,
+            auction.key(),
+            auction,
+            &ctx.accounts.creator,
+        )?;
+        set_auction_status(auction, AuctionStatus::Cancelled)?;
+
+        emit!(AuctionCancelled {
+            auction_id: auction.key(),
+            reason: "NFT left the seller's wallet".to_string(),
+        });
+
+        Ok(())
+    }
+
+    /// Takes down a live SOL auction, bids or not, for cases such as a
+    /// stolen NFT or a legal order. The standing bid is refunded in full,
+    /// the NFT goes back to the creator, and `reason_code` is recorded on
+    /// the auction. Works while paused. Only the config admin.
+    pub fn emergency_cancel<'info>(
+        ctx: Context<'_, '_, '_, 'info, EmergencyCancel<'info>>,
+        reason_code: u8,
+    ) -> Result<()> {
+        ctx.accounts.validate()?;
+        let auction = &mut ctx.accounts.auction;
+
+        require!(
+            auction.status.is_open() || auction.status == AuctionStatus::Listed,
+            AuctionError::AuctionNotActive
+        );
+        require!(auction.is_native(), AuctionError::InvalidBidCurrency);
+        require!(
+            auction.asset_kind == AssetKind::Spl
+                && !auction.partial_fills
+                && auction.deposit_mode == DepositMode::Off,
+            AuctionError::InvalidAsset
+        );
+        require!(!auction.has_pending_bid(), AuctionError::BidPending);
+
+        let refunded = auction.highest_bid;
+        if auction.has_bids() {
+            // Refund the standing bid
+            refund_native_bid(
+                &ctx.accounts.system_program,
+                &ctx.accounts.auction_escrow,
+                ctx.bumps.auction_escrow,
+                auction.key(),
+                &ctx.accounts.previous_bidder,
+                auction.highest_bidder,
+                auction.highest_bid,
+                auction.highest_bid_wrapped,
+                ctx.accounts.wsol_token_program.as_ref(),
+            )?;
+        }
+
+        // Transfer NFT back to creator
+        release_from_vault(
+            auction.is_programmable,
+            auction.lot_size(),
+            &ctx.accounts.pnft,
+            &ctx.accounts.token_program,
+            &ctx.accounts.vault_nft_account,
+            &ctx.accounts.nft_mint,
+            &ctx.accounts.creator_nft_account,
+            Some(&ctx.accounts.creator.to_account_info()),
+            Some(&ctx.accounts.admin.to_account_info()),
+            &ctx.accounts.auction_authority,
+            auction.key(),
+            ctx.bumps.auction_authority,
+            ctx.remaining_accounts,
+        )?;
+
+        // Return the escrow's rent reserve to the creator
+        release_escrow_reserve(
+            &ctx.accounts.system_program,
+            &ctx.accounts.auction_escrow,
+            ctx.bumps.auction_escrow,
+            auction.key(),
+            auction,
+            &ctx.accounts.creator.to_account_info(),
+        )?;
+        set_auction_status(auction, AuctionStatus::Cancelled)?;
+        auction.cancel_reason = reason_code;
+
+        emit!(AuctionEmergencyCancelled {
+            auction_id: auction.key(),
+            admin: ctx.accounts.admin.key(),
+            reason_code,
+            bidder: auction.highest_bidder,
+            refunded,
+        });
+
+        Ok(())
+    }
+
+    /// Unwinds a SOL auction nobody finalized within the config's
+    /// `force_refund_after` of its end time: the standing bid goes back to
+    /// the highest bidder, the NFT to the creator, and the auction is
+    /// cancelled. Anyone may call it; the caller pays for the creator's token
+    /// record when the NFT is programmable.
+    pub fn force_refund<'info>(ctx: Context<'_, '_, '_, 'info, ForceRefund<'info>>) -> Result<()> {
+        ctx.accounts.validate()?;
+        let auction = &mut ctx.accounts.auction;
+        let clock = Clock::get()?;
+        let window = ctx.accounts.config.force_refund_after;
+
+        require!(window > 0, AuctionError::ForceRefundDisabled);
+        require!(auction.status.can_settle(), AuctionError::AuctionNotActive);
+        require!(
+            auction.has_ended(&clock)
+                && clock.unix_timestamp >= auction.end_time.saturating_add(window),
+            AuctionError::ForceRefundNotDue
+        );
+        require!(auction.is_native(), AuctionError::InvalidBidCurrency);
+        require!(
+            auction.asset_kind == AssetKind::Spl
+                && !auction.partial_fills
+                && auction.deposit_mode == DepositMode::Off,
+            AuctionError::InvalidAsset
+        );
+        require!(!auction.has_pending_bid(), AuctionError::BidPending);
+
+        let refunded = auction.highest_bid;
+        if refunded > 0 {
+            refund_native_bid(
+                &ctx.accounts.system_program,
+                &ctx.accounts.auction_escrow,
+                ctx.bumps.auction_escrow,
+                auction.key(),
+                &ctx.accounts.previous_bidder,
+                auction.highest_bidder,
+                refunded,
+                auction.highest_bid_wrapped,
+                ctx.accounts.wsol_token_program.as_ref(),
+            )?;
+        }
+
+        // Transfer NFT back to creator
+        release_from_vault(
+            auction.is_programmable,
+            auction.lot_size(),
+            &ctx.accounts.pnft,
+            &ctx.accounts.token_program,
+            &ctx.accounts.vault_nft_account,
+            &ctx.accounts.nft_mint,
+            &ctx.accounts.creator_nft_account,
+            Some(&ctx.accounts.creator.to_account_info()),
+            Some(&ctx.accounts.caller.to_account_info()),
+            &ctx.accounts.auction_authority,
+            auction.key(),
+            ctx.bumps.auction_authority,
+            ctx.remaining_accounts,
+        )?;
+
+        // Return the escrow's rent reserve to the creator
+        release_escrow_reserve(
+            &ctx.accounts.system_program,
+            &ctx.accounts.auction_escrow,
+            ctx.bumps.auction_escrow,
+            auction.key(),
+            auction,
+            &ctx.accounts.creator.to_account_info(),
+        )?;
+        set_auction_status(auction, AuctionStatus::Cancelled)?;
+
+        emit!(AuctionForceRefunded {
+            auction_id: auction.key(),
+            caller: ctx.accounts.caller.key(),
+            bidder: auction.highest_bidder,
+            refunded,
+        });
+
+        Ok(())
+    }
+
+    /// Chooses a Kamino reserve for the bid mint that escrowed bids may be
+    /// lent to while the auction runs, or clears it. Only the creator of a
+    /// single-mint SPL auction, and only before the first bid.
+    pub fn set_escrow_yield_reserve(ctx: Context<SetEscrowYieldReserve>) -> Result<()> {
+        ctx.accounts.validate()?;
+        let auction = &mut ctx.accounts.auction;
+
+        require!(
+            auction.status == AuctionStatus::Live,
+            AuctionError::AuctionNotActive
+        );
+        require!(!auction.has_bids(), AuctionError::AuctionHasBids);
+        require_keys_eq!(
+            auction.creator,
+            ctx.accounts.creator.key(),
+            AuctionError::UnauthorizedUpdate
+        );
+        require!(
+            !auction.is_native() && !auction.multi_currency,
+            AuctionError::InvalidBidCurrency
+        );
+
+        let reserve = match &ctx.accounts.reserve {
+            Some(reserve) => {
+                require_keys_eq!(
+                    *reserve.owner,
+                    kamino::KLEND_PROGRAM_ID,
+                    AuctionError::InvalidYieldReserve
+                );
+                Some(reserve.key())
+            }
+            None => None,
+        };
+        auction.yield_reserve = reserve.unwrap_or_default();
+
+        emit!(EscrowYieldReserveSet {
+            auction_id: auction.key(),
+            reserve,
+        });
+
+        Ok(())
+    }
+
+    /// Lends the whole bid escrow to the auction's Kamino reserve.
+    /// Permissionless. Until `recall_escrow_yield` brings it back, new bids
+    /// and settlement are blocked, so keepers or the next bidder prepend a
+    /// recall to their transaction. The reserve must be refreshed earlier in
+    /// the same transaction.
+    pub fn deploy_escrow_yield(ctx: Context<DeployEscrowYield>) -> Result<()> {
+        ctx.accounts.validate()?;
+        let auction = &mut ctx.accounts.auction;
+        let clock = Clock::get()?;
+
+        require!(
+            auction.status == AuctionStatus::Live,
+            AuctionError::AuctionNotActive
+        );
+        require!(!auction.has_ended(&clock), AuctionError::AuctionEnded);
+        require_keys_eq!(
+            ctx.accounts.kamino.reserve.key(),
+            auction.yield_reserve,
+            AuctionError::InvalidYieldReserve
+        );
+        require!(auction.yield_principal == 0, AuctionError::EscrowDeployed);
+        let amount = ctx.accounts.escrow_token_account.amount;
+        require!(amount > 0, AuctionError::EmptyEscrow);
+
+        let auction_key = auction.key();
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            AUCTION_SEED,
+            auction_key.as_ref(),
+            &[ctx.bumps.auction_authority],
+        ]];
+        let kamino = &ctx.accounts.kamino;
+        kamino::deposit_reserve_liquidity(
+            kamino::ReserveAccounts {
+                klend_program: &kamino.klend_program,
+                owner: &ctx.accounts.auction_authority,
+                reserve: &kamino.reserve,
+                lending_market: &kamino.lending_market,
+                lending_market_authority: &kamino.lending_market_authority,
+                liquidity_mint: &ctx.accounts.bid_mint.to_account_info(),
+                liquidity_supply: &kamino.liquidity_supply,
+                collateral_mint: &ctx.accounts.collateral_mint.to_account_info(),
+                liquidity: &ctx.accounts.escrow_token_account.to_account_info(),
+                collateral: &ctx.accounts.collateral_token_account.to_account_info(),
+                collateral_token_program: &ctx.accounts.collateral_token_program.to_account_info(),
+                liquidity_token_program: &ctx.accounts.bid_token_program.to_account_info(),
+                sysvar_instructions: &kamino.sysvar_instructions,
+            },
+            amount,
+            signer_seeds,
+        )?;
+        auction.yield_principal = amount;
+
+        emit!(EscrowYieldDeployed {
+            auction_id: auction_key,
+            amount,
+        });
+
+        Ok(())
+    }
+
+    /// Redeems all of the auction's Kamino collateral back into the bid
+    /// escrow. Permissionless. Interest stays in the escrow and is paid to
+    /// the seller at settlement. The reserve must be refreshed earlier in the
+    /// same transaction.
+    pub fn recall_escrow_yield(ctx: Context<RecallEscrowYield>) -> Result<()> {
+        ctx.accounts.validate()?;
+        let auction = &mut ctx.accounts.auction;
+
+        require!(auction.yield_principal > 0, AuctionError::EscrowNotDeployed);
+        require_keys_eq!(
+            ctx.accounts.kamino.reserve.key(),
+            auction.yield_reserve,
+            AuctionError::InvalidYieldReserve
+        );
+
+        let auction_key = auction.key();
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            AUCTION_SEED,
+            auction_key.as_ref(),
+            &[ctx.bumps.auction_authority],
+        ]];
+        let before = ctx.accounts.escrow_token_account.amount;
+        let kamino = &ctx.accounts.kamino;
+        kamino::redeem_reserve_collateral(
+            kamino::ReserveAccounts {
+                klend_program: &kamino.klend_program,
+                owner: &ctx.accounts.auction_authority,
+                reserve: &kamino.reserve,
+                lending_market: &kamino.lending_market,
+                lending_market_authority: &kamino.lending_market_authority,
+                liquidity_mint: &ctx.accounts.bid_mint.to_account_info(),
+                liquidity_supply: &kamino.liquidity_supply,
+                collateral_mint: &ctx.accounts.collateral_mint.to_account_info(),
+                liquidity: &ctx.accounts.escrow_token_account.to_account_info(),
+                collateral: &ctx.accounts.collateral_token_account.to_account_info(),
+                collateral_token_program: &ctx.accounts.collateral_token_program.to_account_info(),
+                liquidity_token_program: &ctx.accounts.bid_token_program.to_account_info(),
+                sysvar_instructions: &kamino.sysvar_instructions,
+            },
+            ctx.accounts.collateral_token_account.amount,
+            signer_seeds,
+        )?;
+        ctx.accounts.escrow_token_account.reload()?;
+        let redeemed = ctx.accounts.escrow_token_account.amount - before;
+
+        let principal = auction.yield_principal;
+        auction.yield_principal = 0;
+
+        emit!(EscrowYieldRecalled {
+            auction_id: auction_key,
+            principal,
+            redeemed,
+        });
+
+        Ok(())
+    }
+
+    /// Places a SOL offer for any NFT in the verified `collection`. The
+    /// amount, plus a rent reserve that comes back on settlement or
+    /// cancellation, is escrowed in the offer's SOL escrow PDA.
+    /// `expires_at` of `None` keeps the offer open until cancelled.
+    pub fn place_collection_offer(
+        ctx: Context<PlaceCollectionOffer>,
+        collection: Pubkey,
+        amount: u64,
+        expires_at: Option<i64>,
+    ) -> Result<()> {
+        ctx.accounts.validate()?;
+        require!(amount > 0, AuctionError::BidTooLow);
+        let clock = Clock::get()?;
+        if let Some(expires_at) = expires_at {
+            require!(expires_at > clock.unix_timestamp, AuctionError::OfferExpired);
+        }
+
+        let deposit = amount
+            .checked_add(Rent::get()?.minimum_balance(0))
+            .ok_or(AuctionError::MathOverflow)?;
+        let transfer_ctx = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            anchor_lang::system_program::Transfer {
+                from: ctx.accounts.buyer.to_account_info(),
+                to: ctx.accounts.offer_escrow.to_account_info(),
+            },
+        );
+        anchor_lang::system_program::transfer(transfer_ctx, deposit)?;
+
+        let offer = &mut ctx.accounts.offer;
+        offer.buyer = ctx.accounts.buyer.key();
+        offer.collection = collection;
+        offer.amount = amount;
+        offer.expires_at = expires_at.unwrap_or_default();
+        offer.bump = ctx.bumps.offer;
+
+        emit!(CollectionOfferPlaced {
+            offer: offer.key(),
+            buyer: offer.buyer,
+            collection,
+            amount,
+            expires_at: offer.expires_at,
+        });
+
+        Ok(())
+    }
+
+    /// Withdraws a collection offer, returning everything escrowed for it.
+    pub fn cancel_collection_offer(ctx: Context<CancelCollectionOffer>) -> Result<()> {
+        ctx.accounts.validate()?;
+        let offer_key = ctx.accounts.offer.key();
+
+        pay_from_native_escrow(
+            &ctx.accounts.system_program,
+            &ctx.accounts.offer_escrow,
+            ctx.bumps.offer_escrow,
+            offer_key,
+            &ctx.accounts.buyer,
+            ctx.accounts.offer_escrow.lamports(),
+        )?;
+
+        emit!(CollectionOfferCancelled { offer: offer_key });
+
+        Ok(())
+    }
+
+    /// Sells an NFT from the offer's collection to the buyer. Settlement is
+    /// the same as an auction's: verified creators are paid their royalty
+    /// share (their wallets lead the remaining accounts, in metadata order),
+    /// then the platform fee and the seller.
+    pub fn accept_collection_offer<'info>(
+        ctx: Context<'_, '_, '_, 'info, AcceptCollectionOffer<'info>>,
+    ) -> Result<()> {
+        ctx.accounts.validate()?;
+        let offer = &ctx.accounts.offer;
+        let offer_key = offer.key();
+        let clock = Clock::get()?;
+
+        require!(
+            offer.expires_at == 0 || clock.unix_timestamp < offer.expires_at,
+            AuctionError::OfferExpired
+        );
+        let listed = token_metadata::load_collection(
+            &ctx.accounts.nft_metadata,
+            &ctx.accounts.nft_mint.key(),
+        )?;
+        require!(
+            listed.is_some_and(|c| c.verified && c.key == offer.collection),
+            AuctionError::CollectionMismatch
+        );
+
+        let (platform_fee, mut seller_amount) =
+            split_proceeds(offer.amount, ctx.accounts.config.fee_bps);
+
+        // Pay verified creators their royalty share before the seller
+        let royalties = token_metadata::load_royalties(
+            &ctx.accounts.nft_metadata,
+            &ctx.accounts.nft_mint.key(),
+        )?;
+        let (payments, remaining_accounts) = pay_royalties(
+            &ctx.accounts.system_program,
+            &ctx.accounts.offer_escrow,
+            ctx.bumps.offer_escrow,
+            offer_key,
+            seller_amount,
+            &royalties,
+            ctx.remaining_accounts,
+        )?;
+        let royalty_total: u64 = payments.iter().map(|p| p.amount).sum();
+        seller_amount = seller_amount
+            .checked_sub(royalty_total)
+            .ok_or(AuctionError::MathOverflow)?;
+
+        // Transfer NFT to buyer
+        if ctx.accounts.pnft.token_metadata_program.is_some() {
+            let seller = ctx.accounts.seller.to_account_info();
+            ctx.accounts.pnft.transfer(
+                &ctx.accounts.token_program,
+                &ctx.accounts.seller_nft_account,
+                &seller,
+                &ctx.accounts.buyer_nft_account,
+                &ctx.accounts.buyer,
+                &ctx.accounts.nft_mint,
+                &seller,
+                &seller,
+                &[],
+            )?;
+        } else {
+            let transfer_ctx = CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.seller_nft_account.to_account_info(),
+                    mint: ctx.accounts.nft_mint.to_account_info(),
+                    to: ctx.accounts.buyer_nft_account.to_account_info(),
+                    authority: ctx.accounts.seller.to_account_info(),
+                },
+            )
+            .with_remaining_accounts(remaining_accounts.to_vec());
+            anchor_spl::token_interface::transfer_checked(
+                transfer_ctx,
+                1,
+                ctx.accounts.nft_mint.decimals,
+            )?;
+        }
+
+        // Transfer funds to seller
+        pay_from_native_escrow(
+            &ctx.accounts.system_program,
+            &ctx.accounts.offer_escrow,
+            ctx.bumps.offer_escrow,
+            offer_key,
+            &ctx.accounts.seller.to_account_info(),
+            seller_amount,
+        )?;
+
+        // Transfer platform fee
+        pay_from_native_escrow(
+            &ctx.accounts.system_program,
+            &ctx.accounts.offer_escrow,
+            ctx.bumps.offer_escrow,
+            offer_key,
+            &ctx.accounts.platform_fee_account,
+            platform_fee,
+        )?;
+
+        // Return the escrow's rent reserve to the buyer
+        pay_from_native_escrow(
+            &ctx.accounts.system_program,
+            &ctx.accounts.offer_escrow,
+            ctx.bumps.offer_escrow,
+            offer_key,
+            &ctx.accounts.buyer,
+            ctx.accounts.offer_escrow.lamports(),
+        )?;
+
+        emit!(CollectionOfferAccepted {
+            offer: offer_key,
+            buyer: ctx.accounts.buyer.key(),
+            seller: ctx.accounts.seller.key(),
+            nft_mint: ctx.accounts.nft_mint.key(),
+            amount: ctx.accounts.offer.amount,
+            platform_fee,
+            royalties: royalty_total,
+        });
+
+        Ok(())
+    }
+
+    /// Turns a SOL auction into a physical-item sale: after settlement the
+    /// seller's share is held in the escrow for `dispute_window` seconds,
+    /// during which the winner may open a dispute for `arbiter` to resolve.
+    /// Royalties and the platform fee are paid at settlement as usual. Only
+    /// the creator, and only before the first bid.
+    pub fn enable_delivery_escrow(
+        ctx: Context<EnableDeliveryEscrow>,
+        dispute_window: i64,
+        arbiter: Pubkey,
+    ) -> Result<()> {
+        ctx.accounts.validate()?;
+        let auction = &mut ctx.accounts.auction;
+
+        require!(
+            auction.status == AuctionStatus::Live,
+            AuctionError::AuctionNotActive
+        );
+        require!(!auction.has_bids(), AuctionError::AuctionHasBids);
+        require_keys_eq!(
+            auction.creator,
+            ctx.accounts.creator.key(),
+            AuctionError::UnauthorizedUpdate
+        );
+        require!(auction.is_native(), AuctionError::InvalidBidCurrency);
+        require!(
+            auction.asset_kind == AssetKind::Spl && !auction.partial_fills,
+            AuctionError::InvalidAsset
+        );
+        require!(
+            auction.lst_payout_pool == Pubkey::default(),
+            AuctionError::InvalidStateTransition
+        );
+        require!(dispute_window > 0, AuctionError::InvalidDuration);
+
+        let dispute = &mut ctx.accounts.dispute;
+        dispute.auction = auction.key();
+        dispute.arbiter = arbiter;
+        dispute.window = dispute_window;
+        dispute.bump = ctx.bumps.dispute;
+        auction.delivery_escrow = true;
+
+        emit!(DeliveryEscrowEnabled {
+            auction_id: auction.key(),
+            arbiter,
+            dispute_window,
+        });
+
+        Ok(())
+    }
+
+    /// Lets the winner contest delivery while the dispute window is open,
+    /// freezing the held proceeds until the arbiter resolves it.
+    pub fn open_dispute(ctx: Context<OpenDispute>) -> Result<()> {
+        ctx.accounts.validate()?;
+        let auction = &mut ctx.accounts.auction;
+        let dispute = &mut ctx.accounts.dispute;
+        let clock = Clock::get()?;
+
+        require_keys_eq!(
+            ctx.accounts.buyer.key(),
+            auction.highest_bidder,
+            AuctionError::Unauthorized
+        );
+        require!(
+            dispute.held > 0 && dispute.status == DisputeStatus::None,
+            AuctionError::InvalidDisputeState
+        );
+        require!(
+            clock.unix_timestamp < dispute.release_at,
+            AuctionError::DisputeWindowClosed
+        );
+
+        dispute.status = DisputeStatus::Open;
+        set_auction_status(auction, AuctionStatus::Disputed)?;
+
+        emit!(DisputeOpened {
+            auction_id: auction.key(),
+            buyer: auction.highest_bidder,
+        });
+
+        Ok(())
+    }
+
+    /// Settles an open dispute: the held proceeds go back to the winner if
+    /// `refund`, otherwise to the seller. Only the auction's arbiter.
+    pub fn resolve_dispute(ctx: Context<ResolveDispute>, refund: bool) -> Result<()> {
+        ctx.accounts.validate()?;
+        let auction = &mut ctx.accounts.auction;
+        let dispute = &mut ctx.accounts.dispute;
+
+        require!(
+            dispute.status == DisputeStatus::Open,
+            AuctionError::InvalidDisputeState
+        );
+
+        let recipient = if refund {
+            &ctx.accounts.buyer
+        } else {
+            &ctx.accounts.creator
+        };
+        let amount = dispute.held;
+        pay_from_native_escrow(
+            &ctx.accounts.system_program,
+            &ctx.accounts.auction_escrow,
+            ctx.bumps.auction_escrow,
+            auction.key(),
+            recipient,
+            amount,
+        )?;
+        dispute.held = 0;
+        dispute.status = if refund {
+            DisputeStatus::Refunded
+        } else {
+            DisputeStatus::Released
+        };
+        let status = if auction.claims_outstanding() {
+            AuctionStatus::Settling
+        } else {
+            AuctionStatus::Settled
+        };
+        set_auction_status(auction, status)?;
+
+        // Return the escrow's rent reserve to the creator
+        release_escrow_reserve(
+            &ctx.accounts.system_program,
+            &ctx.accounts.auction_escrow,
+            ctx.bumps.auction_escrow,
+            auction.key(),
+            auction,
+            &ctx.accounts.creator,
+        )?;
+
+        emit!(DisputeResolved {
+            auction_id: auction.key(),
+            refunded: refund,
+            amount,
+        });
+
+        Ok(())
+    }
+
+    /// Pays the held proceeds to the seller once the dispute window has
+    /// closed without a dispute. Permissionless.
+    pub fn release_held_proceeds(ctx: Context<ReleaseHeldProceeds>) -> Result<()> {
+        ctx.accounts.validate()?;
+        let auction = &mut ctx.accounts.auction;
+        let dispute = &mut ctx.accounts.dispute;
+        let clock = Clock::get()?;
+
+        require!(
+            dispute.held > 0 && dispute.status == DisputeStatus::None,
+            AuctionError::InvalidDisputeState
+        );
+        require!(
+            clock.unix_timestamp >= dispute.release_at,
+            AuctionError::DisputeWindowOpen
+        );
+
+        let amount = dispute.held;
+        pay_from_native_escrow(
+            &ctx.accounts.system_program,
+            &ctx.accounts.auction_escrow,
+            ctx.bumps.auction_escrow,
+            auction.key(),
+            &ctx.accounts.creator,
+            amount,
+        )?;
+        dispute.held = 0;
+        dispute.status = DisputeStatus::Released;
+
+        // Return the escrow's rent reserve to the creator
+        release_escrow_reserve(
+            &ctx.accounts.system_program,
+            &ctx.accounts.auction_escrow,
+            ctx.bumps.auction_escrow,
+            auction.key(),
+            auction,
+            &ctx.accounts.creator,
+        )?;
+
+        emit!(HeldProceedsReleased {
+            auction_id: auction.key(),
+            amount,
+        });
+
+        Ok(())
+    }
+
+    /// Attaches a short message to the caller's standing bid, for "bid with a
+    /// message" auctions. Send it after the bid in the same transaction; the
+    /// note is kept in the bidder's receipt PDA and logged for indexers, and
+    /// a later note replaces it.
+    pub fn attach_bid_note(ctx: Context<AttachBidNote>, note: String) -> Result<()> {
+        ctx.accounts.validate()?;
+        let auction = &ctx.accounts.auction;
+
+        require!(
+            auction.status == AuctionStatus::Live,
+            AuctionError::AuctionNotActive
+        );
+        require_keys_eq!(
+            ctx.accounts.bidder.key(),
+            auction.highest_bidder,
+            AuctionError::Unauthorized
+        );
+        require!(note.len() <= MAX_BID_NOTE_LEN, AuctionError::NoteTooLong);
+
+        let receipt = &mut ctx.accounts.bid_note;
+        receipt.auction = auction.key();
+        receipt.bidder = auction.highest_bidder;
+        receipt.bid_amount = auction.highest_bid;
+        receipt.note = note.clone();
+        receipt.bump = ctx.bumps.bid_note;
+
+        emit!(BidNoteAttached {
+            auction_id: auction.key(),
+            bidder: auction.highest_bidder,
+            bid_amount: auction.highest_bid,
+            note,
+        });
+
+        Ok(())
+    }
+
+    /// Adds `amount` of an SPL token to the lot, escrowed next to the NFT in
+    /// the auction authority's ATA and recorded in the lot manifest. Send it
+    /// right after the auction is created; only the creator, and only before
+    /// the first bid.
+    pub fn attach_lot_tokens(ctx: Context<AttachLotTokens>, amount: u64) -> Result<()> {
+        ctx.accounts.validate()?;
+        let auction = &ctx.accounts.auction;
+        let clock = Clock::get()?;
+
+        require!(
+            auction.status == AuctionStatus::Live,
+            AuctionError::AuctionNotActive
+        );
+        require!(!auction.has_bids(), AuctionError::AuctionHasBids);
+        require_keys_eq!(
+            auction.creator,
+            ctx.accounts.creator.key(),
+            AuctionError::UnauthorizedUpdate
+        );
+        require!(
+            auction.asset_kind == AssetKind::Spl && !auction.partial_fills,
+            AuctionError::InvalidAsset
+        );
+        // The bid escrow and the NFT vault are ATAs of the same authority
+        let token_mint = ctx.accounts.token_mint.key();
+        require!(
+            token_mint != auction.nft_mint && token_mint != auction.bid_mint,
+            AuctionError::InvalidAsset
+        );
+        require!(amount > 0, AuctionError::InvalidQuantity);
+
+        let credited = amount_after_transfer_fee(
+            &ctx.accounts.token_mint.to_account_info(),
+            amount,
+            clock.epoch,
+        )?;
+        let transfer_ctx = CpiContext::new(
+            ctx.accounts.lot_token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.creator_token_account.to_account_info(),
+                mint: ctx.accounts.token_mint.to_account_info(),
+                to: ctx.accounts.vault_token_account.to_account_info(),
+                authority: ctx.accounts.creator.to_account_info(),
+            },
+        );
+        anchor_spl::token_interface::transfer_checked(
+            transfer_ctx,
+            amount,
+            ctx.accounts.token_mint.decimals,
+        )?;
+
+        let manifest = &mut ctx.accounts.lot_manifest;
+        manifest.auction = auction.key();
+        manifest.nft_mint = auction.nft_mint;
+        manifest.token_mint = token_mint;
+        manifest.token_amount = credited;
+        manifest.bump = ctx.bumps.lot_manifest;
+
+        emit!(LotTokensAttached {
+            auction_id: auction.key(),
+            token_mint,
+            amount: credited,
+        });
+
+        Ok(())
+    }
+
+    /// Delivers a lot's SPL tokens once the auction is over: to the winner
+    /// when it sold, otherwise back to the creator. Permissionless, so the
+    /// settling transaction can carry it next to `finalize_auction`. Escheated
+    /// lots are not released.
+    pub fn release_lot_tokens(ctx: Context<ReleaseLotTokens>) -> Result<()> {
+        ctx.accounts.validate()?;
+        let auction = &ctx.accounts.auction;
+
+        let recipient = match auction.status {
+            status if status.is_settled() && auction.highest_bid > 0 => auction.highest_bidder,
+            AuctionStatus::Settled
+            | AuctionStatus::Cancelled
+            | AuctionStatus::Fractionalized
+            | AuctionStatus::Abandoned => auction.creator,
+            AuctionStatus::Scheduled
+            | AuctionStatus::Live
+            | AuctionStatus::Ended
+            | AuctionStatus::Listed => return err!(AuctionError::AuctionNotEnded),
+            AuctionStatus::Settling | AuctionStatus::Disputed | AuctionStatus::Escheated => {
+                return err!(AuctionError::InvalidStateTransition)
+            }
+        };
+        require_keys_eq!(
+            ctx.accounts.recipient.key(),
+            recipient,
+            AuctionError::InvalidRecipient
+        );
+
+        let amount = ctx.accounts.lot_manifest.token_amount;
+        transfer_from_escrow(
+            &ctx.accounts.lot_token_program,
+            &ctx.accounts.vault_token_account,
+            &ctx.accounts.token_mint,
+            &ctx.accounts.recipient_token_account,
+            &ctx.accounts.auction_authority,
+            auction.key(),
+            ctx.bumps.auction_authority,
+            amount,
+        )?;
+
+        emit!(LotTokensReleased {
+            auction_id: auction.key(),
+            recipient,
+            amount,
+        });
+
+        Ok(())
+    }
+
+    /// Pays out everything a bidder's escrow on an auction holds from bids
+    /// of theirs that `place_bid` outbid, and closes it. Outbid wSOL bids are
+    /// paid back as native SOL. The escrow's rent goes back to whoever
+    /// funded it.
+    pub fn claim_refund(ctx: Context<ClaimRefund>) -> Result<()> {
+        ctx.accounts.validate()?;
+        let bid_escrow = &ctx.accounts.bid_escrow;
+        let amount = bid_escrow.refundable;
+
+        let escrow_info = bid_escrow.to_account_info();
+        **escrow_info.try_borrow_mut_lamports()? -= amount;
+        **ctx.accounts.bidder.to_account_info().try_borrow_mut_lamports()? += amount;
+
+        emit!(RefundClaimed {
+            auction_id: bid_escrow.auction,
+            bidder: bid_escrow.bidder,
+            amount,
+        });
+
+        Ok(())
+    }
+
+    /// Places a proxy bid on a native auction. `max_bid` is escrowed and the
+    /// program bids on the bidder's behalf, one minimum increment above each
+    /// challenger, until it runs out: the standing bid sits in the auction
+    /// escrow and the rest in the bidder's `ProxyBid`. A proxy that is
+    /// already leading can be called again to raise its maximum.
+    pub fn place_proxy_bid(ctx: Context<PlaceProxyBid>, max_bid: u64) -> Result<()> {
+        ctx.accounts.validate()?;
+        check_not_blocked(&ctx.accounts.bidder_blocklist_entry)?;
+        let auction = &mut ctx.accounts.auction;
+        let bidder = ctx.accounts.bidder.key();
+        let clock = Clock::get()?;
+
+        require_independent_bidder(auction, &ctx.accounts.creator_links, &bidder)?;
+        require!(!auction.has_ended(&clock), AuctionError::AuctionEnded);
+        require!(auction.is_native(), AuctionError::InvalidBidCurrency);
+
+        let proxy_bid = &mut ctx.accounts.proxy_bid;
+        proxy_bid.open(auction.key(), bidder, ctx.bumps.proxy_bid);
+        if auction.highest_bid > 0 {
+            if let Some(bid_escrow) = ctx.accounts.previous_bid_escrow.as_mut() {
+                bid_escrow.open(
+                    auction.key(),
+                    auction.highest_bidder,
+                    bidder,
+                    ctx.bumps.previous_bid_escrow.unwrap_or_default(),
+                );
+            }
+        }
+
+        if auction.highest_bid > 0 && auction.highest_bidder == bidder {
+            // The leader raising their own maximum
+            require!(
+                auction.status == AuctionStatus::Live,
+                AuctionError::AuctionNotActive
+            );
+            require!(!auction.is_paused(), AuctionError::AuctionPaused);
+            require!(
+                auction.deposit_mode == DepositMode::Off,
+                AuctionError::DepositAuction
+            );
+            let mut standing_max = auction.highest_bid;
+            if auction.proxy_bid_active {
+                standing_max += proxy_bid.reserve;
+            }
+            require!(max_bid > standing_max, AuctionError::BidTooLow);
+
+            set_proxy_reserve(
+                &ctx.accounts.system_program,
+                &ctx.accounts.bidder,
+                proxy_bid,
+                max_bid - auction.highest_bid,
+            )?;
+            proxy_bid.max_bid = max_bid;
+            auction.proxy_bid_active = true;
+
+            emit!(ProxyBidPlaced {
+                auction_id: auction.key(),
+                bidder,
+                max_bid,
+            });
+
+            return Ok(());
+        }
+
+        auction.check_bid(max_bid, None)?;
+        require_registration(auction, &ctx.accounts.registration)?;
+        require_allowlisted(auction, &ctx.accounts.allowlist_entry)?;
+        require!(!auction.has_bid_gate(), AuctionError::BidGated);
+        track_bidder(
+            auction,
+            ctx.accounts.bidder_stats.as_mut(),
+            ctx.accounts.bidder.key(),
+            ctx.bumps.bidder_stats,
+            clock.unix_timestamp,
+        )?;
+        if auction.highest_bid > 0 {
+            auction.contested = true;
+        }
+        let price = if auction.highest_bid == 0 {
+            auction.starting_bid
+        } else {
+            let mut leader_max = auction.highest_bid;
+            if auction.proxy_bid_active {
+                let leader_proxy = ctx
+                    .accounts
+                    .leader_proxy_bid
+                    .as_mut()
+                    .ok_or(AuctionError::MissingProxyBid)?;
+                let answers = max_bid <= auction.highest_bid + leader_proxy.reserve;
+                if answers && !is_blocked(&ctx.accounts.leader_blocklist_entry) {
+                    // The leader's proxy covers this one and answers it
+                    raise_proxy_bid(
+                        auction,
+                        leader_proxy,
+                        &ctx.accounts.auction_escrow,
+                        max_bid,
+                        None,
+                    )?;
+                    auction.apply_extension(clock.unix_timestamp);
+
+                    emit!(BidPlaced {
+                        auction_id: auction.key(),
+                        bidder: auction.highest_bidder,
+                        bid_amount: auction.highest_bid,
+                    });
+
+                    return Ok(());
+                }
+                leader_max += leader_proxy.reserve;
+            }
+            leader_max
+                .saturating_add(auction.min_increment(None))
+                .min(max_bid)
+        };
+        require!(price > 0, AuctionError::BidTooLow);
+
+        if auction.highest_bid > 0 {
+            let bid_escrow = ctx
+                .accounts
+                .previous_bid_escrow
+                .as_mut()
+                .ok_or(AuctionError::MissingBidEscrow)?;
+            credit_bid_escrow(
+                &ctx.accounts.system_program,
+                &ctx.accounts.auction_escrow,
+                ctx.bumps.auction_escrow,
+                auction.key(),
+                bid_escrow,
+                auction.highest_bid,
+            )?;
+        }
+
+        let transfer_ctx = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            anchor_lang::system_program::Transfer {
+                from: ctx.accounts.bidder.to_account_info(),
+                to: ctx.accounts.auction_escrow.to_account_info(),
+            },
+        );
+        anchor_lang::system_program::transfer(transfer_ctx, price)?;
+        set_proxy_reserve(
+            &ctx.accounts.system_program,
+            &ctx.accounts.bidder,
+            proxy_bid,
+            max_bid - price,
+        )?;
+        proxy_bid.max_bid = max_bid;
+
+        auction.highest_bid = price;
+        auction.highest_bidder = bidder;
+        auction.apply_extension(clock.unix_timestamp);
+        auction.highest_bid_wrapped = false;
+        auction.proxy_bid_active = true;
+
+        emit!(BidPlaced {
+            auction_id: auction.key(),
+            bidder,
+            bid_amount: price,
+        });
+        emit!(ProxyBidPlaced {
+            auction_id: auction.key(),
+            bidder,
+            max_bid,
+        });
+
+        Ok(())
+    }
+
+    /// Returns a proxy bid's unspent reserve and closes it. A proxy still
+    /// leading an auction that is open for bids stays locked.
+    pub fn withdraw_proxy_bid(ctx: Context<WithdrawProxyBid>) -> Result<()> {
+        ctx.accounts.validate()?;
+        let auction = &ctx.accounts.auction;
+        let clock = Clock::get()?;
+
+        require!(
+            !(auction.status == AuctionStatus::Live
+                && auction.proxy_bid_active
+                && auction.highest_bidder == ctx.accounts.bidder.key()
+                && !auction.has_ended(&clock)),
+            AuctionError::ProxyBidLocked
+        );
+
+        emit!(ProxyBidWithdrawn {
+            auction_id: auction.key(),
+            bidder: ctx.accounts.bidder.key(),
+            amount: ctx.accounts.proxy_bid.reserve,
+        });
+
+        Ok(())
+    }
+
+    /// Sets the auction's anti-snipe rule: a bid landing within the final
+    /// `extension_window` seconds pushes `end_time` out by
+    /// `extension_amount`, never past `max_end_time` (`0` leaves it
+    /// uncapped). Replaces any rule an experiment variant assigned. Only the
+    /// creator, and only before the first bid.
+    pub fn set_anti_snipe(
+        ctx: Context<SetAntiSnipe>,
+        extension_window: i64,
+        extension_amount: i64,
+        max_end_time: i64,
+    ) -> Result<()> {
+        ctx.accounts.validate()?;
+        let auction = &mut ctx.accounts.auction;
+
+        require!(
+            auction.status == AuctionStatus::Live,
+            AuctionError::AuctionNotActive
+        );
+        require!(!auction.has_bids(), AuctionError::AuctionHasBids);
+        require_keys_eq!(
+            auction.creator,
+            ctx.accounts.creator.key(),
+            AuctionError::UnauthorizedUpdate
+        );
+        require!(
+            extension_window >= 0
+                && extension_amount >= 0
+                && (max_end_time == 0 || max_end_time >= auction.end_time),
+            AuctionError::InvalidExtension
+        );
+
+        auction.extension_window = extension_window;
+        auction.extension_amount = extension_amount;
+        auction.max_end_time = max_end_time;
+
+        emit!(AntiSnipeSet {
+            auction_id: auction.key(),
+            extension_window,
+            extension_amount,
+            max_end_time,
+        });
+
+        Ok(())
+    }
+
+    /// Makes the auction's cutoff the newest SlotHashes entry at
+    /// `end_auction` rather than the clock alone, so a validator skewing the
+    /// clock sysvar cannot keep it open for late bids once anyone has ended
+    /// it. Settlement waits for that cutoff. Only the creator, and only
+    /// before the first bid.
+    pub fn enable_fair_end(ctx: Context<EnableFairEnd>) -> Result<()> {
+        ctx.accounts.validate()?;
+        let auction = &mut ctx.accounts.auction;
+
+        require!(
+            auction.status == AuctionStatus::Live,
+            AuctionError::AuctionNotActive
+        );
+        require!(!auction.has_bids(), AuctionError::AuctionHasBids);
+        require_keys_eq!(
+            auction.creator,
+            ctx.accounts.creator.key(),
+            AuctionError::UnauthorizedUpdate
+        );
+
+        auction.fair_end = true;
+
+        emit!(FairEndEnabled {
+            auction_id: auction.key(),
+        });
+
+        Ok(())
+    }
+
+    /// Switches a SOL auction to deposit bidding: bids escrow only
+    /// `deposit_bps` of their amount, and once bidding closes the winner has
+    /// `settlement_window` seconds to pay the balance with
+    /// `settle_winning_bid`. Only the creator, and only before the first bid.
+    pub fn enable_deposit_bidding(
+        ctx: Context<EnableDepositBidding>,
+        deposit_bps: u16,
+        settlement_window: i64,
+    ) -> Result<()> {
+        ctx.accounts.validate()?;
+        let auction = &mut ctx.accounts.auction;
+
+        require!(
+            auction.status == AuctionStatus::Live,
+            AuctionError::AuctionNotActive
+        );
+        require!(!auction.has_bids(), AuctionError::AuctionHasBids);
+        require_keys_eq!(
+            auction.creator,
+            ctx.accounts.creator.key(),
+            AuctionError::UnauthorizedUpdate
+        );
+        require!(auction.is_native(), AuctionError::InvalidBidCurrency);
+        require!(
+            auction.asset_kind == AssetKind::Spl && !auction.partial_fills,
+            AuctionError::InvalidAsset
+        );
+        require!(
+            deposit_bps > 0 && deposit_bps <= BPS_DENOMINATOR,
+            AuctionError::InvalidDeposit
+        );
+        require!(settlement_window > 0, AuctionError::InvalidDuration);
+
+        let terms = &mut ctx.accounts.deposit_terms;
+        terms.auction = auction.key();
+        terms.deposit_bps = deposit_bps;
+        terms.settlement_window = settlement_window;
+        terms.bump = ctx.bumps.deposit_terms;
+        auction.deposit_mode = DepositMode::Unsettled;
+
+        emit!(DepositBiddingEnabled {
+            auction_id: auction.key(),
+            deposit_bps,
+            settlement_window,
+        });
+
+        Ok(())
+    }
+
+    /// Bids `bid_amount` on a deposit-bidding auction, escrowing only the
+    /// deposit. The outbid leader becomes the runner-up and keeps their
+    /// deposit escrowed as the fallback buyer; the runner-up they displace
+    /// is credited to their `BidEscrow`.
+    pub fn place_deposit_bid(ctx: Context<PlaceDepositBid>, bid_amount: u64) -> Result<()> {
+        ctx.accounts.validate()?;
+        check_not_blocked(&ctx.accounts.bidder_blocklist_entry)?;
+        let auction = &mut ctx.accounts.auction;
+        let terms = &mut ctx.accounts.deposit_terms;
+        let bidder = ctx.accounts.bidder.key();
+        let clock = Clock::get()?;
+
+        require_independent_bidder(auction, &ctx.accounts.creator_links, &bidder)?;
+        require!(!auction.has_ended(&clock), AuctionError::AuctionEnded);
+        require!(
+            auction.deposit_mode == DepositMode::Unsettled,
+            AuctionError::NotDepositAuction
+        );
+        auction.check_bid_amount(bid_amount, None)?;
+        require_registration(auction, &ctx.accounts.registration)?;
+        require_allowlisted(auction, &ctx.accounts.allowlist_entry)?;
+        require!(!auction.has_bid_gate(), AuctionError::BidGated);
+        track_bidder(
+            auction,
+            ctx.accounts.bidder_stats.as_mut(),
+            ctx.accounts.bidder.key(),
+            ctx.bumps.bidder_stats,
+            clock.unix_timestamp,
+        )?;
+        require_keys_neq!(bidder, auction.highest_bidder, AuctionError::AlreadyLeading);
+        if auction.highest_bid > 0 {
+            auction.contested = true;
+        }
+
+        if terms.runner_up_deposit > 0 {
+            // Release the displaced runner-up's deposit
+            let bid_escrow = ctx
+                .accounts
+                .runner_up_escrow
+                .as_mut()
+                .ok_or(AuctionError::MissingBidEscrow)?;
+            bid_escrow.open(
+                auction.key(),
+                terms.runner_up,
+                bidder,
+                ctx.bumps.runner_up_escrow.unwrap_or_default(),
+            );
+            credit_bid_escrow(
+                &ctx.accounts.system_program,
+                &ctx.accounts.auction_escrow,
+                ctx.bumps.auction_escrow,
+                auction.key(),
+                bid_escrow,
+                terms.runner_up_deposit,
+            )?;
+        }
+        terms.runner_up = auction.highest_bidder;
+        terms.runner_up_bid = auction.highest_bid;
+        terms.runner_up_deposit = terms.deposit;
+
+        let deposit = terms.deposit_for(bid_amount);
+        let transfer_ctx = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            anchor_lang::system_program::Transfer {
+                from: ctx.accounts.bidder.to_account_info(),
+                to: ctx.accounts.auction_escrow.to_account_info(),
+            },
+        );
+        anchor_lang::system_program::transfer(transfer_ctx, deposit)?;
+        terms.deposit = deposit;
+
+        auction.highest_bid = bid_amount;
+        auction.highest_bidder = bidder;
+        auction.apply_extension(clock.unix_timestamp);
+        auction.highest_bid_wrapped = false;
+
+        emit!(BidPlaced {
+            auction_id: auction.key(),
+            bidder,
+            bid_amount,
+        });
+
+        Ok(())
+    }
+
+    /// Pays the balance of a deposit-bidding auction's winning bid into the
+    /// escrow once bidding has closed, making the auction ready for
+    /// `finalize_auction`. The runner-up's deposit is credited back to their
+    /// `BidEscrow`. Only the winner, before the settlement deadline.
+    pub fn settle_winning_bid(ctx: Context<SettleWinningBid>) -> Result<()> {
+        ctx.accounts.validate()?;
+        let auction = &mut ctx.accounts.auction;
+        let terms = &mut ctx.accounts.deposit_terms;
+        let clock = Clock::get()?;
+
+        require!(auction.status.can_settle(), AuctionError::AuctionNotActive);
+        require!(
+            auction.deposit_mode == DepositMode::Unsettled,
+            AuctionError::NotDepositAuction
+        );
+        require!(auction.is_closed(&clock), AuctionError::AuctionNotEnded);
+        require!(!auction.is_paused(), AuctionError::AuctionPaused);
+        require!(
+            clock.unix_timestamp < terms.deadline(auction.end_time),
+            AuctionError::SettlementWindowClosed
+        );
+        require!(auction.highest_bid > 0, AuctionError::EmptyEscrow);
+        require_keys_eq!(
+            ctx.accounts.winner.key(),
+            auction.highest_bidder,
+            AuctionError::Unauthorized
+        );
+
+        let balance = auction.highest_bid - terms.deposit;
+        let transfer_ctx = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            anchor_lang::system_program::Transfer {
+                from: ctx.accounts.winner.to_account_info(),
+                to: ctx.accounts.auction_escrow.to_account_info(),
+            },
+        );
+        anchor_lang::system_program::transfer(transfer_ctx, balance)?;
+
+        if terms.runner_up_deposit > 0 {
+            let bid_escrow = ctx
+                .accounts
+                .runner_up_escrow
+                .as_mut()
+                .ok_or(AuctionError::MissingBidEscrow)?;
+            bid_escrow.open(
+                auction.key(),
+                terms.runner_up,
+                ctx.accounts.winner.key(),
+                ctx.bumps.runner_up_escrow.unwrap_or_default(),
+            );
+            credit_bid_escrow(
+                &ctx.accounts.system_program,
+                &ctx.accounts.auction_escrow,
+                ctx.bumps.auction_escrow,
+                auction.key(),
+                bid_escrow,
+                terms.runner_up_deposit,
+            )?;
+        }
+        terms.runner_up = Pubkey::default();
+        terms.runner_up_bid = 0;
+        terms.runner_up_deposit = 0;
+        terms.deposit = auction.highest_bid;
+        auction.deposit_mode = DepositMode::Settled;
+
+        emit!(WinningBidSettled {
+            auction_id: auction.key(),
+            winner: auction.highest_bidder,
+            balance,
+        });
+
+        Ok(())
+    }
+
+    /// Forfeits the deposit of a winner who missed the settlement deadline
+    /// to the seller and offers the lot to the runner-up at their own bid,
+    /// with a fresh settlement window. Without a runner-up the auction is
+    /// left without bids and finalizes as unsold. Permissionless.
+    pub fn forfeit(ctx: Context<Forfeit>) -> Result<()> {
+        ctx.accounts.validate()?;
+        let auction = &mut ctx.accounts.auction;
+        let terms = &mut ctx.accounts.deposit_terms;
+        let clock = Clock::get()?;
+
+        require!(auction.status.can_settle(), AuctionError::AuctionNotActive);
+        require!(
+            auction.deposit_mode == DepositMode::Unsettled,
+            AuctionError::NotDepositAuction
+        );
+        require!(auction.highest_bid > 0, AuctionError::EmptyEscrow);
+        require!(!auction.is_paused(), AuctionError::AuctionPaused);
+        require!(
+            auction.is_closed(&clock) && clock.unix_timestamp >= terms.deadline(auction.end_time),
+            AuctionError::SettlementWindowOpen
+        );
+
+        let forfeited = terms.deposit;
+        pay_from_native_escrow(
+            &ctx.accounts.system_program,
+            &ctx.accounts.auction_escrow,
+            ctx.bumps.auction_escrow,
+            auction.key(),
+            &ctx.accounts.creator,
+            forfeited,
+        )?;
+
+        emit!(BidForfeited {
+            auction_id: auction.key(),
+            bidder: auction.highest_bidder,
+            deposit: forfeited,
+        });
+
+        auction.highest_bidder = terms.runner_up;
+        auction.highest_bid = terms.runner_up_bid;
+        terms.deposit = terms.runner_up_deposit;
+        terms.runner_up = Pubkey::default();
+        terms.runner_up_bid = 0;
+        terms.runner_up_deposit = 0;
+        terms.settle_by = clock.unix_timestamp + terms.settlement_window;
+
+        Ok(())
+    }
+
+    /// Sets the share of a retracted bid that `retract_bid` keeps for the
+    /// treasury. Fee manager only.
+    pub fn set_retraction_penalty(
+        ctx: Context<SetRetractionPenalty>,
+        penalty_bps: u16,
+    ) -> Result<()> {
+        ctx.accounts.validate()?;
+        require!(
+            penalty_bps <= BPS_DENOMINATOR,
+            AuctionError::InvalidRetractionPenalty
+        );
+
+        let config = &mut ctx.accounts.config;
+        config.retraction_penalty_bps = penalty_bps;
+
+        emit!(RetractionPenaltySet { penalty_bps });
+
+        Ok(())
+    }
+
+    /// Sets how many seconds in all a creator may add to an auction's
+    /// `end_time` with `extend_end_time`; `0` turns extensions off. Admin
+    /// only.
+    pub fn set_max_creator_extension(
+        ctx: Context<SetMaxCreatorExtension>,
+        max_extension: i64,
+    ) -> Result<()> {
+        ctx.accounts.validate()?;
+        require!(max_extension >= 0, AuctionError::InvalidDuration);
+
+        let config = &mut ctx.accounts.config;
+        config.max_creator_extension = max_extension;
+
+        emit!(MaxCreatorExtensionSet { max_extension });
+
+        Ok(())
+    }
+
+    /// Sets how many seconds past its end time an unfinalized auction may be
+    /// unwound by anyone with `force_refund`; `0` turns it off. Admin only.
+    pub fn set_force_refund_after(ctx: Context<SetForceRefundAfter>, window: i64) -> Result<()> {
+        ctx.accounts.validate()?;
+        require!(window >= 0, AuctionError::InvalidDuration);
+
+        let config = &mut ctx.accounts.config;
+        config.force_refund_after = window;
+
+        emit!(ForceRefundWindowSet { window });
+
+        Ok(())
+    }
+
+    /// Sets how many seconds after `cancel_auction` a creator must wait to
+    /// list the same mint again; `0` turns the cooldown off. Admin only.
+    pub fn set_relist_cooldown(ctx: Context<SetRelistCooldown>, cooldown: i64) -> Result<()> {
+        ctx.accounts.validate()?;
+        require!(cooldown >= 0, AuctionError::InvalidDuration);
+
+        let config = &mut ctx.accounts.config;
+        config.relist_cooldown = cooldown;
+
+        emit!(RelistCooldownSet { cooldown });
+
+        Ok(())
+    }
+
+    /// Sets the discount winners holding at least `min_balance` of
+    /// `holder_mint` get back out of the platform fee; `0` turns it off.
+    /// Fee manager only.
+    pub fn set_holder_discount(
+        ctx: Context<SetHolderDiscount>,
+        holder_mint: Pubkey,
+        min_balance: u64,
+        discount_bps: u16,
+    ) -> Result<()> {
+        ctx.accounts.validate()?;
+        require!(
+            discount_bps <= BPS_DENOMINATOR,
+            AuctionError::InvalidHolderDiscount
+        );
+
+        let config = &mut ctx.accounts.config;
+        config.holder_mint = holder_mint;
+        config.holder_min_balance = min_balance;
+        config.holder_discount_bps = discount_bps;
+
+        emit!(HolderDiscountSet {
+            holder_mint,
+            min_balance,
+            discount_bps,
+        });
+
+        Ok(())
+    }
+
+    /// Withdraws a bid on a SOL auction while bidding is open. The runner-up
+    /// of a deposit-bidding auction gets their deposit back; a leader nobody
+    /// has bid against gets their bid or deposit back and leaves the auction
+    /// without bids. The config's retraction penalty, taken on the bid
+    /// amount and capped at what is escrowed, goes to the treasury.
+    pub fn retract_bid(ctx: Context<RetractBid>) -> Result<()> {
+        ctx.accounts.validate()?;
+        let auction = &mut ctx.accounts.auction;
+        let bidder = ctx.accounts.bidder.key();
+        let clock = Clock::get()?;
+
+        require!(
+            auction.status == AuctionStatus::Live,
+            AuctionError::AuctionNotActive
+        );
+        require!(!auction.has_ended(&clock), AuctionError::AuctionEnded);
+        require!(auction.is_native(), AuctionError::InvalidBidCurrency);
+        require!(
+            auction.deposit_mode == DepositMode::Off || ctx.accounts.deposit_terms.is_some(),
+            AuctionError::MissingDepositTerms
+        );
+
+        let runner_up = ctx
+            .accounts
+            .deposit_terms
+            .as_ref()
+            .is_some_and(|terms| terms.runner_up == bidder && terms.runner_up_deposit > 0);
+        let (bid_amount, escrowed) = if runner_up {
+            let terms = ctx.accounts.deposit_terms.as_mut().unwrap();
+            let retracted = (terms.runner_up_bid, terms.runner_up_deposit);
+            terms.runner_up = Pubkey::default();
+            terms.runner_up_bid = 0;
+            terms.runner_up_deposit = 0;
+            retracted
+        } else if auction.highest_bidder == bidder && auction.highest_bid > 0 && !auction.contested {
+            let escrowed = match ctx.accounts.deposit_terms.as_mut() {
+                Some(terms) => std::mem::take(&mut terms.deposit),
+                None => auction.highest_bid,
+            };
+            let retracted = (auction.highest_bid, escrowed);
+            auction.highest_bid = 0;
+            auction.highest_bidder = Pubkey::default();
+            auction.highest_bid_wrapped = false;
+            auction.proxy_bid_active = false;
+            retracted
+        } else {
+            return err!(AuctionError::RetractionNotAllowed);
+        };
+
+        let (penalty, _) = split_proceeds(bid_amount, ctx.accounts.config.retraction_penalty_bps);
+        let penalty = penalty.min(escrowed);
+        if penalty > 0 {
+            pay_from_native_escrow(
+                &ctx.accounts.system_program,
+                &ctx.accounts.auction_escrow,
+                ctx.bumps.auction_escrow,
+                auction.key(),
+                &ctx.accounts.treasury,
+                penalty,
+            )?;
+        }
+        let refund = escrowed - penalty;
+        pay_from_native_escrow(
+            &ctx.accounts.system_program,
+            &ctx.accounts.auction_escrow,
+            ctx.bumps.auction_escrow,
+            auction.key(),
+            &ctx.accounts.bidder.to_account_info(),
+            refund,
+        )?;
+
+        emit!(BidRetracted {
+            auction_id: auction.key(),
+            bidder,
+            refund,
+            penalty,
+        });
+
+        Ok(())
+    }
+
+    /// Limits each wallet to one bid per `cooldown` seconds on a SOL
+    /// auction; `0` lifts the limit. Bids must then pass the bidder's
+    /// `BidderStats`. Only the creator, and only before the first bid.
+    pub fn set_bid_cooldown(ctx: Context<SetBidCooldown>, cooldown: i64) -> Result<()> {
+        ctx.accounts.validate()?;
+        let auction = &mut ctx.accounts.auction;
+
+        require!(
+            auction.status == AuctionStatus::Live,
+            AuctionError::AuctionNotActive
+        );
+        require!(!auction.has_bids(), AuctionError::AuctionHasBids);
+        require_keys_eq!(
+            auction.creator,
+            ctx.accounts.creator.key(),
+            AuctionError::UnauthorizedUpdate
+        );
+        require!(auction.is_native(), AuctionError::InvalidBidCurrency);
+        require!(cooldown >= 0, AuctionError::InvalidDuration);
+
+        auction.bid_cooldown = cooldown;
+
+        emit!(BidCooldownSet {
+            auction_id: auction.key(),
+            cooldown,
+        });
+
+        Ok(())
+    }
+
+    /// Caps how many bids any one wallet may place on a SOL auction; `0`
+    /// lifts the cap. Bids must then pass the bidder's `BidderStats`. Only
+    /// the creator, and only before the first bid.
+    pub fn set_bid_cap(ctx: Context<SetBidCap>, max_bids_per_wallet: u32) -> Result<()> {
+        ctx.accounts.validate()?;
+        let auction = &mut ctx.accounts.auction;
+
+        require!(
+            auction.status == AuctionStatus::Live,
+            AuctionError::AuctionNotActive
+        );
+        require!(!auction.has_bids(), AuctionError::AuctionHasBids);
+        require_keys_eq!(
+            auction.creator,
+            ctx.accounts.creator.key(),
+            AuctionError::UnauthorizedUpdate
+        );
+        require!(auction.is_native(), AuctionError::InvalidBidCurrency);
+
+        auction.max_bids_per_wallet = max_bids_per_wallet;
+
+        emit!(BidCapSet {
+            auction_id: auction.key(),
+            max_bids_per_wallet,
+        });
+
+        Ok(())
+    }
+
+    /// Authorizes `session_key` to place SOL bids for the owner until
+    /// `expires_at`, spending at most `spend_limit` lamports, which are
+    /// moved into the session up front. Bids it places are the owner's:
+    /// refunds and won lots go to the owner.
+    pub fn authorize_session_key(
+        ctx: Context<AuthorizeSessionKey>,
+        session_key: Pubkey,
+        spend_limit: u64,
+        expires_at: i64,
+    ) -> Result<()> {
+        ctx.accounts.validate()?;
+        let clock = Clock::get()?;
+        require!(
+            expires_at > clock.unix_timestamp,
+            AuctionError::InvalidDuration
+        );
+
+        let transfer_ctx = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            anchor_lang::system_program::Transfer {
+                from: ctx.accounts.owner.to_account_info(),
+                to: ctx.accounts.session.to_account_info(),
+            },
+        );
+        anchor_lang::system_program::transfer(transfer_ctx, spend_limit)?;
+
+        let session = &mut ctx.accounts.session;
+        session.owner = ctx.accounts.owner.key();
+        session.session_key = session_key;
+        session.spend_limit = spend_limit;
+        session.spent = 0;
+        session.expires_at = expires_at;
+        session.bump = ctx.bumps.session;
+
+        emit!(SessionKeyAuthorized {
+            owner: session.owner,
+            session_key,
+            spend_limit,
+            expires_at,
+        });
+
+        Ok(())
+    }
+
+    /// Revokes a session key, returning its unspent funds and rent to the
+    /// owner.
+    pub fn revoke_session_key(ctx: Context<RevokeSessionKey>) -> Result<()> {
+        ctx.accounts.validate()?;
+        let session = &ctx.accounts.session;
+
+        emit!(SessionKeyRevoked {
+            owner: session.owner,
+            session_key: session.session_key,
+            unspent: session.spend_limit - session.spent,
+        });
+
+        Ok(())
+    }
+
+    /// Adds `amount` lamports to the owner's bid deposit, which `place_bid`
+    /// can draw on in relayer-submitted transactions.
+    pub fn fund_bid_deposit(ctx: Context<FundBidDeposit>, amount: u64) -> Result<()> {
+        ctx.accounts.validate()?;
+
+        let transfer_ctx = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            anchor_lang::system_program::Transfer {
+                from: ctx.accounts.owner.to_account_info(),
+                to: ctx.accounts.bid_deposit.to_account_info(),
+            },
+        );
+        anchor_lang::system_program::transfer(transfer_ctx, amount)?;
+
+        let bid_deposit = &mut ctx.accounts.bid_deposit;
+        if bid_deposit.owner == Pubkey::default() {
+            bid_deposit.owner = ctx.accounts.owner.key();
+            bid_deposit.bump = ctx.bumps.bid_deposit;
+        }
+        bid_deposit.balance = bid_deposit
+            .balance
+            .checked_add(amount)
+            .ok_or(AuctionError::MathOverflow)?;
+
+        emit!(BidDepositFunded {
+            owner: bid_deposit.owner,
+            amount,
+            balance: bid_deposit.balance,
+        });
+
+        Ok(())
+    }
+
+    /// Takes `amount` lamports back out of the owner's bid deposit.
+    pub fn withdraw_bid_deposit(ctx: Context<WithdrawBidDeposit>, amount: u64) -> Result<()> {
+        ctx.accounts.validate()?;
+        let bid_deposit = &mut ctx.accounts.bid_deposit;
+        require!(
+            bid_deposit.balance >= amount,
+            AuctionError::InsufficientDeposit
+        );
+
+        **bid_deposit.to_account_info().try_borrow_mut_lamports()? -= amount;
+        **ctx.accounts.owner.to_account_info().try_borrow_mut_lamports()? += amount;
+        bid_deposit.balance -= amount;
+
+        emit!(BidDepositWithdrawn {
+            owner: bid_deposit.owner,
+            amount,
+            balance: bid_deposit.balance,
+        });
+
+        Ok(())
+    }
+
+    /// Replaces the flat minimum increment with a table of tiers keyed on
+    /// the standing bid, e.g. +0.1 SOL below 10 SOL and +0.5 SOL below
+    /// 100 SOL. Tiers must be sorted by `below`; bids at or above the last
+    /// threshold use the last tier's increment. Only `place_bid` can bid on
+    /// the auction afterwards.
+    pub fn set_increment_tiers(
+        ctx: Context<SetIncrementTiers>,
+        tiers: Vec<IncrementTier>,
+    ) -> Result<()> {
+        ctx.accounts.validate()?;
+        let auction = &mut ctx.accounts.auction;
+
+        require!(
+            auction.status == AuctionStatus::Live,
+            AuctionError::AuctionNotActive
+        );
+        require!(!auction.has_bids(), AuctionError::AuctionHasBids);
+        require_keys_eq!(
+            auction.creator,
+            ctx.accounts.creator.key(),
+            AuctionError::UnauthorizedUpdate
+        );
+        require!(auction.is_native(), AuctionError::InvalidBidCurrency);
+        require!(
+            auction.deposit_mode == DepositMode::Off,
+            AuctionError::DepositAuction
+        );
+        require!(
+            !tiers.is_empty() && tiers.len() <= MAX_INCREMENT_TIERS,
+            AuctionError::InvalidIncrementTiers
+        );
+        require!(
+            tiers.iter().all(|tier| tier.increment > 0)
+                && tiers.windows(2).all(|pair| pair[0].below < pair[1].below),
+            AuctionError::InvalidIncrementTiers
+        );
+
+        let increment_tiers = &mut ctx.accounts.increment_tiers;
+        increment_tiers.auction = auction.key();
+        increment_tiers.tiers = tiers.clone();
+        increment_tiers.bump = ctx.bumps.increment_tiers;
+        auction.tiered_increments = true;
+
+        emit!(IncrementTiersSet {
+            auction_id: auction.key(),
+            tiers,
+        });
+
+        Ok(())
+    }
+
+    /// Routes bids from the last `CLOSING_QUEUE_WINDOW` seconds into a
+    /// queue that `finalize_auction` resolves by amount, then slot, so the
+    /// order transactions land in within the final minute no longer decides
+    /// the winner.
+    pub fn enable_closing_queue(ctx: Context<EnableClosingQueue>) -> Result<()> {
+        ctx.accounts.validate()?;
+        let auction = &mut ctx.accounts.auction;
+
+        require!(
+            auction.status == AuctionStatus::Live,
+            AuctionError::AuctionNotActive
+        );
+        require!(!auction.has_bids(), AuctionError::AuctionHasBids);
+        require_keys_eq!(
+            auction.creator,
+            ctx.accounts.creator.key(),
+            AuctionError::UnauthorizedUpdate
+        );
+        require!(auction.is_native(), AuctionError::InvalidBidCurrency);
+        require!(
+            auction.asset_kind == AssetKind::Spl
+                && !auction.partial_fills
+                && !auction.is_compressed,
+            AuctionError::InvalidAsset
+        );
+        require!(
+            auction.deposit_mode == DepositMode::Off,
+            AuctionError::DepositAuction
+        );
+
+        let queue = &mut ctx.accounts.closing_queue;
+        queue.auction = auction.key();
+        queue.bump = ctx.bumps.closing_queue;
+        auction.closing_queue = true;
+
+        emit!(ClosingQueueEnabled {
+            auction_id: auction.key(),
+        });
+
+        Ok(())
+    }
+
+    /// Queues a bid of `amount` during the closing window, escrowing it in
+    /// the queue. It must clear the standing bid like any other bid. Queuing
+    /// again raises the bidder's entry, paying only the difference, and
+    /// moves it to the current slot.
+    pub fn queue_bid(ctx: Context<QueueBid>, amount: u64) -> Result<()> {
+        ctx.accounts.validate()?;
+        check_not_blocked(&ctx.accounts.bidder_blocklist_entry)?;
+        let auction = &ctx.accounts.auction;
+        let clock = Clock::get()?;
+
+        require_independent_bidder(
+            auction,
+            &ctx.accounts.creator_links,
+            &ctx.accounts.bidder.key(),
+        )?;
+        require!(!auction.has_ended(&clock), AuctionError::AuctionEnded);
+        require!(
+            auction.queue_open(clock.unix_timestamp),
+            AuctionError::ClosingQueueClosed
+        );
+        auction.check_bid_terms(amount, ctx.accounts.increment_tiers.as_deref())?;
+        require_registration(auction, &ctx.accounts.registration)?;
+        require_allowlisted(auction, &ctx.accounts.allowlist_entry)?;
+        require!(!auction.has_bid_gate(), AuctionError::BidGated);
+        track_bidder(
+            auction,
+            ctx.accounts.bidder_stats.as_mut(),
+            ctx.accounts.bidder.key(),
+            ctx.bumps.bidder_stats,
+            clock.unix_timestamp,
+        )?;
+
+        let bidder = ctx.accounts.bidder.key();
+        let owed = ctx
+            .accounts
+            .closing_queue
+            .enqueue(bidder, amount, clock.slot)?;
+
+        let transfer_ctx = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            anchor_lang::system_program::Transfer {
+                from: ctx.accounts.bidder.to_account_info(),
+                to: ctx.accounts.closing_queue.to_account_info(),
+            },
+        );
+        anchor_lang::system_program::transfer(transfer_ctx, owed)?;
+
+        emit!(BidQueued {
+            auction_id: auction.key(),
+            bidder,
+            amount,
+            slot: clock.slot,
+        });
+
+        Ok(())
+    }
+
+    /// Returns a queued bid that did not win, once the queue is resolved or
+    /// the auction ended without resolving it.
+    pub fn withdraw_queued_bid(ctx: Context<WithdrawQueuedBid>) -> Result<()> {
+        ctx.accounts.validate()?;
+        let queue = &mut ctx.accounts.closing_queue;
+
+        require!(
+            queue.resolved || !ctx.accounts.auction.status.is_open(),
+            AuctionError::ClosingQueuePending
+        );
+        let bidder = ctx.accounts.bidder.key();
+        let index = queue
+            .entries
+            .iter()
+            .position(|entry| entry.bidder == bidder)
+            .ok_or(AuctionError::NoQueuedBid)?;
+        let entry = queue.entries.swap_remove(index);
+
+        **queue.to_account_info().try_borrow_mut_lamports()? -= entry.amount;
+        **ctx.accounts.bidder.to_account_info().try_borrow_mut_lamports()? += entry.amount;
+
+        emit!(QueuedBidWithdrawn {
+            auction_id: queue.auction,
+            bidder,
+            amount: entry.amount,
+        });
+
+        Ok(())
+    }
+
+    /// Makes each new highest bid stand for `min_standing_time` seconds
+    /// before it can be outbid; bids arriving sooner are rejected. `0` lifts
+    /// it. Only the creator, and only before the first bid.
+    pub fn set_min_standing_time(
+        ctx: Context<SetMinStandingTime>,
+        min_standing_time: u16,
+    ) -> Result<()> {
+        ctx.accounts.validate()?;
+        let auction = &mut ctx.accounts.auction;
+
+        require!(
+            auction.status == AuctionStatus::Live,
+            AuctionError::AuctionNotActive
+        );
+        require!(!auction.has_bids(), AuctionError::AuctionHasBids);
+        require_keys_eq!(
+            auction.creator,
+            ctx.accounts.creator.key(),
+            AuctionError::UnauthorizedUpdate
+        );
+
+        auction.min_standing_time = min_standing_time;
+
+        emit!(MinStandingTimeSet {
+            auction_id: auction.key(),
+            min_standing_time,
+        });
+
+        Ok(())
+    }
+
+    /// Requires bidders on a SOL auction to register with a refundable
+    /// `deposit` before they can bid; `0` opens bidding to anyone again.
+    /// Only the creator, and only before the first bid.
+    pub fn set_registration_deposit(
+        ctx: Context<SetRegistrationDeposit>,
+        deposit: u64,
+    ) -> Result<()> {
+        ctx.accounts.validate()?;
+        let auction = &mut ctx.accounts.auction;
+
+        require!(
+            auction.status == AuctionStatus::Live,
+            AuctionError::AuctionNotActive
+        );
+        require!(!auction.has_bids(), AuctionError::AuctionHasBids);
+        require_keys_eq!(
+            auction.creator,
+            ctx.accounts.creator.key(),
+            AuctionError::UnauthorizedUpdate
+        );
+        require!(auction.is_native(), AuctionError::InvalidBidCurrency);
+
+        auction.registration_deposit = deposit;
+
+        emit!(RegistrationDepositSet {
+            auction_id: auction.key(),
+            deposit,
+        });
+
+        Ok(())
+    }
+
+    /// Registers the signer as a bidder on an auction that requires it,
+    /// locking the auction's registration deposit until it closes.
+    pub fn register_bidder(ctx: Context<RegisterBidder>) -> Result<()> {
+        ctx.accounts.validate()?;
+        let auction = &ctx.accounts.auction;
+
+        require!(
+            auction.status == AuctionStatus::Live,
+            AuctionError::AuctionNotActive
+        );
+        require!(
+            auction.registration_deposit > 0,
+            AuctionError::RegistrationNotRequired
+        );
+
+        let deposit = auction.registration_deposit;
+        let transfer_ctx = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            anchor_lang::system_program::Transfer {
+                from: ctx.accounts.bidder.to_account_info(),
+                to: ctx.accounts.registration.to_account_info(),
+            },
+        );
+        anchor_lang::system_program::transfer(transfer_ctx, deposit)?;
+
+        let registration = &mut ctx.accounts.registration;
+        registration.auction = auction.key();
+        registration.bidder = ctx.accounts.bidder.key();
+        registration.deposit = deposit;
+        registration.bump = ctx.bumps.registration;
+
+        emit!(BidderRegistered {
+            auction_id: auction.key(),
+            bidder: registration.bidder,
+            deposit,
+        });
+
+        Ok(())
+    }
+
+    /// Returns a registration's deposit, and its rent, once the auction is
+    /// no longer active.
+    pub fn withdraw_registration(ctx: Context<WithdrawRegistration>) -> Result<()> {
+        ctx.accounts.validate()?;
+        require!(
+            !ctx.accounts.auction.status.is_open(),
+            AuctionError::RegistrationLocked
+        );
+
+        let registration = &ctx.accounts.registration;
+        emit!(RegistrationWithdrawn {
+            auction_id: registration.auction,
+            bidder: registration.bidder,
+            deposit: registration.deposit,
+        });
+
+        Ok(())
+    }
+
+    /// Pays out and closes outbid bidders' escrows on a closed auction in
+    /// place of their own `claim_refund`. Open to anyone; a registered
+    /// keeper earns `SWEEP_BOUNTY` per escrow swept from the rebate pool.
+    /// Remaining accounts are `[bid_escrow, bidder, rent_payer]` triples.
+    pub fn sweep_refunds<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SweepRefunds<'info>>,
+    ) -> Result<()> {
+        ctx.accounts.validate()?;
+        let auction = &ctx.accounts.auction;
+        require!(!auction.status.is_open(), AuctionError::AuctionNotEnded);
+        require!(
+            ctx.remaining_accounts
+                .chunks_exact(3)
+                .remainder()
+                .is_empty(),
+            AuctionError::InvalidSweepAccounts
+        );
+
+        let mut swept = 0u64;
+        for accounts in ctx.remaining_accounts.chunks_exact(3) {
+            let (bidder, rent_payer) = (&accounts[1], &accounts[2]);
+            let bid_escrow = Account::<BidEscrow>::try_from(&accounts[0])?;
+            require!(
+                bid_escrow.auction == auction.key()
+                    && bid_escrow.bidder == bidder.key()
+                    && bid_escrow.rent_payer == rent_payer.key(),
+                AuctionError::InvalidSweepAccounts
+            );
+
+            let amount = bid_escrow.refundable;
+            **bid_escrow.to_account_info().try_borrow_mut_lamports()? -= amount;
+            **bidder.try_borrow_mut_lamports()? += amount;
+            bid_escrow.close(rent_payer.clone())?;
+            swept += 1;
+
+            emit!(RefundClaimed {
+                auction_id: auction.key(),
+                bidder: bidder.key(),
+                amount,
+            });
+        }
+
+        let bounty = match (
+            ctx.accounts.rebate_pool.as_mut(),
+            ctx.accounts.keeper_account.as_mut(),
+        ) {
+            (Some(pool), Some(keeper)) => credit_sweep_bounty(pool, keeper, auction.key(), swept)?,
+            _ => 0,
+        };
+
+        emit!(RefundsSwept {
+            auction_id: auction.key(),
+            swept,
+            bounty,
+        });
+
+        Ok(())
+    }
+
+    /// Closes a bid receipt once its auction is no longer active, returning
+    /// the rent to whoever funded it.
+    pub fn close_bid_receipt(ctx: Context<CloseBidReceipt>) -> Result<()> {
+        ctx.accounts.validate()?;
+        require!(
+            !ctx.accounts.auction.status.is_open(),
+            AuctionError::AuctionNotEnded
+        );
+        Ok(())
+    }
+
+    /// Gives each outbid leader of a SOL auction `window` seconds to match
+    /// the bid that outbids them before it stands; `0` turns it off. Only
+    /// the creator, and only before the first bid.
+    pub fn set_first_refusal_window(
+        ctx: Context<SetFirstRefusalWindow>,
+        window: i64,
+    ) -> Result<()> {
+        ctx.accounts.validate()?;
+        let auction = &mut ctx.accounts.auction;
+
+        require!(
+            auction.status == AuctionStatus::Live,
+            AuctionError::AuctionNotActive
+        );
+        require!(!auction.has_bids(), AuctionError::AuctionHasBids);
+        require_keys_eq!(
+            auction.creator,
+            ctx.accounts.creator.key(),
+            AuctionError::UnauthorizedUpdate
+        );
+        require!(auction.is_native(), AuctionError::InvalidBidCurrency);
+        require!(
+            auction.asset_kind == AssetKind::Spl
+                && !auction.partial_fills
+                && !auction.is_compressed,
+            AuctionError::InvalidAsset
+        );
+        require!(window >= 0, AuctionError::InvalidDuration);
+
+        auction.first_refusal_window = window;
+
+        emit!(FirstRefusalWindowSet {
+            auction_id: auction.key(),
+            window,
+        });
+
+        Ok(())
+    }
+
+    /// Lets the leader keep the lead by matching the pending bid within the
+    /// first refusal window, paying the difference. The pending bid is
+    /// credited back to its bidder's refund escrow.
+    pub fn match_first_refusal(ctx: Context<MatchFirstRefusal>) -> Result<()> {
+        ctx.accounts.validate()?;
+        let auction = &mut ctx.accounts.auction;
+        let clock = Clock::get()?;
+
+        require!(auction.has_pending_bid(), AuctionError::NoPendingBid);
+        require!(!auction.is_paused(), AuctionError::AuctionPaused);
+        require_keys_eq!(
+            ctx.accounts.bidder.key(),
+            auction.highest_bidder,
+            AuctionError::Unauthorized
+        );
+        require!(
+            clock.unix_timestamp < auction.pending_until,
+            AuctionError::FirstRefusalExpired
+        );
+
+        let transfer_ctx = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            anchor_lang::system_program::Transfer {
+                from: ctx.accounts.bidder.to_account_info(),
+                to: ctx.accounts.auction_escrow.to_account_info(),
+            },
+        );
+        anchor_lang::system_program::transfer(
+            transfer_ctx,
+            auction.pending_bid - auction.highest_bid,
+        )?;
+
+        let bid_escrow = &mut ctx.accounts.pending_bid_escrow;
+        bid_escrow.open(
+            auction.key(),
+            auction.pending_bidder,
+            ctx.accounts.bidder.key(),
+            ctx.bumps.pending_bid_escrow,
+        );
+        credit_bid_escrow(
+            &ctx.accounts.system_program,
+            &ctx.accounts.auction_escrow,
+            ctx.bumps.auction_escrow,
+            auction.key(),
+            bid_escrow,
+            auction.pending_bid,
+        )?;
+
+        auction.highest_bid = auction.pending_bid;
+        auction.clear_pending_bid();
+
+        emit!(FirstRefusalExercised {
+            auction_id: auction.key(),
+            bidder: auction.highest_bidder,
+            bid_amount: auction.highest_bid,
+        });
+
+        Ok(())
+    }
+
+    /// Makes an unmatched pending bid the standing bid once the first
+    /// refusal window has passed, crediting the outbid leader's refund
+    /// escrow. Anyone may call it.
+    pub fn accept_pending_bid(ctx: Context<AcceptPendingBid>) -> Result<()> {
+        ctx.accounts.validate()?;
+        let auction = &mut ctx.accounts.auction;
+        let clock = Clock::get()?;
+
+        require!(auction.has_pending_bid(), AuctionError::NoPendingBid);
+        require!(!auction.is_paused(), AuctionError::AuctionPaused);
+        require!(
+            clock.unix_timestamp >= auction.pending_until,
+            AuctionError::FirstRefusalOpen
+        );
+
+        let bid_escrow = &mut ctx.accounts.previous_bid_escrow;
+        bid_escrow.open(
+            auction.key(),
+            auction.highest_bidder,
+            ctx.accounts.payer.key(),
+            ctx.bumps.previous_bid_escrow,
+        );
+        credit_bid_escrow(
+            &ctx.accounts.system_program,
+            &ctx.accounts.auction_escrow,
+            ctx.bumps.auction_escrow,
+            auction.key(),
+            bid_escrow,
+            auction.highest_bid,
+        )?;
+
+        auction.highest_bid = auction.pending_bid;
+        auction.highest_bidder = auction.pending_bidder;
+        auction.highest_bid_wrapped = false;
+        auction.proxy_bid_active = false;
+        auction.clear_pending_bid();
+
+        emit!(PendingBidAccepted {
+            auction_id: auction.key(),
+            bidder: auction.highest_bidder,
+            bid_amount: auction.highest_bid,
+        });
+
+        Ok(())
+    }
+
+    /// Sets the reserve price conditional bids are held to; `0` clears it.
+    /// Only the creator, and only before the first bid.
+    pub fn set_reserve_price(ctx: Context<SetReservePrice>, reserve_price: u64) -> Result<()> {
+        ctx.accounts.validate()?;
+        let auction = &mut ctx.accounts.auction;
+
+        require!(
+            auction.status == AuctionStatus::Live,
+            AuctionError::AuctionNotActive
+        );
+        require!(!auction.has_bids(), AuctionError::AuctionHasBids);
+        require_keys_eq!(
+            auction.creator,
+            ctx.accounts.creator.key(),
+            AuctionError::UnauthorizedUpdate
+        );
+        require!(auction.is_native(), AuctionError::InvalidBidCurrency);
+
+        auction.reserve_price = reserve_price;
+
+        emit!(ReservePriceSet {
+            auction_id: auction.key(),
+            reserve_price,
+        });
+
+        Ok(())
+    }
+
+    /// Lowers the reserve price while the auction is running, bids or not,
+    /// so a standing conditional bid may come to meet it; `0` drops it. It
+    /// can never be raised this way. Only the creator.
+    pub fn lower_reserve_price(ctx: Context<LowerReservePrice>, reserve_price: u64) -> Result<()> {
+        ctx.accounts.validate()?;
+        let auction = &mut ctx.accounts.auction;
+        let clock = Clock::get()?;
+
+        require!(
+            auction.status == AuctionStatus::Live,
+            AuctionError::AuctionNotActive
+        );
+        require!(!auction.has_ended(&clock), AuctionError::AuctionEnded);
+        require_keys_eq!(
+            auction.creator,
+            ctx.accounts.creator.key(),
+            AuctionError::UnauthorizedUpdate
+        );
+        require!(auction.reserve_price > 0, AuctionError::NoReservePrice);
+        require!(
+            reserve_price < auction.reserve_price,
+            AuctionError::ReserveNotLowered
+        );
+
+        let previous_reserve = auction.reserve_price;
+        auction.reserve_price = reserve_price;
+
+        emit!(ReserveLowered {
+            auction_id: auction.key(),
+            previous_reserve,
+            reserve_price,
+        });
+
+        Ok(())
+    }
+
+    /// Has `finalize_auction` turn the auction, if it ends without bids,
+    /// into a fixed-price listing at the reserve price that `buy_now` can
+    /// take, instead of leaving the NFT to go back to the creator. The
+    /// creator may still take it down with `cancel_auction`. Only the
+    /// creator, once a reserve is set, and only before the first bid.
+    pub fn enable_fixed_price_fallback(ctx: Context<EnableFixedPriceFallback>) -> Result<()> {
+        ctx.accounts.validate()?;
+        let auction = &mut ctx.accounts.auction;
+
+        require!(
+            auction.status == AuctionStatus::Live,
+            AuctionError::AuctionNotActive
+        );
+        require!(!auction.has_bids(), AuctionError::AuctionHasBids);
+        require_keys_eq!(
+            auction.creator,
+            ctx.accounts.creator.key(),
+            AuctionError::UnauthorizedUpdate
+        );
+        require!(auction.reserve_price > 0, AuctionError::NoReservePrice);
+        require!(
+            auction.is_native()
+                && auction.asset_kind == AssetKind::Spl
+                && !auction.partial_fills
+                && auction.deposit_mode == DepositMode::Off,
+            AuctionError::BuyNowNotAvailable
+        );
+
+        auction.fixed_price_fallback = true;
+
+        emit!(FixedPriceFallbackEnabled {
+            auction_id: auction.key(),
+        });
+
+        Ok(())
+    }
+
+    /// Once the auction has ended below its reserve, moves a standing
+    /// conditional bid into the bidder's refund escrow and clears it, so the
+    /// auction can be closed as unsold. Anyone may call this.
+    pub fn refund_conditional_bid(ctx: Context<RefundConditionalBid>) -> Result<()> {
+        ctx.accounts.validate()?;
+        let auction = &mut ctx.accounts.auction;
+        let clock = Clock::get()?;
+
+        require!(auction.is_closed(&clock), AuctionError::AuctionNotEnded);
+        require!(!auction.is_paused(), AuctionError::AuctionPaused);
+        require!(auction.status.can_settle(), AuctionError::AuctionNotActive);
+        require!(!auction.has_pending_bid(), AuctionError::BidPending);
+        require!(
+            auction.conditional_bid_unmet(),
+            AuctionError::NoConditionalBid
+        );
+
+        let bid_escrow = &mut ctx.accounts.bid_escrow;
+        bid_escrow.open(
+            auction.key(),
+            auction.highest_bidder,
+            ctx.accounts.payer.key(),
+            ctx.bumps.bid_escrow,
+        );
+        credit_bid_escrow(
+            &ctx.accounts.system_program,
+            &ctx.accounts.auction_escrow,
+            ctx.bumps.auction_escrow,
+            auction.key(),
+            bid_escrow,
+            auction.highest_bid,
+        )?;
+
+        emit!(ConditionalBidRefunded {
+            auction_id: auction.key(),
+            bidder: auction.highest_bidder,
+            bid_amount: auction.highest_bid,
+        });
+
+        auction.highest_bid = 0;
+        auction.highest_bidder = Pubkey::default();
+        auction.conditional_bidder = Pubkey::default();
+        auction.conditional_amount = 0;
+
+        Ok(())
+    }
+
+    /// Opens a syndicate pool for a SOL auction. Wallets fund it with
+    /// `contribute_to_pool` and the organizer bids the pooled lamports with
+    /// `place_pool_bid`, the pool bidding as a single bidder. A winning pool
+    /// receives the NFT in a token account it owns.
+    pub fn create_bid_pool(ctx: Context<CreateBidPool>) -> Result<()> {
+        ctx.accounts.validate()?;
+        let auction = &ctx.accounts.auction;
+
+        require!(
+            auction.status == AuctionStatus::Live,
+            AuctionError::AuctionNotActive
+        );
+        require!(auction.is_native(), AuctionError::InvalidBidCurrency);
+        require!(!auction.partial_fills, AuctionError::PartialFillAuction);
+
+        let pool = &mut ctx.accounts.bid_pool;
+        pool.auction = auction.key();
+        pool.organizer = ctx.accounts.organizer.key();
+        pool.bump = ctx.bumps.bid_pool;
+
+        emit!(BidPoolCreated {
+            auction_id: auction.key(),
+            pool: pool.key(),
+            organizer: pool.organizer,
+        });
+
+        Ok(())
+    }
+
+    /// Adds `amount` lamports to a bid pool, recording the contributor's
+    /// share of it.
+    pub fn contribute_to_pool(ctx: Context<ContributeToPool>, amount: u64) -> Result<()> {
+        ctx.accounts.validate()?;
+        require!(
+            ctx.accounts.auction.status == AuctionStatus::Live,
+            AuctionError::AuctionNotActive
+        );
+        require!(amount > 0, AuctionError::InvalidDeposit);
+
+        let transfer_ctx = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            anchor_lang::system_program::Transfer {
+                from: ctx.accounts.contributor.to_account_info(),
+                to: ctx.accounts.bid_pool.to_account_info(),
+            },
+        );
+        anchor_lang::system_program::transfer(transfer_ctx, amount)?;
+
+        let pool = &mut ctx.accounts.bid_pool;
+        let contribution = &mut ctx.accounts.contribution;
+        contribution.open(
+            pool.key(),
+            ctx.accounts.contributor.key(),
+            ctx.bumps.contribution,
+        );
+        contribution.amount = contribution
+            .amount
+            .checked_add(amount)
+            .ok_or(AuctionError::MathOverflow)?;
+        pool.total_contributed = pool
+            .total_contributed
+            .checked_add(amount)
+            .ok_or(AuctionError::MathOverflow)?;
+        pool.unwithdrawn += amount;
+
+        emit!(PoolContributed {
+            pool: pool.key(),
+            contributor: contribution.contributor,
+            amount,
+            total_contributed: pool.total_contributed,
+        });
+
+        Ok(())
+    }
+
+    /// Bids `bid_amount` of a pool's lamports with the pool as the bidder.
+    /// A pool already leading only spends the difference. Outbid pool bids
+    /// are credited to the pool's refund escrow and brought back with
+    /// `reclaim_pool_refund`.
+    pub fn place_pool_bid(ctx: Context<PlacePoolBid>, bid_amount: u64) -> Result<()> {
+        ctx.accounts.validate()?;
+        check_not_blocked(&ctx.accounts.organizer_blocklist_entry)?;
+        let auction = &mut ctx.accounts.auction;
+        let pool = &ctx.accounts.bid_pool;
+        let clock = Clock::get()?;
+
+        require_independent_bidder(
+            auction,
+            &ctx.accounts.creator_links,
+            &ctx.accounts.organizer.key(),
+        )?;
+        require!(!auction.has_ended(&clock), AuctionError::AuctionEnded);
+        require!(!auction.proxy_bid_active, AuctionError::ProxyBidActive);
+        require!(
+            auction.first_refusal_window == 0,
+            AuctionError::FirstRefusalAuction
+        );
+        auction.check_bid(bid_amount, None)?;
+        require_registration(auction, &ctx.accounts.registration)?;
+        require_allowlisted(auction, &ctx.accounts.allowlist_entry)?;
+        require!(!auction.has_bid_gate(), AuctionError::BidGated);
+        track_bidder(
+            auction,
+            ctx.accounts.bidder_stats.as_mut(),
+            pool.key(),
+            ctx.bumps.bidder_stats,
+            clock.unix_timestamp,
+        )?;
+
+        let top_up = auction.highest_bid > 0 && auction.highest_bidder == pool.key();
+        let owed = if top_up {
+            bid_amount - auction.highest_bid
+        } else {
+            bid_amount
+        };
+        let pool_info = pool.to_account_info();
+        let available = pool_info
+            .lamports()
+            .saturating_sub(Rent::get()?.minimum_balance(BidPool::LEN));
+        require!(available >= owed, AuctionError::InsufficientBidPoolFunds);
+
+        if auction.highest_bid > 0 && !top_up {
+            auction.contested = true;
+            let bid_escrow = ctx
+                .accounts
+                .previous_bid_escrow
+                .as_mut()
+                .ok_or(AuctionError::MissingBidEscrow)?;
+            bid_escrow.open(
+                auction.key(),
+                auction.highest_bidder,
+                ctx.accounts.organizer.key(),
+                ctx.bumps.previous_bid_escrow.unwrap_or_default(),
+            );
+            credit_bid_escrow(
+                &ctx.accounts.system_program,
+                &ctx.accounts.auction_escrow,
+                ctx.bumps.auction_escrow,
+                auction.key(),
+                bid_escrow,
+                auction.highest_bid,
+            )?;
+        }
+
+        let escrow_info = ctx.accounts.auction_escrow.to_account_info();
+        **pool_info.try_borrow_mut_lamports()? -= owed;
+        **escrow_info.try_borrow_mut_lamports()? += owed;
+
+        auction.highest_bid = bid_amount;
+        auction.highest_bidder = pool.key();
+        auction.apply_extension(clock.unix_timestamp);
+        auction.highest_bid_wrapped = false;
+
+        emit!(BidPlaced {
+            auction_id: auction.key(),
+            bidder: pool.key(),
+            bid_amount,
+        });
+
+        Ok(())
+    }
+
+    /// Moves a pool's outbid bids from its refund escrow back into the pool
+    /// and closes the escrow. Anyone may call it.
+    pub fn reclaim_pool_refund(ctx: Context<ReclaimPoolRefund>) -> Result<()> {
+        ctx.accounts.validate()?;
+        let bid_escrow = &ctx.accounts.bid_escrow;
+        let amount = bid_escrow.refundable;
+
+        let pool_info = ctx.accounts.bid_pool.to_account_info();
+        **bid_escrow.to_account_info().try_borrow_mut_lamports()? -= amount;
+        **pool_info.try_borrow_mut_lamports()? += amount;
+
+        emit!(RefundClaimed {
+            auction_id: bid_escrow.auction,
+            bidder: bid_escrow.bidder,
+            amount,
+        });
+
+        Ok(())
+    }
+
+    /// Pays a contributor their pro-rata share of what the pool holds once
+    /// the auction has closed: everything if the pool lost, the unspent
+    /// remainder if it won. The contribution stays on record as the
+    /// contributor's share in the pool.
+    pub fn withdraw_pool_share(ctx: Context<WithdrawPoolShare>) -> Result<()> {
+        ctx.accounts.validate()?;
+        require!(
+            !ctx.accounts.auction.status.is_open(),
+            AuctionError::AuctionNotEnded
+        );
+        require!(
+            ctx.accounts.pool_bid_escrow.data_is_empty(),
+            AuctionError::PoolRefundUnclaimed
+        );
+        let contribution = &mut ctx.accounts.contribution;
+        require!(!contribution.withdrawn, AuctionError::PoolShareWithdrawn);
+
+        let pool = &mut ctx.accounts.bid_pool;
+        let pool_info = pool.to_account_info();
+        let available = pool_info
+            .lamports()
+            .saturating_sub(Rent::get()?.minimum_balance(BidPool::LEN));
+        let amount =
+            (contribution.amount as u128 * available as u128 / pool.unwithdrawn as u128) as u64;
+
+        let contributor_info = ctx.accounts.contributor.to_account_info();
+        **pool_info.try_borrow_mut_lamports()? -= amount;
+        **contributor_info.try_borrow_mut_lamports()? += amount;
+        pool.unwithdrawn -= contribution.amount;
+        contribution.withdrawn = true;
+
+        emit!(PoolShareWithdrawn {
+            pool: pool.key(),
+            contributor: contribution.contributor,
+            amount,
+        });
+
+        Ok(())
+    }
+
+    /// Stores or replaces a bidder's auto-rebid strategy on a SOL auction:
+    /// whenever they are outbid, anyone may crank `execute_rebid` to bid
+    /// again for them out of their bid deposit, raising the standing bid by
+    /// `increment_bps` of itself (at least the minimum increment) up to
+    /// `max_price`.
+    pub fn set_rebid_strategy(
+        ctx: Context<SetRebidStrategy>,
+        max_price: u64,
+        increment_bps: u16,
+    ) -> Result<()> {
+        ctx.accounts.validate()?;
+        let auction = &ctx.accounts.auction;
+
+        require!(
+            auction.status == AuctionStatus::Live,
+            AuctionError::AuctionNotActive
+        );
+        require!(auction.is_native(), AuctionError::InvalidBidCurrency);
+        require!(
+            increment_bps <= BPS_DENOMINATOR,
+            AuctionError::InvalidRebidStrategy
+        );
+        require!(max_price > 0, AuctionError::InvalidRebidStrategy);
+
+        let strategy = &mut ctx.accounts.strategy;
+        strategy.auction = auction.key();
+        strategy.bidder = ctx.accounts.bidder.key();
+        strategy.max_price = max_price;
+        strategy.increment_bps = increment_bps;
+        strategy.bump = ctx.bumps.strategy;
+
+        emit!(RebidStrategySet {
+            auction_id: auction.key(),
+            bidder: strategy.bidder,
+            max_price,
+            increment_bps,
+        });
+
+        Ok(())
+    }
+
+    /// Closes a bidder's auto-rebid strategy, returning its rent.
+    pub fn cancel_rebid_strategy(ctx: Context<CancelRebidStrategy>) -> Result<()> {
+        ctx.accounts.validate()?;
+        Ok(())
+    }
+
+    /// Rebids for an outbid bidder per their stored strategy, paying the bid
+    /// out of their bid deposit. Anyone may call it; the caller fronts the
+    /// rent of the outbid leader's refund escrow.
+    pub fn execute_rebid(ctx: Context<ExecuteRebid>) -> Result<()> {
+        ctx.accounts.validate()?;
+        check_not_blocked(&ctx.accounts.bidder_blocklist_entry)?;
+        let auction = &mut ctx.accounts.auction;
+        let strategy = &ctx.accounts.strategy;
+        let bidder = strategy.bidder;
+        let clock = Clock::get()?;
+
+        require_independent_bidder(auction, &ctx.accounts.creator_links, &bidder)?;
+        require!(!auction.has_ended(&clock), AuctionError::AuctionEnded);
+        require!(
+            auction.highest_bid > 0 && auction.highest_bidder != bidder,
+            AuctionError::NotOutbid
+        );
+        require!(!auction.proxy_bid_active, AuctionError::ProxyBidActive);
+        require!(
+            auction.first_refusal_window == 0,
+            AuctionError::FirstRefusalAuction
+        );
+        let tiers = ctx.accounts.increment_tiers.as_deref();
+        let bid_amount = strategy
+            .next_bid(auction, tiers)
+            .ok_or(AuctionError::MathOverflow)?;
+        require!(
+            bid_amount <= strategy.max_price,
+            AuctionError::RebidAboveMax
+        );
+        auction.check_bid(bid_amount, tiers)?;
+        require_registration(auction, &ctx.accounts.registration)?;
+        require_allowlisted(auction, &ctx.accounts.allowlist_entry)?;
+        require!(!auction.has_bid_gate(), AuctionError::BidGated);
+        require!(
+            ctx.accounts.bid_deposit.balance >= bid_amount,
+            AuctionError::InsufficientDeposit
+        );
+        track_bidder(
+            auction,
+            ctx.accounts.bidder_stats.as_mut(),
+            bidder,
+            ctx.bumps.bidder_stats,
+            clock.unix_timestamp,
+        )?;
+
+        auction.contested = true;
+        let bid_escrow = &mut ctx.accounts.previous_bid_escrow;
+        bid_escrow.open(
+            auction.key(),
+            auction.highest_bidder,
+            ctx.accounts.cranker.key(),
+            ctx.bumps.previous_bid_escrow,
+        );
+        credit_bid_escrow(
+            &ctx.accounts.system_program,
+            &ctx.accounts.auction_escrow,
+            ctx.bumps.auction_escrow,
+            auction.key(),
+            bid_escrow,
+            auction.highest_bid,
+        )?;
+        spend_bid_deposit(
+            &mut ctx.accounts.bid_deposit,
+            &ctx.accounts.auction_escrow,
+            bid_amount,
+        )?;
+
+        auction.highest_bid = bid_amount;
+        auction.highest_bidder = bidder;
+        auction.apply_extension(clock.unix_timestamp);
+        auction.highest_bid_wrapped = false;
+
+        emit!(RebidExecuted {
+            auction_id: auction.key(),
+            bidder,
+            bid_amount,
+            cranker: ctx.accounts.cranker.key(),
+        });
+
+        Ok(())
+    }
+
+    /// Sets `rebate_bps` of the platform fee aside at settlement for the
+    /// auction's losing bidders, shared pro-rata to their best bids among
+    /// the top bids. Only the creator, and only before the first bid.
+    pub fn enable_loser_rebate(ctx: Context<EnableLoserRebate>, rebate_bps: u16) -> Result<()> {
+        ctx.accounts.validate()?;
+        let auction = &mut ctx.accounts.auction;
+
+        require!(
+            auction.status == AuctionStatus::Live,
+            AuctionError::AuctionNotActive
+        );
+        require!(!auction.has_bids(), AuctionError::AuctionHasBids);
+        require_keys_eq!(
+            auction.creator,
+            ctx.accounts.creator.key(),
+            AuctionError::UnauthorizedUpdate
+        );
+        require!(auction.is_native(), AuctionError::InvalidBidCurrency);
+        require!(
+            rebate_bps > 0 && rebate_bps <= BPS_DENOMINATOR,
+            AuctionError::InvalidLoserRebate
+        );
+
+        let loser_rebate = &mut ctx.accounts.loser_rebate;
+        loser_rebate.auction = auction.key();
+        loser_rebate.bump = ctx.bumps.loser_rebate;
+        auction.loser_rebate_bps = rebate_bps;
+
+        emit!(LoserRebateEnabled {
+            auction_id: auction.key(),
+            rebate_bps,
+        });
+
+        Ok(())
+    }
+
+    /// Pays a losing bidder their share of the loser rebate once the auction
+    /// is finalized, in proportion to their best bid among the top bids.
+    pub fn claim_loser_rebate(ctx: Context<ClaimLoserRebate>) -> Result<()> {
+        ctx.accounts.validate()?;
+        let auction = &ctx.accounts.auction;
+        let bidder = ctx.accounts.bidder.key();
+
+        require!(auction.status.is_settled(), AuctionError::AuctionNotEnded);
+        require_keys_neq!(
+            bidder,
+            auction.highest_bidder,
+            AuctionError::NotLosingBidder
+        );
+        let entries = &ctx.accounts.top_bids.entries;
+        let rank = entries
+            .iter()
+            .position(|entry| entry.bidder == bidder)
+            .ok_or(AuctionError::NotLosingBidder)?;
+
+        let loser_rebate = &mut ctx.accounts.loser_rebate;
+        require!(
+            !loser_rebate.is_claimed(rank),
+            AuctionError::LoserRebateClaimed
+        );
+        let total: u128 = entries
+            .iter()
+            .filter(|entry| entry.bidder != auction.highest_bidder)
+            .map(|entry| entry.amount as u128)
+            .sum();
+        let share = (loser_rebate.amount as u128 * entries[rank].amount as u128 / total) as u64;
+        loser_rebate.set_claimed(rank);
+
+        let rebate_info = loser_rebate.to_account_info();
+        let bidder_info = ctx.accounts.bidder.to_account_info();
+        **rebate_info.try_borrow_mut_lamports()? -= share;
+        **bidder_info.try_borrow_mut_lamports()? += share;
+
+        emit!(LoserRebateClaimed {
+            auction_id: auction.key(),
+            bidder,
+            amount: share,
+        });
+
+        Ok(())
+    }
+
+    /// Restricts bidding to wallets holding at least `min_balance` of
+    /// `gate_mint`, such as a membership NFT; the default key lifts it.
+    /// Gated auctions take bids through `place_bid` only. Only the creator,
+    /// and only before the first bid.
+    pub fn set_token_gate(
+        ctx: Context<SetTokenGate>,
+        gate_mint: Pubkey,
+        min_balance: u64,
+    ) -> Result<()> {
+        ctx.accounts.validate()?;
+        let auction = &mut ctx.accounts.auction;
+
+        require!(
+            auction.status == AuctionStatus::Live,
+            AuctionError::AuctionNotActive
+        );
+        require!(!auction.has_bids(), AuctionError::AuctionHasBids);
+        require_keys_eq!(
+            auction.creator,
+            ctx.accounts.creator.key(),
+            AuctionError::UnauthorizedUpdate
+        );
+        require!(auction.is_native(), AuctionError::InvalidBidCurrency);
+
+        auction.gate_mint = gate_mint;
+        auction.gate_min_balance = min_balance;
+
+        emit!(TokenGateSet {
+            auction_id: auction.key(),
+            gate_mint,
+            min_balance,
+        });
+
+        Ok(())
+    }
+
+    /// Restricts bidding to wallets holding an active Civic pass gateway
+    /// token of the gatekeeper `network`, for regulated sales; the default
+    /// key lifts it. Gated auctions take bids through `place_bid` only.
+    /// Only the creator, and only before the first bid.
+    pub fn set_attestation_gate(ctx: Context<SetAttestationGate>, network: Pubkey) -> Result<()> {
+        ctx.accounts.validate()?;
+        let auction = &mut ctx.accounts.auction;
+
+        require!(
+            auction.status == AuctionStatus::Live,
+            AuctionError::AuctionNotActive
+        );
+        require!(!auction.has_bids(), AuctionError::AuctionHasBids);
+        require_keys_eq!(
+            auction.creator,
+            ctx.accounts.creator.key(),
+            AuctionError::UnauthorizedUpdate
+        );
+        require!(auction.is_native(), AuctionError::InvalidBidCurrency);
+
+        auction.attestation_network = network;
+
+        emit!(AttestationGateSet {
+            auction_id: auction.key(),
+            network,
+        });
+
+        Ok(())
+    }
+
+    /// Restricts bidding to wallets under a Merkle `root` of allowed
+    /// bidders; all zeroes lifts it. Only the creator, and only before the
+    /// first bid.
+    pub fn set_bidder_allowlist(ctx: Context<SetBidderAllowlist>, root: [u8; 32]) -> Result<()> {
+        ctx.accounts.validate()?;
+        let auction = &mut ctx.accounts.auction;
+
+        require!(
+            auction.status == AuctionStatus::Live,
+            AuctionError::AuctionNotActive
+        );
+        require!(!auction.has_bids(), AuctionError::AuctionHasBids);
+        require_keys_eq!(
+            auction.creator,
+            ctx.accounts.creator.key(),
+            AuctionError::UnauthorizedUpdate
+        );
+        require!(auction.is_native(), AuctionError::InvalidBidCurrency);
+
+        auction.allowlist_root = root;
+
+        emit!(BidderAllowlistSet {
+            auction_id: auction.key(),
+            root,
+        });
+
+        Ok(())
+    }
+
+    /// Rolls everything a bidder's escrow holds from outbid bids on one
+    /// auction into a `place_bid` of `bid_amount` on another, closing the
+    /// escrow. The bid draws on the refund first and the bidder's wallet
+    /// for the rest.
+    pub fn transfer_bid<'info>(
+        ctx: Context<'_, '_, '_, 'info, TransferBid<'info>>,
+        bid_amount: u64,
+        allowlist_proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        ctx.accounts.validate()?;
+        let source = &ctx.accounts.source_escrow;
+        let amount = source.refundable;
+
+        let source_info = source.to_account_info();
+        let bidder_info = ctx.accounts.bid.bidder.to_account_info();
+        **source_info.try_borrow_mut_lamports()? -= amount;
+        **bidder_info.try_borrow_mut_lamports()? += amount;
+
+        emit!(BidTransferred {
+            from_auction: source.auction,
+            to_auction: ctx.accounts.bid.auction.key(),
+            bidder: source.bidder,
+            amount,
+        });
+
+        place_bid(
+            Context::new(
+                ctx.program_id,
+                &mut ctx.accounts.bid,
+                ctx.remaining_accounts,
+                ctx.bumps.bid,
+            ),
+            bid_amount,
+            allowlist_proof,
+        )
+    }
+
+    /// Closes a settled or cancelled auction once `CLOSE_AUCTION_DELAY` has
+    /// passed since it ended, returning the rent of the auction account, the
+    /// empty vault and the escrow's reserve to the creator. Anything bidders
+    /// withdraw through instructions that read the auction must be taken
+    /// out before then.
+    pub fn close_auction(ctx: Context<CloseAuction>) -> Result<()> {
+        ctx.accounts.validate()?;
+        let auction = &ctx.accounts.auction;
+        let clock = Clock::get()?;
+
+        require!(
+            auction.status == AuctionStatus::Settled || auction.status == AuctionStatus::Cancelled,
+            AuctionError::AuctionNotEnded
+        );
+        require!(!auction.nft_parked, AuctionError::VaultNotEmpty);
+        require!(
+            clock.unix_timestamp >= auction.end_time.saturating_add(CLOSE_AUCTION_DELAY),
+            AuctionError::CloseDelayPending
+        );
+        // Lamports beyond the reserve are still owed to someone
+        let escrow_lamports = ctx.accounts.auction_escrow.lamports();
+        require!(
+            escrow_lamports <= auction.escrow_reserve,
+            AuctionError::EscrowNotEmpty
+        );
+        let auction_key = auction.key();
+        if escrow_lamports > 0 {
+            pay_from_native_escrow(
+                &ctx.accounts.system_program,
+                &ctx.accounts.auction_escrow,
+                ctx.bumps.auction_escrow,
+                auction_key,
+                &ctx.accounts.creator.to_account_info(),
+                escrow_lamports,
+            )?;
+        }
+
+        if let Some(vault) = &ctx.accounts.vault_nft_account {
+            require!(vault.amount == 0, AuctionError::VaultNotEmpty);
+            let authority_seeds: &[&[&[u8]]] = &[&[
+                AUCTION_SEED,
+                auction_key.as_ref(),
+                &[ctx.bumps.auction_authority],
+            ]];
+            let close_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                anchor_spl::token_interface::CloseAccount {
+                    account: vault.to_account_info(),
+                    destination: ctx.accounts.creator.to_account_info(),
+                    authority: ctx.accounts.auction_authority.to_account_info(),
+                },
+                authority_seeds,
+            );
+            anchor_spl::token_interface::close_account(close_ctx)?;
+        }
+
+        emit!(AuctionClosed {
+            auction_id: auction_key,
+            creator: ctx.accounts.creator.key(),
+        });
+
+        Ok(())
+    }
+
+    /// Freezes bidding and settlement on a running auction, e.g. while a
+    /// metadata issue or dispute is looked into. The creator or the config
+    /// admin may pause it.
+    pub fn pause_auction(ctx: Context<PauseAuction>) -> Result<()> {
+        ctx.accounts.validate()?;
+        let auction = &mut ctx.accounts.auction;
+        let clock = Clock::get()?;
+
+        require!(
+            auction.status == AuctionStatus::Live,
+            AuctionError::AuctionNotActive
+        );
+        require!(!auction.has_ended(&clock), AuctionError::AuctionEnded);
+        require!(!auction.is_paused(), AuctionError::AuctionPaused);
+
+        auction.paused_at = clock.unix_timestamp;
+
+        emit!(AuctionPaused {
+            auction_id: auction.key(),
+            paused_at: auction.paused_at,
+        });
+
+        Ok(())
+    }
+
+    /// Lifts a pause, pushing `end_time` (and the extension cap and any
+    /// first refusal window) out by however long the auction was paused.
+    /// The creator or the config admin may resume it.
+    pub fn resume_auction(ctx: Context<PauseAuction>) -> Result<()> {
+        ctx.accounts.validate()?;
+        let auction = &mut ctx.accounts.auction;
+        let clock = Clock::get()?;
+
+        require!(auction.is_paused(), AuctionError::AuctionNotPaused);
+
+        let paused_for = clock.unix_timestamp - auction.paused_at;
+        auction.push_deadline(paused_for);
+        if auction.max_end_time > 0 {
+            auction.max_end_time += paused_for;
+        }
+        if auction.has_pending_bid() {
+            auction.pending_until += paused_for;
+        }
+        auction.paused_at = 0;
+
+        emit!(AuctionResumed {
+            auction_id: auction.key(),
+            end_time: auction.end_time,
+        });
+
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct InitializeAuction<'info> {
+    #[account(init, payer = creator, space = Auction::LEN)]
+    pub auction: Account<'info, Auction>,
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    /// CHECK: Blocklist PDA of the creator, which must not exist
+    #[account(seeds = [BLOCKLIST_SEED, creator.key().as_ref()], bump)]
+    pub creator_blocklist_entry: UncheckedAccount<'info>,
+    /// CHECK: Auction authority PDA
+    #[account(
+        seeds = [AUCTION_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_authority: AccountInfo<'info>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_escrow: SystemAccount<'info>,
+    #[account(mint::token_program = token_program)]
+    pub nft_mint: InterfaceAccount<'info, Mint>,
+    /// CHECK: Metadata PDA of the NFT, parsed in handler; required when
+    /// listing under a `required_collection` or taking a snapshot
+    #[account(
+        seeds = [
+            token_metadata::METADATA_SEED,
+            token_metadata::TOKEN_METADATA_PROGRAM_ID.as_ref(),
+            nft_mint.key().as_ref(),
+        ],
+        bump,
+        seeds::program = token_metadata::TOKEN_METADATA_PROGRAM_ID,
+    )]
+    pub nft_metadata: Option<UncheckedAccount<'info>>,
+    /// Snapshot of the NFT's metadata to take at listing, if wanted
+    #[account(
+        init,
+        payer = creator,
+        space = MetadataSnapshot::LEN,
+        seeds = [METADATA_SNAPSHOT_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub metadata_snapshot: Option<Account<'info, MetadataSnapshot>>,
+    #[account(
+        mut,
+        associated_token::mint = nft_mint,
+        associated_token::authority = creator,
+        associated_token::token_program = token_program
+    )]
+    pub creator_nft_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        init_if_needed,
+        payer = creator,
+        associated_token::mint = nft_mint,
+        associated_token::authority = auction_authority,
+        associated_token::token_program = token_program
+    )]
+    pub vault_nft_account: InterfaceAccount<'info, TokenAccount>,
+    /// SPL mint bids are denominated in; omit for native SOL auctions
+    pub bid_mint: Option<InterfaceAccount<'info, Mint>>,
+    /// Program config holding the platform fee, duration bounds and bid
+    /// mint registry
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        constraint = !config.halted @ AuctionError::ProgramHalted,
+    )]
+    pub config: Account<'info, Config>,
+    /// Registry entry of the NFT's verified collection; required while the
+    /// config is curated
+    #[account(
+        seeds = [APPROVED_COLLECTION_SEED, approved_collection.collection.as_ref()],
+        bump = approved_collection.bump,
+    )]
+    pub approved_collection: Option<Account<'info, ApprovedCollection>>,
+    /// Running parameter experiment to enroll the auction in, if any
+    #[account(seeds = [EXPERIMENT_SEED], bump = experiment.bump)]
+    pub experiment: Option<Account<'info, Experiment>>,
+    /// The creator's template to list from; required by `init_from_template`
+    #[account(
+        seeds = [TEMPLATE_SEED, creator.key().as_ref(), &template.template_id.to_le_bytes()],
+        bump = template.bump,
+        has_one = creator @ AuctionError::UnauthorizedUpdate,
+    )]
+    pub template: Option<Account<'info, AuctionTemplate>>,
+    /// The creator's listing defaults, applied where the listing sets none
+    #[account(
+        seeds = [CREATOR_SETTINGS_SEED, creator.key().as_ref()],
+        bump = creator_settings.bump,
+    )]
+    pub creator_settings: Option<Account<'info, CreatorSettings>>,
+    /// CHECK: Cooldown PDA of the mint, read in the handler; absent until
+    /// the mint's first cancellation
+    #[account(seeds = [MINT_COOLDOWN_SEED, nft_mint.key().as_ref()], bump)]
+    pub mint_cooldown: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+    pub pnft: ProgrammableAccounts<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+#[instruction(template_id: u32)]
+pub struct CreateTemplate<'info> {
+    #[account(
+        init,
+        payer = creator,
+        space = AuctionTemplate::LEN,
+        seeds = [TEMPLATE_SEED, creator.key().as_ref(), &template_id.to_le_bytes()],
+        bump,
+    )]
+    pub template: Account<'info, AuctionTemplate>,
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetCreatorSettings<'info> {
+    #[account(
+        init_if_needed,
+        payer = creator,
+        space = CreatorSettings::LEN,
+        seeds = [CREATOR_SETTINGS_SEED, creator.key().as_ref()],
+        bump,
+    )]
+    pub creator_settings: Account<'info, CreatorSettings>,
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct PlaceBid<'info> {
+    #[account(mut)]
+    pub auction: Account<'info, Auction>,
+    /// Pays rent for the accounts the bid creates: a relayer in sponsored
+    /// flows, otherwise the bidder
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// The bidding wallet, or a session key of it when `session` is given
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+    /// Session the signer bids under, on behalf of and funded by its owner
+    #[account(
+        mut,
+        seeds = [SESSION_KEY_SEED, session.owner.as_ref(), bidder.key().as_ref()],
+        bump = session.bump,
+    )]
+    pub session: Option<Account<'info, SessionKey>>,
+    /// CHECK: Blocklist PDA of the bidding wallet, which must not exist
+    #[account(
+        seeds = [BLOCKLIST_SEED, bidding_wallet(&bidder, &session).as_ref()],
+        bump,
+    )]
+    pub bidder_blocklist_entry: UncheckedAccount<'info>,
+    /// Deposit the bid is paid from instead of the signer, so a relayer can
+    /// submit it for a bidder holding no SOL
+    #[account(
+        mut,
+        seeds = [BID_DEPOSIT_SEED, bidding_wallet(&bidder, &session).as_ref()],
+        bump = bid_deposit.bump,
+    )]
+    pub bid_deposit: Option<Account<'info, BidDeposit>>,
+    /// Required when the auction enforces a bid cooldown or bid cap
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = BidderStats::LEN,
+        seeds = [
+            BIDDER_STATS_SEED,
+            auction.key().as_ref(),
+            bidding_wallet(&bidder, &session).as_ref(),
+        ],
+        bump,
+    )]
+    pub bidder_stats: Option<Account<'info, BidderStats>>,
+    /// Required when the auction requires registration
+    #[account(
+        seeds = [
+            BIDDER_REGISTRATION_SEED,
+            auction.key().as_ref(),
+            bidding_wallet(&bidder, &session).as_ref(),
+        ],
+        bump = registration.bump,
+    )]
+    pub registration: Option<Account<'info, BidderRegistration>>,
+    /// Required when the auction has a bidder allowlist; created on the
+    /// wallet's first bid
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = AllowlistEntry::LEN,
+        seeds = [
+            ALLOWLIST_ENTRY_SEED,
+            auction.key().as_ref(),
+            bidding_wallet(&bidder, &session).as_ref(),
+        ],
+        bump,
+    )]
+    pub allowlist_entry: Option<Account<'info, AllowlistEntry>>,
+    /// Refund escrow of the bidder being outbid; required once the auction
+    /// has a bid
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = BidEscrow::LEN,
+        seeds = [BID_ESCROW_SEED, auction.key().as_ref(), auction.highest_bidder.as_ref()],
+        bump,
+    )]
+    pub previous_bid_escrow: Option<Account<'info, BidEscrow>>,
+    /// Proxy bid of the current leader; required when the standing bid is
+    /// proxy-backed
+    #[account(
+        mut,
+        seeds = [PROXY_BID_SEED, auction.key().as_ref(), auction.highest_bidder.as_ref()],
+        bump = leader_proxy_bid.bump,
+    )]
+    pub leader_proxy_bid: Option<Account<'info, ProxyBid>>,
+    /// CHECK: Blocklist PDA of the current leader; a blocked leader's proxy
+    /// does not answer
+    #[account(seeds = [BLOCKLIST_SEED, auction.highest_bidder.as_ref()], bump)]
+    pub leader_blocklist_entry: UncheckedAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = BidHistory::LEN,
+        seeds = [BID_HISTORY_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub bid_history: Account<'info, BidHistory>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = TopBids::LEN,
+        seeds = [TOP_BIDS_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub top_bids: Account<'info, TopBids>,
+    /// Receipt of this bid, numbered by the bids recorded before it
+    #[account(
+        init,
+        payer = payer,
+        space = BidReceipt::LEN,
+        seeds = [
+            BID_RECEIPT_SEED,
+            auction.key().as_ref(),
+            &bid_history.total.to_le_bytes(),
+        ],
+        bump,
+    )]
+    pub bid_receipt: Account<'info, BidReceipt>,
+    /// Required when the auction uses increment tiers
+    #[account(
+        seeds = [INCREMENT_TIERS_SEED, auction.key().as_ref()],
+        bump = increment_tiers.bump,
+    )]
+    pub increment_tiers: Option<Account<'info, IncrementTiers>>,
+    /// CHECK: Wallets linked to the creator; read if the admin created it
+    #[account(
+        seeds = [CREATOR_LINKS_SEED, auction.creator.as_ref()],
+        bump,
+    )]
+    pub creator_links: UncheckedAccount<'info>,
+    /// Program config; also holds the holder mint for `holder_token_account`
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        constraint = !config.halted @ AuctionError::ProgramHalted,
+    )]
+    pub config: Account<'info, Config>,
+    /// Bidder's account of the config's holder mint, to claim the holder
+    /// discount
+    pub holder_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    /// Bidder's account of the gate mint; required on token-gated auctions
+    #[account(
+        token::mint = auction.gate_mint,
+        constraint = gate_token_account.amount >= auction.gate_min_balance
+            @ AuctionError::InsufficientGateBalance,
+    )]
+    pub gate_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    /// CHECK: Bidder's gateway token; required on auctions that need an
+    /// attestation, verified in the handler
+    pub gateway_token: Option<UncheckedAccount<'info>>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_escrow: SystemAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct PlaceWsolBid<'info> {
+    #[account(mut)]
+    pub auction: Account<'info, Auction>,
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        constraint = !config.halted @ AuctionError::ProgramHalted,
+    )]
+    pub config: Account<'info, Config>,
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+    /// CHECK: Blocklist PDA of the bidder, which must not exist
+    #[account(seeds = [BLOCKLIST_SEED, bidder.key().as_ref()], bump)]
+    pub bidder_blocklist_entry: UncheckedAccount<'info>,
+    /// CHECK: Wallets linked to the creator; read if the admin created it
+    #[account(
+        seeds = [CREATOR_LINKS_SEED, auction.creator.as_ref()],
+        bump,
+    )]
+    pub creator_links: UncheckedAccount<'info>,
+    /// Required when the auction enforces a bid cooldown or bid cap
+    #[account(
+        init_if_needed,
+        payer = bidder,
+        space = BidderStats::LEN,
+        seeds = [BIDDER_STATS_SEED, auction.key().as_ref(), bidder.key().as_ref()],
+        bump,
+    )]
+    pub bidder_stats: Option<Account<'info, BidderStats>>,
+    /// Required when the auction requires registration
+    #[account(
+        seeds = [
+            BIDDER_REGISTRATION_SEED,
+            auction.key().as_ref(),
+            bidder.key().as_ref(),
+        ],
+        bump = registration.bump,
+    )]
+    pub registration: Option<Account<'info, BidderRegistration>>,
+    /// Required when the auction has a bidder allowlist
+    #[account(
+        seeds = [
+            ALLOWLIST_ENTRY_SEED,
+            auction.key().as_ref(),
+            bidder.key().as_ref(),
+        ],
+        bump = allowlist_entry.bump,
+    )]
+    pub allowlist_entry: Option<Account<'info, AllowlistEntry>>,
+    /// Refund escrow of the bidder being outbid; required once the auction
+    /// has a bid
+    #[account(
+        init_if_needed,
+        payer = bidder,
+        space = BidEscrow::LEN,
+        seeds = [BID_ESCROW_SEED, auction.key().as_ref(), auction.highest_bidder.as_ref()],
+        bump,
+    )]
+    pub previous_bid_escrow: Option<Account<'info, BidEscrow>>,
+    /// CHECK: Auction authority PDA
+    #[account(
+        seeds = [AUCTION_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_authority: AccountInfo<'info>,
+    #[account(address = anchor_spl::token::spl_token::native_mint::ID)]
+    pub native_mint: Account<'info, anchor_spl::token::Mint>,
+    #[account(
+        mut,
+        token::mint = native_mint,
+        token::authority = bidder,
+    )]
+    pub bidder_wsol_account: Account<'info, anchor_spl::token::TokenAccount>,
+    #[account(
+        init,
+        payer = bidder,
+        seeds = [WSOL_SEED, auction.key().as_ref()],
+        bump,
+        token::mint = native_mint,
+        token::authority = auction_authority,
+    )]
+    pub wsol_unwrap_account: Account<'info, anchor_spl::token::TokenAccount>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_escrow: SystemAccount<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct PlaceTokenBid<'info> {
+    #[account(mut)]
+    pub auction: Account<'info, Auction>,
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        constraint = !config.halted @ AuctionError::ProgramHalted,
+    )]
+    pub config: Account<'info, Config>,
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+    /// CHECK: Blocklist PDA of the bidder, which must not exist
+    #[account(seeds = [BLOCKLIST_SEED, bidder.key().as_ref()], bump)]
+    pub bidder_blocklist_entry: UncheckedAccount<'info>,
+    /// CHECK: Wallets linked to the creator; read if the admin created it
+    #[account(
+        seeds = [CREATOR_LINKS_SEED, auction.creator.as_ref()],
+        bump,
+    )]
+    pub creator_links: UncheckedAccount<'info>,
+    /// CHECK: Auction authority PDA
+    #[account(
+        seeds = [AUCTION_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_authority: AccountInfo<'info>,
+    #[account(
+        address = auction.bid_mint,
+        mint::token_program = token_program
+    )]
+    pub bid_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        token::mint = bid_mint,
+        token::authority = bidder,
+        token::token_program = token_program
+    )]
+    pub bidder_token_account: InterfaceAccount<'info, TokenAccount>,
+    /// Previous highest bidder's ATA for the bid mint, created if needed
+    #[account(
+        init_if_needed,
+        payer = bidder,
+        associated_token::mint = bid_mint,
+        associated_token::authority = previous_bidder,
+        associated_token::token_program = token_program
+    )]
+    pub previous_bidder_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    /// CHECK: Previous highest bidder; must match the auction's highest bidder
+    #[account(address = auction.highest_bidder @ AuctionError::InvalidPreviousBidder)]
+    pub previous_bidder: Option<AccountInfo<'info>>,
+    #[account(
+        init_if_needed,
+        payer = bidder,
+        associated_token::mint = bid_mint,
+        associated_token::authority = auction_authority,
+        associated_token::token_program = token_program
+    )]
+    pub escrow_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct BuyNow<'info> {
+    #[account(mut)]
+    pub auction: Account<'info, Auction>,
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        constraint = !config.halted @ AuctionError::ProgramHalted,
+    )]
+    pub config: Account<'info, Config>,
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+    /// CHECK: Blocklist PDA of the buyer, which must not exist
+    #[account(seeds = [BLOCKLIST_SEED, buyer.key().as_ref()], bump)]
+    pub buyer_blocklist_entry: UncheckedAccount<'info>,
+    /// CHECK: Wallets linked to the creator; read if the admin created it
+    #[account(
+        seeds = [CREATOR_LINKS_SEED, auction.creator.as_ref()],
+        bump,
+    )]
+    pub creator_links: UncheckedAccount<'info>,
+    /// Required when the auction requires registration
+    #[account(
+        seeds = [
+            BIDDER_REGISTRATION_SEED,
+            auction.key().as_ref(),
+            buyer.key().as_ref(),
+        ],
+        bump = registration.bump,
+    )]
+    pub registration: Option<Account<'info, BidderRegistration>>,
+    /// Required when the auction has a bidder allowlist
+    #[account(
+        seeds = [
+            ALLOWLIST_ENTRY_SEED,
+            auction.key().as_ref(),
+            buyer.key().as_ref(),
+        ],
+        bump = allowlist_entry.bump,
+    )]
+    pub allowlist_entry: Option<Account<'info, AllowlistEntry>>,
+    /// Refund escrow of the bidder being outbid; required once the auction
+    /// has a bid
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        space = BidEscrow::LEN,
+        seeds = [BID_ESCROW_SEED, auction.key().as_ref(), auction.highest_bidder.as_ref()],
+        bump,
+    )]
+    pub previous_bid_escrow: Option<Account<'info, BidEscrow>>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_escrow: SystemAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct EndAuction<'info> {
+    #[account(mut)]
+    pub auction: Account<'info, Auction>,
+    /// CHECK: SlotHashes sysvar; required for fair-end auctions
+    #[account(address = anchor_lang::solana_program::sysvar::slot_hashes::ID)]
+    pub slot_hashes: Option<UncheckedAccount<'info>>,
+}
+
+#[derive(Accounts)]
+pub struct FinalizeAuction<'info> {
+    #[account(mut)]
+    pub auction: Account<'info, Auction>,
+    #[account(mut, address = auction.creator @ AuctionError::Unauthorized)]
+    pub creator: SystemAccount<'info>,
+    /// CHECK: Auction authority PDA
+    #[account(
+        mut,
+        seeds = [AUCTION_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_authority: AccountInfo<'info>,
+    #[account(address = auction.nft_mint)]
+    pub nft_mint: InterfaceAccount<'info, Mint>,
+    /// CHECK: Metadata PDA of the NFT; parsed for royalties if it exists
+    #[account(
+        seeds = [
+            token_metadata::METADATA_SEED,
+            token_metadata::TOKEN_METADATA_PROGRAM_ID.as_ref(),
+            nft_mint.key().as_ref(),
+        ],
+        bump,
+        seeds::program = token_metadata::TOKEN_METADATA_PROGRAM_ID,
+    )]
+    pub nft_metadata: UncheckedAccount<'info>,
+    /// Metadata snapshot taken at listing; royalties are paid from it
+    #[account(
+        seeds = [METADATA_SNAPSHOT_SEED, auction.key().as_ref()],
+        bump = metadata_snapshot.bump,
+    )]
+    pub metadata_snapshot: Option<Account<'info, MetadataSnapshot>>,
+    #[account(
+        mut,
+        associated_token::mint = nft_mint,
+        associated_token::authority = auction_authority,
+        associated_token::token_program = token_program
+    )]
+    pub vault_nft_account: InterfaceAccount<'info, TokenAccount>,
+    /// Winner's token account; omit when there were no bids, with
+    /// `settle_auction`, or to leave the NFT for `claim_won_nft`
+    #[account(
+        mut,
+        token::mint = nft_mint,
+        token::authority = auction.highest_bidder,
+        token::token_program = token_program
+    )]
+    pub winner_nft_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_escrow: SystemAccount<'info>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    /// CHECK: Platform fee account, pinned to the config's fee destination
+    #[account(mut, address = config.fee_destination @ AuctionError::InvalidFeeDestination)]
+    pub platform_fee_account: AccountInfo<'info>,
+    #[account(mut, seeds = [REBATE_POOL_SEED], bump = rebate_pool.bump)]
+    pub rebate_pool: Option<Account<'info, RebatePool>>,
+    /// Registered keeper to credit with a rebate; must sign
+    pub keeper: Option<Signer<'info>>,
+    #[account(
+        mut,
+        constraint = keeper.as_ref().map(|k| k.key()) == Some(keeper_account.keeper)
+            @ AuctionError::Unauthorized,
+    )]
+    pub keeper_account: Option<Account<'info, Keeper>>,
+    /// Whoever settles the auction, to be paid the settlement bounty
+    #[account(mut)]
+    pub settler: Option<Signer<'info>>,
+    /// Delivery escrow holding the seller's proceeds; required when the
+    /// auction has one
+    #[account(
+        mut,
+        seeds = [DISPUTE_SEED, auction.key().as_ref()],
+        bump = dispute.bump,
+    )]
+    pub dispute: Option<Account<'info, Dispute>>,
+    /// Losing bidders' share of the fee; required when the auction has a
+    /// loser rebate
+    #[account(
+        mut,
+        seeds = [LOSER_REBATE_SEED, auction.key().as_ref()],
+        bump = loser_rebate.bump,
+    )]
+    pub loser_rebate: Option<Account<'info, LoserRebate>>,
+    /// Bids queued in the closing window; required when the auction has a
+    /// closing queue
+    #[account(
+        mut,
+        seeds = [CLOSING_QUEUE_SEED, auction.key().as_ref()],
+        bump = closing_queue.bump,
+    )]
+    pub closing_queue: Option<Account<'info, ClosingQueue>>,
+    /// Winning bidder; required when they won with the holder discount
+    #[account(mut, address = auction.highest_bidder)]
+    pub winner: Option<SystemAccount<'info>>,
+    pub pnft: ProgrammableAccounts<'info>,
+    pub lst: LstPayoutAccounts<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimProceeds<'info> {
+    #[account(mut)]
+    pub auction: Account<'info, Auction>,
+    #[account(mut, address = auction.creator)]
+    pub creator: SystemAccount<'info>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_escrow: SystemAccount<'info>,
+    pub lst: LstPayoutAccounts<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimItem<'info> {
+    #[account(mut)]
+    pub auction: Account<'info, Auction>,
+    /// CHECK: Auction authority PDA
+    #[account(
+        mut,
+        seeds = [AUCTION_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_authority: AccountInfo<'info>,
+    #[account(address = auction.nft_mint)]
+    pub nft_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = nft_mint,
+        associated_token::authority = auction_authority,
+        associated_token::token_program = token_program
+    )]
+    pub vault_nft_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = nft_mint,
+        token::authority = auction.highest_bidder,
+        token::token_program = token_program
+    )]
+    pub winner_nft_account: InterfaceAccount<'info, TokenAccount>,
+    pub pnft: ProgrammableAccounts<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct RecoverBid<'info> {
+    #[account(mut)]
+    pub auction: Account<'info, Auction>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_escrow: SystemAccount<'info>,
+    /// CHECK: Highest bidder, or their wSOL account for wrapped bids; checked
+    /// against the auction
+    #[account(mut)]
+    pub bidder: AccountInfo<'info>,
+    /// Required when the standing bid was funded with wSOL
+    pub token_program: Option<Program<'info, Token>>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RecoverItem<'info> {
+    #[account(mut, has_one = creator @ AuctionError::UnauthorizedUpdate)]
+    pub auction: Account<'info, Auction>,
+    /// Pays for the token record when the NFT is programmable
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    /// CHECK: Auction authority PDA
+    #[account(
+        mut,
+        seeds = [AUCTION_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_authority: AccountInfo<'info>,
+    #[account(address = auction.nft_mint)]
+    pub nft_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = nft_mint,
+        associated_token::authority = auction_authority,
+        associated_token::token_program = token_program
+    )]
+    pub vault_nft_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = nft_mint,
+        token::authority = creator,
+        token::token_program = token_program
+    )]
+    pub creator_nft_account: InterfaceAccount<'info, TokenAccount>,
+    pub pnft: ProgrammableAccounts<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct FinalizeTokenAuction<'info> {
+    #[account(mut)]
+    pub auction: Account<'info, Auction>,
+    /// CHECK: Auction authority PDA
+    #[account(
+        mut,
+        seeds = [AUCTION_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_authority: AccountInfo<'info>,
+    #[account(address = auction.nft_mint)]
+    pub nft_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = nft_mint,
+        associated_token::authority = auction_authority,
+        associated_token::token_program = token_program
+    )]
+    pub vault_nft_account: InterfaceAccount<'info, TokenAccount>,
+    /// Winner's token account; any account of the mint when there were
+    /// no bids
+    #[account(
+        mut,
+        token::mint = nft_mint,
+        token::token_program = token_program,
+        constraint = auction.highest_bid == 0 || winner_nft_account.owner == auction.highest_bidder
+            @ AuctionError::InvalidRecipient,
+    )]
+    pub winner_nft_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        address = auction.settlement_mint(),
+        mint::token_program = bid_token_program
+    )]
+    pub bid_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = bid_mint,
+        associated_token::authority = auction_authority,
+        associated_token::token_program = bid_token_program
+    )]
+    pub escrow_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        associated_token::mint = bid_mint,
+        associated_token::authority = auction.creator,
+        associated_token::token_program = bid_token_program
+    )]
+    pub creator_token_account: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: Treasury PDA that owns platform fees taken in SPL mints
+    #[account(seeds = [TREASURY_SEED], bump)]
+    pub treasury: AccountInfo<'info>,
+    /// Treasury's ATA for the bid mint; receives the platform fee
+    #[account(
+        mut,
+        associated_token::mint = bid_mint,
+        associated_token::authority = treasury,
+        associated_token::token_program = bid_token_program
+    )]
+    pub platform_fee_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub pnft: ProgrammableAccounts<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub bid_token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawUnsoldNFT<'info> {
+    #[account(mut, has_one = creator @ AuctionError::UnauthorizedUpdate)]
+    pub auction: Account<'info, Auction>,
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    /// CHECK: Auction authority PDA
+    #[account(
+        mut,
+        seeds = [AUCTION_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_authority: AccountInfo<'info>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_escrow: SystemAccount<'info>,
+    #[account(address = auction.nft_mint)]
+    pub nft_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = nft_mint,
+        associated_token::authority = auction_authority,
+        associated_token::token_program = token_program
+    )]
+    pub vault_nft_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = nft_mint,
+        token::authority = creator,
+        token::token_program = token_program
+    )]
+    pub creator_nft_account: InterfaceAccount<'info, TokenAccount>,
+    pub pnft: ProgrammableAccounts<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RelistAuction<'info> {
+    #[account(mut)]
+    pub auction: Account<'info, Auction>,
+    pub creator: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ExpireUnsoldAuctions<'info> {
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FractionalizeUnsoldNFT<'info> {
+    #[account(mut)]
+    pub auction: Account<'info, Auction>,
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    /// CHECK: Auction authority PDA, keeps the NFT and mints the shares
+    #[account(
+        seeds = [AUCTION_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_authority: AccountInfo<'info>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_escrow: SystemAccount<'info>,
+    #[account(
+        init,
+        payer = creator,
+        seeds = [FRACTION_MINT_SEED, auction.key().as_ref()],
+        bump,
+        mint::decimals = 0,
+        mint::authority = auction_authority,
+        mint::token_program = fraction_token_program,
+    )]
+    pub fraction_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        init_if_needed,
+        payer = creator,
+        associated_token::mint = fraction_mint,
+        associated_token::authority = creator,
+        associated_token::token_program = fraction_token_program,
+    )]
+    pub creator_fraction_account: InterfaceAccount<'info, TokenAccount>,
+    pub fraction_token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RedeemFractions<'info> {
+    #[account(mut)]
+    pub auction: Account<'info, Auction>,
+    #[account(mut)]
+    pub redeemer: Signer<'info>,
+    /// CHECK: Auction authority PDA
+    #[account(
+        mut,
+        seeds = [AUCTION_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_authority: AccountInfo<'info>,
+    #[account(address = auction.nft_mint)]
+    pub nft_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = nft_mint,
+        associated_token::authority = auction_authority,
+        associated_token::token_program = token_program
+    )]
+    pub vault_nft_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = nft_mint,
+        token::authority = redeemer,
+        token::token_program = token_program
+    )]
+    pub redeemer_nft_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [FRACTION_MINT_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub fraction_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        token::mint = fraction_mint,
+        token::authority = redeemer,
+        token::token_program = fraction_token_program
+    )]
+    pub redeemer_fraction_account: InterfaceAccount<'info, TokenAccount>,
+    pub pnft: ProgrammableAccounts<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub fraction_token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct CancelAuction<'info> {
+    #[account(mut)]
+    pub auction: Account<'info, Auction>,
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    /// CHECK: Auction authority PDA
+    #[account(
+        mut,
+        seeds = [AUCTION_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_authority: AccountInfo<'info>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_escrow: SystemAccount<'info>,
+    #[account(address = auction.nft_mint)]
+    pub nft_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = nft_mint,
+        associated_token::authority = auction_authority,
+        associated_token::token_program = token_program
+    )]
+    pub vault_nft_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = nft_mint,
+        token::token_program = token_program
+    )]
+    pub creator_nft_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    /// Records when the mint may be listed again; required while the config
+    /// sets a relist cooldown
+    #[account(
+        init_if_needed,
+        payer = creator,
+        space = MintCooldown::LEN,
+        seeds = [MINT_COOLDOWN_SEED, nft_mint.key().as_ref()],
+        bump,
+    )]
+    pub mint_cooldown: Option<Account<'info, MintCooldown>>,
+    pub pnft: ProgrammableAccounts<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+
+#[derive(Accounts)]
+pub struct UpdateAuctionSettings<'info> {
+    #[account(mut)]
+    pub auction: Account<'info, Auction>,
+    pub creator: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ScheduleBidding<'info> {
+    #[account(mut)]
+    pub auction: Account<'info, Auction>,
+    pub creator: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct OpenBidding<'info> {
+    #[account(mut)]
+    pub auction: Account<'info, Auction>,
+}
+
+#[derive(Accounts)]
+pub struct ExtendEndTime<'info> {
+    #[account(mut)]
+    pub auction: Account<'info, Auction>,
+    pub creator: Signer<'info>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = Config::LEN,
+        seeds = [CONFIG_SEED],
+        bump,
+    )]
+    pub config: Account<'info, Config>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, crate::program::EnhancedAuction>,
+    #[account(constraint = program_data.upgrade_authority_address == Some(admin.key()) @ AuctionError::Unauthorized)]
+    pub program_data: Account<'info, ProgramData>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetPlatformParams<'info> {
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = fee_manager @ AuctionError::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+    pub fee_manager: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetRoles<'info> {
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = admin @ AuctionError::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetHalted<'info> {
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = pauser @ AuctionError::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+    pub pauser: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetCurated<'info> {
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = curator @ AuctionError::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+    pub curator: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ApproveCollection<'info> {
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = curator @ AuctionError::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+    #[account(mut)]
+    pub curator: Signer<'info>,
+    /// CHECK: Collection mint being approved
+    pub collection: AccountInfo<'info>,
+    #[account(
+        init,
+        payer = curator,
+        space = ApprovedCollection::LEN,
+        seeds = [APPROVED_COLLECTION_SEED, collection.key().as_ref()],
+        bump,
+    )]
+    pub approved_collection: Account<'info, ApprovedCollection>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeCollection<'info> {
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = curator @ AuctionError::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+    #[account(mut)]
+    pub curator: Signer<'info>,
+    #[account(
+        mut,
+        close = curator,
+        seeds = [APPROVED_COLLECTION_SEED, approved_collection.collection.as_ref()],
+        bump = approved_collection.bump,
+    )]
+    pub approved_collection: Account<'info, ApprovedCollection>,
+}
+
+#[derive(Accounts)]
+pub struct BlockWallet<'info> {
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = admin @ AuctionError::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    /// CHECK: Wallet being blocked
+    pub wallet: AccountInfo<'info>,
+    #[account(
+        init,
+        payer = admin,
+        space = BlocklistEntry::LEN,
+        seeds = [BLOCKLIST_SEED, wallet.key().as_ref()],
+        bump,
+    )]
+    pub blocklist_entry: Account<'info, BlocklistEntry>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UnblockWallet<'info> {
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = admin @ AuctionError::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(
+        mut,
+        close = admin,
+        seeds = [BLOCKLIST_SEED, blocklist_entry.wallet.as_ref()],
+        bump = blocklist_entry.bump,
+    )]
+    pub blocklist_entry: Account<'info, BlocklistEntry>,
+}
+
+#[derive(Accounts)]
+pub struct SetBidMints<'info> {
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = admin @ AuctionError::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(creator: Pubkey)]
+pub struct SetCreatorLinks<'info> {
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = admin @ AuctionError::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = CreatorLinks::LEN,
+        seeds = [CREATOR_LINKS_SEED, creator.as_ref()],
+        bump,
+    )]
+    pub creator_links: Account<'info, CreatorLinks>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawTreasuryFees<'info> {
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = admin @ AuctionError::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+    pub admin: Signer<'info>,
+    /// CHECK: Treasury PDA, owner of the fee ATAs
+    #[account(seeds = [TREASURY_SEED], bump)]
+    pub treasury: AccountInfo<'info>,
+    #[account(mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = treasury,
+        associated_token::token_program = token_program
+    )]
+    pub treasury_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = mint,
+        token::token_program = token_program
+    )]
+    pub destination: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct ImportAuction<'info> {
+    #[account(init, payer = admin, space = Auction::LEN)]
+    pub auction: Account<'info, Auction>,
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = admin @ AuctionError::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    /// CHECK: Executable id of the legacy program that owns `legacy_auction`
+    #[account(executable)]
+    pub legacy_program: AccountInfo<'info>,
+    /// CHECK: Owner and layout are verified in the handler
+    pub legacy_auction: AccountInfo<'info>,
+    #[account(
+        init,
+        payer = admin,
+        space = AuctionProvenance::LEN,
+        seeds = [PROVENANCE_SEED, legacy_auction.key().as_ref()],
+        bump,
+    )]
+    pub provenance: Account<'info, AuctionProvenance>,
+    /// CHECK: Auction authority PDA
+    #[account(
+        seeds = [AUCTION_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_authority: AccountInfo<'info>,
+    #[account(mint::token_program = token_program)]
+    pub nft_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        associated_token::mint = nft_mint,
+        associated_token::authority = auction_authority,
+        associated_token::token_program = token_program
+    )]
+    pub vault_nft_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        seeds = [ESCROW_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_escrow: SystemAccount<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateAuction<'info> {
+    /// CHECK: Auction under any layout version; its discriminator is
+    /// checked in the handler before it is grown and deserialized
+    #[account(mut, owner = crate::ID)]
+    pub auction: UncheckedAccount<'info>,
+    /// Tops up rent for the larger layout
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ValidateAuctionParams<'info> {
+    /// CHECK: Candidate bid mint; inspected read-only by the handler
+    pub bid_mint: Option<UncheckedAccount<'info>>,
+}
+
+#[derive(Accounts)]
+pub struct SnapshotAuction<'info> {
+    pub auction: Account<'info, Auction>,
+    #[account(
+        init,
+        payer = payer,
+        space = AuctionResult::LEN,
+        seeds = [RESULT_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub result: Account<'info, AuctionResult>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct EscheatAuction<'info> {
+    #[account(mut)]
+    pub auction: Account<'info, Auction>,
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = admin @ AuctionError::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    /// CHECK: Auction authority PDA
+    #[account(
+        seeds = [AUCTION_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_authority: AccountInfo<'info>,
+    #[account(address = auction.nft_mint)]
+    pub nft_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = nft_mint,
+        associated_token::authority = auction_authority,
+        associated_token::token_program = token_program
+    )]
+    pub vault_nft_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_escrow: SystemAccount<'info>,
+    /// CHECK: Platform custody PDA
+    #[account(
+        mut,
+        seeds = [CUSTODY_SEED],
+        bump,
+    )]
+    pub custody_authority: AccountInfo<'info>,
+    #[account(
+        init_if_needed,
+        payer = admin,
+        associated_token::mint = nft_mint,
+        associated_token::authority = custody_authority,
+        associated_token::token_program = token_program
+    )]
+    pub custody_nft_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        init,
+        payer = admin,
+        space = EscheatRecord::LEN,
+        seeds = [ESCHEAT_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub escheat_record: Account<'info, EscheatRecord>,
+    pub pnft: ProgrammableAccounts<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetAcceptedCurrencies<'info> {
+    #[account(mut)]
+    pub auction: Account<'info, Auction>,
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_escrow: SystemAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = creator,
+        space = AcceptedCurrencies::LEN,
+        seeds = [CURRENCIES_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub accepted_currencies: Account<'info, AcceptedCurrencies>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct PlaceMultiCurrencyBid<'info> {
+    #[account(mut)]
+    pub auction: Account<'info, Auction>,
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        constraint = !config.halted @ AuctionError::ProgramHalted,
+    )]
+    pub config: Account<'info, Config>,
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+    /// CHECK: Blocklist PDA of the bidder, which must not exist
+    #[account(seeds = [BLOCKLIST_SEED, bidder.key().as_ref()], bump)]
+    pub bidder_blocklist_entry: UncheckedAccount<'info>,
+    /// CHECK: Wallets linked to the creator; read if the admin created it
+    #[account(
+        seeds = [CREATOR_LINKS_SEED, auction.creator.as_ref()],
+        bump,
+    )]
+    pub creator_links: UncheckedAccount<'info>,
+    /// CHECK: Auction authority PDA
+    #[account(
+        seeds = [AUCTION_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_authority: AccountInfo<'info>,
+    #[account(
+        seeds = [CURRENCIES_SEED, auction.key().as_ref()],
+        bump = accepted_currencies.bump,
+    )]
+    pub accepted_currencies: Account<'info, AcceptedCurrencies>,
+    #[account(mint::token_program = token_program)]
+    pub bid_mint: InterfaceAccount<'info, Mint>,
+    /// CHECK: Pyth PriceUpdateV2 account, validated in the handler
+    pub price_feed: AccountInfo<'info>,
+    #[account(
+        mut,
+        token::mint = bid_mint,
+        token::authority = bidder,
+        token::token_program = token_program
+    )]
+    pub bidder_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        init_if_needed,
+        payer = bidder,
+        associated_token::mint = bid_mint,
+        associated_token::authority = auction_authority,
+        associated_token::token_program = token_program
+    )]
+    pub escrow_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub previous_bid_mint: Option<InterfaceAccount<'info, Mint>>,
+    #[account(mut)]
+    pub previous_escrow_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut)]
+    pub previous_bidder_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub previous_token_program: Option<Interface<'info, TokenInterface>>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeCompressedAuction<'info> {
+    #[account(init, payer = creator, space = Auction::LEN)]
+    pub auction: Account<'info, Auction>,
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        constraint = !config.halted @ AuctionError::ProgramHalted,
+    )]
+    pub config: Account<'info, Config>,
+    /// CHECK: Auction authority PDA, new leaf owner
+    #[account(
+        seeds = [AUCTION_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_authority: AccountInfo<'info>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_escrow: SystemAccount<'info>,
+    #[account(
+        init,
+        payer = creator,
+        space = CompressedAsset::LEN,
+        seeds = [COMPRESSED_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub compressed_asset: Account<'info, CompressedAsset>,
+    /// Running parameter experiment to enroll the auction in, if any
+    #[account(seeds = [EXPERIMENT_SEED], bump = experiment.bump)]
+    pub experiment: Option<Account<'info, Experiment>>,
+    /// CHECK: Bubblegum tree config, validated by Bubblegum
+    pub tree_authority: AccountInfo<'info>,
+    /// CHECK: Concurrent Merkle tree, validated by Bubblegum
+    #[account(mut)]
+    pub merkle_tree: AccountInfo<'info>,
+    /// CHECK: SPL Noop program
+    #[account(address = bubblegum::SPL_NOOP_PROGRAM_ID)]
+    pub log_wrapper: AccountInfo<'info>,
+    /// CHECK: SPL Account Compression program
+    #[account(address = bubblegum::SPL_ACCOUNT_COMPRESSION_PROGRAM_ID)]
+    pub compression_program: AccountInfo<'info>,
+    /// CHECK: Bubblegum program
+    #[account(address = bubblegum::BUBBLEGUM_PROGRAM_ID)]
+    pub bubblegum_program: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FinalizeCompressedAuction<'info> {
+    #[account(mut)]
+    pub auction: Account<'info, Auction>,
+    #[account(mut, address = auction.creator)]
+    pub creator: SystemAccount<'info>,
+    /// CHECK: Auction authority PDA, current leaf owner
+    #[account(
+        seeds = [AUCTION_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_authority: AccountInfo<'info>,
+    #[account(
+        seeds = [COMPRESSED_SEED, auction.key().as_ref()],
+        bump = compressed_asset.bump,
+    )]
+    pub compressed_asset: Account<'info, CompressedAsset>,
+    /// CHECK: Winner, or creator when there were no bids; checked in handler
+    pub recipient: AccountInfo<'info>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_escrow: SystemAccount<'info>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    /// CHECK: Platform fee account, pinned to the config's fee destination
+    #[account(mut, address = config.fee_destination @ AuctionError::InvalidFeeDestination)]
+    pub platform_fee_account: AccountInfo<'info>,
+    /// CHECK: Bubblegum tree config, validated by Bubblegum
+    pub tree_authority: AccountInfo<'info>,
+    /// CHECK: Concurrent Merkle tree recorded at listing
+    #[account(mut, address = compressed_asset.merkle_tree)]
+    pub merkle_tree: AccountInfo<'info>,
+    /// CHECK: SPL Noop program
+    #[account(address = bubblegum::SPL_NOOP_PROGRAM_ID)]
+    pub log_wrapper: AccountInfo<'info>,
+    /// CHECK: SPL Account Compression program
+    #[account(address = bubblegum::SPL_ACCOUNT_COMPRESSION_PROGRAM_ID)]
+    pub compression_program: AccountInfo<'info>,
+    /// CHECK: Bubblegum program
+    #[account(address = bubblegum::BUBBLEGUM_PROGRAM_ID)]
+    pub bubblegum_program: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ConfigureExperiment<'info> {
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = admin @ AuctionError::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = Experiment::LEN,
+        seeds = [EXPERIMENT_SEED],
+        bump,
+    )]
+    pub experiment: Account<'info, Experiment>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ConfigureRebatePool<'info> {
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = admin @ AuctionError::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = RebatePool::LEN,
+        seeds = [REBATE_POOL_SEED],
+        bump,
+    )]
+    pub rebate_pool: Account<'info, RebatePool>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RegisterKeeper<'info> {
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = admin @ AuctionError::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    /// CHECK: Keeper wallet being approved
+    pub keeper: AccountInfo<'info>,
+    #[account(
+        init,
+        payer = admin,
+        space = Keeper::LEN,
+        seeds = [KEEPER_SEED, keeper.key().as_ref()],
+        bump,
+    )]
+    pub keeper_account: Account<'info, Keeper>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimKeeperRebates<'info> {
+    #[account(mut, seeds = [REBATE_POOL_SEED], bump = rebate_pool.bump)]
+    pub rebate_pool: Account<'info, RebatePool>,
+    #[account(mut)]
+    pub keeper: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [KEEPER_SEED, keeper.key().as_ref()],
+        bump = keeper_account.bump,
+    )]
+    pub keeper_account: Account<'info, Keeper>,
+}
+
+#[derive(Accounts)]
+pub struct ExpireAuction<'info> {
+    #[account(mut)]
+    pub auction: Account<'info, Auction>,
+    #[account(mut, address = auction.creator)]
+    pub creator: SystemAccount<'info>,
+    /// CHECK: Auction authority PDA
+    #[account(
+        seeds = [AUCTION_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_authority: AccountInfo<'info>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_escrow: SystemAccount<'info>,
+    #[account(address = auction.nft_mint)]
+    pub nft_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = nft_mint,
+        associated_token::authority = auction_authority,
+        associated_token::token_program = token_program
+    )]
+    pub vault_nft_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        associated_token::mint = nft_mint,
+        associated_token::authority = auction.creator,
+        associated_token::token_program = token_program
+    )]
+    pub creator_nft_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, seeds = [REBATE_POOL_SEED], bump = rebate_pool.bump)]
+    pub rebate_pool: Option<Account<'info, RebatePool>>,
+    /// Registered keeper to credit with a rebate; must sign
+    pub keeper: Option<Signer<'info>>,
+    #[account(
+        mut,
+        constraint = keeper.as_ref().map(|k| k.key()) == Some(keeper_account.keeper)
+            @ AuctionError::Unauthorized,
+    )]
+    pub keeper_account: Option<Account<'info, Keeper>>,
+    pub pnft: ProgrammableAccounts<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Extra accounts needed to move a programmable NFT through Token Metadata.
+/// Omit them all for regular SPL / Token-2022 NFTs.
+#[derive(Accounts)]
+pub struct ProgrammableAccounts<'info> {
+    /// CHECK: Token Metadata program
+    #[account(address = token_metadata::TOKEN_METADATA_PROGRAM_ID)]
+    pub token_metadata_program: Option<UncheckedAccount<'info>>,
+    /// CHECK: Metadata PDA of the NFT, validated by Token Metadata
+    #[account(mut)]
+    pub metadata: Option<UncheckedAccount<'info>>,
+    /// CHECK: Master edition PDA, validated by Token Metadata
+    pub edition: Option<UncheckedAccount<'info>>,
+    /// CHECK: Token record of the source token account
+    #[account(mut)]
+    pub owner_token_record: Option<UncheckedAccount<'info>>,
+    /// CHECK: Token record of the destination token account
+    #[account(mut)]
+    pub destination_token_record: Option<UncheckedAccount<'info>>,
+    /// CHECK: Wallet owning the destination token account, when not
+    /// otherwise present in the instruction
+    pub destination_owner: Option<UncheckedAccount<'info>>,
+    /// Pays for the destination token record, when no other signer does
+    #[account(mut)]
+    pub payer: Option<Signer<'info>>,
+    /// CHECK: Token Auth Rules program
+    pub authorization_rules_program: Option<UncheckedAccount<'info>>,
+    /// CHECK: Rule set, validated by Token Auth Rules
+    pub authorization_rules: Option<UncheckedAccount<'info>>,
+    /// CHECK: Instructions sysvar
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub sysvar_instructions: Option<UncheckedAccount<'info>>,
+    pub associated_token_program: Option<Program<'info, AssociatedToken>>,
+    pub system_program: Option<Program<'info, System>>,
+}
+
+/// Stake pool accounts needed to pay the seller in a liquid staking token.
+/// Omit them all unless the auction has an LST payout pool set.
+#[derive(Accounts)]
+pub struct LstPayoutAccounts<'info> {
+    /// CHECK: Stake pool chosen by the seller, checked against the auction
+    #[account(mut)]
+    pub stake_pool: Option<UncheckedAccount<'info>>,
+    /// CHECK: Pool withdraw authority, validated by the stake pool program
+    pub withdraw_authority: Option<UncheckedAccount<'info>>,
+    /// CHECK: Pool reserve stake account, validated by the stake pool program
+    #[account(mut)]
+    pub reserve_stake: Option<UncheckedAccount<'info>>,
+    /// CHECK: Pool manager fee account, validated by the stake pool program
+    #[account(mut)]
+    pub manager_fee_account: Option<UncheckedAccount<'info>>,
+    /// CHECK: Pool mint, validated by the stake pool program
+    #[account(mut)]
+    pub pool_mint: Option<UncheckedAccount<'info>>,
+    /// Seller's token account for the pool mint
+    #[account(mut)]
+    pub seller_lst_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    /// CHECK: SPL stake pool program
+    #[account(address = stake_pool::STAKE_POOL_PROGRAM_ID)]
+    pub stake_pool_program: Option<UncheckedAccount<'info>>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+}
+
+/// `route` takes no fixed accounts; see [`enhanced_auction::route`].
+#[derive(Accounts)]
+pub struct Route {}
+
+#[derive(Accounts)]
+pub struct InitializeCoreAuction<'info> {
+    #[account(init, payer = creator, space = Auction::LEN)]
+    pub auction: Account<'info, Auction>,
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        constraint = !config.halted @ AuctionError::ProgramHalted,
+    )]
+    pub config: Account<'info, Config>,
+    /// CHECK: Auction authority PDA, new asset owner
+    #[account(
+        seeds = [AUCTION_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_authority: AccountInfo<'info>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_escrow: SystemAccount<'info>,
+    /// CHECK: Core asset, parsed in handler and validated by Core
+    #[account(mut)]
+    pub asset: AccountInfo<'info>,
+    /// CHECK: Collection the asset belongs to, validated by Core
+    pub collection: Option<UncheckedAccount<'info>>,
+    /// Running parameter experiment to enroll the auction in, if any
+    #[account(seeds = [EXPERIMENT_SEED], bump = experiment.bump)]
+    pub experiment: Option<Account<'info, Experiment>>,
+    /// CHECK: Metaplex Core program
+    #[account(address = mpl_core::MPL_CORE_PROGRAM_ID)]
+    pub core_program: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FinalizeCoreAuction<'info> {
+    #[account(mut)]
+    pub auction: Account<'info, Auction>,
+    #[account(mut, address = auction.creator)]
+    pub creator: SystemAccount<'info>,
+    /// CHECK: Auction authority PDA, current asset owner
+    #[account(
+        mut,
+        seeds = [AUCTION_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_authority: AccountInfo<'info>,
+    /// CHECK: Core asset recorded at listing, validated by Core
+    #[account(mut, address = auction.nft_mint)]
+    pub asset: AccountInfo<'info>,
+    /// CHECK: Collection the asset belongs to, validated by Core
+    pub collection: Option<UncheckedAccount<'info>>,
+    /// CHECK: Winner, or creator when there were no bids; checked in handler
+    pub recipient: AccountInfo<'info>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_escrow: SystemAccount<'info>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    /// CHECK: Platform fee account, pinned to the config's fee destination
+    #[account(mut, address = config.fee_destination @ AuctionError::InvalidFeeDestination)]
+    pub platform_fee_account: AccountInfo<'info>,
+    /// CHECK: Metaplex Core program
+    #[account(address = mpl_core::MPL_CORE_PROGRAM_ID)]
+    pub core_program: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeDomainAuction<'info> {
+    #[account(init, payer = creator, space = Auction::LEN)]
+    pub auction: Account<'info, Auction>,
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        constraint = !config.halted @ AuctionError::ProgramHalted,
+    )]
+    pub config: Account<'info, Config>,
+    /// CHECK: Auction authority PDA, new domain owner
+    #[account(
+        seeds = [AUCTION_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_authority: AccountInfo<'info>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_escrow: SystemAccount<'info>,
+    /// CHECK: Domain name record, parsed in handler
+    #[account(mut)]
+    pub name_account: AccountInfo<'info>,
+    /// Running parameter experiment to enroll the auction in, if any
+    #[account(seeds = [EXPERIMENT_SEED], bump = experiment.bump)]
+    pub experiment: Option<Account<'info, Experiment>>,
+    /// CHECK: SPL name service program
+    #[account(address = sns::NAME_SERVICE_PROGRAM_ID)]
+    pub name_service_program: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FinalizeDomainAuction<'info> {
+    #[account(mut)]
+    pub auction: Account<'info, Auction>,
+    #[account(mut, address = auction.creator)]
+    pub creator: SystemAccount<'info>,
+    /// CHECK: Auction authority PDA, current domain owner
+    #[account(
+        seeds = [AUCTION_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_authority: AccountInfo<'info>,
+    /// CHECK: Domain name record recorded at listing
+    #[account(mut, address = auction.nft_mint)]
+    pub name_account: AccountInfo<'info>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_escrow: SystemAccount<'info>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    /// CHECK: Platform fee account, pinned to the config's fee destination
+    #[account(mut, address = config.fee_destination @ AuctionError::InvalidFeeDestination)]
+    pub platform_fee_account: AccountInfo<'info>,
+    /// CHECK: SPL name service program
+    #[account(address = sns::NAME_SERVICE_PROGRAM_ID)]
+    pub name_service_program: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeTokenAccountAuction<'info> {
+    #[account(init, payer = creator, space = Auction::LEN)]
+    pub auction: Account<'info, Auction>,
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        constraint = !config.halted @ AuctionError::ProgramHalted,
+    )]
+    pub config: Account<'info, Config>,
+    /// CHECK: Auction authority PDA, new owner of the position account
+    #[account(
+        seeds = [AUCTION_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_authority: AccountInfo<'info>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_escrow: SystemAccount<'info>,
+    #[account(
+        mut,
+        token::authority = creator,
+        token::token_program = token_program,
+        constraint = position.close_authority.is_none() @ AuctionError::InvalidAsset,
+    )]
+    pub position: InterfaceAccount<'info, TokenAccount>,
+    /// Running parameter experiment to enroll the auction in, if any
+    #[account(seeds = [EXPERIMENT_SEED], bump = experiment.bump)]
+    pub experiment: Option<Account<'info, Experiment>>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FinalizeTokenAccountAuction<'info> {
+    #[account(mut)]
+    pub auction: Account<'info, Auction>,
+    #[account(mut, address = auction.creator)]
+    pub creator: SystemAccount<'info>,
+    /// CHECK: Auction authority PDA, current owner of the position account
+    #[account(
+        seeds = [AUCTION_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_authority: AccountInfo<'info>,
+    #[account(
+        mut,
+        address = auction.nft_mint,
+        token::authority = auction_authority,
+        token::token_program = token_program,
+    )]
+    pub position: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: Winner, or creator when there were no bids; checked in handler
+    pub recipient: AccountInfo<'info>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_escrow: SystemAccount<'info>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    /// CHECK: Platform fee account, pinned to the config's fee destination
+    #[account(mut, address = config.fee_destination @ AuctionError::InvalidFeeDestination)]
+    pub platform_fee_account: AccountInfo<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeDelegatedAuction<'info> {
+    #[account(init, payer = creator, space = Auction::LEN)]
+    pub auction: Account<'info, Auction>,
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        constraint = !config.halted @ AuctionError::ProgramHalted,
+    )]
+    pub config: Account<'info, Config>,
+    /// CHECK: Auction authority PDA, delegate of the seller's token account
+    #[account(
+        seeds = [AUCTION_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_authority: AccountInfo<'info>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_escrow: SystemAccount<'info>,
+    #[account(mint::token_program = token_program)]
+    pub nft_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        token::mint = nft_mint,
+        token::authority = creator,
+        token::token_program = token_program,
+    )]
+    pub creator_nft_account: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: Master edition PDA, the mint's freeze authority
+    #[account(
+        seeds = [
+            token_metadata::METADATA_SEED,
+            token_metadata::TOKEN_METADATA_PROGRAM_ID.as_ref(),
+            nft_mint.key().as_ref(),
+            token_metadata::EDITION_SEED,
+        ],
+        bump,
+        seeds::program = token_metadata::TOKEN_METADATA_PROGRAM_ID,
+    )]
+    pub nft_edition: UncheckedAccount<'info>,
+    /// CHECK: Cooldown PDA of the mint, read in the handler; absent until
+    /// the mint's first cancellation
+    #[account(seeds = [MINT_COOLDOWN_SEED, nft_mint.key().as_ref()], bump)]
+    pub mint_cooldown: UncheckedAccount<'info>,
+    /// Running parameter experiment to enroll the auction in, if any
+    #[account(seeds = [EXPERIMENT_SEED], bump = experiment.bump)]
+    pub experiment: Option<Account<'info, Experiment>>,
+    /// CHECK: Token Metadata program
+    #[account(address = token_metadata::TOKEN_METADATA_PROGRAM_ID)]
+    pub token_metadata_program: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FinalizeDelegatedAuction<'info> {
+    #[account(mut)]
+    pub auction: Account<'info, Auction>,
+    #[account(mut, address = auction.creator)]
+    pub creator: SystemAccount<'info>,
+    /// CHECK: Auction authority PDA, delegate of the seller's token account
+    #[account(
+        seeds = [AUCTION_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_authority: AccountInfo<'info>,
+    #[account(address = auction.nft_mint)]
+    pub nft_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        token::mint = nft_mint,
+        token::authority = creator,
+        token::token_program = token_program,
+    )]
+    pub creator_nft_account: InterfaceAccount<'info, TokenAccount>,
+    /// Winner's token account; omit when there were no bids
+    #[account(
+        mut,
+        token::mint = nft_mint,
+        token::token_program = token_program,
+    )]
+    pub winner_nft_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    /// CHECK: Master edition PDA, the mint's freeze authority
+    #[account(
+        seeds = [
+            token_metadata::METADATA_SEED,
+            token_metadata::TOKEN_METADATA_PROGRAM_ID.as_ref(),
+            nft_mint.key().as_ref(),
+            token_metadata::EDITION_SEED,
+        ],
+        bump,
+        seeds::program = token_metadata::TOKEN_METADATA_PROGRAM_ID,
+    )]
+    pub nft_edition: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_escrow: SystemAccount<'info>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    /// CHECK: Platform fee account, pinned to the config's fee destination
+    #[account(mut, address = config.fee_destination @ AuctionError::InvalidFeeDestination)]
+    pub platform_fee_account: AccountInfo<'info>,
+    /// CHECK: Token Metadata program
+    #[account(address = token_metadata::TOKEN_METADATA_PROGRAM_ID)]
+    pub token_metadata_program: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeCompressedBundleAuction<'info> {
+    #[account(init, payer = creator, space = Auction::LEN)]
+    pub auction: Account<'info, Auction>,
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        constraint = !config.halted @ AuctionError::ProgramHalted,
+    )]
+    pub config: Account<'info, Config>,
+    /// CHECK: Auction authority PDA, new owner of every leaf
+    #[account(
+        seeds = [AUCTION_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_authority: AccountInfo<'info>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_escrow: SystemAccount<'info>,
+    #[account(
+        init,
+        payer = creator,
+        space = CompressedBundle::LEN,
+        seeds = [COMPRESSED_BUNDLE_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub bundle: Account<'info, CompressedBundle>,
+    /// Running parameter experiment to enroll the auction in, if any
+    #[account(seeds = [EXPERIMENT_SEED], bump = experiment.bump)]
+    pub experiment: Option<Account<'info, Experiment>>,
+    /// CHECK: Bubblegum tree config, validated by Bubblegum
+    pub tree_authority: AccountInfo<'info>,
+    /// CHECK: Concurrent Merkle tree holding every leaf, validated by Bubblegum
+    #[account(mut)]
+    pub merkle_tree: AccountInfo<'info>,
+    /// CHECK: SPL Noop program
+    #[account(address = bubblegum::SPL_NOOP_PROGRAM_ID)]
+    pub log_wrapper: AccountInfo<'info>,
+    /// CHECK: SPL Account Compression program
+    #[account(address = bubblegum::SPL_ACCOUNT_COMPRESSION_PROGRAM_ID)]
+    pub compression_program: AccountInfo<'info>,
+    /// CHECK: Bubblegum program
+    #[account(address = bubblegum::BUBBLEGUM_PROGRAM_ID)]
+    pub bubblegum_program: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FinalizeCompressedBundleAuction<'info> {
+    #[account(mut)]
+    pub auction: Account<'info, Auction>,
+    #[account(mut, address = auction.creator)]
+    pub creator: SystemAccount<'info>,
+    /// CHECK: Auction authority PDA, current owner of every leaf
+    #[account(
+        seeds = [AUCTION_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_authority: AccountInfo<'info>,
+    #[account(
+        address = auction.nft_mint,
+        seeds = [COMPRESSED_BUNDLE_SEED, auction.key().as_ref()],
+        bump = bundle.bump,
+    )]
+    pub bundle: Account<'info, CompressedBundle>,
+    /// CHECK: Winner, or creator when there were no bids; checked in handler
+    pub recipient: AccountInfo<'info>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_escrow: SystemAccount<'info>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    /// CHECK: Platform fee account, pinned to the config's fee destination
+    #[account(mut, address = config.fee_destination @ AuctionError::InvalidFeeDestination)]
+    pub platform_fee_account: AccountInfo<'info>,
+    /// CHECK: Bubblegum tree config, validated by Bubblegum
+    pub tree_authority: AccountInfo<'info>,
+    /// CHECK: Concurrent Merkle tree recorded at listing
+    #[account(mut, address = bundle.merkle_tree)]
+    pub merkle_tree: AccountInfo<'info>,
+    /// CHECK: SPL Noop program
+    #[account(address = bubblegum::SPL_NOOP_PROGRAM_ID)]
+    pub log_wrapper: AccountInfo<'info>,
+    /// CHECK: SPL Account Compression program
+    #[account(address = bubblegum::SPL_ACCOUNT_COMPRESSION_PROGRAM_ID)]
+    pub compression_program: AccountInfo<'info>,
+    /// CHECK: Bubblegum program
+    #[account(address = bubblegum::BUBBLEGUM_PROGRAM_ID)]
+    pub bubblegum_program: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct EnablePartialFills<'info> {
+    #[account(mut)]
+    pub auction: Account<'info, Auction>,
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    #[account(
+        init,
+        payer = creator,
+        space = PartialFillBook::LEN,
+        seeds = [PARTIAL_FILL_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub book: Account<'info, PartialFillBook>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct PlacePartialBid<'info> {
+    #[account(mut)]
+    pub auction: Account<'info, Auction>,
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        constraint = !config.halted @ AuctionError::ProgramHalted,
+    )]
+    pub config: Account<'info, Config>,
+    #[account(
+        mut,
+        seeds = [PARTIAL_FILL_SEED, auction.key().as_ref()],
+        bump = book.bump,
+    )]
+    pub book: Account<'info, PartialFillBook>,
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+    /// CHECK: Blocklist PDA of the bidder, which must not exist
+    #[account(seeds = [BLOCKLIST_SEED, bidder.key().as_ref()], bump)]
+    pub bidder_blocklist_entry: UncheckedAccount<'info>,
+    /// CHECK: Wallets linked to the creator; read if the admin created it
+    #[account(
+        seeds = [CREATOR_LINKS_SEED, auction.creator.as_ref()],
+        bump,
+    )]
+    pub creator_links: UncheckedAccount<'info>,
+    /// CHECK: Bidder of the lowest entry when the book is full; checked in handler
+    #[account(mut)]
+    pub evicted_bidder: Option<AccountInfo<'info>>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_escrow: SystemAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FinalizePartialFillAuction<'info> {
+    #[account(mut)]
+    pub auction: Account<'info, Auction>,
+    #[account(
+        mut,
+        seeds = [PARTIAL_FILL_SEED, auction.key().as_ref()],
+        bump = book.bump,
+    )]
+    pub book: Account<'info, PartialFillBook>,
+    #[account(mut, address = auction.creator)]
+    pub creator: SystemAccount<'info>,
+    /// CHECK: Auction authority PDA
+    #[account(
+        seeds = [AUCTION_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_authority: AccountInfo<'info>,
+    #[account(address = auction.nft_mint)]
+    pub nft_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = nft_mint,
+        associated_token::authority = auction_authority,
+        associated_token::token_program = token_program
+    )]
+    pub vault_nft_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = nft_mint,
+        token::authority = creator,
+        token::token_program = token_program
+    )]
+    pub creator_nft_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_escrow: SystemAccount<'info>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    /// CHECK: Platform fee account, pinned to the config's fee destination
+    #[account(mut, address = config.fee_destination @ AuctionError::InvalidFeeDestination)]
+    pub platform_fee_account: AccountInfo<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimPartialFill<'info> {
+    #[account(mut)]
+    pub auction: Account<'info, Auction>,
+    #[account(
+        mut,
+        seeds = [PARTIAL_FILL_SEED, auction.key().as_ref()],
+        bump = book.bump,
+    )]
+    pub book: Account<'info, PartialFillBook>,
+    #[account(mut, address = auction.creator)]
+    pub creator: SystemAccount<'info>,
+    /// CHECK: Bidder of the claimed entry; checked in handler
+    #[account(mut)]
+    pub bidder: AccountInfo<'info>,
+    /// CHECK: Auction authority PDA
+    #[account(
+        seeds = [AUCTION_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_authority: AccountInfo<'info>,
+    #[account(address = auction.nft_mint)]
+    pub nft_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = nft_mint,
+        associated_token::authority = auction_authority,
+        associated_token::token_program = token_program
+    )]
+    pub vault_nft_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = nft_mint,
+        associated_token::authority = bidder,
+        associated_token::token_program = token_program
+    )]
+    pub bidder_nft_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_escrow: SystemAccount<'info>,
+    /// Pays for the bidder's token account if it does not exist yet
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetLstPayout<'info> {
+    #[account(mut)]
+    pub auction: Account<'info, Auction>,
+    pub creator: Signer<'info>,
+    /// CHECK: Stake pool to pay out in; omit to pay plain SOL
+    pub stake_pool: Option<UncheckedAccount<'info>>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeEscrowlessAuction<'info> {
+    #[account(init, payer = creator, space = Auction::LEN)]
+    pub auction: Account<'info, Auction>,
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        constraint = !config.halted @ AuctionError::ProgramHalted,
+    )]
+    pub config: Account<'info, Config>,
+    /// CHECK: Auction authority PDA, delegate of the seller's token account
+    #[account(
+        seeds = [AUCTION_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_authority: AccountInfo<'info>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_escrow: SystemAccount<'info>,
+    #[account(mint::token_program = token_program)]
+    pub nft_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        token::mint = nft_mint,
+        token::authority = creator,
+        token::token_program = token_program,
+    )]
+    pub creator_nft_account: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: Cooldown PDA of the mint, read in the handler; absent until
+    /// the mint's first cancellation
+    #[account(seeds = [MINT_COOLDOWN_SEED, nft_mint.key().as_ref()], bump)]
+    pub mint_cooldown: UncheckedAccount<'info>,
+    /// Running parameter experiment to enroll the auction in, if any
+    #[account(seeds = [EXPERIMENT_SEED], bump = experiment.bump)]
+    pub experiment: Option<Account<'info, Experiment>>,
     pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelBrokenListing<'info> {
+    #[account(mut)]
+    pub auction: Account<'info, Auction>,
+    #[account(mut, address = auction.creator)]
+    pub creator: SystemAccount<'info>,
+    /// CHECK: Auction authority PDA
+    #[account(
+        seeds = [AUCTION_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_authority: AccountInfo<'info>,
+    /// CHECK: Seller token account recorded at listing; may have been closed
+    #[account(address = auction.seller_token_account)]
+    pub seller_token_account: UncheckedAccount<'info>,
+    /// CHECK: Highest bidder account for refund, checked against the auction
+    #[account(mut)]
+    pub previous_bidder: AccountInfo<'info>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_escrow: SystemAccount<'info>,
+    /// Required when the standing bid was funded with wSOL
+    pub token_program: Option<Program<'info, Token>>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct EmergencyCancel<'info> {
+    #[account(mut)]
+    pub auction: Account<'info, Auction>,
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = admin @ AuctionError::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+    /// Pays for the creator's token record when the NFT is programmable
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(mut, address = auction.creator)]
+    pub creator: SystemAccount<'info>,
+    /// CHECK: Auction authority PDA
+    #[account(
+        mut,
+        seeds = [AUCTION_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_authority: AccountInfo<'info>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_escrow: SystemAccount<'info>,
+    #[account(address = auction.nft_mint)]
+    pub nft_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = nft_mint,
+        associated_token::authority = auction_authority,
+        associated_token::token_program = token_program
+    )]
+    pub vault_nft_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = nft_mint,
+        token::authority = creator,
+        token::token_program = token_program
+    )]
+    pub creator_nft_account: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: Highest bidder account for refund, checked against the auction
+    #[account(mut)]
+    pub previous_bidder: AccountInfo<'info>,
+    pub pnft: ProgrammableAccounts<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    /// Required when the standing bid was funded with wSOL
+    pub wsol_token_program: Option<Program<'info, Token>>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ForceRefund<'info> {
+    #[account(mut)]
+    pub auction: Account<'info, Auction>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    /// Pays for the creator's token record when the NFT is programmable
+    #[account(mut)]
+    pub caller: Signer<'info>,
+    #[account(mut, address = auction.creator)]
+    pub creator: SystemAccount<'info>,
+    /// CHECK: Auction authority PDA
+    #[account(
+        mut,
+        seeds = [AUCTION_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_authority: AccountInfo<'info>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_escrow: SystemAccount<'info>,
+    #[account(address = auction.nft_mint)]
+    pub nft_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = nft_mint,
+        associated_token::authority = auction_authority,
+        associated_token::token_program = token_program
+    )]
+    pub vault_nft_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = nft_mint,
+        token::authority = creator,
+        token::token_program = token_program
+    )]
+    pub creator_nft_account: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: Highest bidder account for refund, checked against the auction
+    #[account(mut)]
+    pub previous_bidder: AccountInfo<'info>,
+    pub pnft: ProgrammableAccounts<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    /// Required when the standing bid was funded with wSOL
+    pub wsol_token_program: Option<Program<'info, Token>>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(collection: Pubkey)]
+pub struct PlaceCollectionOffer<'info> {
+    #[account(
+        init,
+        payer = buyer,
+        space = CollectionOffer::LEN,
+        seeds = [COLLECTION_OFFER_SEED, collection.as_ref(), buyer.key().as_ref()],
+        bump,
+    )]
+    pub offer: Account<'info, CollectionOffer>,
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, offer.key().as_ref()],
+        bump,
+    )]
+    pub offer_escrow: SystemAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelCollectionOffer<'info> {
+    #[account(
+        mut,
+        close = buyer,
+        seeds = [COLLECTION_OFFER_SEED, offer.collection.as_ref(), buyer.key().as_ref()],
+        bump = offer.bump,
+    )]
+    pub offer: Account<'info, CollectionOffer>,
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, offer.key().as_ref()],
+        bump,
+    )]
+    pub offer_escrow: SystemAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptCollectionOffer<'info> {
+    #[account(
+        mut,
+        close = buyer,
+        seeds = [COLLECTION_OFFER_SEED, offer.collection.as_ref(), offer.buyer.as_ref()],
+        bump = offer.bump,
+    )]
+    pub offer: Account<'info, CollectionOffer>,
+    /// CHECK: Buyer who placed the offer; receives the NFT and the reserve
+    #[account(mut, address = offer.buyer)]
+    pub buyer: AccountInfo<'info>,
+    #[account(mut)]
+    pub seller: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, offer.key().as_ref()],
+        bump,
+    )]
+    pub offer_escrow: SystemAccount<'info>,
+    #[account(mint::token_program = token_program)]
+    pub nft_mint: InterfaceAccount<'info, Mint>,
+    /// CHECK: Metadata PDA of the NFT; parsed for collection and royalties
+    #[account(
+        seeds = [
+            token_metadata::METADATA_SEED,
+            token_metadata::TOKEN_METADATA_PROGRAM_ID.as_ref(),
+            nft_mint.key().as_ref(),
+        ],
+        bump,
+        seeds::program = token_metadata::TOKEN_METADATA_PROGRAM_ID,
+    )]
+    pub nft_metadata: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        token::mint = nft_mint,
+        token::authority = seller,
+        token::token_program = token_program
+    )]
+    pub seller_nft_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        init_if_needed,
+        payer = seller,
+        associated_token::mint = nft_mint,
+        associated_token::authority = buyer,
+        associated_token::token_program = token_program
+    )]
+    pub buyer_nft_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    /// CHECK: Platform fee account, pinned to the config's fee destination
+    #[account(mut, address = config.fee_destination @ AuctionError::InvalidFeeDestination)]
+    pub platform_fee_account: AccountInfo<'info>,
+    pub pnft: ProgrammableAccounts<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetEscrowYieldReserve<'info> {
+    #[account(mut)]
+    pub auction: Account<'info, Auction>,
+    pub creator: Signer<'info>,
+    /// CHECK: Kamino reserve to lend the escrow to; omit to disable lending
+    pub reserve: Option<UncheckedAccount<'info>>,
+}
+
+#[derive(Accounts)]
+pub struct DeployEscrowYield<'info> {
+    #[account(mut)]
+    pub auction: Account<'info, Auction>,
+    /// Pays for the collateral account the first time the escrow is lent
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// CHECK: Auction authority PDA, owner of the escrow and collateral
+    #[account(
+        seeds = [AUCTION_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_authority: AccountInfo<'info>,
+    #[account(address = auction.bid_mint, mint::token_program = bid_token_program)]
+    pub bid_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = bid_mint,
+        associated_token::authority = auction_authority,
+        associated_token::token_program = bid_token_program
+    )]
+    pub escrow_token_account: InterfaceAccount<'info, TokenAccount>,
+    /// Reserve collateral mint, validated by klend
+    #[account(mut)]
+    pub collateral_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = collateral_mint,
+        associated_token::authority = auction_authority,
+        associated_token::token_program = collateral_token_program
+    )]
+    pub collateral_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub kamino: KaminoReserveAccounts<'info>,
+    pub bid_token_program: Interface<'info, TokenInterface>,
+    pub collateral_token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RecallEscrowYield<'info> {
+    #[account(mut)]
+    pub auction: Account<'info, Auction>,
+    /// CHECK: Auction authority PDA, owner of the escrow and collateral
+    #[account(
+        seeds = [AUCTION_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_authority: AccountInfo<'info>,
+    #[account(address = auction.bid_mint, mint::token_program = bid_token_program)]
+    pub bid_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = bid_mint,
+        associated_token::authority = auction_authority,
+        associated_token::token_program = bid_token_program
+    )]
+    pub escrow_token_account: InterfaceAccount<'info, TokenAccount>,
+    /// Reserve collateral mint, validated by klend
+    #[account(mut)]
+    pub collateral_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = collateral_mint,
+        associated_token::authority = auction_authority,
+        associated_token::token_program = collateral_token_program
+    )]
+    pub collateral_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub kamino: KaminoReserveAccounts<'info>,
+    pub bid_token_program: Interface<'info, TokenInterface>,
+    pub collateral_token_program: Program<'info, Token>,
+}
+
+/// Kamino lending accounts the bid escrow is lent through. The reserve is
+/// checked against the auction in the handler; klend validates the rest.
+#[derive(Accounts)]
+pub struct KaminoReserveAccounts<'info> {
+    /// CHECK: Kamino reserve, checked against the auction
+    #[account(mut)]
+    pub reserve: UncheckedAccount<'info>,
+    /// CHECK: Lending market of the reserve, validated by klend
+    pub lending_market: UncheckedAccount<'info>,
+    /// CHECK: Lending market authority PDA, validated by klend
+    pub lending_market_authority: UncheckedAccount<'info>,
+    /// CHECK: Reserve liquidity supply, validated by klend
+    #[account(mut)]
+    pub liquidity_supply: UncheckedAccount<'info>,
+    /// CHECK: Kamino lending program
+    #[account(address = kamino::KLEND_PROGRAM_ID)]
+    pub klend_program: UncheckedAccount<'info>,
+    /// CHECK: Instructions sysvar
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub sysvar_instructions: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct EnableDeliveryEscrow<'info> {
+    #[account(mut)]
+    pub auction: Account<'info, Auction>,
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    #[account(
+        init,
+        payer = creator,
+        space = Dispute::LEN,
+        seeds = [DISPUTE_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub dispute: Account<'info, Dispute>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct OpenDispute<'info> {
+    #[account(mut)]
+    pub auction: Account<'info, Auction>,
+    #[account(
+        mut,
+        seeds = [DISPUTE_SEED, auction.key().as_ref()],
+        bump = dispute.bump,
+    )]
+    pub dispute: Account<'info, Dispute>,
+    pub buyer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ResolveDispute<'info> {
+    #[account(mut)]
+    pub auction: Account<'info, Auction>,
+    #[account(
+        mut,
+        seeds = [DISPUTE_SEED, auction.key().as_ref()],
+        bump = dispute.bump,
+    )]
+    pub dispute: Account<'info, Dispute>,
+    #[account(address = dispute.arbiter @ AuctionError::Unauthorized)]
+    pub arbiter: Signer<'info>,
+    #[account(mut, address = auction.creator)]
+    pub creator: SystemAccount<'info>,
+    #[account(mut, address = auction.highest_bidder)]
+    pub buyer: SystemAccount<'info>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_escrow: SystemAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ReleaseHeldProceeds<'info> {
+    #[account(mut)]
+    pub auction: Account<'info, Auction>,
+    #[account(
+        mut,
+        seeds = [DISPUTE_SEED, auction.key().as_ref()],
+        bump = dispute.bump,
+    )]
+    pub dispute: Account<'info, Dispute>,
+    #[account(mut, address = auction.creator)]
+    pub creator: SystemAccount<'info>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_escrow: SystemAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AttachBidNote<'info> {
+    pub auction: Account<'info, Auction>,
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+    #[account(
+        init_if_needed,
+        payer = bidder,
+        space = BidNote::LEN,
+        seeds = [BID_NOTE_SEED, auction.key().as_ref(), bidder.key().as_ref()],
+        bump,
+    )]
+    pub bid_note: Account<'info, BidNote>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AttachLotTokens<'info> {
+    pub auction: Account<'info, Auction>,
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    /// CHECK: Auction authority PDA, owner of the lot's token vault
+    #[account(
+        seeds = [AUCTION_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_authority: AccountInfo<'info>,
+    #[account(
+        init,
+        payer = creator,
+        space = LotManifest::LEN,
+        seeds = [LOT_MANIFEST_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub lot_manifest: Account<'info, LotManifest>,
+    #[account(mint::token_program = lot_token_program)]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        token::mint = token_mint,
+        token::authority = creator,
+        token::token_program = lot_token_program
+    )]
+    pub creator_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        init_if_needed,
+        payer = creator,
+        associated_token::mint = token_mint,
+        associated_token::authority = auction_authority,
+        associated_token::token_program = lot_token_program
+    )]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub lot_token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ReleaseLotTokens<'info> {
+    pub auction: Account<'info, Auction>,
+    #[account(
+        mut,
+        close = creator,
+        seeds = [LOT_MANIFEST_SEED, auction.key().as_ref()],
+        bump = lot_manifest.bump,
+    )]
+    pub lot_manifest: Account<'info, LotManifest>,
+    #[account(mut, address = auction.creator)]
+    pub creator: SystemAccount<'info>,
+    /// Pays for the recipient's ATA if it doesn't exist yet
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// CHECK: Auction authority PDA, owner of the lot's token vault
+    #[account(
+        seeds = [AUCTION_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_authority: AccountInfo<'info>,
+    /// CHECK: Winner, or creator when the lot didn't sell; checked in handler
+    pub recipient: AccountInfo<'info>,
+    #[account(address = lot_manifest.token_mint, mint::token_program = lot_token_program)]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = auction_authority,
+        associated_token::token_program = lot_token_program
+    )]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = token_mint,
+        associated_token::authority = recipient,
+        associated_token::token_program = lot_token_program
+    )]
+    pub recipient_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub lot_token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
-    pub rent: Sysvar<'info, Rent>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimRefund<'info> {
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+    #[account(
+        mut,
+        close = rent_payer,
+        has_one = bidder,
+        seeds = [BID_ESCROW_SEED, bid_escrow.auction.as_ref(), bidder.key().as_ref()],
+        bump = bid_escrow.bump,
+    )]
+    pub bid_escrow: Account<'info, BidEscrow>,
+    /// CHECK: Funder of the escrow's rent, checked against the escrow
+    #[account(mut, address = bid_escrow.rent_payer)]
+    pub rent_payer: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct PlaceProxyBid<'info> {
+    #[account(mut)]
+    pub auction: Account<'info, Auction>,
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        constraint = !config.halted @ AuctionError::ProgramHalted,
+    )]
+    pub config: Account<'info, Config>,
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+    /// CHECK: Blocklist PDA of the bidder, which must not exist
+    #[account(seeds = [BLOCKLIST_SEED, bidder.key().as_ref()], bump)]
+    pub bidder_blocklist_entry: UncheckedAccount<'info>,
+    /// CHECK: Wallets linked to the creator; read if the admin created it
+    #[account(
+        seeds = [CREATOR_LINKS_SEED, auction.creator.as_ref()],
+        bump,
+    )]
+    pub creator_links: UncheckedAccount<'info>,
+    /// Required when the auction enforces a bid cooldown or bid cap
+    #[account(
+        init_if_needed,
+        payer = bidder,
+        space = BidderStats::LEN,
+        seeds = [BIDDER_STATS_SEED, auction.key().as_ref(), bidder.key().as_ref()],
+        bump,
+    )]
+    pub bidder_stats: Option<Account<'info, BidderStats>>,
+    /// Required when the auction requires registration
+    #[account(
+        seeds = [
+            BIDDER_REGISTRATION_SEED,
+            auction.key().as_ref(),
+            bidder.key().as_ref(),
+        ],
+        bump = registration.bump,
+    )]
+    pub registration: Option<Account<'info, BidderRegistration>>,
+    /// Required when the auction has a bidder allowlist
+    #[account(
+        seeds = [
+            ALLOWLIST_ENTRY_SEED,
+            auction.key().as_ref(),
+            bidder.key().as_ref(),
+        ],
+        bump = allowlist_entry.bump,
+    )]
+    pub allowlist_entry: Option<Account<'info, AllowlistEntry>>,
+    #[account(
+        init_if_needed,
+        payer = bidder,
+        space = ProxyBid::LEN,
+        seeds = [PROXY_BID_SEED, auction.key().as_ref(), bidder.key().as_ref()],
+        bump,
+    )]
+    pub proxy_bid: Account<'info, ProxyBid>,
+    /// Proxy bid of the current leader; required when the standing bid is
+    /// proxy-backed and held by another bidder
+    #[account(
+        mut,
+        seeds = [PROXY_BID_SEED, auction.key().as_ref(), auction.highest_bidder.as_ref()],
+        bump = leader_proxy_bid.bump,
+    )]
+    pub leader_proxy_bid: Option<Account<'info, ProxyBid>>,
+    /// CHECK: Blocklist PDA of the current leader; a blocked leader's proxy
+    /// does not answer
+    #[account(seeds = [BLOCKLIST_SEED, auction.highest_bidder.as_ref()], bump)]
+    pub leader_blocklist_entry: UncheckedAccount<'info>,
+    /// Refund escrow of the bidder being outbid; required once the auction
+    /// has a bid held by another bidder
+    #[account(
+        init_if_needed,
+        payer = bidder,
+        space = BidEscrow::LEN,
+        seeds = [BID_ESCROW_SEED, auction.key().as_ref(), auction.highest_bidder.as_ref()],
+        bump,
+    )]
+    pub previous_bid_escrow: Option<Account<'info, BidEscrow>>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_escrow: SystemAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawProxyBid<'info> {
+    pub auction: Account<'info, Auction>,
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+    #[account(
+        mut,
+        close = bidder,
+        has_one = auction,
+        has_one = bidder,
+        seeds = [PROXY_BID_SEED, auction.key().as_ref(), bidder.key().as_ref()],
+        bump = proxy_bid.bump,
+    )]
+    pub proxy_bid: Account<'info, ProxyBid>,
+}
+
+#[derive(Accounts)]
+pub struct SetAntiSnipe<'info> {
+    #[account(mut)]
+    pub auction: Account<'info, Auction>,
+    pub creator: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct EnableFairEnd<'info> {
+    #[account(mut)]
+    pub auction: Account<'info, Auction>,
+    pub creator: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct EnableDepositBidding<'info> {
+    #[account(mut)]
+    pub auction: Account<'info, Auction>,
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    #[account(
+        init,
+        payer = creator,
+        space = DepositTerms::LEN,
+        seeds = [DEPOSIT_TERMS_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub deposit_terms: Account<'info, DepositTerms>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct PlaceDepositBid<'info> {
+    #[account(mut)]
+    pub auction: Account<'info, Auction>,
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        constraint = !config.halted @ AuctionError::ProgramHalted,
+    )]
+    pub config: Account<'info, Config>,
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+    /// CHECK: Blocklist PDA of the bidder, which must not exist
+    #[account(seeds = [BLOCKLIST_SEED, bidder.key().as_ref()], bump)]
+    pub bidder_blocklist_entry: UncheckedAccount<'info>,
+    /// CHECK: Wallets linked to the creator; read if the admin created it
+    #[account(
+        seeds = [CREATOR_LINKS_SEED, auction.creator.as_ref()],
+        bump,
+    )]
+    pub creator_links: UncheckedAccount<'info>,
+    /// Required when the auction enforces a bid cooldown or bid cap
+    #[account(
+        init_if_needed,
+        payer = bidder,
+        space = BidderStats::LEN,
+        seeds = [BIDDER_STATS_SEED, auction.key().as_ref(), bidder.key().as_ref()],
+        bump,
+    )]
+    pub bidder_stats: Option<Account<'info, BidderStats>>,
+    /// Required when the auction requires registration
+    #[account(
+        seeds = [
+            BIDDER_REGISTRATION_SEED,
+            auction.key().as_ref(),
+            bidder.key().as_ref(),
+        ],
+        bump = registration.bump,
+    )]
+    pub registration: Option<Account<'info, BidderRegistration>>,
+    /// Required when the auction has a bidder allowlist
+    #[account(
+        seeds = [
+            ALLOWLIST_ENTRY_SEED,
+            auction.key().as_ref(),
+            bidder.key().as_ref(),
+        ],
+        bump = allowlist_entry.bump,
+    )]
+    pub allowlist_entry: Option<Account<'info, AllowlistEntry>>,
+    #[account(
+        mut,
+        seeds = [DEPOSIT_TERMS_SEED, auction.key().as_ref()],
+        bump = deposit_terms.bump,
+    )]
+    pub deposit_terms: Account<'info, DepositTerms>,
+    /// Refund escrow of the runner-up being displaced; required once the
+    /// auction has a runner-up
+    #[account(
+        init_if_needed,
+        payer = bidder,
+        space = BidEscrow::LEN,
+        seeds = [BID_ESCROW_SEED, auction.key().as_ref(), deposit_terms.runner_up.as_ref()],
+        bump,
+    )]
+    pub runner_up_escrow: Option<Account<'info, BidEscrow>>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_escrow: SystemAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SettleWinningBid<'info> {
+    #[account(mut)]
+    pub auction: Account<'info, Auction>,
+    #[account(mut)]
+    pub winner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [DEPOSIT_TERMS_SEED, auction.key().as_ref()],
+        bump = deposit_terms.bump,
+    )]
+    pub deposit_terms: Account<'info, DepositTerms>,
+    /// Refund escrow of the runner-up; required when there is one
+    #[account(
+        init_if_needed,
+        payer = winner,
+        space = BidEscrow::LEN,
+        seeds = [BID_ESCROW_SEED, auction.key().as_ref(), deposit_terms.runner_up.as_ref()],
+        bump,
+    )]
+    pub runner_up_escrow: Option<Account<'info, BidEscrow>>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_escrow: SystemAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Forfeit<'info> {
+    #[account(mut)]
+    pub auction: Account<'info, Auction>,
+    #[account(
+        mut,
+        seeds = [DEPOSIT_TERMS_SEED, auction.key().as_ref()],
+        bump = deposit_terms.bump,
+    )]
+    pub deposit_terms: Account<'info, DepositTerms>,
+    #[account(mut, address = auction.creator)]
+    pub creator: SystemAccount<'info>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_escrow: SystemAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetRetractionPenalty<'info> {
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = fee_manager @ AuctionError::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+    pub fee_manager: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetMaxCreatorExtension<'info> {
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = admin @ AuctionError::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetForceRefundAfter<'info> {
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = admin @ AuctionError::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetRelistCooldown<'info> {
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = admin @ AuctionError::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetHolderDiscount<'info> {
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = fee_manager @ AuctionError::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+    pub fee_manager: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RetractBid<'info> {
+    #[account(mut)]
+    pub auction: Account<'info, Auction>,
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    /// Required for deposit-bidding auctions
+    #[account(
+        mut,
+        seeds = [DEPOSIT_TERMS_SEED, auction.key().as_ref()],
+        bump = deposit_terms.bump,
+    )]
+    pub deposit_terms: Option<Account<'info, DepositTerms>>,
+    /// Treasury PDA; receives the retraction penalty. It must already hold
+    /// its rent-exempt minimum
+    #[account(mut, seeds = [TREASURY_SEED], bump)]
+    pub treasury: SystemAccount<'info>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_escrow: SystemAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetBidCooldown<'info> {
+    #[account(mut)]
+    pub auction: Account<'info, Auction>,
+    pub creator: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetBidCap<'info> {
+    #[account(mut)]
+    pub auction: Account<'info, Auction>,
+    pub creator: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(session_key: Pubkey)]
+pub struct AuthorizeSessionKey<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(
+        init,
+        payer = owner,
+        space = SessionKey::LEN,
+        seeds = [SESSION_KEY_SEED, owner.key().as_ref(), session_key.as_ref()],
+        bump,
+    )]
+    pub session: Account<'info, SessionKey>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeSessionKey<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        close = owner,
+        has_one = owner,
+        seeds = [SESSION_KEY_SEED, owner.key().as_ref(), session.session_key.as_ref()],
+        bump = session.bump,
+    )]
+    pub session: Account<'info, SessionKey>,
+}
+
+#[derive(Accounts)]
+pub struct FundBidDeposit<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = BidDeposit::LEN,
+        seeds = [BID_DEPOSIT_SEED, owner.key().as_ref()],
+        bump,
+    )]
+    pub bid_deposit: Account<'info, BidDeposit>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawBidDeposit<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        has_one = owner,
+        seeds = [BID_DEPOSIT_SEED, owner.key().as_ref()],
+        bump = bid_deposit.bump,
+    )]
+    pub bid_deposit: Account<'info, BidDeposit>,
+}
+
+#[derive(Accounts)]
+pub struct SetIncrementTiers<'info> {
+    #[account(mut)]
+    pub auction: Account<'info, Auction>,
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    #[account(
+        init_if_needed,
+        payer = creator,
+        space = IncrementTiers::LEN,
+        seeds = [INCREMENT_TIERS_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub increment_tiers: Account<'info, IncrementTiers>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct EnableClosingQueue<'info> {
+    #[account(mut)]
+    pub auction: Account<'info, Auction>,
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    #[account(
+        init,
+        payer = creator,
+        space = ClosingQueue::LEN,
+        seeds = [CLOSING_QUEUE_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub closing_queue: Account<'info, ClosingQueue>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct QueueBid<'info> {
+    pub auction: Account<'info, Auction>,
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+    /// CHECK: Blocklist PDA of the bidder, which must not exist
+    #[account(seeds = [BLOCKLIST_SEED, bidder.key().as_ref()], bump)]
+    pub bidder_blocklist_entry: UncheckedAccount<'info>,
+    /// CHECK: Wallets linked to the creator; read if the admin created it
+    #[account(
+        seeds = [CREATOR_LINKS_SEED, auction.creator.as_ref()],
+        bump,
+    )]
+    pub creator_links: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [CLOSING_QUEUE_SEED, auction.key().as_ref()],
+        bump = closing_queue.bump,
+    )]
+    pub closing_queue: Account<'info, ClosingQueue>,
+    /// Required when the auction enforces a bid cooldown or bid cap
+    #[account(
+        init_if_needed,
+        payer = bidder,
+        space = BidderStats::LEN,
+        seeds = [BIDDER_STATS_SEED, auction.key().as_ref(), bidder.key().as_ref()],
+        bump,
+    )]
+    pub bidder_stats: Option<Account<'info, BidderStats>>,
+    /// Required when the auction requires registration
+    #[account(
+        seeds = [
+            BIDDER_REGISTRATION_SEED,
+            auction.key().as_ref(),
+            bidder.key().as_ref(),
+        ],
+        bump = registration.bump,
+    )]
+    pub registration: Option<Account<'info, BidderRegistration>>,
+    /// Required when the auction has a bidder allowlist
+    #[account(
+        seeds = [
+            ALLOWLIST_ENTRY_SEED,
+            auction.key().as_ref(),
+            bidder.key().as_ref(),
+        ],
+        bump = allowlist_entry.bump,
+    )]
+    pub allowlist_entry: Option<Account<'info, AllowlistEntry>>,
+    /// Required when the auction uses increment tiers
+    #[account(
+        seeds = [INCREMENT_TIERS_SEED, auction.key().as_ref()],
+        bump = increment_tiers.bump,
+    )]
+    pub increment_tiers: Option<Account<'info, IncrementTiers>>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawQueuedBid<'info> {
+    pub auction: Account<'info, Auction>,
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [CLOSING_QUEUE_SEED, auction.key().as_ref()],
+        bump = closing_queue.bump,
+    )]
+    pub closing_queue: Account<'info, ClosingQueue>,
+}
+
+#[derive(Accounts)]
+pub struct SetMinStandingTime<'info> {
+    #[account(mut)]
+    pub auction: Account<'info, Auction>,
+    pub creator: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetRegistrationDeposit<'info> {
+    #[account(mut)]
+    pub auction: Account<'info, Auction>,
+    pub creator: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RegisterBidder<'info> {
+    pub auction: Account<'info, Auction>,
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+    #[account(
+        init,
+        payer = bidder,
+        space = BidderRegistration::LEN,
+        seeds = [
+            BIDDER_REGISTRATION_SEED,
+            auction.key().as_ref(),
+            bidder.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub registration: Account<'info, BidderRegistration>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawRegistration<'info> {
+    #[account(address = registration.auction)]
+    pub auction: Account<'info, Auction>,
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+    #[account(
+        mut,
+        close = bidder,
+        has_one = bidder,
+        seeds = [
+            BIDDER_REGISTRATION_SEED,
+            registration.auction.as_ref(),
+            bidder.key().as_ref(),
+        ],
+        bump = registration.bump,
+    )]
+    pub registration: Account<'info, BidderRegistration>,
+}
+
+#[derive(Accounts)]
+pub struct SweepRefunds<'info> {
+    pub auction: Account<'info, Auction>,
+    #[account(mut, seeds = [REBATE_POOL_SEED], bump = rebate_pool.bump)]
+    pub rebate_pool: Option<Account<'info, RebatePool>>,
+    /// Registered keeper to credit with the bounty; must sign
+    pub keeper: Option<Signer<'info>>,
+    #[account(
+        mut,
+        constraint = keeper.as_ref().map(|k| k.key()) == Some(keeper_account.keeper)
+            @ AuctionError::Unauthorized,
+    )]
+    pub keeper_account: Option<Account<'info, Keeper>>,
+}
+
+#[derive(Accounts)]
+pub struct CloseBidReceipt<'info> {
+    #[account(address = bid_receipt.auction)]
+    pub auction: Account<'info, Auction>,
+    #[account(mut)]
+    pub rent_payer: Signer<'info>,
+    #[account(mut, close = rent_payer, has_one = rent_payer)]
+    pub bid_receipt: Account<'info, BidReceipt>,
+}
+
+#[derive(Accounts)]
+pub struct SetFirstRefusalWindow<'info> {
+    #[account(mut)]
+    pub auction: Account<'info, Auction>,
+    pub creator: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct MatchFirstRefusal<'info> {
+    #[account(mut)]
+    pub auction: Account<'info, Auction>,
+    /// The standing leader
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+    /// Refund escrow of the pending bidder
+    #[account(
+        init_if_needed,
+        payer = bidder,
+        space = BidEscrow::LEN,
+        seeds = [BID_ESCROW_SEED, auction.key().as_ref(), auction.pending_bidder.as_ref()],
+        bump,
+    )]
+    pub pending_bid_escrow: Account<'info, BidEscrow>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_escrow: SystemAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptPendingBid<'info> {
+    #[account(mut)]
+    pub auction: Account<'info, Auction>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// Refund escrow of the leader being outbid
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = BidEscrow::LEN,
+        seeds = [BID_ESCROW_SEED, auction.key().as_ref(), auction.highest_bidder.as_ref()],
+        bump,
+    )]
+    pub previous_bid_escrow: Account<'info, BidEscrow>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_escrow: SystemAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetReservePrice<'info> {
+    #[account(mut)]
+    pub auction: Account<'info, Auction>,
+    pub creator: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct LowerReservePrice<'info> {
+    #[account(mut)]
+    pub auction: Account<'info, Auction>,
+    pub creator: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct EnableFixedPriceFallback<'info> {
+    #[account(mut)]
+    pub auction: Account<'info, Auction>,
+    pub creator: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RefundConditionalBid<'info> {
+    #[account(mut)]
+    pub auction: Account<'info, Auction>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// Refund escrow of the conditional bidder
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = BidEscrow::LEN,
+        seeds = [BID_ESCROW_SEED, auction.key().as_ref(), auction.highest_bidder.as_ref()],
+        bump,
+    )]
+    pub bid_escrow: Account<'info, BidEscrow>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_escrow: SystemAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CreateBidPool<'info> {
+    pub auction: Account<'info, Auction>,
+    #[account(mut)]
+    pub organizer: Signer<'info>,
+    #[account(
+        init,
+        payer = organizer,
+        space = BidPool::LEN,
+        seeds = [BID_POOL_SEED, auction.key().as_ref(), organizer.key().as_ref()],
+        bump,
+    )]
+    pub bid_pool: Account<'info, BidPool>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ContributeToPool<'info> {
+    #[account(address = bid_pool.auction)]
+    pub auction: Account<'info, Auction>,
+    #[account(
+        mut,
+        seeds = [BID_POOL_SEED, bid_pool.auction.as_ref(), bid_pool.organizer.as_ref()],
+        bump = bid_pool.bump,
+    )]
+    pub bid_pool: Account<'info, BidPool>,
+    #[account(mut)]
+    pub contributor: Signer<'info>,
+    #[account(
+        init_if_needed,
+        payer = contributor,
+        space = PoolContribution::LEN,
+        seeds = [POOL_CONTRIBUTION_SEED, bid_pool.key().as_ref(), contributor.key().as_ref()],
+        bump,
+    )]
+    pub contribution: Account<'info, PoolContribution>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct PlacePoolBid<'info> {
+    #[account(mut, address = bid_pool.auction)]
+    pub auction: Account<'info, Auction>,
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        constraint = !config.halted @ AuctionError::ProgramHalted,
+    )]
+    pub config: Account<'info, Config>,
+    #[account(mut)]
+    pub organizer: Signer<'info>,
+    /// CHECK: Blocklist PDA of the organizer, which must not exist
+    #[account(seeds = [BLOCKLIST_SEED, organizer.key().as_ref()], bump)]
+    pub organizer_blocklist_entry: UncheckedAccount<'info>,
+    /// CHECK: Wallets linked to the creator; read if the admin created it
+    #[account(
+        seeds = [CREATOR_LINKS_SEED, auction.creator.as_ref()],
+        bump,
+    )]
+    pub creator_links: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        has_one = organizer,
+        seeds = [BID_POOL_SEED, bid_pool.auction.as_ref(), organizer.key().as_ref()],
+        bump = bid_pool.bump,
+    )]
+    pub bid_pool: Account<'info, BidPool>,
+    /// Required when the auction enforces a bid cooldown or bid cap
+    #[account(
+        init_if_needed,
+        payer = organizer,
+        space = BidderStats::LEN,
+        seeds = [BIDDER_STATS_SEED, auction.key().as_ref(), bid_pool.key().as_ref()],
+        bump,
+    )]
+    pub bidder_stats: Option<Account<'info, BidderStats>>,
+    /// Required when the auction requires registration
+    #[account(
+        seeds = [
+            BIDDER_REGISTRATION_SEED,
+            auction.key().as_ref(),
+            bid_pool.key().as_ref(),
+        ],
+        bump = registration.bump,
+    )]
+    pub registration: Option<Account<'info, BidderRegistration>>,
+    /// Required when the auction has a bidder allowlist
+    #[account(
+        seeds = [
+            ALLOWLIST_ENTRY_SEED,
+            auction.key().as_ref(),
+            bid_pool.key().as_ref(),
+        ],
+        bump = allowlist_entry.bump,
+    )]
+    pub allowlist_entry: Option<Account<'info, AllowlistEntry>>,
+    /// Refund escrow of the bidder being outbid; required once the auction
+    /// has a bid
+    #[account(
+        init_if_needed,
+        payer = organizer,
+        space = BidEscrow::LEN,
+        seeds = [BID_ESCROW_SEED, auction.key().as_ref(), auction.highest_bidder.as_ref()],
+        bump,
+    )]
+    pub previous_bid_escrow: Option<Account<'info, BidEscrow>>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_escrow: SystemAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ReclaimPoolRefund<'info> {
+    #[account(
+        mut,
+        seeds = [BID_POOL_SEED, bid_pool.auction.as_ref(), bid_pool.organizer.as_ref()],
+        bump = bid_pool.bump,
+    )]
+    pub bid_pool: Account<'info, BidPool>,
+    #[account(
+        mut,
+        close = rent_payer,
+        seeds = [BID_ESCROW_SEED, bid_pool.auction.as_ref(), bid_pool.key().as_ref()],
+        bump = bid_escrow.bump,
+    )]
+    pub bid_escrow: Account<'info, BidEscrow>,
+    /// CHECK: Funder of the escrow's rent, checked against the escrow
+    #[account(mut, address = bid_escrow.rent_payer)]
+    pub rent_payer: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawPoolShare<'info> {
+    #[account(address = bid_pool.auction)]
+    pub auction: Account<'info, Auction>,
+    #[account(
+        mut,
+        seeds = [BID_POOL_SEED, bid_pool.auction.as_ref(), bid_pool.organizer.as_ref()],
+        bump = bid_pool.bump,
+    )]
+    pub bid_pool: Account<'info, BidPool>,
+    #[account(mut)]
+    pub contributor: Signer<'info>,
+    #[account(
+        mut,
+        has_one = contributor,
+        seeds = [POOL_CONTRIBUTION_SEED, bid_pool.key().as_ref(), contributor.key().as_ref()],
+        bump = contribution.bump,
+    )]
+    pub contribution: Account<'info, PoolContribution>,
+    /// CHECK: The pool's refund escrow, which must be reclaimed first
+    #[account(
+        seeds = [BID_ESCROW_SEED, bid_pool.auction.as_ref(), bid_pool.key().as_ref()],
+        bump,
+    )]
+    pub pool_bid_escrow: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetRebidStrategy<'info> {
+    pub auction: Account<'info, Auction>,
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+    #[account(
+        init_if_needed,
+        payer = bidder,
+        space = RebidStrategy::LEN,
+        seeds = [REBID_STRATEGY_SEED, auction.key().as_ref(), bidder.key().as_ref()],
+        bump,
+    )]
+    pub strategy: Account<'info, RebidStrategy>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelRebidStrategy<'info> {
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+    #[account(
+        mut,
+        has_one = bidder,
+        close = bidder,
+        seeds = [REBID_STRATEGY_SEED, strategy.auction.as_ref(), bidder.key().as_ref()],
+        bump = strategy.bump,
+    )]
+    pub strategy: Account<'info, RebidStrategy>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteRebid<'info> {
+    #[account(mut, address = strategy.auction)]
+    pub auction: Account<'info, Auction>,
+    /// Whoever cranks the rebid; pays the refund escrow's rent
+    #[account(mut)]
+    pub cranker: Signer<'info>,
+    #[account(
+        seeds = [REBID_STRATEGY_SEED, strategy.auction.as_ref(), strategy.bidder.as_ref()],
+        bump = strategy.bump,
+    )]
+    pub strategy: Account<'info, RebidStrategy>,
+    /// CHECK: Blocklist PDA of the strategy's bidder, which must not exist
+    #[account(seeds = [BLOCKLIST_SEED, strategy.bidder.as_ref()], bump)]
+    pub bidder_blocklist_entry: UncheckedAccount<'info>,
+    /// CHECK: Wallets linked to the creator; read if the admin created it
+    #[account(
+        seeds = [CREATOR_LINKS_SEED, auction.creator.as_ref()],
+        bump,
+    )]
+    pub creator_links: UncheckedAccount<'info>,
+    /// Deposit the rebid is paid from
+    #[account(
+        mut,
+        seeds = [BID_DEPOSIT_SEED, strategy.bidder.as_ref()],
+        bump = bid_deposit.bump,
+    )]
+    pub bid_deposit: Account<'info, BidDeposit>,
+    /// Required when the auction enforces a bid cooldown or bid cap
+    #[account(
+        init_if_needed,
+        payer = cranker,
+        space = BidderStats::LEN,
+        seeds = [BIDDER_STATS_SEED, auction.key().as_ref(), strategy.bidder.as_ref()],
+        bump,
+    )]
+    pub bidder_stats: Option<Account<'info, BidderStats>>,
+    /// Required when the auction requires registration
+    #[account(
+        seeds = [
+            BIDDER_REGISTRATION_SEED,
+            auction.key().as_ref(),
+            strategy.bidder.as_ref(),
+        ],
+        bump = registration.bump,
+    )]
+    pub registration: Option<Account<'info, BidderRegistration>>,
+    /// Required when the auction has a bidder allowlist
+    #[account(
+        seeds = [
+            ALLOWLIST_ENTRY_SEED,
+            auction.key().as_ref(),
+            strategy.bidder.as_ref(),
+        ],
+        bump = allowlist_entry.bump,
+    )]
+    pub allowlist_entry: Option<Account<'info, AllowlistEntry>>,
+    /// Refund escrow of the bidder being outbid
+    #[account(
+        init_if_needed,
+        payer = cranker,
+        space = BidEscrow::LEN,
+        seeds = [BID_ESCROW_SEED, auction.key().as_ref(), auction.highest_bidder.as_ref()],
+        bump,
+    )]
+    pub previous_bid_escrow: Account<'info, BidEscrow>,
+    /// Required when the auction uses increment tiers
+    #[account(
+        seeds = [INCREMENT_TIERS_SEED, auction.key().as_ref()],
+        bump = increment_tiers.bump,
+    )]
+    pub increment_tiers: Option<Account<'info, IncrementTiers>>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_escrow: SystemAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct EnableLoserRebate<'info> {
+    #[account(mut)]
+    pub auction: Account<'info, Auction>,
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    #[account(
+        init,
+        payer = creator,
+        space = LoserRebate::LEN,
+        seeds = [LOSER_REBATE_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub loser_rebate: Account<'info, LoserRebate>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimLoserRebate<'info> {
+    #[account(address = loser_rebate.auction)]
+    pub auction: Account<'info, Auction>,
+    #[account(
+        seeds = [TOP_BIDS_SEED, auction.key().as_ref()],
+        bump = top_bids.bump,
+    )]
+    pub top_bids: Account<'info, TopBids>,
+    #[account(
+        mut,
+        seeds = [LOSER_REBATE_SEED, loser_rebate.auction.as_ref()],
+        bump = loser_rebate.bump,
+    )]
+    pub loser_rebate: Account<'info, LoserRebate>,
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetBidderAllowlist<'info> {
+    #[account(mut)]
+    pub auction: Account<'info, Auction>,
+    pub creator: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetTokenGate<'info> {
+    #[account(mut)]
+    pub auction: Account<'info, Auction>,
+    pub creator: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetAttestationGate<'info> {
+    #[account(mut)]
+    pub auction: Account<'info, Auction>,
+    pub creator: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct TransferBid<'info> {
+    /// Escrow of the bidder's outbid bids on the auction they are leaving
+    #[account(
+        mut,
+        close = rent_payer,
+        seeds = [BID_ESCROW_SEED, source_escrow.auction.as_ref(), bid.bidder.key().as_ref()],
+        bump = source_escrow.bump,
+    )]
+    pub source_escrow: Account<'info, BidEscrow>,
+    /// CHECK: Funder of the escrow's rent, checked against the escrow
+    #[account(mut, address = source_escrow.rent_payer)]
+    pub rent_payer: AccountInfo<'info>,
+    /// The bid on the auction the escrow moves to
+    pub bid: PlaceBid<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseAuction<'info> {
+    #[account(
+        mut,
+        has_one = creator @ AuctionError::UnauthorizedUpdate,
+        close = creator,
+    )]
+    pub auction: Account<'info, Auction>,
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    /// CHECK: Auction authority PDA
+    #[account(
+        seeds = [AUCTION_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_authority: AccountInfo<'info>,
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, auction.key().as_ref()],
+        bump,
+    )]
+    pub auction_escrow: SystemAccount<'info>,
+    #[account(address = auction.nft_mint)]
+    pub nft_mint: InterfaceAccount<'info, Mint>,
+    /// Vault the lot was held in; omit for assets custodied without one
+    #[account(
+        mut,
+        associated_token::mint = nft_mint,
+        associated_token::authority = auction_authority,
+        associated_token::token_program = token_program
+    )]
+    pub vault_nft_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct PauseAuction<'info> {
+    #[account(mut)]
+    pub auction: Account<'info, Auction>,
+    /// The auction's creator, or the config admin
+    pub authority: Signer<'info>,
+    /// Required when the admin signs
+    #[account(seeds = [CONFIG_SEED], bump = config.bump)]
+    pub config: Option<Account<'info, Config>>,
+}
+
+#[account]
+pub struct Auction {
+    pub creator: Pubkey,
+    pub nft_mint: Pubkey,
+    pub starting_bid: u64,
+    pub min_bid_increment: u64,
+    pub end_time: i64,
+    pub highest_bid: u64,
+    pub highest_bidder: Pubkey,
+    pub status: AuctionStatus,
+    pub buy_now_price: u64,
+    pub buy_now_executed: bool,
+    pub bid_mint: Pubkey,
+    pub highest_bid_wrapped: bool,
+    pub stable_preset: bool,
+    pub multi_currency: bool,
+    pub highest_bid_mint: Pubkey,
+    pub highest_bid_value: u64,
+    pub is_compressed: bool,
+    pub fee_bps: u16,
+    pub extension_window: i64,
+    pub extension_amount: i64,
+    pub experiment_id: u32,
+    pub experiment_variant: u8,
+    pub is_programmable: bool,
+    /// Token amount escrowed and settled to the winner
+    pub quantity: u64,
+    pub asset_kind: AssetKind,
+    /// Lamports the creator put into the SOL escrow to keep it rent-exempt;
+    /// returned once the escrow is emptied at settlement or cancellation
+    pub escrow_reserve: u64,
+    /// Verified collection the NFT was required to belong to at listing, or
+    /// the default key for unscoped auctions
+    pub collection: Pubkey,
+    /// Bids fill the lot by unit price instead of a single winner taking it
+    pub partial_fills: bool,
+    /// Lamports escrowed for partial-fill bids not yet paid out or refunded
+    pub partial_escrowed: u64,
+    /// Seller token account the NFT is pulled from in delegated and
+    /// escrowless auctions
+    pub seller_token_account: Pubkey,
+    /// Stake pool the seller's SOL proceeds are deposited into at finalize;
+    /// default pays out plain lamports
+    pub lst_payout_pool: Pubkey,
+    /// Kamino reserve escrowed SPL bids may be lent to; default disables it
+    pub yield_reserve: Pubkey,
+    /// Escrowed amount currently lent to `yield_reserve`
+    pub yield_principal: u64,
+    /// Seller proceeds are held in a `Dispute` delivery escrow after
+    /// settlement
+    pub delivery_escrow: bool,
+    /// The standing bid is backed by the highest bidder's `ProxyBid`
+    pub proxy_bid_active: bool,
+    /// Latest `end_time` anti-snipe extensions may reach; `0` is uncapped
+    pub max_end_time: i64,
+    /// Minimum increment as a share of the standing bid; bids must clear the
+    /// larger of this and `min_bid_increment`
+    pub min_bid_increment_bps: u16,
+    pub deposit_mode: DepositMode,
+    /// Some bid has been outbid; the leader can no longer retract
+    pub contested: bool,
+    /// Seconds each wallet must wait between bids; `0` is unlimited
+    pub bid_cooldown: i64,
+    /// Most bids one wallet may place; `0` is unlimited
+    pub max_bids_per_wallet: u32,
+    /// Minimum increments follow the auction's `IncrementTiers`
+    pub tiered_increments: bool,
+    /// Bids in the last `CLOSING_QUEUE_WINDOW` seconds go to a
+    /// `ClosingQueue` resolved at finalize
+    pub closing_queue: bool,
+    /// Seconds a new highest bid stands before it can be outbid; `0` is
+    /// unlimited
+    pub min_standing_time: u16,
+    /// When the latest bid landed
+    pub last_bid_at: i64,
+    /// Refundable deposit bidders must lock with `register_bidder` before
+    /// bidding; `0` lets anyone bid
+    pub registration_deposit: u64,
+    /// Seconds an outbid leader has to match a new bid before it stands;
+    /// `0` disables the right of first refusal
+    pub first_refusal_window: i64,
+    /// Bid waiting on the leader's right of first refusal; default when none
+    pub pending_bidder: Pubkey,
+    pub pending_bid: u64,
+    /// When the pending bid can be accepted
+    pub pending_until: i64,
+    /// Price conditional bids must reach to count; `0` when unset
+    pub reserve_price: u64,
+    /// Last conditional bid placed; it is only conditional while it is
+    /// still the standing bid
+    pub conditional_bidder: Pubkey,
+    pub conditional_amount: u64,
+    /// Last leader who showed they hold the config's holder mint, and the
+    /// discount they bid with
+    pub discount_holder: Pubkey,
+    pub holder_discount_bps: u16,
+    /// Share of the platform fee set aside for losing bidders; `0` when off
+    pub loser_rebate_bps: u16,
+    /// Merkle root of the wallets allowed to bid; all zeroes when anyone may
+    pub allowlist_root: [u8; 32],
+    /// When the auction was paused; `0` while it runs
+    pub paused_at: i64,
+    /// Seconds the creator has added to `end_time` with `extend_end_time`
+    pub creator_extension: i64,
+    /// Reason code the admin gave for an `emergency_cancel`; `0` otherwise
+    pub cancel_reason: u8,
+    /// Settled with `settle_auction`: the seller and winner pull their legs
+    /// with `claim_proceeds` and `claim_item`
+    pub split_settlement: bool,
+    /// Seller proceeds left in the SOL escrow for `claim_proceeds`
+    pub proceeds_owed: u64,
+    pub item_claimed: bool,
+    /// Layout version the account was created or last migrated under; `0`
+    /// predates versioning
+    pub version: u8,
+    /// When marketplaces may start showing a scheduled auction; `0` when it
+    /// was never scheduled
+    pub preview_start: i64,
+    /// When a scheduled auction opens for bids
+    pub bid_start: i64,
+    /// Slot bidding ends at for slot-timed auctions, which then keep
+    /// `end_time` only as an estimate; `0` ends at `end_time`
+    pub end_slot: u64,
+    /// The highest bidder took back their bid with `recover_bid`
+    pub bid_recovered: bool,
+    /// Settles only once `end_auction` has recorded a SlotHashes cutoff
+    pub fair_end: bool,
+    /// Newest SlotHashes entry when `end_auction` ran on a fair-end auction
+    pub cutoff_slot: u64,
+    pub cutoff_hash: [u8; 32],
+    /// `finalize_auction` left the NFT in the vault for `claim_won_nft`
+    pub nft_parked: bool,
+    /// Unsold, the auction becomes a fixed-price listing at the reserve
+    pub fixed_price_fallback: bool,
+    /// Mint bidders must hold `gate_min_balance` of to bid; default when
+    /// the auction is not token-gated
+    pub gate_mint: Pubkey,
+    pub gate_min_balance: u64,
+    /// Gatekeeper network bidders need an active gateway token from;
+    /// default when bidding needs no attestation
+    pub attestation_network: Pubkey,
+}
+
+impl Auction {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // creator
+        32 + // nft_mint
+        8 + // starting_bid
+        8 + // min_bid_increment
+        8 + // end_time
+        8 + // highest_bid
+        32 + // highest_bidder
+        1 + // status
+        8 + // buy_now_price
+        1 + // buy_now_executed
+        32 + // bid_mint
+        1 + // highest_bid_wrapped
+        1 + // stable_preset
+        1 + // multi_currency
+        32 + // highest_bid_mint
+        8 + // highest_bid_value
+        1 + // is_compressed
+        2 + // fee_bps
+        8 + // extension_window
+        8 + // extension_amount
+        4 + // experiment_id
+        1 + // experiment_variant
+        1 + // is_programmable
+        8 + // quantity
+        1 + // asset_kind
+        8 + // escrow_reserve
+        32 + // collection
+        1 + // partial_fills
+        8 + // partial_escrowed
+        32 + // seller_token_account
+        32 + // lst_payout_pool
+        32 + // yield_reserve
+        8 + // yield_principal
+        1 + // delivery_escrow
+        1 + // proxy_bid_active
+        8 + // max_end_time
+        2 + // min_bid_increment_bps
+        1 + // deposit_mode
+        1 + // contested
+        8 + // bid_cooldown
+        4 + // max_bids_per_wallet
+        1 + // tiered_increments
+        1 + // closing_queue
+        2 + // min_standing_time
+        8 + // last_bid_at
+        8 + // registration_deposit
+        8 + // first_refusal_window
+        32 + // pending_bidder
+        8 + // pending_bid
+        8 + // pending_until
+        8 + // reserve_price
+        32 + // conditional_bidder
+        8 + // conditional_amount
+        32 + // discount_holder
+        2 + // holder_discount_bps
+        2 + // loser_rebate_bps
+        32 + // allowlist_root
+        8 + // paused_at
+        8 + // creator_extension
+        1 + // cancel_reason
+        1 + // split_settlement
+        8 + // proceeds_owed
+        1 + // item_claimed
+        1 + // version
+        8 + // preview_start
+        8 + // bid_start
+        8 + // end_slot
+        1 + // bid_recovered
+        1 + // fair_end
+        8 + // cutoff_slot
+        32 + // cutoff_hash
+        1 + // nft_parked
+        1 + // fixed_price_fallback
+        32 + // gate_mint
+        8 + // gate_min_balance
+        32; // attestation_network
+
+    /// Sets up a freshly created auction for `params`, ending `duration`
+    /// seconds after `now`.
+    pub fn open(&mut self, creator: Pubkey, nft_mint: Pubkey, params: &AuctionParams, now: i64) {
+        self.version = AUCTION_VERSION;
+        self.creator = creator;
+        self.nft_mint = nft_mint;
+        self.starting_bid = params.starting_bid;
+        self.min_bid_increment = params.min_bid_increment;
+        self.end_time = now + params.duration;
+        self.highest_bid = 0;
+        self.highest_bidder = Pubkey::default();
+        self.status = AuctionStatus::Live;
+        self.buy_now_price = params.buy_now_price.unwrap_or(0);
+        self.buy_now_executed = false;
+        self.fee_bps = DEFAULT_FEE_BPS;
+        self.quantity = 1;
+    }
+
+    /// Restarts an unsold auction now with new `params`. Caps, creator
+    /// extensions and any fair-end cutoff tied to the previous end time are
+    /// cleared.
+    pub fn relist(&mut self, params: &AuctionParams, clock: &Clock) {
+        self.starting_bid = params.starting_bid;
+        self.min_bid_increment = params.min_bid_increment;
+        self.set_deadline(clock, params.duration);
+        self.buy_now_price = params.buy_now_price.unwrap_or(0);
+        self.max_end_time = 0;
+        self.creator_extension = 0;
+        self.last_bid_at = 0;
+        self.cutoff_slot = 0;
+        self.cutoff_hash = [0; 32];
+    }
+
+    /// Token amount held in the vault. Auctions created before quantities
+    /// were introduced store `0` and always escrow a single token.
+    pub fn lot_size(&self) -> u64 {
+        self.quantity.max(1)
+    }
+
+    /// Platform fee charged at settlement. Auctions created before the fee
+    /// was recorded per auction store `0` and pay `DEFAULT_FEE_BPS`.
+    pub fn platform_fee_bps(&self) -> u16 {
+        if self.version == 0 && self.fee_bps == 0 {
+            DEFAULT_FEE_BPS
+        } else {
+            self.fee_bps
+        }
+    }
+
+    /// Fills in fields an account laid out under `previous_version` left at
+    /// zero where zero now means something else, so migrating keeps the
+    /// auction settling the way it was listed.
+    pub fn backfill(&mut self, previous_version: u8) {
+        if previous_version < 1 {
+            self.fee_bps = self.platform_fee_bps();
+            self.quantity = self.lot_size();
+        }
+    }
+
+    /// Enrolls the auction in `experiment` if it is active, applying the
+    /// variant picked deterministically from the auction's key.
+    pub fn join_experiment(&mut self, auction_key: &Pubkey, experiment: &Experiment) {
+        if !experiment.active {
+            return;
+        }
+        let variant = experiment.assign(auction_key);
+        let params = &experiment.variants[variant as usize];
+        self.experiment_id = experiment.id;
+        self.experiment_variant = variant;
+        self.fee_bps = params.fee_bps;
+        self.extension_window = params.extension_window;
+        self.extension_amount = params.extension_amount;
+    }
+
+    /// Records a bid landing at `now`, and pushes `end_time` out by
+    /// `extension_amount` when it lands inside the final `extension_window`
+    /// seconds, up to `max_end_time` when set.
+    pub fn apply_extension(&mut self, now: i64) {
+        self.last_bid_at = now;
+        if self.extension_window > 0 && self.end_time - now <= self.extension_window {
+            let mut extended = self.end_time + self.extension_amount;
+            if self.max_end_time > 0 {
+                extended = extended.min(self.max_end_time);
+            }
+            self.push_deadline((extended - self.end_time).max(0));
+        }
+    }
+
+    /// Whether bidding time is up: past `end_slot` for slot-timed auctions,
+    /// otherwise past `end_time`.
+    pub fn has_ended(&self, clock: &Clock) -> bool {
+        if self.end_slot > 0 {
+            clock.slot >= self.end_slot
+        } else {
+            clock.unix_timestamp >= self.end_time
+        }
+    }
+
+    /// Whether bidding time is up and, for fair-end auctions, `end_auction`
+    /// has recorded the cutoff, so the auction may be settled.
+    pub fn is_closed(&self, clock: &Clock) -> bool {
+        self.has_ended(clock) && (!self.fair_end || self.cutoff_slot > 0)
+    }
+
+    /// Sets the deadline `duration` seconds from now, in slots as well for
+    /// slot-timed auctions.
+    pub fn set_deadline(&mut self, clock: &Clock, duration: i64) {
+        self.end_time = clock.unix_timestamp + duration;
+        if self.end_slot > 0 {
+            self.end_slot = clock.slot + seconds_to_slots(duration);
+        }
+    }
+
+    /// Pushes the deadline out by `seconds`, in slots as well for slot-timed
+    /// auctions.
+    pub fn push_deadline(&mut self, seconds: i64) {
+        self.end_time += seconds;
+        if self.end_slot > 0 {
+            self.end_slot += seconds_to_slots(seconds);
+        }
+    }
+
+    /// Whether bids are escrowed in native SOL rather than an SPL mint.
+    pub fn is_native(&self) -> bool {
+        self.bid_mint == Pubkey::default() && !self.multi_currency
+    }
+
+    /// Whether any bid, single or partial-fill, is held in escrow.
+    pub fn has_bids(&self) -> bool {
+        self.highest_bid > 0 || self.partial_escrowed > 0
+    }
+
+    /// Whether a split settlement still owes the seller their proceeds or
+    /// the winner the item.
+    pub fn claims_outstanding(&self) -> bool {
+        self.split_settlement
+            && (self.proceeds_owed > 0 || (self.highest_bid > 0 && !self.item_claimed))
+    }
+
+    /// Mint the winning bid is escrowed in.
+    pub fn settlement_mint(&self) -> Pubkey {
+        if self.multi_currency {
+            self.highest_bid_mint
+        } else {
+            self.bid_mint
+        }
+    }
+
+    /// The standing bid that new bids are compared against. Multi-currency
+    /// auctions compare normalized micro-USD values rather than raw amounts.
+    pub fn current_price(&self) -> u64 {
+        if self.multi_currency {
+            self.highest_bid_value
+        } else {
+            self.highest_bid
+        }
+    }
+
+    /// The bid `premium_bps` above the standing bid, or the starting bid
+    /// when there is none.
+    pub fn relative_bid(&self, premium_bps: u16) -> Option<u64> {
+        if self.highest_bid == 0 {
+            return Some(self.starting_bid);
+        }
+        let premium = self.highest_bid as u128 * premium_bps as u128 / BPS_DENOMINATOR as u128;
+        self.highest_bid.checked_add(premium as u64)
+    }
+
+    /// Smallest raise over the standing bid a new bid must clear: the largest
+    /// of the flat increment, `min_bid_increment_bps` of the current price
+    /// and the increment `tiers` set for the current price.
+    pub fn min_increment(&self, tiers: Option<&IncrementTiers>) -> u64 {
+        let share = self.current_price() as u128 * self.min_bid_increment_bps as u128
+            / BPS_DENOMINATOR as u128;
+        let tier = tiers.map_or(0, |tiers| tiers.increment_for(self.current_price()));
+        self.min_bid_increment.max(share as u64).max(tier)
+    }
+
+    /// Checks that the auction accepts bids and that `amount` clears the
+    /// starting bid and minimum increment. Auctions with increment tiers
+    /// must be passed their `IncrementTiers`.
+    pub fn check_bid(&self, amount: u64, tiers: Option<&IncrementTiers>) -> Result<()> {
+        require!(
+            self.deposit_mode == DepositMode::Off,
+            AuctionError::DepositAuction
+        );
+        self.check_bid_amount(amount, tiers)
+    }
+
+    /// `check_bid` for any bidding mode, including deposit bidding.
+    pub fn check_bid_amount(&self, amount: u64, tiers: Option<&IncrementTiers>) -> Result<()> {
+        if self.closing_queue || self.min_standing_time > 0 {
+            let now = Clock::get()?.unix_timestamp;
+            require!(!self.queue_open(now), AuctionError::ClosingQueueOpen);
+            require!(
+                self.highest_bid == 0 || now >= self.standing_until(),
+                AuctionError::BidStillStanding
+            );
+        }
+        self.check_bid_terms(amount, tiers)
+    }
+
+    /// Whether a bid is waiting on the outbid leader's right of first refusal.
+    pub fn has_pending_bid(&self) -> bool {
+        self.pending_bidder != Pubkey::default()
+    }
+
+    /// Whether the standing bid is a conditional bid below the reserve.
+    pub fn conditional_bid_unmet(&self) -> bool {
+        self.highest_bid > 0
+            && self.highest_bid < self.reserve_price
+            && self.highest_bidder == self.conditional_bidder
+            && self.highest_bid == self.conditional_amount
+    }
+
+    /// Whether the auction is paused.
+    pub fn is_paused(&self) -> bool {
+        self.paused_at > 0
+    }
+
+    /// Whether bidding is restricted to an allowlist.
+    pub fn has_allowlist(&self) -> bool {
+        self.allowlist_root != [0; 32]
+    }
+
+    /// Whether bidders must hold the gate mint.
+    pub fn is_token_gated(&self) -> bool {
+        self.gate_mint != Pubkey::default()
+    }
+
+    /// Whether bidders must show a gateway token of `attestation_network`.
+    pub fn requires_attestation(&self) -> bool {
+        self.attestation_network != Pubkey::default()
+    }
+
+    /// Whether bidding has a gate only `place_bid` checks.
+    pub fn has_bid_gate(&self) -> bool {
+        self.is_token_gated() || self.requires_attestation()
+    }
+
+    /// Whether the standing bid was placed with the holder discount.
+    pub fn winner_has_holder_discount(&self) -> bool {
+        self.holder_discount_bps > 0 && self.discount_holder == self.highest_bidder
+    }
+
+    /// Drops the pending bid once it is matched or accepted.
+    pub fn clear_pending_bid(&mut self) {
+        self.pending_bidder = Pubkey::default();
+        self.pending_bid = 0;
+        self.pending_until = 0;
+    }
+
+    /// When the standing bid may first be outbid.
+    pub fn standing_until(&self) -> i64 {
+        self.last_bid_at + self.min_standing_time as i64
+    }
+
+    /// Whether bids at `now` go to the closing queue instead of the auction.
+    pub fn queue_open(&self, now: i64) -> bool {
+        self.closing_queue && now >= self.end_time - CLOSING_QUEUE_WINDOW
+    }
+
+    /// `check_bid_amount` without the closing window check, for bids
+    /// entering the closing queue.
+    pub fn check_bid_terms(&self, amount: u64, tiers: Option<&IncrementTiers>) -> Result<()> {
+        require!(
+            self.status != AuctionStatus::Scheduled,
+            AuctionError::BiddingNotOpen
+        );
+        require!(
+            self.status == AuctionStatus::Live,
+            AuctionError::AuctionNotActive
+        );
+        require!(!self.is_paused(), AuctionError::AuctionPaused);
+        require!(!self.has_pending_bid(), AuctionError::BidPending);
+        require!(
+            !self.tiered_increments || tiers.is_some(),
+            AuctionError::MissingIncrementTiers
+        );
+        require!(!self.partial_fills, AuctionError::PartialFillAuction);
+        require!(
+            !self.buy_now_executed,
+            AuctionError::BuyNowAlreadyExecuted
+        );
+        require!(amount >= self.starting_bid, AuctionError::BidTooLow);
+        if self.highest_bid > 0 {
+            require!(
+                amount >= self.current_price() + self.min_increment(tiers),
+                AuctionError::BidIncrementTooLow
+            );
+        }
+        Ok(())
+    }
+}
+
+
+#[account]
+pub struct Config {
+    pub admin: Pubkey,
+    pub bump: u8,
+    /// SPL mints auctions may take bids in
+    pub bid_mints: Vec<Pubkey>,
+    /// Share of a retracted bid kept for the treasury
+    pub retraction_penalty_bps: u16,
+    /// Mint whose holders win with `holder_discount_bps` back
+    pub holder_mint: Pubkey,
+    /// Balance of the holder mint a bidder must hold for the discount
+    pub holder_min_balance: u64,
+    /// Share of the winning bid returned to a holder out of the platform fee
+    pub holder_discount_bps: u16,
+    /// Most seconds a creator may add to an auction's `end_time` in all
+    pub max_creator_extension: i64,
+    /// Seconds a cancelled mint must wait before it is listed again
+    pub relist_cooldown: i64,
+    /// Seconds past `end_time` after which anyone may `force_refund` an
+    /// unfinalized auction; `0` when off
+    pub force_refund_after: i64,
+    /// Platform fee new auctions are listed at
+    pub fee_bps: u16,
+    /// Only account finalize may pay the platform fee to
+    pub fee_destination: Pubkey,
+    /// Shortest duration an auction may be listed for
+    pub min_duration: i64,
+    /// Longest duration an auction may be listed for; `0` when unbounded
+    pub max_duration: i64,
+    /// Stops new listings and bids program-wide while set
+    pub halted: bool,
+    /// `initialize_auction` only lists NFTs of approved collections while set
+    pub curated: bool,
+    /// May halt and resume the program
+    pub pauser: Pubkey,
+    /// May set the platform fee and the fee-like config parameters
+    pub fee_manager: Pubkey,
+    /// May run curated mode and its collection registry
+    pub curator: Pubkey,
+}
+
+impl Config {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // admin
+        1 + // bump
+        4 + MAX_BID_MINTS * 32 + // bid_mints
+        2 + // retraction_penalty_bps
+        32 + // holder_mint
+        8 + // holder_min_balance
+        2 + // holder_discount_bps
+        8 + // max_creator_extension
+        8 + // relist_cooldown
+        8 + // force_refund_after
+        2 + // fee_bps
+        32 + // fee_destination
+        8 + // min_duration
+        8 + // max_duration
+        1 + // halted
+        1 + // curated
+        32 + // pauser
+        32 + // fee_manager
+        32 + // curator
+        2; // padding for future extensions
+
+    pub fn accepts_bid_mint(&self, mint: &Pubkey) -> bool {
+        self.bid_mints.contains(mint)
+    }
+
+    /// Holds a newly opened auction to the platform's duration bounds and
+    /// lists it at the platform fee.
+    pub fn apply_terms(&self, auction: &mut Auction, duration: i64) -> Result<()> {
+        require!(
+            duration >= self.min_duration
+                && (self.max_duration == 0 || duration <= self.max_duration),
+            AuctionError::DurationOutOfBounds
+        );
+        auction.fee_bps = self.fee_bps;
+        Ok(())
+    }
+}
+
+/// Marks a verified collection as listable while the config is curated.
+#[account]
+pub struct ApprovedCollection {
+    pub collection: Pubkey,
+    pub bump: u8,
+}
+
+impl ApprovedCollection {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // collection
+        1; // bump
+}
+
+/// Bars a wallet from creating auctions or bidding while it exists.
+#[account]
+pub struct BlocklistEntry {
+    pub wallet: Pubkey,
+    pub blocked_at: i64,
+    pub bump: u8,
+}
+
+impl BlocklistEntry {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // wallet
+        8 + // blocked_at
+        1; // bump
+}
+
+/// When a mint cancelled with `cancel_auction` may be listed again.
+#[account]
+pub struct MintCooldown {
+    pub mint: Pubkey,
+    pub relist_after: i64,
+    pub bump: u8,
+}
+
+impl MintCooldown {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // mint
+        8 + // relist_after
+        1; // bump
+}
+
+/// Wallets the admin has linked to a creator as known sybils, barred from
+/// bidding on the creator's auctions.
+#[account]
+pub struct CreatorLinks {
+    pub creator: Pubkey,
+    pub linked: Vec<Pubkey>,
+    pub bump: u8,
+}
+
+impl CreatorLinks {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // creator
+        4 + MAX_CREATOR_LINKS * 32 + // linked
+        1; // bump
+}
+
+/// Records where an imported auction came from.
+#[account]
+pub struct AuctionProvenance {
+    pub auction: Pubkey,
+    pub legacy_program: Pubkey,
+    pub legacy_account: Pubkey,
+    pub imported_by: Pubkey,
+    pub imported_at: i64,
+    pub bump: u8,
+}
+
+impl AuctionProvenance {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // auction
+        32 + // legacy_program
+        32 + // legacy_account
+        32 + // imported_by
+        8 + // imported_at
+        1; // bump
+}
+
+/// Account layout used by the legacy fork, read by `import_auction`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct LegacyAuction {
+    pub creator: Pubkey,
+    pub nft_mint: Pubkey,
+    pub starting_bid: u64,
+    pub min_bid_increment: u64,
+    pub end_time: i64,
+    pub highest_bid: u64,
+    pub highest_bidder: Pubkey,
+    pub status: AuctionStatus,
+}
+
+/// Parameter set accepted by `initialize_auction`, used for dry-run
+/// validation.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Debug)]
+pub struct AuctionParams {
+    pub starting_bid: u64,
+    pub min_bid_increment: u64,
+    pub duration: i64,
+    pub buy_now_price: Option<u64>,
+}
+
+impl AuctionParams {
+    /// Collects every problem with the parameter set. Errors mirror the checks
+    /// `initialize_auction` enforces; warnings flag configurations that are
+    /// accepted but likely unintended.
+    pub fn check(&self) -> Result<()> {
+        require!(self.duration > 0, AuctionError::InvalidDuration);
+        require!(self.starting_bid > 0, AuctionError::InvalidStartingBid);
+        require!(self.min_bid_increment > 0, AuctionError::InvalidBidIncrement);
+        if let Some(price) = self.buy_now_price {
+            require!(price > self.starting_bid, AuctionError::InvalidBuyNowPrice);
+        }
+        Ok(())
+    }
+
+    /// Stable auctions take prices in whole cents of the stable mint.
+    pub fn check_cent_aligned(&self) -> Result<()> {
+        for amount in [
+            Some(self.starting_bid),
+            Some(self.min_bid_increment),
+            self.buy_now_price,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            require!(is_cent_aligned(amount), AuctionError::AmountNotCentAligned);
+        }
+        Ok(())
+    }
+
+    pub fn diagnose(&self, bid_mint: Option<&AccountInfo>) -> Result<Vec<ParamDiagnostic>> {
+        let mut diagnostics = Vec::new();
+        let mut push = |issue, severity| diagnostics.push(ParamDiagnostic { issue, severity });
+
+        if self.duration <= 0 {
+            push(ParamIssue::InvalidDuration, Severity::Error);
+        } else if self.duration < MIN_RECOMMENDED_DURATION {
+            push(ParamIssue::DurationVeryShort, Severity::Warning);
+        }
+        if self.starting_bid == 0 {
+            push(ParamIssue::InvalidStartingBid, Severity::Error);
+        } else if split_proceeds(self.starting_bid, DEFAULT_FEE_BPS).0 == 0 {
+            push(ParamIssue::PlatformFeeRoundsToZero, Severity::Warning);
+        }
+        if self.min_bid_increment == 0 {
+            push(ParamIssue::InvalidBidIncrement, Severity::Error);
+        }
+        if let Some(price) = self.buy_now_price {
+            if price <= self.starting_bid {
+                push(ParamIssue::InvalidBuyNowPrice, Severity::Error);
+            } else if price < self.starting_bid.saturating_add(self.min_bid_increment) {
+                push(ParamIssue::BuyNowBelowFirstIncrement, Severity::Warning);
+            }
+        }
+
+        if let Some(mint) = bid_mint {
+            if *mint.owner != anchor_spl::token::ID && *mint.owner != spl_token_2022::ID {
+                push(ParamIssue::BidMintNotTokenMint, Severity::Error);
+            } else {
+                let data = mint.try_borrow_data()?;
+                match StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data) {
+                    Ok(state) if state.base.is_initialized => {
+                        if state.base.freeze_authority.is_some() {
+                            push(ParamIssue::BidMintHasFreezeAuthority, Severity::Warning);
+                        }
+                        if state.get_extension::<TransferFeeConfig>().is_ok() {
+                            push(ParamIssue::BidMintHasTransferFee, Severity::Warning);
+                        }
+                    }
+                    _ => push(ParamIssue::BidMintNotTokenMint, Severity::Error),
+                }
+            }
+        }
+
+        Ok(diagnostics)
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ParamIssue {
+    InvalidDuration,
+    DurationVeryShort,
+    InvalidStartingBid,
+    PlatformFeeRoundsToZero,
+    InvalidBidIncrement,
+    InvalidBuyNowPrice,
+    BuyNowBelowFirstIncrement,
+    BidMintNotTokenMint,
+    BidMintHasFreezeAuthority,
+    BidMintHasTransferFee,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Debug)]
+pub struct ParamDiagnostic {
+    pub issue: ParamIssue,
+    pub severity: Severity,
+}
+
+/// Returned from `validate_auction_params`. `valid` is true when no
+/// diagnostic has `Severity::Error`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Debug)]
+pub struct ParamValidation {
+    pub valid: bool,
+    pub diagnostics: Vec<ParamDiagnostic>,
+}
+
+/// An auction's result as `snapshot_auction` found it once bidding closed.
+#[account]
+pub struct AuctionResult {
+    pub auction: Pubkey,
+    pub nft_mint: Pubkey,
+    pub winner: Pubkey,
+    pub winning_bid: u64,
+    /// Mint the winning bid is in; default for SOL
+    pub bid_mint: Pubkey,
+    pub reserve_met: bool,
+    pub end_time: i64,
+    pub taken_at: i64,
+    pub slot: u64,
+    pub bump: u8,
+}
+
+impl AuctionResult {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // auction
+        32 + // nft_mint
+        32 + // winner
+        8 + // winning_bid
+        32 + // bid_mint
+        1 + // reserve_met
+        8 + // end_time
+        8 + // taken_at
+        8 + // slot
+        1; // bump
+}
+
+/// Audit trail for an escheated auction.
+#[account]
+pub struct EscheatRecord {
+    pub auction: Pubkey,
+    pub nft_mint: Pubkey,
+    pub creator: Pubkey,
+    pub highest_bidder: Pubkey,
+    pub escrowed_amount: u64,
+    pub end_time: i64,
+    pub escheated_at: i64,
+    pub custody_nft_account: Pubkey,
+    pub operator: Pubkey,
+    pub bump: u8,
+}
+
+impl EscheatRecord {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // auction
+        32 + // nft_mint
+        32 + // creator
+        32 + // highest_bidder
+        8 + // escrowed_amount
+        8 + // end_time
+        8 + // escheated_at
+        32 + // custody_nft_account
+        32 + // operator
+        1; // bump
+}
+
+/// Currencies a multi-currency auction accepts, each priced by a Pyth feed.
+#[account]
+pub struct AcceptedCurrencies {
+    pub auction: Pubkey,
+    pub currencies: Vec<AcceptedCurrency>,
+    pub bump: u8,
+}
+
+impl AcceptedCurrencies {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // auction
+        4 + MAX_ACCEPTED_CURRENCIES * AcceptedCurrency::LEN + // currencies
+        1; // bump
+
+    pub fn find(&self, mint: &Pubkey) -> Option<&AcceptedCurrency> {
+        self.currencies.iter().find(|currency| currency.mint == *mint)
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Debug)]
+pub struct AcceptedCurrency {
+    pub mint: Pubkey,
+    /// Pyth feed id pricing `mint` in USD
+    pub feed_id: [u8; 32],
+}
+
+impl AcceptedCurrency {
+    pub const LEN: usize = 32 + 32;
+}
+
+/// Identifies the compressed NFT held by a cNFT auction.
+#[account]
+pub struct CompressedAsset {
+    pub merkle_tree: Pubkey,
+    pub leaf: bubblegum::LeafArgs,
+    pub bump: u8,
+}
+
+impl CompressedAsset {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // merkle_tree
+        bubblegum::LeafArgs::LEN + // leaf
+        1; // bump
+}
+
+/// Order book of a partial-fill auction.
+#[account]
+pub struct PartialFillBook {
+    pub auction: Pubkey,
+    pub entries: Vec<PartialFillEntry>,
+    pub bump: u8,
+}
+
+impl PartialFillBook {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // auction
+        4 + MAX_PARTIAL_BIDS * PartialFillEntry::LEN + // entries
+        1; // bump
+
+    /// Allocates up to `supply` units to the entries, highest unit price
+    /// first and earlier entries first among equal prices. Returns the
+    /// units left unsold.
+    pub fn fill(&mut self, supply: u64) -> u64 {
+        let mut order: Vec<usize> = (0..self.entries.len()).collect();
+        order.sort_by_key(|&i| std::cmp::Reverse(self.entries[i].unit_price));
+
+        let mut remaining = supply;
+        for i in order {
+            let entry = &mut self.entries[i];
+            entry.filled = entry.quantity.min(remaining);
+            remaining -= entry.filled;
+        }
+        remaining
+    }
+
+    /// Lamports owed to the seller for the filled units.
+    pub fn proceeds(&self) -> Result<u64> {
+        self.entries.iter().try_fold(0u64, |total, entry| {
+            entry
+                .unit_price
+                .checked_mul(entry.filled)
+                .and_then(|value| total.checked_add(value))
+                .ok_or_else(|| error!(AuctionError::MathOverflow))
+        })
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct PartialFillEntry {
+    pub bidder: Pubkey,
+    pub unit_price: u64,
+    pub quantity: u64,
+    /// Units allocated at clearing
+    pub filled: u64,
+    pub claimed: bool,
+}
+
+impl PartialFillEntry {
+    pub const LEN: usize = 32 + 8 + 8 + 8 + 1;
+
+    /// Lamports escrowed for the entry.
+    pub fn escrowed(&self) -> Result<u64> {
+        self.unit_price
+            .checked_mul(self.quantity)
+            .ok_or_else(|| error!(AuctionError::MathOverflow))
+    }
+}
+
+/// Contents of a multi-asset lot: the auctioned NFT plus SPL tokens
+/// escrowed with it and delivered to the same party.
+#[account]
+pub struct LotManifest {
+    pub auction: Pubkey,
+    pub nft_mint: Pubkey,
+    pub token_mint: Pubkey,
+    /// Tokens held in the auction authority's ATA, net of transfer fees
+    pub token_amount: u64,
+    pub bump: u8,
+}
+
+impl LotManifest {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // auction
+        32 + // nft_mint
+        32 + // token_mint
+        8 + // token_amount
+        1; // bump
+}
+
+/// The latest bids placed on an auction through `place_bid`, including the
+/// raises a leading proxy answers them with. Once full, each new bid
+/// overwrites the oldest.
+#[account]
+pub struct BidHistory {
+    pub auction: Pubkey,
+    /// Bids recorded so far; the next one goes to slot
+    /// `total % MAX_BID_HISTORY`
+    pub total: u64,
+    pub entries: Vec<BidRecord>,
+    pub bump: u8,
+}
+
+impl BidHistory {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // auction
+        8 + // total
+        4 + MAX_BID_HISTORY * BidRecord::LEN + // entries
+        1; // bump
+
+    /// Fills in a freshly created history; no-op once it is open.
+    pub fn open(&mut self, auction: Pubkey, bump: u8) {
+        if self.auction == Pubkey::default() {
+            self.auction = auction;
+            self.bump = bump;
+        }
+    }
+
+    /// Appends a bid, overwriting the oldest once the history is full.
+    pub fn record(&mut self, bidder: Pubkey, amount: u64, timestamp: i64) {
+        let record = BidRecord {
+            bidder,
+            amount,
+            timestamp,
+        };
+        if self.entries.len() < MAX_BID_HISTORY {
+            self.entries.push(record);
+        } else {
+            self.entries[(self.total % MAX_BID_HISTORY as u64) as usize] = record;
+        }
+        self.total += 1;
+    }
+}
+
+/// On-chain record of one bid placed through `place_bid`, kept for
+/// provenance until its auction closes.
+#[account]
+pub struct BidReceipt {
+    pub auction: Pubkey,
+    pub bidder: Pubkey,
+    pub amount: u64,
+    pub slot: u64,
+    /// Funder of the receipt's rent
+    pub rent_payer: Pubkey,
+    pub bump: u8,
+}
+
+impl BidReceipt {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // auction
+        32 + // bidder
+        8 + // amount
+        8 + // slot
+        32 + // rent_payer
+        1; // bump
+}
+
+/// Each bidder's best bid placed through `place_bid`, highest first, for
+/// the top `MAX_TOP_BIDS` bidders. Equal amounts rank by time placed.
+#[account]
+pub struct TopBids {
+    pub auction: Pubkey,
+    pub entries: Vec<BidRecord>,
+    pub bump: u8,
+}
+
+impl TopBids {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // auction
+        4 + MAX_TOP_BIDS * BidRecord::LEN + // entries
+        1; // bump
+
+    /// Fills in a freshly created list; no-op once it is open.
+    pub fn open(&mut self, auction: Pubkey, bump: u8) {
+        if self.auction == Pubkey::default() {
+            self.auction = auction;
+            self.bump = bump;
+        }
+    }
+
+    /// Ranks a bid, replacing the bidder's earlier entry when it is higher
+    /// and dropping whatever falls below the top `MAX_TOP_BIDS`.
+    pub fn insert(&mut self, bidder: Pubkey, amount: u64, timestamp: i64) {
+        if let Some(i) = self.entries.iter().position(|entry| entry.bidder == bidder) {
+            if self.entries[i].amount >= amount {
+                return;
+            }
+            self.entries.remove(i);
+        }
+        let rank = self.entries.partition_point(|entry| entry.amount >= amount);
+        if rank < MAX_TOP_BIDS {
+            self.entries.insert(
+                rank,
+                BidRecord {
+                    bidder,
+                    amount,
+                    timestamp,
+                },
+            );
+            self.entries.truncate(MAX_TOP_BIDS);
+        }
+    }
+}
+
+/// Bids placed in an auction's closing window, escrowed here until
+/// `finalize_auction` picks the winner. Losing entries stay until their
+/// bidders withdraw them.
+#[account]
+pub struct ClosingQueue {
+    pub auction: Pubkey,
+    /// One entry per bidder
+    pub entries: Vec<QueuedBid>,
+    pub resolved: bool,
+    pub bump: u8,
+}
+
+impl ClosingQueue {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // auction
+        4 + MAX_QUEUED_BIDS * QueuedBid::LEN + // entries
+        1 + // resolved
+        1; // bump
+
+    /// Queues `amount` for `bidder`, or raises their entry to it, and
+    /// returns the lamports still owed for it.
+    pub fn enqueue(&mut self, bidder: Pubkey, amount: u64, slot: u64) -> Result<u64> {
+        match self.entries.iter_mut().find(|entry| entry.bidder == bidder) {
+            Some(entry) => {
+                require!(amount > entry.amount, AuctionError::BidTooLow);
+                let owed = amount - entry.amount;
+                entry.amount = amount;
+                entry.slot = slot;
+                Ok(owed)
+            }
+            None => {
+                require!(
+                    self.entries.len() < MAX_QUEUED_BIDS,
+                    AuctionError::ClosingQueueFull
+                );
+                self.entries.push(QueuedBid {
+                    bidder,
+                    amount,
+                    slot,
+                });
+                Ok(amount)
+            }
+        }
+    }
+
+    /// Removes and returns the winning entry: the highest amount, then the
+    /// earliest slot, then the lowest bidder key, so the result does not
+    /// depend on the order entries were queued in.
+    pub fn take_winner(&mut self) -> Option<QueuedBid> {
+        let (index, _) = self.entries.iter().enumerate().max_by(|(_, a), (_, b)| {
+            a.amount
+                .cmp(&b.amount)
+                .then(b.slot.cmp(&a.slot))
+                .then(b.bidder.cmp(&a.bidder))
+        })?;
+        Some(self.entries.swap_remove(index))
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct QueuedBid {
+    pub bidder: Pubkey,
+    pub amount: u64,
+    /// Slot the bid was last raised in; `0` for a standing bid displaced at
+    /// resolution
+    pub slot: u64,
+}
+
+impl QueuedBid {
+    pub const LEN: usize = 32 + 8 + 8;
+}
+
+/// Minimum increments of an auction, by standing bid.
+#[account]
+pub struct IncrementTiers {
+    pub auction: Pubkey,
+    /// Sorted by `below`
+    pub tiers: Vec<IncrementTier>,
+    pub bump: u8,
+}
+
+impl IncrementTiers {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // auction
+        4 + MAX_INCREMENT_TIERS * IncrementTier::LEN + // tiers
+        1; // bump
+
+    /// Increment of the first tier `price` falls below, or of the last tier
+    /// once it is past every threshold.
+    pub fn increment_for(&self, price: u64) -> u64 {
+        self.tiers
+            .iter()
+            .find(|tier| price < tier.below)
+            .or(self.tiers.last())
+            .map_or(0, |tier| tier.increment)
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct IncrementTier {
+    /// Standing bids below this amount use `increment`
+    pub below: u64,
+    pub increment: u64,
+}
+
+impl IncrementTier {
+    pub const LEN: usize = 8 + 8;
+}
+
+/// Lamports a wallet set aside for bids, so that bids can be submitted and
+/// paid for by a relayer.
+#[account]
+pub struct BidDeposit {
+    pub owner: Pubkey,
+    /// Lamports available for bids, on top of rent
+    pub balance: u64,
+    pub bump: u8,
+}
+
+impl BidDeposit {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // owner
+        8 + // balance
+        1; // bump
+}
+
+/// A temporary key a wallet lets bid on its behalf, holding the lamports
+/// it may still spend on top of rent.
+#[account]
+pub struct SessionKey {
+    pub owner: Pubkey,
+    pub session_key: Pubkey,
+    pub spend_limit: u64,
+    pub spent: u64,
+    pub expires_at: i64,
+    pub bump: u8,
+}
+
+impl SessionKey {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // owner
+        32 + // session_key
+        8 + // spend_limit
+        8 + // spent
+        8 + // expires_at
+        1; // bump
+
+    /// Checks that the session is live and can still fund `amount`.
+    pub fn check_spend(&self, amount: u64, now: i64) -> Result<()> {
+        require!(now < self.expires_at, AuctionError::SessionExpired);
+        require!(
+            self.spent
+                .checked_add(amount)
+                .is_some_and(|total| total <= self.spend_limit),
+            AuctionError::SessionSpendLimit
+        );
+        Ok(())
+    }
+}
+
+/// A syndicate of wallets bidding on an auction as one bidder.
+#[account]
+pub struct BidPool {
+    pub auction: Pubkey,
+    /// Places the pool's bids
+    pub organizer: Pubkey,
+    /// Lamports contributed overall; each contribution's share of the pool
+    /// is measured against it
+    pub total_contributed: u64,
+    /// Contributions whose share has not been withdrawn
+    pub unwithdrawn: u64,
+    pub bump: u8,
+}
+
+impl BidPool {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // auction
+        32 + // organizer
+        8 + // total_contributed
+        8 + // unwithdrawn
+        1; // bump
+}
+
+/// One wallet's stake in a `BidPool`.
+#[account]
+pub struct PoolContribution {
+    pub pool: Pubkey,
+    pub contributor: Pubkey,
+    pub amount: u64,
+    /// The contributor has taken out their share of the pool's lamports
+    pub withdrawn: bool,
+    pub bump: u8,
+}
+
+impl PoolContribution {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // pool
+        32 + // contributor
+        8 + // amount
+        1 + // withdrawn
+        1; // bump
+
+    /// Fills in a freshly created contribution; no-op once it is open.
+    pub fn open(&mut self, pool: Pubkey, contributor: Pubkey, bump: u8) {
+        if self.contributor == Pubkey::default() {
+            self.pool = pool;
+            self.contributor = contributor;
+            self.bump = bump;
+        }
+    }
+}
+
+/// A wallet's proven place on an auction's bidder allowlist, cached on its
+/// first bid.
+#[account]
+pub struct AllowlistEntry {
+    pub auction: Pubkey,
+    pub bidder: Pubkey,
+    pub bump: u8,
+}
+
+impl AllowlistEntry {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // auction
+        32 + // bidder
+        1; // bump
+}
+
+/// Share of an auction's platform fee set aside at settlement for its
+/// losing bidders, who claim it pro-rata to their best bids in `TopBids`.
+#[account]
+pub struct LoserRebate {
+    pub auction: Pubkey,
+    /// Lamports set aside, on top of rent
+    pub amount: u64,
+    /// Bit per `TopBids` rank whose share has been claimed
+    pub claimed: u8,
+    pub bump: u8,
+}
+
+impl LoserRebate {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // auction
+        8 + // amount
+        1 + // claimed
+        1; // bump
+
+    pub fn is_claimed(&self, rank: usize) -> bool {
+        self.claimed & (1 << rank) != 0
+    }
+
+    pub fn set_claimed(&mut self, rank: usize) {
+        self.claimed |= 1 << rank;
+    }
+}
+
+/// A bidder's stored auto-rebid strategy, executed from their bid deposit
+/// by `execute_rebid` whenever they are outbid.
+#[account]
+pub struct RebidStrategy {
+    pub auction: Pubkey,
+    pub bidder: Pubkey,
+    /// Most the strategy will bid
+    pub max_price: u64,
+    /// Raise over the standing bid, as a share of it; the minimum increment
+    /// when that is less
+    pub increment_bps: u16,
+    pub bump: u8,
+}
+
+impl RebidStrategy {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // auction
+        32 + // bidder
+        8 + // max_price
+        2 + // increment_bps
+        1; // bump
+
+    /// The bid the strategy answers the standing bid with.
+    pub fn next_bid(&self, auction: &Auction, tiers: Option<&IncrementTiers>) -> Option<u64> {
+        let raise =
+            auction.highest_bid as u128 * self.increment_bps as u128 / BPS_DENOMINATOR as u128;
+        let raise = (raise as u64).max(auction.min_increment(tiers));
+        auction.highest_bid.checked_add(raise)
+    }
+}
+
+/// A bidder's registration on an auction that requires one, holding their
+/// qualification deposit.
+#[account]
+pub struct BidderRegistration {
+    pub auction: Pubkey,
+    pub bidder: Pubkey,
+    /// Lamports locked on top of rent
+    pub deposit: u64,
+    pub bump: u8,
+}
+
+impl BidderRegistration {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // auction
+        32 + // bidder
+        8 + // deposit
+        1; // bump
+}
+
+/// A bidder's activity on an auction, kept for the per-wallet bid limits.
+#[account]
+pub struct BidderStats {
+    pub auction: Pubkey,
+    pub bidder: Pubkey,
+    pub last_bid_at: i64,
+    pub bid_count: u32,
+    pub bump: u8,
+}
+
+impl BidderStats {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // auction
+        32 + // bidder
+        8 + // last_bid_at
+        4 + // bid_count
+        1; // bump
+
+    /// Fills in freshly created stats; no-op once they are open.
+    pub fn open(&mut self, auction: Pubkey, bidder: Pubkey, bump: u8) {
+        if self.bidder == Pubkey::default() {
+            self.auction = auction;
+            self.bidder = bidder;
+            self.bump = bump;
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct BidRecord {
+    pub bidder: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+impl BidRecord {
+    pub const LEN: usize = 32 + 8 + 8;
+}
+
+/// Outbid native bids owed to a bidder on an auction, held until they
+/// claim them.
+#[account]
+pub struct BidEscrow {
+    pub auction: Pubkey,
+    pub bidder: Pubkey,
+    pub refundable: u64,
+    /// Paid the escrow's rent, which is returned to it on claim
+    pub rent_payer: Pubkey,
+    pub bump: u8,
+}
+
+impl BidEscrow {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // auction
+        32 + // bidder
+        8 + // refundable
+        32 + // rent_payer
+        1; // bump
+
+    /// Fills in a freshly created escrow; no-op once it is open.
+    pub fn open(&mut self, auction: Pubkey, bidder: Pubkey, rent_payer: Pubkey, bump: u8) {
+        if self.bidder == Pubkey::default() {
+            self.auction = auction;
+            self.bidder = bidder;
+            self.rent_payer = rent_payer;
+            self.bump = bump;
+        }
+    }
+}
+
+/// A bidder's proxy on a native auction: the most the program may raise
+/// their bid to, with the lamports above their standing bid held here.
+#[account]
+pub struct ProxyBid {
+    pub auction: Pubkey,
+    pub bidder: Pubkey,
+    pub max_bid: u64,
+    /// Lamports held for raises, on top of rent
+    pub reserve: u64,
+    pub bump: u8,
+}
+
+impl ProxyBid {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // auction
+        32 + // bidder
+        8 + // max_bid
+        8 + // reserve
+        1; // bump
+
+    /// Fills in a freshly created proxy; no-op once it is open.
+    pub fn open(&mut self, auction: Pubkey, bidder: Pubkey, bump: u8) {
+        if self.bidder == Pubkey::default() {
+            self.auction = auction;
+            self.bidder = bidder;
+            self.bump = bump;
+        }
+    }
+}
+
+/// A bidder's latest note on an auction, with the bid it accompanied.
+#[account]
+pub struct BidNote {
+    pub auction: Pubkey,
+    pub bidder: Pubkey,
+    pub bid_amount: u64,
+    pub note: String,
+    pub bump: u8,
+}
+
+impl BidNote {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // auction
+        32 + // bidder
+        8 + // bid_amount
+        4 + MAX_BID_NOTE_LEN + // note
+        1; // bump
+}
+
+/// Delivery escrow of a physical-item auction. Holds the seller's share of
+/// the winning bid from settlement until `release_at`, or until the arbiter
+/// resolves a dispute the winner opened.
+#[account]
+pub struct Dispute {
+    pub auction: Pubkey,
+    pub arbiter: Pubkey,
+    /// Seconds proceeds are held after settlement
+    pub window: i64,
+    /// Lamports held in the auction's SOL escrow for the seller
+    pub held: u64,
+    pub release_at: i64,
+    pub status: DisputeStatus,
+    pub bump: u8,
+}
+
+impl Dispute {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // auction
+        32 + // arbiter
+        8 + // window
+        8 + // held
+        8 + // release_at
+        1 + // status
+        1; // bump
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum DisputeStatus {
+    #[default]
+    None,
+    Open,
+    Refunded,
+    Released,
+}
+
+/// Whether an auction takes deposit bids, and whether its winning bid has
+/// been paid in full.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum DepositMode {
+    #[default]
+    Off,
+    Unsettled,
+    Settled,
+}
+
+/// Terms and bookkeeping of a deposit-bidding auction. The SOL escrow holds
+/// the deposits of the leader and the runner-up, until the winner pays the
+/// balance or forfeits.
+#[account]
+pub struct DepositTerms {
+    pub auction: Pubkey,
+    /// Share of each bid escrowed at bid time
+    pub deposit_bps: u16,
+    /// Seconds the winner has to pay the balance
+    pub settlement_window: i64,
+    /// Deposit escrowed for the standing bid
+    pub deposit: u64,
+    /// Last leader outbid, kept as the fallback buyer
+    pub runner_up: Pubkey,
+    pub runner_up_bid: u64,
+    pub runner_up_deposit: u64,
+    /// Deadline of a runner-up promoted by `forfeit`; `0` until then
+    pub settle_by: i64,
+    pub bump: u8,
+}
+
+impl DepositTerms {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // auction
+        2 + // deposit_bps
+        8 + // settlement_window
+        8 + // deposit
+        32 + // runner_up
+        8 + // runner_up_bid
+        8 + // runner_up_deposit
+        8 + // settle_by
+        1; // bump
+
+    /// Deposit owed on a bid of `amount`, rounded up.
+    pub fn deposit_for(&self, amount: u64) -> u64 {
+        (amount as u128 * self.deposit_bps as u128).div_ceil(BPS_DENOMINATOR as u128) as u64
+    }
+
+    /// When the current winner's time to settle runs out.
+    pub fn deadline(&self, end_time: i64) -> i64 {
+        if self.settle_by > 0 {
+            self.settle_by
+        } else {
+            end_time + self.settlement_window
+        }
+    }
+}
+
+/// Standing SOL offer for any NFT of a verified collection. The offered
+/// amount sits in the SOL escrow PDA derived from the offer's address.
+#[account]
+pub struct CollectionOffer {
+    pub buyer: Pubkey,
+    pub collection: Pubkey,
+    pub amount: u64,
+    /// Unix timestamp after which the offer can't be accepted; 0 for none
+    pub expires_at: i64,
+    pub bump: u8,
+}
+
+impl CollectionOffer {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // buyer
+        32 + // collection
+        8 + // amount
+        8 + // expires_at
+        1; // bump
+}
+
+/// The NFT's metadata as it stood at listing, so settlement and indexers
+/// don't depend on the mutable metadata account and later edits show up.
+#[account]
+pub struct MetadataSnapshot {
+    pub auction: Pubkey,
+    pub name: String,
+    pub symbol: String,
+    /// SHA-256 of the metadata URI
+    pub uri_hash: [u8; 32],
+    pub seller_fee_basis_points: u16,
+    pub creators: Vec<token_metadata::Creator>,
+    pub bump: u8,
+}
+
+impl MetadataSnapshot {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // auction
+        4 + token_metadata::MAX_NAME_LENGTH + // name
+        4 + token_metadata::MAX_SYMBOL_LENGTH + // symbol
+        32 + // uri_hash
+        2 + // seller_fee_basis_points
+        4 + token_metadata::MAX_CREATOR_LIMIT * token_metadata::Creator::LEN + // creators
+        1; // bump
+
+    pub fn capture(
+        &mut self,
+        descriptor: &token_metadata::Descriptor,
+        royalties: &token_metadata::Royalties,
+    ) {
+        self.name = descriptor.name.clone();
+        self.symbol = descriptor.symbol.clone();
+        self.uri_hash = hash(descriptor.uri.as_bytes()).to_bytes();
+        self.seller_fee_basis_points = royalties.seller_fee_basis_points;
+        self.creators = royalties.creators.clone();
+    }
+
+    pub fn royalties(&self) -> token_metadata::Royalties {
+        token_metadata::Royalties {
+            seller_fee_basis_points: self.seller_fee_basis_points,
+            creators: self.creators.clone(),
+        }
+    }
+
+    /// Whether the live metadata still matches the snapshot. Metadata that
+    /// was closed or can no longer be parsed counts as a mismatch.
+    pub fn matches(&self, metadata: &AccountInfo, mint: &Pubkey) -> bool {
+        let Ok((descriptor, royalties)) = token_metadata::load_descriptor(metadata, mint) else {
+            return false;
+        };
+        descriptor.name == self.name
+            && descriptor.symbol == self.symbol
+            && hash(descriptor.uri.as_bytes()).to_bytes() == self.uri_hash
+            && royalties == self.royalties()
+    }
+}
+
+/// Leaves of a multi-cNFT lot, all held in one tree.
+#[account]
+pub struct CompressedBundle {
+    pub merkle_tree: Pubkey,
+    pub leaves: Vec<bubblegum::LeafArgs>,
+    pub bump: u8,
+}
+
+impl CompressedBundle {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // merkle_tree
+        4 + MAX_BUNDLE_LEAVES * bubblegum::LeafArgs::LEN + // leaves
+        1; // bump
+}
+
+/// A creator's preset of auction parameters, listed from with
+/// `init_from_template`.
+#[account]
+pub struct AuctionTemplate {
+    pub creator: Pubkey,
+    pub template_id: u32,
+    pub params: AuctionTemplateParams,
+    pub bump: u8,
+}
+
+impl AuctionTemplate {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // creator
+        4 + // template_id
+        AuctionTemplateParams::LEN + // params
+        1; // bump
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Debug)]
+pub struct AuctionTemplateParams {
+    pub starting_bid: u64,
+    pub min_bid_increment: u64,
+    pub duration: i64,
+    pub buy_now_price: Option<u64>,
+    pub extension_window: i64,
+    pub extension_amount: i64,
+    /// Most seconds anti-snipe extensions may add past the initial end
+    /// time; `0` leaves them uncapped
+    pub max_extension: i64,
+}
+
+/// A creator's defaults for their `initialize_auction` listings.
+#[account]
+pub struct CreatorSettings {
+    pub creator: Pubkey,
+    pub defaults: CreatorDefaults,
+    pub bump: u8,
+}
+
+impl CreatorSettings {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // creator
+        CreatorDefaults::LEN + // defaults
+        1; // bump
+
+    /// Gives a freshly opened auction the default anti-snipe rule, unless
+    /// the listing brought its own.
+    pub fn apply_anti_snipe(&self, auction: &mut Auction) {
+        if auction.extension_window > 0 || auction.extension_amount > 0 {
+            return;
+        }
+        auction.extension_window = self.defaults.extension_window;
+        auction.extension_amount = self.defaults.extension_amount;
+        if self.defaults.max_extension > 0 {
+            auction.max_end_time = auction.end_time + self.defaults.max_extension;
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Debug)]
+pub struct CreatorDefaults {
+    /// Increment used by listings that pass `0`
+    pub min_bid_increment: u64,
+    /// Anti-snipe rule used by listings that set none
+    pub extension_window: i64,
+    pub extension_amount: i64,
+    /// Most seconds anti-snipe extensions may add past the initial end
+    /// time; `0` leaves them uncapped
+    pub max_extension: i64,
+}
+
+impl CreatorDefaults {
+    pub const LEN: usize = 8 + 8 + 8 + 8;
+
+    /// Applies `set_anti_snipe`'s checks.
+    pub fn check(&self) -> Result<()> {
+        require!(
+            self.extension_window >= 0 && self.extension_amount >= 0 && self.max_extension >= 0,
+            AuctionError::InvalidExtension
+        );
+        Ok(())
+    }
+}
+
+impl AuctionTemplateParams {
+    pub const LEN: usize = 8 + 8 + 8 + 9 + 8 + 8 + 8;
+
+    /// Applies `initialize_auction`'s checks and `set_anti_snipe`'s.
+    pub fn check(&self) -> Result<()> {
+        AuctionParams {
+            starting_bid: self.starting_bid,
+            min_bid_increment: self.min_bid_increment,
+            duration: self.duration,
+            buy_now_price: self.buy_now_price,
+        }
+        .check()?;
+        require!(
+            self.extension_window >= 0 && self.extension_amount >= 0 && self.max_extension >= 0,
+            AuctionError::InvalidExtension
+        );
+        Ok(())
+    }
+}
+
+/// Admin-defined A/B test over auction parameters.
+#[account]
+pub struct Experiment {
+    pub id: u32,
+    pub variants: [ExperimentVariant; 2],
+    pub split_bps: u16,
+    pub active: bool,
+    pub bump: u8,
+}
+
+impl Experiment {
+    pub const LEN: usize = 8 + // discriminator
+        4 + // id
+        2 * ExperimentVariant::LEN + // variants
+        2 + // split_bps
+        1 + // active
+        1; // bump
+
+    /// Deterministically maps an auction to variant 0 or 1.
+    pub fn assign(&self, auction: &Pubkey) -> u8 {
+        let digest = anchor_lang::solana_program::hash::hash(auction.as_ref()).to_bytes();
+        let bucket = u16::from_le_bytes([digest[0], digest[1]]) % BPS_DENOMINATOR;
+        u8::from(bucket < self.split_bps)
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Debug)]
+pub struct ExperimentVariant {
+    pub fee_bps: u16,
+    pub extension_window: i64,
+    pub extension_amount: i64,
+}
+
+impl ExperimentVariant {
+    pub const LEN: usize = 2 + 8 + 8;
+}
+
+/// Lamports set aside to reimburse keepers for settlement transactions.
+#[account]
+pub struct RebatePool {
+    pub max_rebate: u64,
+    pub fee_share_bps: u16,
+    /// Funded lamports not yet credited to any keeper
+    pub available: u64,
+    pub bump: u8,
+}
+
+impl RebatePool {
+    pub const LEN: usize = 8 + // discriminator
+        8 + // max_rebate
+        2 + // fee_share_bps
+        8 + // available
+        1; // bump
+
+    pub fn share_of(&self, platform_fee: u64) -> u64 {
+        ((platform_fee as u128 * self.fee_share_bps as u128) / BPS_DENOMINATOR as u128) as u64
+    }
+}
+
+#[account]
+pub struct Keeper {
+    pub keeper: Pubkey,
+    pub accrued: u64,
+    pub claimed: u64,
+    pub settlements: u64,
+    pub bump: u8,
+}
+
+impl Keeper {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // keeper
+        8 + // accrued
+        8 + // claimed
+        8 + // settlements
+        1; // bump
+}
+
+/// Instruction selector for `route`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Debug)]
+pub enum RouteAction {
+    Create {
+        starting_bid: u64,
+        min_bid_increment: u64,
+        duration: i64,
+        buy_now_price: Option<u64>,
+        quantity: Option<u64>,
+        required_collection: Option<Pubkey>,
+    },
+    Bid {
+        amount: u64,
+        allowlist_proof: Vec<[u8; 32]>,
+    },
+    BuyNow,
+    Finalize,
+}
+
+/// Royalty paid to one verified creator at settlement.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct CreatorRoyalty {
+    pub creator: Pubkey,
+    pub amount: u64,
+}
+
+/// Asset standard an auction custodies. Mint-based NFTs (SPL, Token-2022,
+/// programmable) sit in a vault token account; Core assets are single
+/// accounts whose owner is set to the auction authority. Whole token
+/// accounts are custodied the same way, through the token program's
+/// account-owner authority. Delegated NFTs stay in the seller's token
+/// account, delegated to and frozen by the auction authority; escrowless
+/// ones are delegated but left unfrozen. `.sol` domains are name records
+/// whose owner is set to the auction authority.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum AssetKind {
+    #[default]
+    Spl,
+    Core,
+    TokenAccount,
+    Delegated,
+    Escrowless,
+    Domain,
+}
+
+/// Lifecycle of an auction. Moves between states go through
+/// `set_auction_status`, which only allows those in `can_become`. Stored by
+/// index, so new states are appended.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AuctionStatus {
+    /// Taking bids; stays here past `end_time` until someone calls
+    /// `end_auction` or settles it
+    Live,
+    /// Sold and fully delivered, or closed out with nothing owed
+    Settled,
+    Cancelled,
+    Escheated,
+    Fractionalized,
+    /// Listed but not yet open for bids
+    Scheduled,
+    /// Bidding is over and the auction awaits settlement
+    Ended,
+    /// Settled with `settle_auction`; the seller's proceeds or the item are
+    /// still to be claimed
+    Settling,
+    /// The winner contested delivery; held proceeds wait on the arbiter
+    Disputed,
+    /// Never finalized; the bidder and creator are taking back their sides
+    /// with `recover_bid` and `recover_item`
+    Abandoned,
+    /// Ended unsold and left up for `buy_now` at the reserve price
+    Listed,
+}
+
+impl AuctionStatus {
+    /// Whether the lifecycle allows moving from this state to `to`.
+    pub fn can_become(self, to: AuctionStatus) -> bool {
+        use AuctionStatus::*;
+        matches!(
+            (self, to),
+            (Scheduled, Live | Cancelled)
+                | (
+                    Live,
+                    Scheduled
+                        | Ended
+                        | Settling
+                        | Settled
+                        | Cancelled
+                        | Escheated
+                        | Fractionalized
+                        | Abandoned
+                        | Listed
+                )
+                | (
+                    Ended,
+                    Live | Settling
+                        | Settled
+                        | Cancelled
+                        | Escheated
+                        | Fractionalized
+                        | Abandoned
+                        | Listed
+                )
+                | (Settling, Settled | Disputed)
+                | (Settled, Disputed)
+                | (Disputed, Settling | Settled)
+                | (Fractionalized, Settled)
+                | (Abandoned, Cancelled)
+                | (Listed, Ended | Cancelled)
+        )
+    }
+
+    /// Not yet settled or cancelled.
+    pub fn is_open(self) -> bool {
+        matches!(
+            self,
+            AuctionStatus::Scheduled | AuctionStatus::Live | AuctionStatus::Ended
+        )
+    }
+
+    /// Bidding has run and the auction can be settled, cancelled as unsold
+    /// or otherwise wound up.
+    pub fn can_settle(self) -> bool {
+        matches!(self, AuctionStatus::Live | AuctionStatus::Ended)
+    }
+
+    /// The winning bid has been taken, whether or not every leg has been
+    /// delivered yet.
+    pub fn is_settled(self) -> bool {
+        matches!(
+            self,
+            AuctionStatus::Settling | AuctionStatus::Settled | AuctionStatus::Disputed
+        )
+    }
+}
+
+/// Returned from `finalize_auction` via return data. Amounts are in
+/// lamports, or in base units of the bid mint for SPL settlements; legs
+/// that did not execute are reported as zero / `None`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Debug)]
+pub struct SettlementSummary {
+    pub auction_id: Pubkey,
+    pub winner: Pubkey,
+    pub winning_bid: u64,
+    pub seller: Pubkey,
+    pub seller_amount: u64,
+    pub platform_fee_account: Pubkey,
+    pub platform_fee: u64,
+    pub nft_recipient_account: Option<Pubkey>,
+    /// Credited to the finalizing keeper from the rebate pool
+    pub keeper_rebate: u64,
+    /// Paid to whoever settled the auction out of the platform fee
+    pub settler_bounty: u64,
+    /// Paid to verified creators out of the seller's proceeds
+    pub royalties: u64,
+}
+
+#[error_code]
+pub enum AuctionError {
+    #[msg("The auction duration must be greater than 0")]
+    InvalidDuration,
+    #[msg("The starting bid must be greater than 0")]
+    InvalidStartingBid,
+    #[msg("The minimum bid increment must be greater than 0")]
+    InvalidBidIncrement,
+    #[msg("The auction has already ended")]
+    AuctionEnded,
+    #[msg("The auction is not active")]
+    AuctionNotActive,
+    #[msg("The bid amount is too low")]
+    BidTooLow,
+    #[msg("The bid increment is too low")]
+    BidIncrementTooLow,
+    #[msg("The auction has not ended yet")]
+    AuctionNotEnded,
+    #[msg("The auction already has bids")]
+    AuctionHasBids,
+    #[msg("Unauthorized to cancel this auction")]
+    UnauthorizedCancellation,
+    #[msg("Unauthorized to update this auction")]
+    UnauthorizedUpdate,
+    #[msg("Invalid auction state transition")]
+    InvalidStateTransition,
+    #[msg("The buy-now price must be greater than the starting bid")]
+    InvalidBuyNowPrice,
+    #[msg("This auction does not offer buy-now")]
+    BuyNowNotAvailable,
+    #[msg("The auction has already been bought out")]
+    BuyNowAlreadyExecuted,
+    #[msg("The previous bidder does not match the highest bidder")]
+    InvalidPreviousBidder,
+    #[msg("This instruction does not match the auction's bid currency")]
+    InvalidBidCurrency,
+    #[msg("Arithmetic overflow")]
+    MathOverflow,
+    #[msg("Signer is not authorized for this action")]
+    Unauthorized,
+    #[msg("The legacy auction account is invalid")]
+    InvalidLegacyAccount,
+    #[msg("The NFT or escrowed bid has not been moved into custody")]
+    LegacyCustodyMissing,
+    #[msg("The token program account is required for this refund")]
+    MissingTokenProgram,
+    #[msg("Stablecoin auctions require a 6-decimal bid mint")]
+    InvalidStableMint,
+    #[msg("Stablecoin amounts must be whole cents")]
+    AmountNotCentAligned,
+    #[msg("The escheat window has not elapsed")]
+    EscheatWindowNotReached,
+    #[msg("Too many or too few accepted currencies")]
+    TooManyCurrencies,
+    #[msg("The price feed is invalid for this currency")]
+    InvalidPriceFeed,
+    #[msg("The price feed is stale")]
+    StalePrice,
+    #[msg("The recipient does not match the auction outcome")]
+    InvalidRecipient,
+    #[msg("The experiment configuration is invalid")]
+    InvalidExperiment,
+    #[msg("The rebate pool configuration is invalid")]
+    InvalidRebateConfig,
+    #[msg("There is nothing to claim")]
+    NothingToClaim,
+    #[msg("The rebate pool cannot cover this claim")]
+    InsufficientPoolFunds,
+    #[msg("Programmable NFT transfers require the Token Metadata accounts")]
+    MissingProgrammableAccounts,
+    #[msg("The NFT metadata account is invalid")]
+    InvalidMetadata,
+    #[msg("A creator wallet is missing or out of order")]
+    InvalidCreatorAccount,
+    #[msg("The auctioned quantity is invalid")]
+    InvalidQuantity,
+    #[msg("The asset is not of the expected standard")]
+    InvalidAsset,
+    #[msg("The SOL escrow would be left below its rent-exempt minimum")]
+    EscrowBelowRent,
+    #[msg("The NFT is not a verified member of the required collection")]
+    CollectionMismatch,
+    #[msg("The proof accounts do not match the bundled leaves")]
+    InvalidProofs,
+    #[msg("This instruction does not match the auction's partial-fill mode")]
+    PartialFillAuction,
+    #[msg("The listed NFT is still deliverable")]
+    ListingIntact,
+    #[msg("Not a stake pool of the SPL stake pool program")]
+    InvalidStakePool,
+    #[msg("Missing stake pool accounts for the LST payout")]
+    MissingLstPayoutAccounts,
+    #[msg("Auction was not fractionalized")]
+    AuctionNotFractionalized,
+    #[msg("Redeeming requires every outstanding share")]
+    IncompleteFractions,
+    #[msg("Not the auction's Kamino reserve")]
+    InvalidYieldReserve,
+    #[msg("Escrowed bids are lent out; recall them first")]
+    EscrowDeployed,
+    #[msg("Escrowed bids are not lent out")]
+    EscrowNotDeployed,
+    #[msg("There are no escrowed bids to lend")]
+    EmptyEscrow,
+    #[msg("The offer has expired")]
+    OfferExpired,
+    #[msg("Delivery-escrow auctions settle with their dispute account")]
+    MissingDispute,
+    #[msg("The dispute is not in a state that allows this")]
+    InvalidDisputeState,
+    #[msg("The dispute window has not closed")]
+    DisputeWindowOpen,
+    #[msg("The dispute window has closed")]
+    DisputeWindowClosed,
+    #[msg("The bid note is too long")]
+    NoteTooLong,
+    #[msg("The outbid bidder's refund escrow is required")]
+    MissingBidEscrow,
+    #[msg("The leading proxy bid account is required")]
+    MissingProxyBid,
+    #[msg("Raise a leading proxy bid with place_proxy_bid")]
+    ProxyBidActive,
+    #[msg("A leading proxy bid cannot be withdrawn while bidding is open")]
+    ProxyBidLocked,
+    #[msg("The leading proxy bid is the bidder's own; leave it out")]
+    OwnProxyBid,
+    #[msg("Extension window and amount must be non-negative and the cap no earlier than the end time")]
+    InvalidExtension,
+    #[msg("Deposit must be between 1 and 10000 basis points")]
+    InvalidDeposit,
+    #[msg("Deposit-bidding auctions only take deposit bids")]
+    DepositAuction,
+    #[msg("The auction does not take deposit bids")]
+    NotDepositAuction,
+    #[msg("The bidder already holds the standing bid")]
+    AlreadyLeading,
+    #[msg("The winning bid has not been settled")]
+    SettlementPending,
+    #[msg("The settlement window has not closed")]
+    SettlementWindowOpen,
+    #[msg("The settlement window has closed")]
+    SettlementWindowClosed,
+    #[msg("Retraction penalty must be at most 10000 basis points")]
+    InvalidRetractionPenalty,
+    #[msg("The deposit terms account is required")]
+    MissingDepositTerms,
+    #[msg("Only a runner-up, or a leader nobody has bid against, may retract")]
+    RetractionNotAllowed,
+    #[msg("The bidder's stats account is required")]
+    MissingBidderStats,
+    #[msg("The bidder must wait out the cooldown before bidding again")]
+    BidCooldown,
+    #[msg("The bidder has placed as many bids as the auction allows")]
+    BidCapReached,
+    #[msg("The session key has expired")]
+    SessionExpired,
+    #[msg("The bid exceeds the session key's spend limit")]
+    SessionSpendLimit,
+    #[msg("The bid deposit does not cover the bid")]
+    InsufficientDeposit,
+    #[msg("Increment tiers must be non-empty, sorted and have positive increments")]
+    InvalidIncrementTiers,
+    #[msg("The auction uses increment tiers; pass its IncrementTiers account")]
+    MissingIncrementTiers,
+    #[msg("Bids in the closing window must go through the closing queue")]
+    ClosingQueueOpen,
+    #[msg("The closing queue only takes bids in the closing window")]
+    ClosingQueueClosed,
+    #[msg("The closing queue is full")]
+    ClosingQueueFull,
+    #[msg("The auction has a closing queue; pass its ClosingQueue account")]
+    MissingClosingQueue,
+    #[msg("The closing queue has not been resolved")]
+    ClosingQueuePending,
+    #[msg("No queued bid for this bidder")]
+    NoQueuedBid,
+    #[msg("The highest bid cannot be outbid until its minimum standing time passes")]
+    BidStillStanding,
+    #[msg("The auction does not require registration")]
+    RegistrationNotRequired,
+    #[msg("The auction requires bidders to register first")]
+    MissingRegistration,
+    #[msg("Registration deposits are held until the auction closes")]
+    RegistrationLocked,
+    #[msg("The relative bid comes to more than the bidder's maximum")]
+    RelativeBidAboveMax,
+    #[msg("Sweep accounts must be bid escrow, bidder and rent payer triples of this auction")]
+    InvalidSweepAccounts,
+    #[msg("A bid is pending the outbid leader's right of first refusal")]
+    BidPending,
+    #[msg("No bid is pending")]
+    NoPendingBid,
+    #[msg("The first refusal window has passed")]
+    FirstRefusalExpired,
+    #[msg("The first refusal window is still open")]
+    FirstRefusalOpen,
+    #[msg("Pool bids are not available on auctions with a right of first refusal")]
+    FirstRefusalAuction,
+    #[msg("The bid pool does not hold enough lamports for the bid")]
+    InsufficientBidPoolFunds,
+    #[msg("Reclaim the pool's outbid bids before withdrawing")]
+    PoolRefundUnclaimed,
+    #[msg("This contribution's share has already been withdrawn")]
+    PoolShareWithdrawn,
+    #[msg("The auction has no reserve price")]
+    NoReservePrice,
+    #[msg("The standing bid is conditional on an unmet reserve")]
+    ReserveNotMet,
+    #[msg("The standing bid is not a conditional bid below the reserve")]
+    NoConditionalBid,
+    #[msg("Holder discount must be at most 10000 bps")]
+    InvalidHolderDiscount,
+    #[msg("No holder discount is configured")]
+    NoHolderDiscount,
+    #[msg("Holder token account is not the bidder's account of the holder mint")]
+    InvalidHolderAccount,
+    #[msg("Holder token account is below the minimum balance")]
+    InsufficientHolderBalance,
+    #[msg("Config account is required to claim the holder discount")]
+    MissingConfig,
+    #[msg("Winner account is required to pay the holder discount")]
+    MissingWinner,
+    #[msg("Rebid strategy needs a max price and an increment of at most 10000 bps")]
+    InvalidRebidStrategy,
+    #[msg("The strategy's bidder is not outbid")]
+    NotOutbid,
+    #[msg("The rebid would exceed the strategy's max price")]
+    RebidAboveMax,
+    #[msg("The creator may not bid on their own auction")]
+    CreatorBid,
+    #[msg("Wallet is linked to the auction's creator")]
+    LinkedWalletBid,
+    #[msg("Too many wallets linked to one creator")]
+    TooManyCreatorLinks,
+    #[msg("Loser rebate must be between 1 and 10000 bps of the fee")]
+    InvalidLoserRebate,
+    #[msg("Loser rebate account is required for this auction")]
+    MissingLoserRebate,
+    #[msg("Only losing bidders among the top bids share the rebate")]
+    NotLosingBidder,
+    #[msg("This bidder's rebate has already been claimed")]
+    LoserRebateClaimed,
+    #[msg("Allowlist entry is required for this auction")]
+    MissingAllowlistEntry,
+    #[msg("Proof does not place the bidder on the allowlist")]
+    NotAllowlisted,
+    #[msg("Auction is paused")]
+    AuctionPaused,
+    #[msg("Auction is not paused")]
+    AuctionNotPaused,
+    #[msg("Extension exceeds the most a creator may add to the end time")]
+    ExtensionTooLong,
+    #[msg("The auction cannot be closed until the close delay has passed")]
+    CloseDelayPending,
+    #[msg("Escrow still holds funds owed to bidders or the seller")]
+    EscrowNotEmpty,
+    #[msg("Vault still holds the lot")]
+    VaultNotEmpty,
+    #[msg("Expiry accounts must be seven-account groups of an unsold auction")]
+    InvalidExpiryAccounts,
+    #[msg("Account is not an auction of this program")]
+    InvalidAuctionAccount,
+    #[msg("The auction already has the current layout")]
+    AuctionUpToDate,
+    #[msg("Bidding must open after now and before the auction ends, and not before the preview")]
+    InvalidBidStart,
+    #[msg("The auction is not open for bids yet")]
+    BiddingNotOpen,
+    #[msg("The auction can only be recovered once DEADMAN_WINDOW has passed since it ended")]
+    RecoveryWindowNotReached,
+    #[msg("init_from_template requires the template account")]
+    MissingTemplate,
+    #[msg("This mint was cancelled recently and cannot be listed again yet")]
+    RelistCooldownActive,
+    #[msg("The mint cooldown account is required while a relist cooldown is set")]
+    MissingMintCooldown,
+    #[msg("Fair-end auctions need the SlotHashes sysvar to record their cutoff")]
+    SlotHashesUnavailable,
+    #[msg("The new reserve price must be below the current one")]
+    ReserveNotLowered,
+    #[msg("The winner is not determined until the closing queue is resolved")]
+    ResultUndetermined,
+    #[msg("Forced refunds are turned off")]
+    ForceRefundDisabled,
+    #[msg("The force-refund window has not passed since the auction ended")]
+    ForceRefundNotDue,
+    #[msg("Platform fee must not exceed 10000 basis points")]
+    InvalidPlatformFee,
+    #[msg("Auction duration is outside the platform's bounds")]
+    DurationOutOfBounds,
+    #[msg("Platform fee account does not match the config's fee destination")]
+    InvalidFeeDestination,
+    #[msg("The program is halted")]
+    ProgramHalted,
+    #[msg("The NFT's collection is not approved for listing")]
+    CollectionNotApproved,
+    #[msg("Bidding requires the bidder's token account of the gate mint")]
+    MissingGateAccount,
+    #[msg("Bidder holds less than the auction's gate balance")]
+    InsufficientGateBalance,
+    #[msg("Gated auctions only take bids through place_bid")]
+    BidGated,
+    #[msg("Gateway token is not an active pass of the auction's network")]
+    InvalidAttestation,
+    #[msg("Gateway token has expired")]
+    AttestationExpired,
+    #[msg("Bidding requires the bidder's gateway token")]
+    MissingAttestation,
+    #[msg("Wallet is on the blocklist")]
+    WalletOnBlocklist,
+}
+
+
+#[event]
+pub struct AuctionCreated {
+    pub auction_id: Pubkey,
+    pub creator: Pubkey,
+    pub nft_mint: Pubkey,
+    pub starting_bid: u64,
+    pub end_time: i64,
+    pub quantity: u64,
+}
+
+#[event]
+pub struct BidPlaced {
+    pub auction_id: Pubkey,
+    pub bidder: Pubkey,
+    pub bid_amount: u64,
+}
+
+#[event]
+pub struct BuyNowExecuted {
+    pub auction_id: Pubkey,
+    pub buyer: Pubkey,
+    pub price: u64,
+}
+
+#[event]
+pub struct AuctionFinalized {
+    pub auction_id: Pubkey,
+    pub winner: Pubkey,
+    pub winning_bid: u64,
+    /// Experiment the auction ran under, or 0 if none
+    pub experiment_id: u32,
+    pub variant: u8,
+}
+
+#[event]
+pub struct AuctionRelisted {
+    pub auction_id: Pubkey,
+    pub previous_end_time: i64,
+    pub starting_bid: u64,
+    pub end_time: i64,
+}
+
+#[event]
+pub struct AuctionCancelled {
+    pub auction_id: Pubkey,
+    pub reason: String,
+}
+
+#[event]
+pub struct AuctionUpdated {
+    pub auction_id: Pubkey,
+    pub new_duration: Option<i64>,
+    pub new_min_increment: Option<u64>,
+    pub new_min_increment_bps: Option<u16>,
+}
+
+
+#[event]
+pub struct ConfigInitialized {
+    pub admin: Pubkey,
+}
+
+#[event]
+pub struct PlatformParamsSet {
+    pub fee_bps: u16,
+    pub fee_destination: Pubkey,
+    pub min_duration: i64,
+    pub max_duration: i64,
+}
+
+#[event]
+pub struct RolesSet {
+    pub pauser: Pubkey,
+    pub fee_manager: Pubkey,
+    pub curator: Pubkey,
+}
+
+#[event]
+pub struct ProgramHaltSet {
+    pub halted: bool,
+}
+
+#[event]
+pub struct CuratedModeSet {
+    pub curated: bool,
+}
+
+#[event]
+pub struct CollectionApproved {
+    pub collection: Pubkey,
+}
+
+#[event]
+pub struct CollectionRevoked {
+    pub collection: Pubkey,
+}
+
+#[event]
+pub struct WalletBlocked {
+    pub wallet: Pubkey,
+}
+
+#[event]
+pub struct WalletUnblocked {
+    pub wallet: Pubkey,
+}
+
+#[event]
+pub struct PartialBidPlaced {
+    pub auction_id: Pubkey,
+    pub bidder: Pubkey,
+    pub unit_price: u64,
+    pub quantity: u64,
+}
+
+#[event]
+pub struct PartialFillClaimed {
+    pub auction_id: Pubkey,
+    pub bidder: Pubkey,
+    pub filled: u64,
+    pub refund: u64,
+}
+
+#[event]
+pub struct BidMintsSet {
+    pub mints: Vec<Pubkey>,
+}
+
+#[event]
+pub struct LotTokensAttached {
+    pub auction_id: Pubkey,
+    pub token_mint: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct LotTokensReleased {
+    pub auction_id: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct BidRefundCredited {
+    pub auction_id: Pubkey,
+    pub bidder: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct RefundClaimed {
+    pub auction_id: Pubkey,
+    pub bidder: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct ProxyBidPlaced {
+    pub auction_id: Pubkey,
+    pub bidder: Pubkey,
+    pub max_bid: u64,
+}
+
+#[event]
+pub struct ProxyBidWithdrawn {
+    pub auction_id: Pubkey,
+    pub bidder: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct BidNoteAttached {
+    pub auction_id: Pubkey,
+    pub bidder: Pubkey,
+    pub bid_amount: u64,
+    pub note: String,
+}
+
+#[event]
+pub struct TemplateCreated {
+    pub template: Pubkey,
+    pub creator: Pubkey,
+    pub template_id: u32,
+}
+
+#[event]
+pub struct CreatorSettingsSet {
+    pub creator: Pubkey,
+    pub defaults: CreatorDefaults,
+}
+
+#[event]
+pub struct FairEndEnabled {
+    pub auction_id: Pubkey,
+}
+
+#[event]
+pub struct FairCutoffRecorded {
+    pub auction_id: Pubkey,
+    pub slot: u64,
+    pub hash: [u8; 32],
+}
+
+#[event]
+pub struct AntiSnipeSet {
+    pub auction_id: Pubkey,
+    pub extension_window: i64,
+    pub extension_amount: i64,
+    pub max_end_time: i64,
+}
+
+#[event]
+pub struct DepositBiddingEnabled {
+    pub auction_id: Pubkey,
+    pub deposit_bps: u16,
+    pub settlement_window: i64,
+}
+
+#[event]
+pub struct WinningBidSettled {
+    pub auction_id: Pubkey,
+    pub winner: Pubkey,
+    pub balance: u64,
+}
+
+#[event]
+pub struct BidForfeited {
+    pub auction_id: Pubkey,
+    pub bidder: Pubkey,
+    pub deposit: u64,
+}
+
+#[event]
+pub struct RetractionPenaltySet {
+    pub penalty_bps: u16,
+}
+
+#[event]
+pub struct MaxCreatorExtensionSet {
+    pub max_extension: i64,
+}
+
+#[event]
+pub struct ForceRefundWindowSet {
+    pub window: i64,
+}
+
+#[event]
+pub struct RelistCooldownSet {
+    pub cooldown: i64,
+}
+
+#[event]
+pub struct RelistCooldownStarted {
+    pub mint: Pubkey,
+    pub relist_after: i64,
+}
+
+#[event]
+pub struct BiddingScheduled {
+    pub auction_id: Pubkey,
+    pub preview_start: i64,
+    pub bid_start: i64,
+}
+
+#[event]
+pub struct EndTimeExtended {
+    pub auction_id: Pubkey,
+    pub previous_end_time: i64,
+    pub end_time: i64,
+}
+
+#[event]
+pub struct HolderDiscountSet {
+    pub holder_mint: Pubkey,
+    pub min_balance: u64,
+    pub discount_bps: u16,
+}
+
+#[event]
+pub struct HolderDiscountPaid {
+    pub auction_id: Pubkey,
+    pub winner: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct BidRetracted {
+    pub auction_id: Pubkey,
+    pub bidder: Pubkey,
+    pub refund: u64,
+    pub penalty: u64,
+}
+
+#[event]
+pub struct BidCooldownSet {
+    pub auction_id: Pubkey,
+    pub cooldown: i64,
+}
+
+#[event]
+pub struct BidCapSet {
+    pub auction_id: Pubkey,
+    pub max_bids_per_wallet: u32,
+}
+
+#[event]
+pub struct SessionKeyAuthorized {
+    pub owner: Pubkey,
+    pub session_key: Pubkey,
+    pub spend_limit: u64,
+    pub expires_at: i64,
+}
+
+#[event]
+pub struct SessionKeyRevoked {
+    pub owner: Pubkey,
+    pub session_key: Pubkey,
+    pub unspent: u64,
+}
+
+#[event]
+pub struct BidDepositFunded {
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub balance: u64,
+}
+
+#[event]
+pub struct BidDepositWithdrawn {
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub balance: u64,
+}
+
+#[event]
+pub struct IncrementTiersSet {
+    pub auction_id: Pubkey,
+    pub tiers: Vec<IncrementTier>,
+}
+
+#[event]
+pub struct ClosingQueueEnabled {
+    pub auction_id: Pubkey,
+}
+
+#[event]
+pub struct BidQueued {
+    pub auction_id: Pubkey,
+    pub bidder: Pubkey,
+    pub amount: u64,
+    pub slot: u64,
+}
+
+#[event]
+pub struct ClosingQueueResolved {
+    pub auction_id: Pubkey,
+    pub winner: Pubkey,
+    pub winning_bid: u64,
+}
+
+#[event]
+pub struct QueuedBidWithdrawn {
+    pub auction_id: Pubkey,
+    pub bidder: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct MinStandingTimeSet {
+    pub auction_id: Pubkey,
+    pub min_standing_time: u16,
+}
+
+#[event]
+pub struct RegistrationDepositSet {
+    pub auction_id: Pubkey,
+    pub deposit: u64,
+}
+
+#[event]
+pub struct BidderRegistered {
+    pub auction_id: Pubkey,
+    pub bidder: Pubkey,
+    pub deposit: u64,
+}
+
+#[event]
+pub struct RegistrationWithdrawn {
+    pub auction_id: Pubkey,
+    pub bidder: Pubkey,
+    pub deposit: u64,
+}
+
+#[event]
+pub struct RefundsSwept {
+    pub auction_id: Pubkey,
+    pub swept: u64,
+    pub bounty: u64,
+}
+
+#[event]
+pub struct FirstRefusalWindowSet {
+    pub auction_id: Pubkey,
+    pub window: i64,
+}
+
+#[event]
+pub struct BidPendingFirstRefusal {
+    pub auction_id: Pubkey,
+    pub bidder: Pubkey,
+    pub bid_amount: u64,
+    pub pending_until: i64,
+}
+
+#[event]
+pub struct FirstRefusalExercised {
+    pub auction_id: Pubkey,
+    pub bidder: Pubkey,
+    pub bid_amount: u64,
+}
+
+#[event]
+pub struct PendingBidAccepted {
+    pub auction_id: Pubkey,
+    pub bidder: Pubkey,
+    pub bid_amount: u64,
+}
+
+#[event]
+pub struct BidPoolCreated {
+    pub auction_id: Pubkey,
+    pub pool: Pubkey,
+    pub organizer: Pubkey,
+}
+
+#[event]
+pub struct PoolContributed {
+    pub pool: Pubkey,
+    pub contributor: Pubkey,
+    pub amount: u64,
+    pub total_contributed: u64,
+}
+
+#[event]
+pub struct PoolShareWithdrawn {
+    pub pool: Pubkey,
+    pub contributor: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct CreatorLinksSet {
+    pub creator: Pubkey,
+    pub linked: Vec<Pubkey>,
+}
+
+#[event]
+pub struct AuctionClosed {
+    pub auction_id: Pubkey,
+    pub creator: Pubkey,
+}
+
+#[event]
+pub struct ProceedsClaimed {
+    pub auction_id: Pubkey,
+    pub seller: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct WonNftParked {
+    pub auction_id: Pubkey,
+    pub winner: Pubkey,
+}
+
+#[event]
+pub struct ItemClaimed {
+    pub auction_id: Pubkey,
+    pub winner: Pubkey,
+    pub nft_recipient_account: Pubkey,
+}
+
+#[event]
+pub struct BidRecovered {
+    pub auction_id: Pubkey,
+    pub bidder: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct ItemRecovered {
+    pub auction_id: Pubkey,
+    pub creator: Pubkey,
+    pub nft_recipient_account: Pubkey,
+}
+
+#[event]
+pub struct AuctionForceRefunded {
+    pub auction_id: Pubkey,
+    pub caller: Pubkey,
+    /// Bidder whose standing bid was refunded; default when there was none
+    pub bidder: Pubkey,
+    pub refunded: u64,
+}
+
+#[event]
+pub struct AuctionEmergencyCancelled {
+    pub auction_id: Pubkey,
+    pub admin: Pubkey,
+    pub reason_code: u8,
+    /// Bidder whose standing bid was refunded; default when there was none
+    pub bidder: Pubkey,
+    pub refunded: u64,
+}
+
+#[event]
+pub struct StatusChanged {
+    pub auction_id: Pubkey,
+    pub from: AuctionStatus,
+    pub to: AuctionStatus,
+}
+
+#[event]
+pub struct AuctionPaused {
+    pub auction_id: Pubkey,
+    pub paused_at: i64,
+}
+
+#[event]
+pub struct AuctionResumed {
+    pub auction_id: Pubkey,
+    pub end_time: i64,
+}
+
+#[event]
+pub struct BidTransferred {
+    pub from_auction: Pubkey,
+    pub to_auction: Pubkey,
+    pub bidder: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct BidderAllowlistSet {
+    pub auction_id: Pubkey,
+    pub root: [u8; 32],
+}
+
+#[event]
+pub struct TokenGateSet {
+    pub auction_id: Pubkey,
+    pub gate_mint: Pubkey,
+    pub min_balance: u64,
+}
+
+#[event]
+pub struct AttestationGateSet {
+    pub auction_id: Pubkey,
+    pub network: Pubkey,
+}
+
+#[event]
+pub struct LoserRebateEnabled {
+    pub auction_id: Pubkey,
+    pub rebate_bps: u16,
+}
+
+#[event]
+pub struct LoserRebateClaimed {
+    pub auction_id: Pubkey,
+    pub bidder: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct RebidStrategySet {
+    pub auction_id: Pubkey,
+    pub bidder: Pubkey,
+    pub max_price: u64,
+    pub increment_bps: u16,
+}
+
+#[event]
+pub struct RebidExecuted {
+    pub auction_id: Pubkey,
+    pub bidder: Pubkey,
+    pub bid_amount: u64,
+    pub cranker: Pubkey,
+}
+
+#[event]
+pub struct ReservePriceSet {
+    pub auction_id: Pubkey,
+    pub reserve_price: u64,
+}
+
+#[event]
+pub struct FixedPriceFallbackEnabled {
+    pub auction_id: Pubkey,
+}
+
+#[event]
+pub struct ConvertedToFixedPrice {
+    pub auction_id: Pubkey,
+    pub price: u64,
+}
+
+#[event]
+pub struct ReserveLowered {
+    pub auction_id: Pubkey,
+    pub previous_reserve: u64,
+    pub reserve_price: u64,
+}
+
+#[event]
+pub struct ConditionalBidRefunded {
+    pub auction_id: Pubkey,
+    pub bidder: Pubkey,
+    pub bid_amount: u64,
+}
+
+#[event]
+pub struct DeliveryEscrowEnabled {
+    pub auction_id: Pubkey,
+    pub arbiter: Pubkey,
+    pub dispute_window: i64,
+}
+
+#[event]
+pub struct DisputeOpened {
+    pub auction_id: Pubkey,
+    pub buyer: Pubkey,
+}
+
+#[event]
+pub struct DisputeResolved {
+    pub auction_id: Pubkey,
+    pub refunded: bool,
+    pub amount: u64,
+}
+
+#[event]
+pub struct HeldProceedsReleased {
+    pub auction_id: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct CollectionOfferPlaced {
+    pub offer: Pubkey,
+    pub buyer: Pubkey,
+    pub collection: Pubkey,
+    pub amount: u64,
+    pub expires_at: i64,
+}
+
+#[event]
+pub struct CollectionOfferCancelled {
+    pub offer: Pubkey,
+}
+
+#[event]
+pub struct CollectionOfferAccepted {
+    pub offer: Pubkey,
+    pub buyer: Pubkey,
+    pub seller: Pubkey,
+    pub nft_mint: Pubkey,
+    pub amount: u64,
+    pub platform_fee: u64,
+    pub royalties: u64,
+}
+
+#[event]
+pub struct EscrowYieldReserveSet {
+    pub auction_id: Pubkey,
+    pub reserve: Option<Pubkey>,
+}
+
+#[event]
+pub struct EscrowYieldDeployed {
+    pub auction_id: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct EscrowYieldRecalled {
+    pub auction_id: Pubkey,
+    pub principal: u64,
+    /// Liquidity returned to the escrow, principal plus interest
+    pub redeemed: u64,
+}
+
+#[event]
+pub struct MetadataDrifted {
+    pub auction_id: Pubkey,
+    pub nft_mint: Pubkey,
+}
+
+#[event]
+pub struct AuctionFractionalized {
+    pub auction_id: Pubkey,
+    pub fraction_mint: Pubkey,
+    pub shares: u64,
+}
+
+#[event]
+pub struct FractionsRedeemed {
+    pub auction_id: Pubkey,
+    pub redeemer: Pubkey,
+    pub shares: u64,
+}
+
+#[event]
+pub struct LstPayoutSet {
+    pub auction_id: Pubkey,
+    pub stake_pool: Option<Pubkey>,
+}
+
+#[event]
+pub struct TreasuryFeesWithdrawn {
+    pub mint: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct AuctionMigrated {
+    pub auction_id: Pubkey,
+    pub previous_version: u8,
+    pub version: u8,
+}
+
+#[event]
+pub struct AuctionImported {
+    pub auction_id: Pubkey,
+    pub legacy_program: Pubkey,
+    pub legacy_account: Pubkey,
+    pub imported_by: Pubkey,
+}
+
+#[event]
+pub struct AuctionSnapshotted {
+    pub auction_id: Pubkey,
+    pub winner: Pubkey,
+    pub winning_bid: u64,
+    pub reserve_met: bool,
+}
+
+#[event]
+pub struct AuctionEscheated {
+    pub auction_id: Pubkey,
+    pub nft_mint: Pubkey,
+    pub creator: Pubkey,
+    pub highest_bidder: Pubkey,
+    pub escrowed_amount: u64,
+    pub operator: Pubkey,
+}
+
+#[event]
+pub struct AcceptedCurrenciesSet {
+    pub auction_id: Pubkey,
+    pub mints: Vec<Pubkey>,
+}
+
+#[event]
+pub struct MultiCurrencyBidPlaced {
+    pub auction_id: Pubkey,
+    pub bidder: Pubkey,
+    pub bid_mint: Pubkey,
+    pub bid_amount: u64,
+    pub normalized_value: u64,
+}
+
+#[event]
+pub struct ExperimentConfigured {
+    pub id: u32,
+    pub variants: [ExperimentVariant; 2],
+    pub split_bps: u16,
+    pub active: bool,
+}
+
+#[event]
+pub struct KeeperRegistered {
+    pub keeper: Pubkey,
+}
+
+#[event]
+pub struct KeeperRebateAccrued {
+    pub keeper: Pubkey,
+    pub auction_id: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct KeeperRebatesClaimed {
+    pub keeper: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct RoyaltiesPaid {
+    pub auction_id: Pubkey,
+    pub seller_fee_basis_points: u16,
+    pub payments: Vec<CreatorRoyalty>,
+}
+
+pub const AUCTION_SEED: &[u8] = b"auction";
+pub const ESCROW_SEED: &[u8] = b"escrow";
+pub const VAULT_SEED: &[u8] = b"vault";
+pub const CONFIG_SEED: &[u8] = b"config";
+pub const PROVENANCE_SEED: &[u8] = b"provenance";
+pub const WSOL_SEED: &[u8] = b"wsol";
+pub const TEMPLATE_SEED: &[u8] = b"template";
+pub const CREATOR_SETTINGS_SEED: &[u8] = b"creator_settings";
+pub const MINT_COOLDOWN_SEED: &[u8] = b"mint_cooldown";
+pub const RESULT_SEED: &[u8] = b"result";
+pub const APPROVED_COLLECTION_SEED: &[u8] = b"approved_collection";
+pub const BLOCKLIST_SEED: &[u8] = b"blocklist";
+/// Durations shorter than this are accepted but flagged by
+/// `validate_auction_params`.
+pub const MIN_RECOMMENDED_DURATION: i64 = 60 * 60;
+/// Decimals expected of the bid mint in stablecoin auctions.
+pub const STABLE_DECIMALS: u8 = 6;
+/// One cent in base units of a 6-decimal stablecoin.
+pub const STABLE_CENT: u64 = 10_000;
+pub const CUSTODY_SEED: &[u8] = b"custody";
+pub const TREASURY_SEED: &[u8] = b"treasury";
+pub const ESCHEAT_SEED: &[u8] = b"escheat";
+/// Time after `end_time` before an unsettled lot may be escheated (3 years).
+pub const ESCHEAT_WINDOW: i64 = 3 * 365 * 24 * 60 * 60;
+/// Time after `end_time` before the parties to an auction nobody finalized
+/// may take back their bid and NFT (30 days).
+pub const DEADMAN_WINDOW: i64 = 30 * 24 * 60 * 60;
+/// Time after `end_time` before a finished auction may be closed (30 days).
+pub const CLOSE_AUCTION_DELAY: i64 = 30 * 24 * 60 * 60;
+pub const CURRENCIES_SEED: &[u8] = b"currencies";
+pub const MAX_ACCEPTED_CURRENCIES: usize = 4;
+/// Most bid mints the config registry can hold.
+pub const MAX_BID_MINTS: usize = 6;
+/// Multi-currency bids are compared in micro-USD.
+pub const USD_DECIMALS: u8 = 6;
+/// Oldest Pyth price, in seconds, accepted for bid valuation.
+pub const MAX_PRICE_AGE: i64 = 60;
+pub const COMPRESSED_SEED: &[u8] = b"compressed";
+pub const COMPRESSED_BUNDLE_SEED: &[u8] = b"compressed_bundle";
+/// Most compressed NFTs one bundle may hold.
+pub const MAX_BUNDLE_LEAVES: usize = 8;
+pub const PARTIAL_FILL_SEED: &[u8] = b"partial_fill";
+pub const FRACTION_MINT_SEED: &[u8] = b"fraction_mint";
+pub const METADATA_SNAPSHOT_SEED: &[u8] = b"metadata_snapshot";
+pub const COLLECTION_OFFER_SEED: &[u8] = b"collection_offer";
+pub const DISPUTE_SEED: &[u8] = b"dispute";
+pub const BID_NOTE_SEED: &[u8] = b"bid_note";
+pub const LOT_MANIFEST_SEED: &[u8] = b"lot_manifest";
+pub const BID_ESCROW_SEED: &[u8] = b"bid_escrow";
+pub const PROXY_BID_SEED: &[u8] = b"proxy_bid";
+pub const DEPOSIT_TERMS_SEED: &[u8] = b"deposit_terms";
+pub const BID_HISTORY_SEED: &[u8] = b"bid_history";
+pub const BID_RECEIPT_SEED: &[u8] = b"bid_receipt";
+pub const BID_POOL_SEED: &[u8] = b"bid_pool";
+pub const POOL_CONTRIBUTION_SEED: &[u8] = b"pool_contribution";
+pub const BIDDER_STATS_SEED: &[u8] = b"bidder_stats";
+pub const BIDDER_REGISTRATION_SEED: &[u8] = b"bidder_registration";
+pub const SESSION_KEY_SEED: &[u8] = b"session_key";
+pub const BID_DEPOSIT_SEED: &[u8] = b"bid_deposit";
+pub const REBID_STRATEGY_SEED: &[u8] = b"rebid_strategy";
+pub const CREATOR_LINKS_SEED: &[u8] = b"creator_links";
+pub const LOSER_REBATE_SEED: &[u8] = b"loser_rebate";
+pub const ALLOWLIST_ENTRY_SEED: &[u8] = b"allowlist_entry";
+/// Most wallets a `CreatorLinks` list can hold.
+pub const MAX_CREATOR_LINKS: usize = 16;
+/// Bids a `BidHistory` keeps before overwriting the oldest.
+pub const MAX_BID_HISTORY: usize = 16;
+pub const TOP_BIDS_SEED: &[u8] = b"top_bids";
+/// Bidders a `TopBids` list ranks.
+pub const MAX_TOP_BIDS: usize = 8;
+pub const INCREMENT_TIERS_SEED: &[u8] = b"increment_tiers";
+/// Most tiers an `IncrementTiers` table can hold.
+pub const MAX_INCREMENT_TIERS: usize = 8;
+pub const CLOSING_QUEUE_SEED: &[u8] = b"closing_queue";
+/// Seconds before `end_time` during which bids go to the closing queue.
+pub const CLOSING_QUEUE_WINDOW: i64 = 60;
+/// Most bidders a `ClosingQueue` can hold.
+pub const MAX_QUEUED_BIDS: usize = 16;
+/// Longest note, in bytes, a bidder may attach to a bid.
+pub const MAX_BID_NOTE_LEN: usize = 140;
+/// Most open entries a partial-fill book can hold.
+pub const MAX_PARTIAL_BIDS: usize = 16;
+pub const EXPERIMENT_SEED: &[u8] = b"experiment";
+pub const BPS_DENOMINATOR: u16 = 10_000;
+/// Platform fee applied when no experiment overrides it (2.5%).
+pub const DEFAULT_FEE_BPS: u16 = 250;
+/// Current `Auction` layout; bump alongside new fields so older accounts
+/// can be brought up to date with `migrate_auction`.
+pub const AUCTION_VERSION: u8 = 9;
+/// Nominal slot time used to convert between slot and second durations.
+pub const SLOT_DURATION_MS: u64 = 400;
+pub const REBATE_POOL_SEED: &[u8] = b"rebate_pool";
+pub const KEEPER_SEED: &[u8] = b"keeper";
+/// Lamports a keeper earns per refund swept by `sweep_refunds`.
+pub const SWEEP_BOUNTY: u64 = 5_000;
+/// Share of the platform fee `finalize_auction` pays its settler (5%).
+pub const FINALIZE_BOUNTY_BPS: u16 = 500;
+
+
+impl Route {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> InitializeAuction<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> PlaceBid<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> PlaceTokenBid<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> BuyNow<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> EndAuction<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> EnableFairEnd<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> ScheduleBidding<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> OpenBidding<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> FinalizeAuction<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> ClaimProceeds<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> ClaimItem<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> CreateTemplate<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> SetCreatorSettings<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> RecoverBid<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> RecoverItem<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> MigrateAuction<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> FinalizeTokenAuction<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> WithdrawUnsoldNFT<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> RelistAuction<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> ExpireUnsoldAuctions<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> EmergencyCancel<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> ForceRefund<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> FractionalizeUnsoldNFT<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> RedeemFractions<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> CancelAuction<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> UpdateAuctionSettings<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+impl<'info> InitializeConfig<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> SetPlatformParams<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> SetRoles<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> SetHalted<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> SetCurated<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> ApproveCollection<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> RevokeCollection<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> BlockWallet<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> UnblockWallet<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> SetBidMints<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> WithdrawTreasuryFees<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> ImportAuction<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> PlaceWsolBid<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> ValidateAuctionParams<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> SnapshotAuction<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> EscheatAuction<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> SetAcceptedCurrencies<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> PlaceMultiCurrencyBid<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> InitializeCompressedAuction<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> FinalizeCompressedAuction<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> ConfigureExperiment<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> ConfigureRebatePool<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> RegisterKeeper<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> ClaimKeeperRebates<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> ExpireAuction<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> InitializeCoreAuction<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> FinalizeCoreAuction<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> InitializeDomainAuction<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> FinalizeDomainAuction<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> InitializeTokenAccountAuction<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> FinalizeTokenAccountAuction<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> InitializeDelegatedAuction<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+
+    pub fn delegated_freeze_accounts(&self) -> token_metadata::DelegatedFreezeAccounts<'_, 'info> {
+        token_metadata::DelegatedFreezeAccounts {
+            token_metadata_program: &self.token_metadata_program,
+            delegate: &self.auction_authority,
+            token_account: self.creator_nft_account.as_ref(),
+            edition: &self.nft_edition,
+            mint: self.nft_mint.as_ref(),
+            token_program: self.token_program.as_ref(),
+        }
+    }
+}
+
+impl<'info> FinalizeDelegatedAuction<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+
+    pub fn delegated_freeze_accounts(&self) -> token_metadata::DelegatedFreezeAccounts<'_, 'info> {
+        token_metadata::DelegatedFreezeAccounts {
+            token_metadata_program: &self.token_metadata_program,
+            delegate: &self.auction_authority,
+            token_account: self.creator_nft_account.as_ref(),
+            edition: &self.nft_edition,
+            mint: self.nft_mint.as_ref(),
+            token_program: self.token_program.as_ref(),
+        }
+    }
+}
+
+impl<'info> InitializeCompressedBundleAuction<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> FinalizeCompressedBundleAuction<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> EnablePartialFills<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> PlacePartialBid<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> FinalizePartialFillAuction<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> ClaimPartialFill<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> InitializeEscrowlessAuction<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> CancelBrokenListing<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> SetLstPayout<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> LstPayoutAccounts<'info> {
+    /// Stakes `lamports` from the SOL escrow into `expected_pool` and mints
+    /// the pool tokens to the seller's account.
+    #[allow(clippy::too_many_arguments)]
+    pub fn deposit(
+        &self,
+        expected_pool: Pubkey,
+        seller: &Pubkey,
+        system_program: &Program<'info, System>,
+        escrow: &SystemAccount<'info>,
+        escrow_bump: u8,
+        auction: Pubkey,
+        lamports: u64,
+    ) -> Result<()> {
+        let missing = || error!(AuctionError::MissingLstPayoutAccounts);
+        let pool = self.stake_pool.as_ref().ok_or_else(missing)?;
+        require_keys_eq!(pool.key(), expected_pool, AuctionError::InvalidStakePool);
+        let destination = self.seller_lst_account.as_ref().ok_or_else(missing)?;
+        require_keys_eq!(destination.owner, *seller, AuctionError::InvalidRecipient);
+
+        let signer_seeds: &[&[&[u8]]] = &[&[ESCROW_SEED, auction.as_ref(), &[escrow_bump]]];
+        stake_pool::deposit_sol(
+            stake_pool::DepositSolAccounts {
+                stake_pool_program: self.stake_pool_program.as_ref().ok_or_else(missing)?,
+                stake_pool: pool,
+                withdraw_authority: self.withdraw_authority.as_ref().ok_or_else(missing)?,
+                reserve_stake: self.reserve_stake.as_ref().ok_or_else(missing)?,
+                lamports_from: &escrow.to_account_info(),
+                destination: &destination.to_account_info(),
+                manager_fee_account: self.manager_fee_account.as_ref().ok_or_else(missing)?,
+                pool_mint: self.pool_mint.as_ref().ok_or_else(missing)?,
+                system_program: &system_program.to_account_info(),
+                token_program: &self.token_program.as_ref().ok_or_else(missing)?.to_account_info(),
+            },
+            lamports,
+            signer_seeds,
+        )
+    }
+}
+
+impl<'info> AttachLotTokens<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> ReleaseLotTokens<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> ClaimRefund<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> PlaceProxyBid<'info> {
+    pub fn validate(&self) -> Result<()> {
+        if let Some(leader_proxy_bid) = &self.leader_proxy_bid {
+            require_keys_neq!(
+                leader_proxy_bid.key(),
+                self.proxy_bid.key(),
+                AuctionError::OwnProxyBid
+            );
+        }
+        Ok(())
+    }
+}
+
+impl<'info> WithdrawProxyBid<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> SetAntiSnipe<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> EnableDepositBidding<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> PlaceDepositBid<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> SettleWinningBid<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> Forfeit<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> SetRetractionPenalty<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> RetractBid<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> SetBidCooldown<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> SetBidCap<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> AuthorizeSessionKey<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> RevokeSessionKey<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> FundBidDeposit<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> WithdrawBidDeposit<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> SetIncrementTiers<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> EnableClosingQueue<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> QueueBid<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> WithdrawQueuedBid<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> SetMinStandingTime<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> SetRegistrationDeposit<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> RegisterBidder<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> WithdrawRegistration<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> SweepRefunds<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> CloseBidReceipt<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> SetFirstRefusalWindow<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> MatchFirstRefusal<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> AcceptPendingBid<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> CreateBidPool<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> ContributeToPool<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
 }
 
-#[derive(Accounts)]
-pub struct PlaceBid<'info> {
-    #[account(mut)]
-    pub auction: Account<'info, Auction>,
-    #[account(mut)]
-    pub bidder: Signer<'info>,
-    /// CHECK: Previous bidder account for refund
-    #[account(mut)]
-    pub previous_bidder: AccountInfo<'info>,
-    #[account(
-        mut,
-        seeds = [ESCROW_SEED, auction.key().as_ref()],
-        bump,
-    )]
-    pub auction_escrow: SystemAccount<'info>,
-    pub system_program: Program<'info, System>,
+impl<'info> PlacePoolBid<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
 }
 
-#[derive(Accounts)]
-pub struct FinalizeAuction<'info> {
-    #[account(mut)]
-    pub auction: Account<'info, Auction>,
-    #[account(mut)]
-    pub creator: SystemAccount<'info>,
-    /// CHECK: Auction authority PDA
-    #[account(
-        mut,
-        seeds = [AUCTION_SEED, auction.key().as_ref()],
-        bump,
-    )]
-    pub auction_authority: AccountInfo<'info>,
-    #[account(mut)]
-    pub vault_nft_account: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub winner_nft_account: Account<'info, TokenAccount>,
-    #[account(
-        mut,
-        seeds = [ESCROW_SEED, auction.key().as_ref()],
-        bump,
-    )]
-    pub auction_escrow: SystemAccount<'info>,
-    /// CHECK: Platform fee account
-    #[account(mut)]
-    pub platform_fee_account: AccountInfo<'info>,
-    pub token_program: Program<'info, Token>,
-    pub system_program: Program<'info, System>,
+impl<'info> ReclaimPoolRefund<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
 }
 
-#[derive(Accounts)]
-pub struct WithdrawUnsoldNFT<'info> {
-    #[account(mut)]
-    pub auction: Account<'info, Auction>,
-    #[account(mut)]
-    pub creator: Signer<'info>,
-    /// CHECK: Auction authority PDA
-    #[account(
-        mut,
-        seeds = [AUCTION_SEED, auction.key().as_ref()],
-        bump,
-    )]
-    pub auction_authority: AccountInfo<'info>,
-    #[account(mut)]
-    pub vault_nft_account: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub creator_nft_account: Account<'info, TokenAccount>,
-    pub token_program: Program<'info, Token>,
+impl<'info> WithdrawPoolShare<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> SetReservePrice<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> LowerReservePrice<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> EnableFixedPriceFallback<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> RefundConditionalBid<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> SetHolderDiscount<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> SetRebidStrategy<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> CancelRebidStrategy<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> ExecuteRebid<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> SetCreatorLinks<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> EnableLoserRebate<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> ClaimLoserRebate<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> SetBidderAllowlist<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> SetTokenGate<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> SetAttestationGate<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> TransferBid<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> PauseAuction<'info> {
+    pub fn validate(&self) -> Result<()> {
+        let authority = self.authority.key();
+        let admin = self.config.as_ref().map(|config| config.admin);
+        require!(
+            authority == self.auction.creator || admin == Some(authority),
+            AuctionError::Unauthorized
+        );
+        Ok(())
+    }
+}
+
+impl<'info> ExtendEndTime<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> SetMaxCreatorExtension<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> SetForceRefundAfter<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> SetRelistCooldown<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> CloseAuction<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> AttachBidNote<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> EnableDeliveryEscrow<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> OpenDispute<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> ResolveDispute<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> ReleaseHeldProceeds<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> PlaceCollectionOffer<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> CancelCollectionOffer<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> AcceptCollectionOffer<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> SetEscrowYieldReserve<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> DeployEscrowYield<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> RecallEscrowYield<'info> {
+    pub fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'info> ProgrammableAccounts<'info> {
+    /// Moves the pNFT from `from` to `to` via Token Metadata `Transfer`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn transfer(
+        &self,
+        token_program: &Interface<'info, TokenInterface>,
+        from: &InterfaceAccount<'info, TokenAccount>,
+        from_owner: &AccountInfo<'info>,
+        to: &InterfaceAccount<'info, TokenAccount>,
+        to_owner: &AccountInfo<'info>,
+        mint: &InterfaceAccount<'info, Mint>,
+        authority: &AccountInfo<'info>,
+        payer: &AccountInfo<'info>,
+        signer_seeds: &[&[&[u8]]],
+    ) -> Result<()> {
+        let missing = || error!(AuctionError::MissingProgrammableAccounts);
+        require_keys_eq!(to.owner, to_owner.key(), AuctionError::InvalidRecipient);
+        token_metadata::transfer_v1(
+            token_metadata::TransferV1Accounts {
+                token_metadata_program: self.token_metadata_program.as_ref().ok_or_else(missing)?,
+                token: &from.to_account_info(),
+                token_owner: from_owner,
+                destination_token: &to.to_account_info(),
+                destination_owner: to_owner,
+                mint: &mint.to_account_info(),
+                metadata: self.metadata.as_ref().ok_or_else(missing)?,
+                edition: self.edition.as_deref(),
+                owner_token_record: self.owner_token_record.as_deref(),
+                destination_token_record: self.destination_token_record.as_deref(),
+                authority,
+                payer,
+                system_program: &self.system_program.as_ref().ok_or_else(missing)?.to_account_info(),
+                sysvar_instructions: self.sysvar_instructions.as_ref().ok_or_else(missing)?,
+                spl_token_program: &token_program.to_account_info(),
+                spl_ata_program: &self
+                    .associated_token_program
+                    .as_ref()
+                    .ok_or_else(missing)?
+                    .to_account_info(),
+                authorization_rules_program: self.authorization_rules_program.as_deref(),
+                authorization_rules: self.authorization_rules.as_deref(),
+            },
+            1,
+            signer_seeds,
+        )
+    }
+}
+
+/// Moves the auctioned NFT out of the vault to `to`, routing programmable
+/// NFTs through Token Metadata and everything else through
+/// [`transfer_from_vault`]. `to_owner` and `payer` fall back to the
+/// corresponding accounts in `pnft` when the instruction has no better
+/// candidate.
+#[allow(clippy::too_many_arguments)]
+pub fn release_from_vault<'info>(
+    programmable: bool,
+    amount: u64,
+    pnft: &ProgrammableAccounts<'info>,
+    token_program: &Interface<'info, TokenInterface>,
+    vault: &InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    to: &InterfaceAccount<'info, TokenAccount>,
+    to_owner: Option<&AccountInfo<'info>>,
+    payer: Option<&AccountInfo<'info>>,
+    auction_authority: &AccountInfo<'info>,
+    auction: Pubkey,
+    authority_bump: u8,
+    remaining_accounts: &[AccountInfo<'info>],
+) -> Result<()> {
+    if !programmable {
+        return transfer_from_vault(
+            token_program,
+            vault,
+            mint,
+            amount,
+            to,
+            auction_authority,
+            auction,
+            authority_bump,
+            remaining_accounts,
+        );
+    }
+
+    let missing = || error!(AuctionError::MissingProgrammableAccounts);
+    let to_owner = match to_owner {
+        Some(owner) => owner.clone(),
+        None => pnft.destination_owner.as_ref().ok_or_else(missing)?.to_account_info(),
+    };
+    let payer = match payer {
+        Some(payer) => payer.clone(),
+        None => pnft.payer.as_ref().ok_or_else(missing)?.to_account_info(),
+    };
+    let signer_seeds: &[&[&[u8]]] = &[&[AUCTION_SEED, auction.as_ref(), &[authority_bump]]];
+    pnft.transfer(
+        token_program,
+        vault,
+        auction_authority,
+        to,
+        &to_owner,
+        mint,
+        auction_authority,
+        &payer,
+        signer_seeds,
+    )
+}
+
+/// Moves `amount` of the auctioned mint out of the vault, signing as the
+/// auction authority PDA. Works for both SPL Token and Token-2022 mints; any
+/// extra accounts required by a transfer hook are forwarded from
+/// `remaining_accounts`.
+#[allow(clippy::too_many_arguments)]
+pub fn transfer_from_vault<'info>(
+    token_program: &Interface<'info, TokenInterface>,
+    vault: &InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    amount: u64,
+    to: &InterfaceAccount<'info, TokenAccount>,
+    auction_authority: &AccountInfo<'info>,
+    auction: Pubkey,
+    authority_bump: u8,
+    remaining_accounts: &[AccountInfo<'info>],
+) -> Result<()> {
+    let signer_seeds: &[&[&[u8]]] = &[&[AUCTION_SEED, auction.as_ref(), &[authority_bump]]];
+    let transfer_ctx = CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        TransferChecked {
+            from: vault.to_account_info(),
+            mint: mint.to_account_info(),
+            to: to.to_account_info(),
+            authority: auction_authority.to_account_info(),
+        },
+        signer_seeds,
+    )
+    .with_remaining_accounts(remaining_accounts.to_vec());
+    anchor_spl::token_interface::transfer_checked(transfer_ctx, amount, mint.decimals)
+}
+
+/// Moves `amount` of the bid mint out of the escrow token account, signing as
+/// the auction authority PDA.
+#[allow(clippy::too_many_arguments)]
+pub fn transfer_from_escrow<'info>(
+    token_program: &Interface<'info, TokenInterface>,
+    escrow: &InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    to: &InterfaceAccount<'info, TokenAccount>,
+    auction_authority: &AccountInfo<'info>,
+    auction: Pubkey,
+    authority_bump: u8,
+    amount: u64,
+) -> Result<()> {
+    let signer_seeds: &[&[&[u8]]] = &[&[AUCTION_SEED, auction.as_ref(), &[authority_bump]]];
+    let transfer_ctx = CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        TransferChecked {
+            from: escrow.to_account_info(),
+            mint: mint.to_account_info(),
+            to: to.to_account_info(),
+            authority: auction_authority.to_account_info(),
+        },
+        signer_seeds,
+    );
+    anchor_spl::token_interface::transfer_checked(transfer_ctx, amount, mint.decimals)
+}
+
+/// Hands ownership of a whole token account to `new_owner`. `owner` is the
+/// current owner and signs directly, or through `signer_seeds` when it is
+/// the auction authority PDA. The token program clears any delegate.
+pub fn set_account_owner<'info>(
+    token_program: &Interface<'info, TokenInterface>,
+    account: &InterfaceAccount<'info, TokenAccount>,
+    owner: &AccountInfo<'info>,
+    new_owner: Pubkey,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let set_authority_ctx = CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        anchor_spl::token_interface::SetAuthority {
+            current_authority: owner.clone(),
+            account_or_mint: account.to_account_info(),
+        },
+        signer_seeds,
+    );
+    anchor_spl::token_interface::set_authority(
+        set_authority_ctx,
+        spl_token_2022::instruction::AuthorityType::AccountOwner,
+        Some(new_owner),
+    )
+}
+
+/// Whether `amount` is a whole number of `STABLE_CENT`s.
+// `u64::is_multiple_of` is newer than the SBF toolchain's rustc
+#[allow(clippy::manual_is_multiple_of)]
+pub fn is_cent_aligned(amount: u64) -> bool {
+    amount % STABLE_CENT == 0
+}
+
+/// Returns how much of `amount` actually arrives at the destination when
+/// transferring `mint`, accounting for the Token-2022 TransferFee extension.
+/// Mints without the extension (including legacy SPL mints) are fee-free.
+pub fn amount_after_transfer_fee(mint: &AccountInfo, amount: u64, epoch: u64) -> Result<u64> {
+    if *mint.owner != spl_token_2022::ID {
+        return Ok(amount);
+    }
+    let data = mint.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+    let fee = match mint_state.get_extension::<TransferFeeConfig>() {
+        Ok(config) => config
+            .calculate_epoch_fee(epoch, amount)
+            .ok_or(AuctionError::MathOverflow)?,
+        Err(_) => 0,
+    };
+    amount
+        .checked_sub(fee)
+        .ok_or_else(|| error!(AuctionError::MathOverflow))
+}
+
+/// Slots in `seconds` at the nominal `SLOT_DURATION_MS`; negative spans are
+/// zero.
+pub fn seconds_to_slots(seconds: i64) -> u64 {
+    (seconds.max(0) as u64).saturating_mul(1_000) / SLOT_DURATION_MS
+}
+
+/// Seconds `slots` take at the nominal `SLOT_DURATION_MS`, rounded up.
+pub fn slots_to_seconds(slots: u64) -> i64 {
+    slots.saturating_mul(SLOT_DURATION_MS).div_ceil(1_000) as i64
+}
+
+/// Splits a winning bid into `(platform_fee, seller_amount)` at `fee_bps`.
+pub fn split_proceeds(amount: u64, fee_bps: u16) -> (u64, u64) {
+    let platform_fee = ((amount as u128 * fee_bps as u128) / BPS_DENOMINATOR as u128) as u64;
+    (platform_fee, amount - platform_fee)
 }
 
-#[derive(Accounts)]
-pub struct CancelAuction<'info> {
-    #[account(mut)]
-    pub auction: Account<'info, Auction>,
-    #[account(mut)]
-    pub creator: Signer<'info>,
-    /// CHECK: Auction authority PDA
-    #[account(
-        mut,
-        seeds = [AUCTION_SEED, auction.key().as_ref()],
-        bump,
-    )]
-    pub auction_authority: AccountInfo<'info>,
-    #[account(mut)]
-    pub vault_nft_account: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub creator_nft_account: Account<'info, TokenAccount>,
-    pub token_program: Program<'info, Token>,
+/// Returns a native-SOL bid from the escrow PDA to the outbid bidder. Bids
+/// that were funded with wSOL are refunded into the bidder's wSOL account and
+/// synced, so wSOL-only wallets get back what they put in.
+#[allow(clippy::too_many_arguments)]
+pub fn refund_native_bid<'info>(
+    system_program: &Program<'info, System>,
+    escrow: &SystemAccount<'info>,
+    escrow_bump: u8,
+    auction: Pubkey,
+    recipient: &AccountInfo<'info>,
+    bidder: Pubkey,
+    amount: u64,
+    wrapped: bool,
+    token_program: Option<&Program<'info, Token>>,
+) -> Result<()> {
+    if wrapped {
+        let wsol_account = anchor_spl::token::TokenAccount::try_deserialize(
+            &mut &recipient.try_borrow_data()?[..],
+        )?;
+        require!(
+            wsol_account.mint == anchor_spl::token::spl_token::native_mint::ID
+                && wsol_account.owner == bidder,
+            AuctionError::InvalidPreviousBidder
+        );
+    } else {
+        require_keys_eq!(recipient.key(), bidder, AuctionError::InvalidPreviousBidder);
+    }
+
+    pay_from_native_escrow(system_program, escrow, escrow_bump, auction, recipient, amount)?;
+
+    if wrapped {
+        let token_program = token_program.ok_or(AuctionError::MissingTokenProgram)?;
+        let sync_ctx = CpiContext::new(
+            token_program.to_account_info(),
+            anchor_spl::token::SyncNative {
+                account: recipient.clone(),
+            },
+        );
+        anchor_spl::token::sync_native(sync_ctx)?;
+    }
+
+    Ok(())
 }
 
+/// Sends `amount` lamports out of the auction's SOL escrow PDA. The escrow
+/// must either stay rent-exempt or be emptied completely.
+pub fn pay_from_native_escrow<'info>(
+    system_program: &Program<'info, System>,
+    escrow: &SystemAccount<'info>,
+    escrow_bump: u8,
+    auction: Pubkey,
+    to: &AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    let remaining = escrow
+        .lamports()
+        .checked_sub(amount)
+        .ok_or(AuctionError::MathOverflow)?;
+    require!(
+        remaining == 0 || remaining >= Rent::get()?.minimum_balance(0),
+        AuctionError::EscrowBelowRent
+    );
 
-#[derive(Accounts)]
-pub struct UpdateAuctionSettings<'info> {
-    #[account(mut)]
-    pub auction: Account<'info, Auction>,
-    pub creator: Signer<'info>,
+    let signer_seeds: &[&[&[u8]]] = &[&[ESCROW_SEED, auction.as_ref(), &[escrow_bump]]];
+    let transfer_ctx = CpiContext::new_with_signer(
+        system_program.to_account_info(),
+        anchor_lang::system_program::Transfer {
+            from: escrow.to_account_info(),
+            to: to.clone(),
+        },
+        signer_seeds,
+    );
+    anchor_lang::system_program::transfer(transfer_ctx, amount)
 }
 
-#[account]
-pub struct Auction {
-    pub creator: Pubkey,
-    pub nft_mint: Pubkey,
-    pub starting_bid: u64,
-    pub min_bid_increment: u64,
-    pub end_time: i64,
-    pub highest_bid: u64,
-    pub highest_bidder: Pubkey,
-    pub status: AuctionStatus,
+/// The wallet a bid is placed for: the session's owner when a session key
+/// signs, otherwise the signer.
+pub fn bidding_wallet<'info>(
+    bidder: &Signer<'info>,
+    session: &Option<Account<'info, SessionKey>>,
+) -> Pubkey {
+    session
+        .as_ref()
+        .map_or_else(|| bidder.key(), |session| session.owner)
 }
 
-impl Auction {
-    pub const LEN: usize = 8 + // discriminator
-        32 + // creator
-        32 + // nft_mint
-        8 + // starting_bid
-        8 + // min_bid_increment
-        8 + // end_time
-        8 + // highest_bid
-        32 + // highest_bidder
-        1 + // status
-        200; // padding for future extensions
+/// Settles a closing queue into its auction: the winning entry's lamports
+/// move to the native escrow and it becomes the standing bid, while the bid
+/// it displaces moves into the queue for its bidder to withdraw.
+pub fn resolve_closing_queue<'info>(
+    system_program: &Program<'info, System>,
+    escrow: &SystemAccount<'info>,
+    escrow_bump: u8,
+    auction: &mut Account<'info, Auction>,
+    queue: &mut Account<'info, ClosingQueue>,
+) -> Result<()> {
+    require!(!queue.resolved, AuctionError::ClosingQueuePending);
+    queue.resolved = true;
+    let Some(winner) = queue.take_winner() else {
+        return Ok(());
+    };
+
+    **queue.to_account_info().try_borrow_mut_lamports()? -= winner.amount;
+    **escrow.to_account_info().try_borrow_mut_lamports()? += winner.amount;
+    if auction.highest_bid > 0 {
+        pay_from_native_escrow(
+            system_program,
+            escrow,
+            escrow_bump,
+            auction.key(),
+            &queue.to_account_info(),
+            auction.highest_bid,
+        )?;
+        queue.entries.push(QueuedBid {
+            bidder: auction.highest_bidder,
+            amount: auction.highest_bid,
+            slot: 0,
+        });
+        auction.contested = true;
+    }
+
+    auction.highest_bid = winner.amount;
+    auction.highest_bidder = winner.bidder;
+    auction.highest_bid_wrapped = false;
+    auction.proxy_bid_active = false;
+
+    emit!(ClosingQueueResolved {
+        auction_id: auction.key(),
+        winner: winner.bidder,
+        winning_bid: winner.amount,
+    });
+    Ok(())
 }
 
+/// Moves `amount` of a bid deposit into the native escrow.
+pub fn spend_bid_deposit<'info>(
+    bid_deposit: &mut Account<'info, BidDeposit>,
+    escrow: &SystemAccount<'info>,
+    amount: u64,
+) -> Result<()> {
+    **bid_deposit.to_account_info().try_borrow_mut_lamports()? -= amount;
+    **escrow.to_account_info().try_borrow_mut_lamports()? += amount;
+    bid_deposit.balance -= amount;
+    Ok(())
+}
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq)]
-pub enum AuctionStatus {
-    Active,
-    Completed,
-    Cancelled,
+/// Moves `amount` of a session's funds into the native escrow.
+pub fn spend_session<'info>(
+    session: &mut Account<'info, SessionKey>,
+    escrow: &SystemAccount<'info>,
+    amount: u64,
+) -> Result<()> {
+    **session.to_account_info().try_borrow_mut_lamports()? -= amount;
+    **escrow.to_account_info().try_borrow_mut_lamports()? += amount;
+    session.spent += amount;
+    Ok(())
 }
 
-#[error_code]
-pub enum AuctionError {
-    #[msg("The auction duration must be greater than 0")]
-    InvalidDuration,
-    #[msg("The starting bid must be greater than 0")]
-    InvalidStartingBid,
-    #[msg("The minimum bid increment must be greater than 0")]
-    InvalidBidIncrement,
-    #[msg("The auction has already ended")]
-    AuctionEnded,
-    #[msg("The auction is not active")]
-    AuctionNotActive,
-    #[msg("The bid amount is too low")]
-    BidTooLow,
-    #[msg("The bid increment is too low")]
-    BidIncrementTooLow,
-    #[msg("The auction has not ended yet")]
-    AuctionNotEnded,
-    #[msg("The auction already has bids")]
-    AuctionHasBids,
-    #[msg("Unauthorized to cancel this auction")]
-    UnauthorizedCancellation,
-    #[msg("Unauthorized to update this auction")]
-    UnauthorizedUpdate,
-    #[msg("Invalid auction state transition")]
-    InvalidStateTransition,
+/// Rejects bids on auctions requiring registration from bidders who have
+/// not registered; the registration's seeds tie it to the bidder.
+pub fn require_registration(
+    auction: &Auction,
+    registration: &Option<Account<BidderRegistration>>,
+) -> Result<()> {
+    require!(
+        auction.registration_deposit == 0 || registration.is_some(),
+        AuctionError::MissingRegistration
+    );
+    Ok(())
 }
 
+/// Rejects bids on allowlisted auctions from wallets that have not proven
+/// their place with a first `place_bid`; the entry's seeds tie it to the
+/// bidder.
+pub fn require_allowlisted(
+    auction: &Auction,
+    allowlist_entry: &Option<Account<AllowlistEntry>>,
+) -> Result<()> {
+    require!(
+        !auction.has_allowlist() || allowlist_entry.is_some(),
+        AuctionError::MissingAllowlistEntry
+    );
+    Ok(())
+}
 
-#[event]
-pub struct AuctionCreated {
-    pub auction_id: Pubkey,
-    pub creator: Pubkey,
-    pub nft_mint: Pubkey,
-    pub starting_bid: u64,
-    pub end_time: i64,
+/// Checks `proof` places `wallet` under the allowlist `root`. Leaves are
+/// the hash of the wallet's key; each pair of nodes is hashed in sorted
+/// order.
+pub fn verify_allowlist_proof(proof: &[[u8; 32]], root: [u8; 32], wallet: &Pubkey) -> bool {
+    let mut node = hash(wallet.as_ref()).to_bytes();
+    for sibling in proof {
+        node = if node <= *sibling {
+            hashv(&[&node, sibling]).to_bytes()
+        } else {
+            hashv(&[sibling, &node]).to_bytes()
+        };
+    }
+    node == root
 }
 
-#[event]
-pub struct BidPlaced {
-    pub auction_id: Pubkey,
-    pub bidder: Pubkey,
-    pub bid_amount: u64,
+/// Whether `wallet` is on the admin's list of wallets linked to an
+/// auction's creator; a list that was never created links none.
+pub fn is_creator_linked(creator_links: &AccountInfo, wallet: &Pubkey) -> Result<bool> {
+    if creator_links.data_is_empty() {
+        return Ok(false);
+    }
+    let data = creator_links.try_borrow_data()?;
+    let links = CreatorLinks::try_deserialize(&mut &data[..])?;
+    Ok(links.linked.contains(wallet))
 }
 
-#[event]
-pub struct AuctionFinalized {
-    pub auction_id: Pubkey,
-    pub winner: Pubkey,
-    pub winning_bid: u64,
+/// Rejects bids from an auction's creator and from wallets linked to it.
+pub fn require_independent_bidder(
+    auction: &Auction,
+    creator_links: &AccountInfo,
+    bidder: &Pubkey,
+) -> Result<()> {
+    require_keys_neq!(*bidder, auction.creator, AuctionError::CreatorBid);
+    require!(
+        !is_creator_linked(creator_links, bidder)?,
+        AuctionError::LinkedWalletBid
+    );
+    Ok(())
 }
 
-#[event]
-pub struct AuctionCancelled {
-    pub auction_id: Pubkey,
-    pub reason: String,
+/// The config's holder discount, once `token_account` shows `bidder`
+/// holding at least the minimum balance of the holder mint.
+pub fn holder_discount(
+    config: &Config,
+    token_account: &InterfaceAccount<TokenAccount>,
+    bidder: Pubkey,
+) -> Result<u16> {
+    require!(
+        config.holder_discount_bps > 0,
+        AuctionError::NoHolderDiscount
+    );
+    require_keys_eq!(
+        token_account.mint,
+        config.holder_mint,
+        AuctionError::InvalidHolderAccount
+    );
+    require_keys_eq!(
+        token_account.owner,
+        bidder,
+        AuctionError::InvalidHolderAccount
+    );
+    require!(
+        token_account.amount >= config.holder_min_balance,
+        AuctionError::InsufficientHolderBalance
+    );
+    Ok(config.holder_discount_bps)
 }
 
-#[event]
-pub struct AuctionUpdated {
-    pub auction_id: Pubkey,
-    pub new_duration: Option<i64>,
-    pub new_min_increment: Option<u64>,
+/// Stamps a bid on the bidder's stats, enforcing the auction's per-wallet
+/// bid cooldown and bid cap. The stats are only required while either is
+/// set.
+pub fn track_bidder<'info>(
+    auction: &Account<'info, Auction>,
+    bidder_stats: Option<&mut Account<'info, BidderStats>>,
+    bidder: Pubkey,
+    bump: Option<u8>,
+    now: i64,
+) -> Result<()> {
+    let Some(stats) = bidder_stats else {
+        require!(
+            auction.bid_cooldown == 0 && auction.max_bids_per_wallet == 0,
+            AuctionError::MissingBidderStats
+        );
+        return Ok(());
+    };
+    stats.open(auction.key(), bidder, bump.unwrap_or_default());
+    require!(
+        stats.last_bid_at == 0 || now >= stats.last_bid_at + auction.bid_cooldown,
+        AuctionError::BidCooldown
+    );
+    require!(
+        auction.max_bids_per_wallet == 0 || stats.bid_count < auction.max_bids_per_wallet,
+        AuctionError::BidCapReached
+    );
+    stats.last_bid_at = now;
+    stats.bid_count += 1;
+    Ok(())
 }
 
+/// Moves `amount` of an outbid bidder's funds out of the native escrow into
+/// their `BidEscrow`, where they pull it out with `claim_refund`.
+pub fn credit_bid_escrow<'info>(
+    system_program: &Program<'info, System>,
+    escrow: &SystemAccount<'info>,
+    escrow_bump: u8,
+    auction: Pubkey,
+    bid_escrow: &mut Account<'info, BidEscrow>,
+    amount: u64,
+) -> Result<()> {
+    pay_from_native_escrow(
+        system_program,
+        escrow,
+        escrow_bump,
+        auction,
+        &bid_escrow.to_account_info(),
+        amount,
+    )?;
+    bid_escrow.refundable = bid_escrow
+        .refundable
+        .checked_add(amount)
+        .ok_or(AuctionError::MathOverflow)?;
 
-pub const AUCTION_SEED: &[u8] = b"auction";
-pub const ESCROW_SEED: &[u8] = b"escrow";
-pub const VAULT_SEED: &[u8] = b"vault";
+    emit!(BidRefundCredited {
+        auction_id: auction,
+        bidder: bid_escrow.bidder,
+        amount,
+    });
+    Ok(())
+}
 
+/// Answers a challenger bidding up to `challenger_max` on behalf of a
+/// proxy-backed leader: the standing bid goes one increment above the
+/// challenger, capped at the leader's maximum, and the raise moves from the
+/// proxy's reserve into the native escrow.
+pub fn raise_proxy_bid<'info>(
+    auction: &mut Auction,
+    proxy_bid: &mut Account<'info, ProxyBid>,
+    escrow: &SystemAccount<'info>,
+    challenger_max: u64,
+    tiers: Option<&IncrementTiers>,
+) -> Result<()> {
+    let leader_max = auction
+        .highest_bid
+        .checked_add(proxy_bid.reserve)
+        .ok_or(AuctionError::MathOverflow)?;
+    let price = challenger_max
+        .saturating_add(auction.min_increment(tiers))
+        .min(leader_max);
+    let raise = price - auction.highest_bid;
 
-impl<'info> InitializeAuction<'info> {
-    pub fn validate(&self) -> Result<()> {
-        Ok(())
+    **proxy_bid.to_account_info().try_borrow_mut_lamports()? -= raise;
+    **escrow.to_account_info().try_borrow_mut_lamports()? += raise;
+    proxy_bid.reserve -= raise;
+    auction.highest_bid = price;
+    Ok(())
+}
+
+/// Tops up or draws down a proxy's reserve to exactly `reserve` lamports,
+/// moving the difference between the bidder and the proxy account.
+pub fn set_proxy_reserve<'info>(
+    system_program: &Program<'info, System>,
+    bidder: &Signer<'info>,
+    proxy_bid: &mut Account<'info, ProxyBid>,
+    reserve: u64,
+) -> Result<()> {
+    if reserve > proxy_bid.reserve {
+        let transfer_ctx = CpiContext::new(
+            system_program.to_account_info(),
+            anchor_lang::system_program::Transfer {
+                from: bidder.to_account_info(),
+                to: proxy_bid.to_account_info(),
+            },
+        );
+        anchor_lang::system_program::transfer(transfer_ctx, reserve - proxy_bid.reserve)?;
+    } else {
+        let excess = proxy_bid.reserve - reserve;
+        **proxy_bid.to_account_info().try_borrow_mut_lamports()? -= excess;
+        **bidder.to_account_info().try_borrow_mut_lamports()? += excess;
     }
+    proxy_bid.reserve = reserve;
+    Ok(())
 }
 
-impl<'info> PlaceBid<'info> {
-    pub fn validate(&self) -> Result<()> {
-        Ok(())
+/// Whether an escrowless listing can still be settled: the seller's token
+/// account exists, is owned by the creator, holds the NFT, is not frozen and
+/// still delegates it to the auction authority.
+pub fn listing_intact(
+    seller_token_account: &AccountInfo,
+    auction: &Auction,
+    auction_authority: &Pubkey,
+) -> bool {
+    if *seller_token_account.owner != anchor_spl::token::ID {
+        return false;
     }
+    let Ok(data) = seller_token_account.try_borrow_data() else {
+        return false;
+    };
+    let Ok(account) = anchor_spl::token::TokenAccount::try_deserialize(&mut &data[..])
+    else {
+        return false;
+    };
+    account.mint == auction.nft_mint
+        && account.owner == auction.creator
+        && account.amount >= 1
+        && !account.is_frozen()
+        && account.delegate == Some(*auction_authority).into()
+        && account.delegated_amount >= 1
 }
 
-impl<'info> FinalizeAuction<'info> {
-    pub fn validate(&self) -> Result<()> {
-        Ok(())
+/// Tops the SOL escrow up to the rent-exempt minimum of a data-less account
+/// and records what `payer` put in as the auction's escrow reserve. Bids sit
+/// on top of the reserve, so deposits below rent are accepted and refunds
+/// never leave the escrow rent-paying.
+pub fn fund_escrow_reserve<'info>(
+    system_program: &Program<'info, System>,
+    payer: &AccountInfo<'info>,
+    escrow: &SystemAccount<'info>,
+    auction: &mut Auction,
+) -> Result<()> {
+    let shortfall = Rent::get()?
+        .minimum_balance(0)
+        .saturating_sub(escrow.lamports());
+    if shortfall > 0 {
+        let transfer_ctx = CpiContext::new(
+            system_program.to_account_info(),
+            anchor_lang::system_program::Transfer {
+                from: payer.clone(),
+                to: escrow.to_account_info(),
+            },
+        );
+        anchor_lang::system_program::transfer(transfer_ctx, shortfall)?;
     }
+    auction.escrow_reserve = shortfall;
+    Ok(())
 }
 
-impl<'info> WithdrawUnsoldNFT<'info> {
-    pub fn validate(&self) -> Result<()> {
-        Ok(())
+/// Moves `auction` to `status`, failing with `InvalidStateTransition` when
+/// the lifecycle does not allow it, and logs the change.
+pub fn set_auction_status(auction: &mut Account<Auction>, status: AuctionStatus) -> Result<()> {
+    require!(
+        auction.status.can_become(status),
+        AuctionError::InvalidStateTransition
+    );
+    emit!(StatusChanged {
+        auction_id: auction.key(),
+        from: auction.status,
+        to: status,
+    });
+    auction.status = status;
+    Ok(())
+}
+
+/// Checks that an auction nobody finalized is past `DEADMAN_WINDOW` and can
+/// be recovered, and marks it `Abandoned` on the first recovery.
+pub fn begin_recovery(auction: &mut Account<Auction>, clock: &Clock) -> Result<()> {
+    if auction.status == AuctionStatus::Abandoned {
+        return Ok(());
     }
+    require!(auction.status.can_settle(), AuctionError::AuctionNotActive);
+    require!(
+        auction.has_ended(clock)
+            && clock.unix_timestamp >= auction.end_time.saturating_add(DEADMAN_WINDOW),
+        AuctionError::RecoveryWindowNotReached
+    );
+    require!(auction.is_native(), AuctionError::InvalidBidCurrency);
+    require!(
+        auction.asset_kind == AssetKind::Spl
+            && !auction.partial_fills
+            && auction.deposit_mode == DepositMode::Off,
+        AuctionError::InvalidAsset
+    );
+    require!(!auction.has_pending_bid(), AuctionError::BidPending);
+    set_auction_status(auction, AuctionStatus::Abandoned)
 }
 
-impl<'info> CancelAuction<'info> {
-    pub fn validate(&self) -> Result<()> {
-        Ok(())
+/// Cancels an abandoned auction once both the bid and the NFT are back with
+/// their owners.
+pub fn finish_recovery(auction: &mut Account<Auction>) -> Result<()> {
+    let bid_outstanding = auction.highest_bid > 0 && !auction.bid_recovered;
+    if !bid_outstanding && auction.item_claimed {
+        set_auction_status(auction, AuctionStatus::Cancelled)?;
     }
+    Ok(())
 }
 
-impl<'info> UpdateAuctionSettings<'info> {
-    pub fn validate(&self) -> Result<()> {
-        Ok(())
+/// Whether the wallet's blocklist PDA exists.
+pub fn is_blocked(blocklist_entry: &UncheckedAccount) -> bool {
+    !blocklist_entry.data_is_empty()
+}
+
+/// Fails when the wallet's blocklist PDA exists.
+pub fn check_not_blocked(blocklist_entry: &UncheckedAccount) -> Result<()> {
+    require!(
+        !is_blocked(blocklist_entry),
+        AuctionError::WalletOnBlocklist
+    );
+    Ok(())
+}
+
+/// Fails while the mint's cooldown PDA, if it exists, holds a relist time
+/// still ahead of `now`.
+pub fn check_relist_cooldown(mint_cooldown: &UncheckedAccount, now: i64) -> Result<()> {
+    if mint_cooldown.data_is_empty() {
+        return Ok(());
+    }
+    let cooldown = MintCooldown::try_deserialize(&mut &mint_cooldown.try_borrow_data()?[..])?;
+    require!(
+        now >= cooldown.relist_after,
+        AuctionError::RelistCooldownActive
+    );
+    Ok(())
+}
+
+/// Newest `(slot, hash)` entry of the SlotHashes sysvar, read straight from
+/// its account data since the sysvar is too large to deserialize whole.
+pub fn latest_slot_hash(slot_hashes: &AccountInfo) -> Result<(u64, [u8; 32])> {
+    let data = slot_hashes.try_borrow_data()?;
+    require!(
+        data.len() >= 48 && data[..8] != [0; 8],
+        AuctionError::SlotHashesUnavailable
+    );
+    let slot = u64::from_le_bytes(data[8..16].try_into().unwrap());
+    let mut hash = [0; 32];
+    hash.copy_from_slice(&data[16..48]);
+    Ok((slot, hash))
+}
+
+/// Empties the SOL escrow once every bid has been paid out or refunded,
+/// returning the reserve and any stray lamports to `to`. Returns the amount
+/// released.
+pub fn release_escrow_reserve<'info>(
+    system_program: &Program<'info, System>,
+    escrow: &SystemAccount<'info>,
+    escrow_bump: u8,
+    auction_key: Pubkey,
+    auction: &mut Auction,
+    to: &AccountInfo<'info>,
+) -> Result<u64> {
+    let amount = escrow.lamports();
+    if amount > 0 {
+        pay_from_native_escrow(system_program, escrow, escrow_bump, auction_key, to, amount)?;
+    }
+    auction.escrow_reserve = 0;
+    Ok(amount)
+}
+
+/// Credits a keeper with a bounded rebate from the pool's unallocated funds
+/// for one successful settlement. Returns the amount credited.
+pub fn credit_keeper_rebate(
+    pool: &mut RebatePool,
+    keeper: &mut Keeper,
+    auction: Pubkey,
+) -> Result<u64> {
+    let amount = pool.max_rebate.min(pool.available);
+    pool.available -= amount;
+    keeper.accrued = keeper
+        .accrued
+        .checked_add(amount)
+        .ok_or(AuctionError::MathOverflow)?;
+    keeper.settlements += 1;
+
+    emit!(KeeperRebateAccrued {
+        keeper: keeper.keeper,
+        auction_id: auction,
+        amount,
+    });
+
+    Ok(amount)
+}
+
+/// Credits a keeper with `SWEEP_BOUNTY` for each of `swept` refunds, bounded
+/// like a settlement rebate by the pool's `max_rebate` and unallocated funds.
+/// Returns the amount credited.
+pub fn credit_sweep_bounty(
+    pool: &mut RebatePool,
+    keeper: &mut Keeper,
+    auction: Pubkey,
+    swept: u64,
+) -> Result<u64> {
+    let amount = SWEEP_BOUNTY
+        .saturating_mul(swept)
+        .min(pool.max_rebate)
+        .min(pool.available);
+    pool.available -= amount;
+    keeper.accrued = keeper
+        .accrued
+        .checked_add(amount)
+        .ok_or(AuctionError::MathOverflow)?;
+
+    emit!(KeeperRebateAccrued {
+        keeper: keeper.keeper,
+        auction_id: auction,
+        amount,
+    });
+
+    Ok(amount)
+}
+
+/// Deserializes `T` from the front of `accounts`, runs `handler` with the
+/// rest as its remaining accounts, and persists `T` afterwards, mirroring
+/// what Anchor's generated entrypoint does for a top-level instruction.
+pub fn dispatch_route<'info, T, R>(
+    program_id: &Pubkey,
+    accounts: &'info [AccountInfo<'info>],
+    handler: impl FnOnce(Context<'_, '_, 'info, 'info, T>) -> Result<R>,
+) -> Result<R>
+where
+    T: Accounts<'info, T::Bumps> + anchor_lang::Bumps + AccountsExit<'info>,
+    T::Bumps: Default,
+{
+    let mut remaining = accounts;
+    let mut bumps = T::Bumps::default();
+    let mut reallocs = std::collections::BTreeSet::new();
+    let mut route_accounts =
+        T::try_accounts(program_id, &mut remaining, &[], &mut bumps, &mut reallocs)?;
+    let result = handler(Context::new(program_id, &mut route_accounts, remaining, bumps))?;
+    route_accounts.exit(program_id)?;
+    Ok(result)
+}
+
+/// Pays each verified creator with a non-zero share its cut of the
+/// royalty on `proceeds`, the sale price net of the platform fee, out of the
+/// native escrow. Royalty terms above 100% are capped, so the payments never
+/// exceed `proceeds`. Creator wallets are read in metadata order from the
+/// front of `remaining_accounts`; the unconsumed tail is returned for the
+/// NFT transfer.
+#[allow(clippy::too_many_arguments)]
+pub fn pay_royalties<'a, 'info>(
+    system_program: &Program<'info, System>,
+    escrow: &SystemAccount<'info>,
+    escrow_bump: u8,
+    auction: Pubkey,
+    proceeds: u64,
+    royalties: &token_metadata::Royalties,
+    remaining_accounts: &'a [AccountInfo<'info>],
+) -> Result<(Vec<CreatorRoyalty>, &'a [AccountInfo<'info>])> {
+    let bps = royalties.seller_fee_basis_points.min(BPS_DENOMINATOR);
+    let (royalty, _) = split_proceeds(proceeds, bps);
+    let mut unpaid = royalty;
+    let mut payments = Vec::new();
+    let mut wallets = remaining_accounts.iter();
+    for creator in royalties
+        .creators
+        .iter()
+        .filter(|c| c.verified && c.share > 0)
+    {
+        let wallet = wallets.next().ok_or(AuctionError::InvalidCreatorAccount)?;
+        require_keys_eq!(
+            wallet.key(),
+            creator.address,
+            AuctionError::InvalidCreatorAccount
+        );
+        let amount = (((royalty as u128 * creator.share as u128) / 100) as u64).min(unpaid);
+        unpaid -= amount;
+        pay_from_native_escrow(system_program, escrow, escrow_bump, auction, wallet, amount)?;
+        payments.push(CreatorRoyalty {
+            creator: creator.address,
+            amount,
+        });
+    }
+    let rest = &remaining_accounts[payments.len()..];
+    Ok((payments, rest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use spl_token_2022::extension::{
+        transfer_fee::TransferFee, BaseStateWithExtensionsMut, ExtensionType,
+        StateWithExtensionsMut,
+    };
+
+    fn blank_auction() -> Auction {
+        let data = vec![0u8; Auction::LEN];
+        Auction::try_deserialize_unchecked(&mut &data[..]).unwrap()
+    }
+
+    #[test]
+    fn legacy_auction_pays_default_fee() {
+        let mut auction = blank_auction();
+        assert_eq!(auction.platform_fee_bps(), DEFAULT_FEE_BPS);
+        let (fee, seller) = split_proceeds(1_000_000, auction.platform_fee_bps());
+        assert_eq!((fee, seller), (25_000, 975_000));
+
+        auction.version = AUCTION_VERSION;
+        assert_eq!(auction.platform_fee_bps(), 0);
+        auction.fee_bps = 100;
+        assert_eq!(auction.platform_fee_bps(), 100);
+    }
+
+    #[test]
+    fn migration_backfills_legacy_fields() {
+        let mut auction = blank_auction();
+        auction.backfill(0);
+        auction.version = AUCTION_VERSION;
+        assert_eq!(auction.fee_bps, DEFAULT_FEE_BPS);
+        assert_eq!(auction.platform_fee_bps(), DEFAULT_FEE_BPS);
+        assert_eq!(auction.quantity, 1);
+
+        let mut auction = blank_auction();
+        auction.version = 1;
+        auction.backfill(1);
+        assert_eq!(auction.fee_bps, 0);
+        assert_eq!(auction.quantity, 0);
+    }
+
+    #[test]
+    fn allowlist_proof_matches_sorted_pairs() {
+        let a = Pubkey::new_unique();
+        let b = Pubkey::new_unique();
+        let c = Pubkey::new_unique();
+        let leaf_a = hash(a.as_ref()).to_bytes();
+        let leaf_b = hash(b.as_ref()).to_bytes();
+        let root = if leaf_a <= leaf_b {
+            hashv(&[&leaf_a, &leaf_b]).to_bytes()
+        } else {
+            hashv(&[&leaf_b, &leaf_a]).to_bytes()
+        };
+        assert!(verify_allowlist_proof(&[leaf_b], root, &a));
+        assert!(verify_allowlist_proof(&[leaf_a], root, &b));
+        assert!(!verify_allowlist_proof(&[leaf_b], root, &c));
+        assert!(!verify_allowlist_proof(&[], root, &a));
+    }
+
+    fn transfer_fee_mint(bps: u16, maximum_fee: u64) -> Vec<u8> {
+        let len = ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(&[
+            ExtensionType::TransferFeeConfig,
+        ])
+        .unwrap();
+        let mut data = vec![0u8; len];
+        let mut state =
+            StateWithExtensionsMut::<spl_token_2022::state::Mint>::unpack_uninitialized(&mut data)
+                .unwrap();
+        let config = state.init_extension::<TransferFeeConfig>(true).unwrap();
+        let fee = TransferFee {
+            epoch: 0.into(),
+            maximum_fee: maximum_fee.into(),
+            transfer_fee_basis_points: bps.into(),
+        };
+        config.older_transfer_fee = fee;
+        config.newer_transfer_fee = fee;
+        state.base.decimals = 6;
+        state.base.is_initialized = true;
+        state.pack_base();
+        state.init_account_type().unwrap();
+        data
+    }
+
+    /// Runs `f` on a read-only account holding `data`, owned by `owner`.
+    fn with_account<R>(owner: &Pubkey, data: &mut [u8], f: impl FnOnce(&AccountInfo) -> R) -> R {
+        let key = Pubkey::new_unique();
+        let mut lamports = 0;
+        f(&AccountInfo::new(
+            &key,
+            false,
+            false,
+            &mut lamports,
+            data,
+            owner,
+            false,
+            0,
+        ))
     }
-}
\ No newline at end of file
+
+    fn fee_after(owner: &Pubkey, mut data: Vec<u8>, amount: u64) -> u64 {
+        with_account(owner, &mut data, |mint| {
+            amount_after_transfer_fee(mint, amount, 0).unwrap()
+        })
+    }
+
+    #[test]
+    fn token_bids_are_credited_net_of_transfer_fees() {
+        let data = transfer_fee_mint(100, u64::MAX);
+        assert_eq!(fee_after(&spl_token_2022::ID, data, 1_000_000), 990_000);
+        let capped = transfer_fee_mint(100, 5_000);
+        assert_eq!(fee_after(&spl_token_2022::ID, capped, 1_000_000), 995_000);
+        // Legacy SPL mints never charge a fee
+        let legacy = vec![0u8; anchor_spl::token::Mint::LEN];
+        assert_eq!(
+            fee_after(&anchor_spl::token::ID, legacy, 1_000_000),
+            1_000_000
+        );
+    }
+
+    #[test]
+    fn proceeds_split_rounds_the_fee_down() {
+        assert_eq!(split_proceeds(1_000_000, 250), (25_000, 975_000));
+        assert_eq!(split_proceeds(39, 250), (0, 39));
+        assert_eq!(split_proceeds(u64::MAX, BPS_DENOMINATOR), (u64::MAX, 0));
+        assert_eq!(split_proceeds(1_000, 0), (0, 1_000));
+    }
+
+    fn price_update(verified: bool, price: i64, exponent: i32, publish_time: i64) -> Vec<u8> {
+        let mut data = vec![34, 241, 35, 99, 157, 126, 244, 205];
+        data.extend_from_slice(&[0; 32]);
+        data.push(verified as u8);
+        data.extend_from_slice(&[7; 32]);
+        data.extend_from_slice(&price.to_le_bytes());
+        data.extend_from_slice(&1_000u64.to_le_bytes());
+        data.extend_from_slice(&exponent.to_le_bytes());
+        data.extend_from_slice(&publish_time.to_le_bytes());
+        data
+    }
+
+    #[test]
+    fn pyth_prices_load_only_when_verified_and_fresh() {
+        let owner = pyth::PYTH_RECEIVER_PROGRAM_ID;
+        let mut data = price_update(true, 15_012_345_678, -8, 1_000);
+        let price = with_account(&owner, &mut data, |account| {
+            pyth::load_price(account, 1_030, 60).unwrap()
+        });
+        assert_eq!(price.feed_id, [7; 32]);
+        assert_eq!((price.price, price.exponent), (15_012_345_678, -8));
+
+        let stale = with_account(&owner, &mut data, |account| {
+            pyth::load_price(account, 1_061, 60).unwrap_err()
+        });
+        assert_eq!(stale, AuctionError::StalePrice.into());
+
+        let mut partial = price_update(false, 15_012_345_678, -8, 1_000);
+        let err = with_account(&owner, &mut partial, |account| {
+            pyth::load_price(account, 1_000, 60).unwrap_err()
+        });
+        assert_eq!(err, AuctionError::InvalidPriceFeed.into());
+
+        let err = with_account(&Pubkey::new_unique(), &mut data, |account| {
+            pyth::load_price(account, 1_000, 60).unwrap_err()
+        });
+        assert_eq!(err, AuctionError::InvalidPriceFeed.into());
+    }
+
+    #[test]
+    fn bids_normalize_through_the_feed_price() {
+        let sol_usd = pyth::PythPrice {
+            feed_id: [0; 32],
+            price: 15_012_345_678,
+            conf: 0,
+            exponent: -8,
+            publish_time: 0,
+        };
+        // 1 SOL at $150.12345678, in micro-dollars
+        assert_eq!(
+            pyth::normalize(1_000_000_000, 9, &sol_usd, 6).unwrap(),
+            150_123_456
+        );
+        let usd = pyth::PythPrice {
+            price: 1,
+            exponent: 0,
+            ..sol_usd
+        };
+        assert_eq!(pyth::normalize(5, 0, &usd, 6).unwrap(), 5_000_000);
+        assert!(pyth::normalize(u64::MAX, 0, &usd, 18).is_err());
+    }
+
+    #[test]
+    fn compressed_leaves_address_bubblegum_assets() {
+        let tree = Pubkey::new_unique();
+        let (expected, _) = Pubkey::find_program_address(
+            &[b"asset", tree.as_ref(), &7u64.to_le_bytes()],
+            &bubblegum::BUBBLEGUM_PROGRAM_ID,
+        );
+        assert_eq!(bubblegum::asset_id(&tree, 7), expected);
+        assert_ne!(bubblegum::asset_id(&tree, 8), expected);
+
+        let leaf = bubblegum::LeafArgs {
+            data_hash: [1; 32],
+            creator_hash: [2; 32],
+            nonce: 7,
+            index: 7,
+        };
+        let bytes = leaf.try_to_vec().unwrap();
+        assert_eq!(bytes.len(), bubblegum::LeafArgs::LEN);
+        assert_eq!(bubblegum::LeafArgs::try_from_slice(&bytes).unwrap(), leaf);
+    }
+
+    #[test]
+    fn opened_auctions_start_live_at_the_default_fee() {
+        let params = AuctionParams {
+            starting_bid: 100,
+            min_bid_increment: 10,
+            duration: 60,
+            buy_now_price: Some(500),
+        };
+        params.check().unwrap();
+        let mut auction = blank_auction();
+        let creator = Pubkey::new_unique();
+        auction.open(creator, Pubkey::new_unique(), &params, 1_000);
+        assert_eq!(auction.creator, creator);
+        assert_eq!(auction.end_time, 1_060);
+        assert_eq!(auction.buy_now_price, 500);
+        assert_eq!(auction.status, AuctionStatus::Live);
+        assert_eq!(auction.platform_fee_bps(), DEFAULT_FEE_BPS);
+        assert_eq!(auction.version, AUCTION_VERSION);
+
+        let cheap_buy_now = AuctionParams {
+            buy_now_price: Some(100),
+            ..params
+        };
+        assert_eq!(
+            cheap_buy_now.check().unwrap_err(),
+            AuctionError::InvalidBuyNowPrice.into()
+        );
+    }
+
+    #[test]
+    fn pnft_transfers_encode_token_metadata_transfer_v1() {
+        let program = token_metadata::TOKEN_METADATA_PROGRAM_ID;
+        let mut keys: Vec<Pubkey> = (0..17).map(|_| Pubkey::new_unique()).collect();
+        // No owner token record, destination token record or rule set
+        for absent in [7, 8, 15, 16] {
+            keys[absent] = program;
+        }
+        let ix = token_metadata::transfer_v1_instruction(&keys, 1);
+        assert_eq!(ix.program_id, program);
+        assert_eq!(ix.data, [49, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0]);
+
+        let writable: Vec<usize> = (0..17).filter(|&i| ix.accounts[i].is_writable).collect();
+        assert_eq!(writable, [0, 2, 5, 10]);
+        let signers: Vec<usize> = (0..17).filter(|&i| ix.accounts[i].is_signer).collect();
+        assert_eq!(signers, [9, 10]);
+        assert!(ix
+            .accounts
+            .iter()
+            .zip(&keys)
+            .all(|(meta, key)| meta.pubkey == *key));
+    }
+
+    #[test]
+    fn core_assets_report_their_owner() {
+        let owner = Pubkey::new_unique();
+        let mut asset = vec![1];
+        asset.extend_from_slice(owner.as_ref());
+        asset.extend_from_slice(&[0; 40]);
+        let core = mpl_core::MPL_CORE_PROGRAM_ID;
+        let read = with_account(&core, &mut asset, mpl_core::owner);
+        assert_eq!(read.unwrap(), owner);
+
+        // Collections and other Core accounts are not assets
+        asset[0] = 5;
+        let err = with_account(&core, &mut asset, mpl_core::owner);
+        assert_eq!(err.unwrap_err(), AuctionError::InvalidAsset.into());
+
+        asset[0] = 1;
+        let err = with_account(&Pubkey::new_unique(), &mut asset, mpl_core::owner);
+        assert_eq!(err.unwrap_err(), AuctionError::InvalidAsset.into());
+        let err = with_account(&core, &mut asset[..20], mpl_core::owner);
+        assert_eq!(err.unwrap_err(), AuctionError::InvalidAsset.into());
+    }
+
+    #[test]
+    fn partial_fills_clear_by_unit_price_then_arrival() {
+        let entry = |unit_price, quantity| PartialFillEntry {
+            bidder: Pubkey::new_unique(),
+            unit_price,
+            quantity,
+            filled: 0,
+            claimed: false,
+        };
+        let mut book = PartialFillBook {
+            auction: Pubkey::new_unique(),
+            entries: vec![entry(10, 5), entry(20, 3), entry(10, 4)],
+            bump: 0,
+        };
+        assert_eq!(book.entries[0].escrowed().unwrap(), 50);
+
+        assert_eq!(book.fill(6), 0);
+        let filled: Vec<u64> = book.entries.iter().map(|e| e.filled).collect();
+        assert_eq!(filled, [3, 3, 0]);
+        assert_eq!(book.proceeds().unwrap(), 90);
+
+        // Refilling with more supply than demand leaves the rest unsold
+        assert_eq!(book.fill(20), 8);
+        assert_eq!(book.proceeds().unwrap(), 150);
+    }
+
+    #[test]
+    fn lst_payouts_only_accept_initialized_stake_pools() {
+        let program = stake_pool::STAKE_POOL_PROGRAM_ID;
+        let mut pool = vec![1; 64];
+        with_account(&program, &mut pool, stake_pool::check_pool).unwrap();
+
+        // Validator lists and uninitialized accounts are not pools
+        let mut validator_list = vec![2; 64];
+        let err = with_account(&program, &mut validator_list, stake_pool::check_pool);
+        assert_eq!(err.unwrap_err(), AuctionError::InvalidStakePool.into());
+        let err = with_account(&program, &mut [], stake_pool::check_pool);
+        assert_eq!(err.unwrap_err(), AuctionError::InvalidStakePool.into());
+        let err = with_account(&Pubkey::new_unique(), &mut pool, stake_pool::check_pool);
+        assert_eq!(err.unwrap_err(), AuctionError::InvalidStakePool.into());
+    }
+
+    fn name_record(parent: Pubkey, owner: Pubkey, class: Pubkey) -> Vec<u8> {
+        let mut data = [parent.to_bytes(), owner.to_bytes(), class.to_bytes()].concat();
+        data.extend_from_slice(&[0; 32]);
+        data
+    }
+
+    #[test]
+    fn sol_domains_report_their_owner() {
+        let program = sns::NAME_SERVICE_PROGRAM_ID;
+        let owner = Pubkey::new_unique();
+        let mut domain = name_record(sns::SOL_TLD, owner, Pubkey::default());
+        let read = with_account(&program, &mut domain, sns::domain_owner);
+        assert_eq!(read.unwrap(), owner);
+
+        // Subdomains, classed records and foreign accounts are refused
+        let mut subdomain = name_record(Pubkey::new_unique(), owner, Pubkey::default());
+        let err = with_account(&program, &mut subdomain, sns::domain_owner);
+        assert_eq!(err.unwrap_err(), AuctionError::InvalidAsset.into());
+        let mut classed = name_record(sns::SOL_TLD, owner, Pubkey::new_unique());
+        let err = with_account(&program, &mut classed, sns::domain_owner);
+        assert_eq!(err.unwrap_err(), AuctionError::InvalidAsset.into());
+        let err = with_account(&Pubkey::new_unique(), &mut domain, sns::domain_owner);
+        assert_eq!(err.unwrap_err(), AuctionError::InvalidAsset.into());
+        let err = with_account(&program, &mut domain[..64], sns::domain_owner);
+        assert_eq!(err.unwrap_err(), AuctionError::InvalidAsset.into());
+    }
+
+    #[test]
+    fn top_bids_keep_each_bidders_best_in_rank_order() {
+        let mut top = TopBids {
+            auction: Pubkey::new_unique(),
+            entries: vec![],
+            bump: 0,
+        };
+        let (first, second) = (Pubkey::new_unique(), Pubkey::new_unique());
+        top.insert(first, 100, 1);
+        top.insert(second, 100, 2);
+        top.insert(first, 90, 3);
+        let ranked: Vec<(Pubkey, u64)> = top.entries.iter().map(|e| (e.bidder, e.amount)).collect();
+        assert_eq!(ranked, [(first, 100), (second, 100)]);
+
+        // Raising a bid moves the bidder's single entry up
+        top.insert(second, 120, 4);
+        assert_eq!(top.entries[0].bidder, second);
+        assert_eq!(top.entries.len(), 2);
+
+        // Only the top MAX_TOP_BIDS survive, and low bids do not displace them
+        for amount in 200..200 + MAX_TOP_BIDS as u64 {
+            top.insert(Pubkey::new_unique(), amount, 5);
+        }
+        top.insert(Pubkey::new_unique(), 1, 6);
+        assert_eq!(top.entries.len(), MAX_TOP_BIDS);
+        assert!(top.entries.windows(2).all(|w| w[0].amount >= w[1].amount));
+        assert!(top.entries.iter().all(|e| e.amount >= 200));
+    }
+
+    #[test]
+    fn increments_follow_the_tier_below_the_standing_bid() {
+        let tier = |below, increment| IncrementTier { below, increment };
+        let tiers = IncrementTiers {
+            auction: Pubkey::new_unique(),
+            tiers: vec![tier(1_000, 10), tier(10_000, 100)],
+            bump: 0,
+        };
+        assert_eq!(tiers.increment_for(0), 10);
+        assert_eq!(tiers.increment_for(999), 10);
+        assert_eq!(tiers.increment_for(1_000), 100);
+        // Past every threshold the last tier still applies
+        assert_eq!(tiers.increment_for(u64::MAX), 100);
+
+        let empty = IncrementTiers {
+            tiers: vec![],
+            ..tiers
+        };
+        assert_eq!(empty.increment_for(500), 0);
+    }
+
+    #[test]
+    fn closing_queue_charges_raises_and_picks_a_deterministic_winner() {
+        let mut queue = ClosingQueue {
+            auction: Pubkey::new_unique(),
+            entries: vec![],
+            resolved: false,
+            bump: 0,
+        };
+        let (a, b) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (low, high) = (a.min(b), a.max(b));
+        let late = Pubkey::new_unique();
+
+        assert_eq!(queue.enqueue(high, 100, 5).unwrap(), 100);
+        assert_eq!(queue.enqueue(late, 200, 9).unwrap(), 200);
+        // A raise is charged only the difference, and must be a raise
+        assert_eq!(queue.enqueue(high, 200, 7).unwrap(), 100);
+        let err = queue.enqueue(high, 200, 8).unwrap_err();
+        assert_eq!(err, AuctionError::BidTooLow.into());
+        assert_eq!(queue.enqueue(low, 200, 7).unwrap(), 200);
+
+        // Equal amounts go to the earliest slot, then the lowest key
+        assert_eq!(queue.take_winner().unwrap().bidder, low);
+        assert_eq!(queue.take_winner().unwrap().bidder, high);
+        assert_eq!(queue.take_winner().unwrap().bidder, late);
+        assert!(queue.take_winner().is_none());
+
+        for _ in 0..MAX_QUEUED_BIDS {
+            queue.enqueue(Pubkey::new_unique(), 1, 1).unwrap();
+        }
+        let err = queue.enqueue(Pubkey::new_unique(), 1, 1).unwrap_err();
+        assert_eq!(err, AuctionError::ClosingQueueFull.into());
+    }
+}
@@ -0,0 +1,79 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        program::invoke_signed,
+    },
+};
+
+use crate::AuctionError;
+
+pub const MPL_CORE_PROGRAM_ID: Pubkey = pubkey!("CoREENxT6tW1HoK8ypY1SxRMZTcVPm7R94rH4PZNhX7d");
+
+/// `Key::AssetV1`, the first byte of every Core asset account.
+const ASSET_V1_KEY: u8 = 1;
+/// Core instruction index of `TransferV1`.
+const TRANSFER_V1_INSTRUCTION: u8 = 14;
+
+/// Accounts Core's `TransferV1` needs. The collection is required when the
+/// asset belongs to one.
+pub struct TransferAccounts<'a, 'info> {
+    pub core_program: &'a AccountInfo<'info>,
+    pub asset: &'a AccountInfo<'info>,
+    pub collection: Option<&'a AccountInfo<'info>>,
+    pub payer: &'a AccountInfo<'info>,
+    pub authority: &'a AccountInfo<'info>,
+    pub new_owner: &'a AccountInfo<'info>,
+    pub system_program: &'a AccountInfo<'info>,
+}
+
+/// Reads the current owner of a Core asset, rejecting accounts that are not
+/// `AssetV1` accounts owned by the Core program.
+pub fn owner(asset: &AccountInfo) -> Result<Pubkey> {
+    require_keys_eq!(*asset.owner, MPL_CORE_PROGRAM_ID, AuctionError::InvalidAsset);
+    let data = asset.try_borrow_data()?;
+    require!(
+        data.len() >= 33 && data[0] == ASSET_V1_KEY,
+        AuctionError::InvalidAsset
+    );
+    Ok(Pubkey::try_from(&data[1..33]).unwrap())
+}
+
+/// CPIs Core `TransferV1` to hand the asset to `new_owner`. `authority` is
+/// the current owner and signs directly, or through `signer_seeds` when it is
+/// a PDA. An absent collection is passed as the Core program id, which is how
+/// the program encodes `None`.
+pub fn transfer<'info>(accounts: TransferAccounts<'_, 'info>, signer_seeds: &[&[&[u8]]]) -> Result<()> {
+    let program = accounts.core_program;
+    let collection = accounts.collection.unwrap_or(program);
+
+    let metas = vec![
+        AccountMeta::new(accounts.asset.key(), false),
+        AccountMeta::new_readonly(collection.key(), false),
+        AccountMeta::new(accounts.payer.key(), true),
+        AccountMeta::new_readonly(accounts.authority.key(), true),
+        AccountMeta::new_readonly(accounts.new_owner.key(), false),
+        AccountMeta::new_readonly(accounts.system_program.key(), false),
+        // log_wrapper: None
+        AccountMeta::new_readonly(program.key(), false),
+    ];
+    let infos = [
+        accounts.asset.clone(),
+        collection.clone(),
+        accounts.payer.clone(),
+        accounts.authority.clone(),
+        accounts.new_owner.clone(),
+        accounts.system_program.clone(),
+        program.clone(),
+    ];
+
+    // TransferV1Args { compression_proof: None }
+    let data = vec![TRANSFER_V1_INSTRUCTION, 0];
+
+    let ix = Instruction {
+        program_id: MPL_CORE_PROGRAM_ID,
+        accounts: metas,
+        data,
+    };
+    invoke_signed(&ix, &infos, signer_seeds).map_err(Into::into)
+}
@@ -0,0 +1,90 @@
+use anchor_lang::prelude::*;
+
+use crate::AuctionError;
+
+/// Pyth Solana receiver program, owner of `PriceUpdateV2` accounts.
+pub const PYTH_RECEIVER_PROGRAM_ID: Pubkey =
+    pubkey!("rec5EKMGg6MxZYaMdyBfgwp4d5rB9T1VQH5pJv5LtFJ");
+
+/// Anchor discriminator of the receiver's `PriceUpdateV2` account.
+const PRICE_UPDATE_V2_DISCRIMINATOR: [u8; 8] = [34, 241, 35, 99, 157, 126, 244, 205];
+
+/// The subset of a Pyth `PriceUpdateV2` account the auction relies on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PythPrice {
+    pub feed_id: [u8; 32],
+    pub price: i64,
+    pub conf: u64,
+    pub exponent: i32,
+    pub publish_time: i64,
+}
+
+/// Reads a fully verified price from a Pyth `PriceUpdateV2` account and
+/// rejects it if it is older than `max_age` seconds or non-positive.
+pub fn load_price(account: &AccountInfo, now: i64, max_age: i64) -> Result<PythPrice> {
+    require_keys_eq!(
+        *account.owner,
+        PYTH_RECEIVER_PROGRAM_ID,
+        AuctionError::InvalidPriceFeed
+    );
+    let data = account.try_borrow_data()?;
+    require!(
+        data.len() >= 8 && data[..8] == PRICE_UPDATE_V2_DISCRIMINATOR,
+        AuctionError::InvalidPriceFeed
+    );
+
+    // write_authority: Pubkey, then the VerificationLevel enum. Only `Full`
+    // (variant 1, no payload) is accepted; `Partial` carries a u8 payload.
+    let mut offset = 8 + 32;
+    require!(
+        data.get(offset) == Some(&1),
+        AuctionError::InvalidPriceFeed
+    );
+    offset += 1;
+
+    let mut feed_id = [0u8; 32];
+    feed_id.copy_from_slice(read(&data, offset, 32)?);
+    offset += 32;
+    let price = i64::from_le_bytes(read(&data, offset, 8)?.try_into().unwrap());
+    offset += 8;
+    let conf = u64::from_le_bytes(read(&data, offset, 8)?.try_into().unwrap());
+    offset += 8;
+    let exponent = i32::from_le_bytes(read(&data, offset, 4)?.try_into().unwrap());
+    offset += 4;
+    let publish_time = i64::from_le_bytes(read(&data, offset, 8)?.try_into().unwrap());
+
+    require!(price > 0, AuctionError::InvalidPriceFeed);
+    require!(
+        now.saturating_sub(publish_time) <= max_age,
+        AuctionError::StalePrice
+    );
+
+    Ok(PythPrice {
+        feed_id,
+        price,
+        conf,
+        exponent,
+        publish_time,
+    })
+}
+
+fn read(data: &[u8], offset: usize, len: usize) -> Result<&[u8]> {
+    data.get(offset..offset + len)
+        .ok_or_else(|| error!(AuctionError::InvalidPriceFeed))
+}
+
+/// Converts `amount` base units of a mint with `decimals` into units of
+/// `10^-target_decimals` of the feed's quote currency.
+pub fn normalize(amount: u64, decimals: u8, price: &PythPrice, target_decimals: u8) -> Result<u64> {
+    let scale = price.exponent + target_decimals as i32 - decimals as i32;
+    let raw = (amount as u128)
+        .checked_mul(price.price as u128)
+        .ok_or(AuctionError::MathOverflow)?;
+    let value = if scale >= 0 {
+        raw.checked_mul(10u128.pow(scale as u32))
+    } else {
+        raw.checked_div(10u128.pow(scale.unsigned_abs()))
+    }
+    .ok_or(AuctionError::MathOverflow)?;
+    u64::try_from(value).map_err(|_| error!(AuctionError::MathOverflow))
+}
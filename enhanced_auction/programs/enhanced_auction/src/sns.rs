@@ -0,0 +1,70 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        program::invoke_signed,
+    },
+};
+
+use crate::AuctionError;
+
+pub const NAME_SERVICE_PROGRAM_ID: Pubkey = pubkey!("namesLPneVptA9Z5rqUDD9tMTWEJwofgaYwp8cawRkX");
+/// Name account of the `.sol` top-level domain, parent of every `.sol` name.
+pub const SOL_TLD: Pubkey = pubkey!("58PwtjSDuFHuUkYjH9BYnnQKHfwo9reZhC2zMJv9JPkx");
+
+/// `NameRecordHeader` is parent name, owner and class, 32 bytes each.
+const HEADER_LEN: usize = 96;
+/// Name service instruction index of `Transfer`.
+const TRANSFER_INSTRUCTION: u8 = 2;
+
+/// Reads the current owner of a `.sol` domain, rejecting accounts that are
+/// not name records of the name service under the `.sol` TLD, or that carry
+/// a class, since the class would have to co-sign every transfer.
+pub fn domain_owner(name_account: &AccountInfo) -> Result<Pubkey> {
+    require_keys_eq!(
+        *name_account.owner,
+        NAME_SERVICE_PROGRAM_ID,
+        AuctionError::InvalidAsset
+    );
+    let data = name_account.try_borrow_data()?;
+    require!(data.len() >= HEADER_LEN, AuctionError::InvalidAsset);
+    let parent = Pubkey::try_from(&data[0..32]).unwrap();
+    let class = Pubkey::try_from(&data[64..96]).unwrap();
+    require!(
+        parent == SOL_TLD && class == Pubkey::default(),
+        AuctionError::InvalidAsset
+    );
+    Ok(Pubkey::try_from(&data[32..64]).unwrap())
+}
+
+/// CPIs the name service `Transfer` to hand the domain to `new_owner`.
+/// `owner` is the current owner and signs directly, or through
+/// `signer_seeds` when it is a PDA.
+pub fn transfer<'info>(
+    name_service_program: &AccountInfo<'info>,
+    name_account: &AccountInfo<'info>,
+    owner: &AccountInfo<'info>,
+    new_owner: Pubkey,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let metas = vec![
+        AccountMeta::new(name_account.key(), false),
+        AccountMeta::new_readonly(owner.key(), true),
+    ];
+    let infos = [
+        name_account.clone(),
+        owner.clone(),
+        name_service_program.clone(),
+    ];
+
+    let mut data = Vec::with_capacity(33);
+    data.push(TRANSFER_INSTRUCTION);
+    data.extend_from_slice(new_owner.as_ref());
+
+    let ix = Instruction {
+        program_id: NAME_SERVICE_PROGRAM_ID,
+        accounts: metas,
+        data,
+    };
+    invoke_signed(&ix, &infos, signer_seeds).map_err(Into::into)
+}
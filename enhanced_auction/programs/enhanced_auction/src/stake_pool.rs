@@ -0,0 +1,96 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        program::invoke_signed,
+    },
+};
+
+use crate::AuctionError;
+
+pub const STAKE_POOL_PROGRAM_ID: Pubkey = pubkey!("SPoo1Ku8WFXoNDMHPsrGSTSG1Y47rzgn41SLUNakuHy");
+
+/// `AccountType::StakePool`, the first byte of an initialized pool account.
+const STAKE_POOL_ACCOUNT_TYPE: u8 = 1;
+/// Stake pool instruction index of `DepositSol`.
+const DEPOSIT_SOL_INSTRUCTION: u8 = 14;
+
+/// Accounts the stake pool's `DepositSol` needs. The pool validates the
+/// withdraw authority, reserve, manager fee account and pool mint against
+/// its own state, so they are passed through unchecked.
+pub struct DepositSolAccounts<'a, 'info> {
+    pub stake_pool_program: &'a AccountInfo<'info>,
+    pub stake_pool: &'a AccountInfo<'info>,
+    pub withdraw_authority: &'a AccountInfo<'info>,
+    pub reserve_stake: &'a AccountInfo<'info>,
+    pub lamports_from: &'a AccountInfo<'info>,
+    pub destination: &'a AccountInfo<'info>,
+    pub manager_fee_account: &'a AccountInfo<'info>,
+    pub pool_mint: &'a AccountInfo<'info>,
+    pub system_program: &'a AccountInfo<'info>,
+    pub token_program: &'a AccountInfo<'info>,
+}
+
+/// Rejects accounts that are not initialized pools of the SPL stake pool
+/// program.
+pub fn check_pool(stake_pool: &AccountInfo) -> Result<()> {
+    require_keys_eq!(
+        *stake_pool.owner,
+        STAKE_POOL_PROGRAM_ID,
+        AuctionError::InvalidStakePool
+    );
+    let data = stake_pool.try_borrow_data()?;
+    require!(
+        data.first() == Some(&STAKE_POOL_ACCOUNT_TYPE),
+        AuctionError::InvalidStakePool
+    );
+    Ok(())
+}
+
+/// CPIs `DepositSol` to stake `lamports` from `lamports_from` and mint the
+/// pool tokens to `destination`. `lamports_from` signs through
+/// `signer_seeds`. The destination doubles as the referrer, so any referral
+/// fee goes back to the same account. Pools gated by a SOL deposit authority
+/// are not supported.
+pub fn deposit_sol<'info>(
+    accounts: DepositSolAccounts<'_, 'info>,
+    lamports: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let metas = vec![
+        AccountMeta::new(accounts.stake_pool.key(), false),
+        AccountMeta::new_readonly(accounts.withdraw_authority.key(), false),
+        AccountMeta::new(accounts.reserve_stake.key(), false),
+        AccountMeta::new(accounts.lamports_from.key(), true),
+        AccountMeta::new(accounts.destination.key(), false),
+        AccountMeta::new(accounts.manager_fee_account.key(), false),
+        // referrer_pool_tokens_account
+        AccountMeta::new(accounts.destination.key(), false),
+        AccountMeta::new(accounts.pool_mint.key(), false),
+        AccountMeta::new_readonly(accounts.system_program.key(), false),
+        AccountMeta::new_readonly(accounts.token_program.key(), false),
+    ];
+    let infos = [
+        accounts.stake_pool.clone(),
+        accounts.withdraw_authority.clone(),
+        accounts.reserve_stake.clone(),
+        accounts.lamports_from.clone(),
+        accounts.destination.clone(),
+        accounts.manager_fee_account.clone(),
+        accounts.pool_mint.clone(),
+        accounts.system_program.clone(),
+        accounts.token_program.clone(),
+        accounts.stake_pool_program.clone(),
+    ];
+
+    let mut data = Vec::with_capacity(9);
+    data.push(DEPOSIT_SOL_INSTRUCTION);
+    data.extend_from_slice(&lamports.to_le_bytes());
+
+    let ix = Instruction {
+        program_id: STAKE_POOL_PROGRAM_ID,
+        accounts: metas,
+        data,
+    };
+    invoke_signed(&ix, &infos, signer_seeds).map_err(Into::into)
+}
@@ -0,0 +1,225 @@
+//! Canonical byte layouts for fixed inputs, so alternative clients can check
+//! their account decoding, PDA derivation and event parsing byte-for-byte
+//! against the program itself. Enabled with the `test-vectors` feature.
+
+use anchor_lang::{prelude::*, Discriminator, Event};
+
+use crate::{
+    AuctionCreated, AuctionFinalized, AuctionParams, BidPlaced, ALLOWLIST_ENTRY_SEED, AUCTION_SEED,
+    BIDDER_REGISTRATION_SEED, BIDDER_STATS_SEED, BID_DEPOSIT_SEED, BID_ESCROW_SEED,
+    BID_HISTORY_SEED, BID_NOTE_SEED, BID_POOL_SEED, BID_RECEIPT_SEED, CLOSING_QUEUE_SEED,
+    COLLECTION_OFFER_SEED, COMPRESSED_BUNDLE_SEED, COMPRESSED_SEED, CONFIG_SEED, CREATOR_LINKS_SEED,
+    CURRENCIES_SEED, DEPOSIT_TERMS_SEED, DISPUTE_SEED, ESCROW_SEED, EXPERIMENT_SEED,
+    FRACTION_MINT_SEED, INCREMENT_TIERS_SEED, KEEPER_SEED, LOSER_REBATE_SEED, LOT_MANIFEST_SEED,
+    METADATA_SNAPSHOT_SEED, PARTIAL_FILL_SEED, PROXY_BID_SEED, REBATE_POOL_SEED,
+    REBID_STRATEGY_SEED, SESSION_KEY_SEED, TOP_BIDS_SEED, TREASURY_SEED, WSOL_SEED,
+};
+
+/// Fixed inputs every vector is derived from.
+pub const AUCTION: Pubkey = Pubkey::new_from_array([1; 32]);
+pub const CREATOR: Pubkey = Pubkey::new_from_array([2; 32]);
+pub const NFT_MINT: Pubkey = Pubkey::new_from_array([3; 32]);
+pub const BIDDER: Pubkey = Pubkey::new_from_array([4; 32]);
+pub const NOW: i64 = 1_700_000_000;
+pub const PARAMS: AuctionParams = AuctionParams {
+    starting_bid: 1_000_000_000,
+    min_bid_increment: 100_000_000,
+    duration: 86_400,
+    buy_now_price: Some(5_000_000_000),
+};
+pub const BID: u64 = 1_500_000_000;
+
+/// One named vector: the exact bytes the program produces or derives.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TestVector {
+    pub name: &'static str,
+    pub bytes: Vec<u8>,
+}
+
+impl TestVector {
+    fn new(name: &'static str, bytes: impl Into<Vec<u8>>) -> Self {
+        Self {
+            name,
+            bytes: bytes.into(),
+        }
+    }
+}
+
+/// An `Auction` opened from the fixed inputs with one bid placed on it.
+pub fn auction() -> crate::Auction {
+    let zeroed = [0u8; crate::Auction::LEN];
+    let mut auction = crate::Auction::try_deserialize_unchecked(&mut &zeroed[..])
+        .expect("zeroed auction account");
+    auction.open(CREATOR, NFT_MINT, &PARAMS, NOW);
+    auction.highest_bid = BID;
+    auction.highest_bidder = BIDDER;
+    auction
+}
+
+/// Account data as stored on chain: discriminator then Borsh fields.
+pub fn account_vectors() -> Vec<TestVector> {
+    let mut data = Vec::new();
+    auction()
+        .try_serialize(&mut data)
+        .expect("serialize auction");
+    vec![
+        TestVector::new("account.auction.discriminator", crate::Auction::DISCRIMINATOR),
+        TestVector::new("account.auction", data),
+    ]
+}
+
+/// Program-derived addresses for the fixed auction, bidder and globals.
+pub fn pda_vectors() -> Vec<TestVector> {
+    let pda = |seeds: &[&[u8]]| Pubkey::find_program_address(seeds, &crate::ID).0.to_bytes();
+    vec![
+        TestVector::new("pda.auction_authority", pda(&[AUCTION_SEED, AUCTION.as_ref()])),
+        TestVector::new("pda.escrow", pda(&[ESCROW_SEED, AUCTION.as_ref()])),
+        TestVector::new("pda.wsol", pda(&[WSOL_SEED, AUCTION.as_ref()])),
+        TestVector::new("pda.currencies", pda(&[CURRENCIES_SEED, AUCTION.as_ref()])),
+        TestVector::new("pda.compressed", pda(&[COMPRESSED_SEED, AUCTION.as_ref()])),
+        TestVector::new(
+            "pda.compressed_bundle",
+            pda(&[COMPRESSED_BUNDLE_SEED, AUCTION.as_ref()]),
+        ),
+        TestVector::new(
+            "pda.deposit_terms",
+            pda(&[DEPOSIT_TERMS_SEED, AUCTION.as_ref()]),
+        ),
+        TestVector::new("pda.bid_history", pda(&[BID_HISTORY_SEED, AUCTION.as_ref()])),
+        TestVector::new("pda.top_bids", pda(&[TOP_BIDS_SEED, AUCTION.as_ref()])),
+        TestVector::new("pda.dispute", pda(&[DISPUTE_SEED, AUCTION.as_ref()])),
+        TestVector::new("pda.partial_fill", pda(&[PARTIAL_FILL_SEED, AUCTION.as_ref()])),
+        TestVector::new("pda.fraction_mint", pda(&[FRACTION_MINT_SEED, AUCTION.as_ref()])),
+        TestVector::new("pda.lot_manifest", pda(&[LOSER_REBATE_SEED, LOT_MANIFEST_SEED, AUCTION.as_ref()])),
+        TestVector::new(
+            "pda.metadata_snapshot",
+            pda(&[METADATA_SNAPSHOT_SEED, AUCTION.as_ref()]),
+        ),
+        TestVector::new("pda.keeper", pda(&[KEEPER_SEED, BIDDER.as_ref()])),
+        TestVector::new(
+            "pda.bid_escrow",
+            pda(&[BID_ESCROW_SEED, AUCTION.as_ref(), BIDDER.as_ref()]),
+        ),
+        TestVector::new(
+            "pda.proxy_bid",
+            pda(&[PROXY_BID_SEED, AUCTION.as_ref(), BIDDER.as_ref()]),
+        ),
+        TestVector::new(
+            "pda.bidder_stats",
+            pda(&[BIDDER_STATS_SEED, AUCTION.as_ref(), BIDDER.as_ref()]),
+        ),
+        TestVector::new(
+            "pda.bid_note",
+            pda(&[BID_NOTE_SEED, AUCTION.as_ref(), BIDDER.as_ref()]),
+        ),
+        TestVector::new(
+            "pda.session_key",
+            pda(&[SESSION_KEY_SEED, BIDDER.as_ref(), CREATOR.as_ref()]),
+        ),
+        TestVector::new("pda.bid_deposit", pda(&[BID_DEPOSIT_SEED, BIDDER.as_ref()])),
+        TestVector::new(
+            "pda.increment_tiers",
+            pda(&[INCREMENT_TIERS_SEED, AUCTION.as_ref()]),
+        ),
+        TestVector::new(
+            "pda.closing_queue",
+            pda(&[CLOSING_QUEUE_SEED, AUCTION.as_ref()]),
+        ),
+        TestVector::new(
+            "pda.bidder_registration",
+            pda(&[BIDDER_REGISTRATION_SEED, AUCTION.as_ref(), BIDDER.as_ref()]),
+        ),
+        TestVector::new(
+            "pda.bid_receipt",
+            pda(&[BID_RECEIPT_SEED, AUCTION.as_ref(), &0u64.to_le_bytes()]),
+        ),
+        TestVector::new(
+            "pda.bid_pool",
+            pda(&[BID_POOL_SEED, AUCTION.as_ref(), CREATOR.as_ref()]),
+        ),
+        TestVector::new(
+            "pda.allowlist_entry",
+            pda(&[ALLOWLIST_ENTRY_SEED, AUCTION.as_ref(), BIDDER.as_ref()]),
+        ),
+        TestVector::new(
+            "pda.loser_rebate",
+            pda(&[LOSER_REBATE_SEED, AUCTION.as_ref()]),
+        ),
+        TestVector::new(
+            "pda.rebid_strategy",
+            pda(&[REBID_STRATEGY_SEED, AUCTION.as_ref(), BIDDER.as_ref()]),
+        ),
+        TestVector::new(
+            "pda.collection_offer",
+            pda(&[COLLECTION_OFFER_SEED, NFT_MINT.as_ref(), BIDDER.as_ref()]),
+        ),
+        TestVector::new(
+            "pda.creator_links",
+            pda(&[CREATOR_LINKS_SEED, CREATOR.as_ref()]),
+        ),
+        TestVector::new("pda.config", pda(&[CONFIG_SEED])),
+        TestVector::new("pda.experiment", pda(&[EXPERIMENT_SEED])),
+        TestVector::new("pda.rebate_pool", pda(&[REBATE_POOL_SEED])),
+        TestVector::new("pda.treasury", pda(&[TREASURY_SEED])),
+    ]
+}
+
+/// Event payloads as logged via `emit!`: discriminator then Borsh fields,
+/// before base64 encoding.
+pub fn event_vectors() -> Vec<TestVector> {
+    let auction = auction();
+    vec![
+        TestVector::new(
+            "event.auction_created",
+            AuctionCreated {
+                auction_id: AUCTION,
+                creator: CREATOR,
+                nft_mint: NFT_MINT,
+                starting_bid: auction.starting_bid,
+                end_time: auction.end_time,
+                quantity: auction.quantity,
+            }
+            .data(),
+        ),
+        TestVector::new(
+            "event.bid_placed",
+            BidPlaced {
+                auction_id: AUCTION,
+                bidder: BIDDER,
+                bid_amount: BID,
+            }
+            .data(),
+        ),
+        TestVector::new(
+            "event.auction_finalized",
+            AuctionFinalized {
+                auction_id: AUCTION,
+                winner: BIDDER,
+                winning_bid: BID,
+                experiment_id: auction.experiment_id,
+                variant: auction.experiment_variant,
+            }
+            .data(),
+        ),
+    ]
+}
+
+/// Every vector, in a stable order.
+pub fn all() -> Vec<TestVector> {
+    let mut vectors = account_vectors();
+    vectors.extend(pda_vectors());
+    vectors.extend(event_vectors());
+    vectors
+}
+
+/// Renders `vectors` as `name=hex` lines, the format client test suites
+/// load.
+pub fn render(vectors: &[TestVector]) -> String {
+    vectors
+        .iter()
+        .map(|v| {
+            let hex: String = v.bytes.iter().map(|b| format!("{b:02x}")).collect();
+            format!("{}={}\n", v.name, hex)
+        })
+        .collect()
+}
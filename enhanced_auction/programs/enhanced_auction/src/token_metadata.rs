@@ -0,0 +1,334 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        program::invoke_signed,
+    },
+};
+
+use crate::AuctionError;
+
+pub const TOKEN_METADATA_PROGRAM_ID: Pubkey =
+    pubkey!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+
+/// Seed prefix of metadata PDAs.
+pub const METADATA_SEED: &[u8] = b"metadata";
+/// Trailing seed of master edition PDAs.
+pub const EDITION_SEED: &[u8] = b"edition";
+/// `Key::MetadataV1`, the first byte of every metadata account.
+const METADATA_V1_KEY: u8 = 4;
+/// Longest name, symbol and creators list Token Metadata accepts.
+pub const MAX_NAME_LENGTH: usize = 32;
+pub const MAX_SYMBOL_LENGTH: usize = 10;
+pub const MAX_CREATOR_LIMIT: usize = 5;
+
+/// Token Metadata instruction index of `Transfer`.
+const TRANSFER_INSTRUCTION: u8 = 49;
+/// `TransferArgs::V1` variant index.
+const TRANSFER_ARGS_V1: u8 = 0;
+/// Token Metadata instruction index of `FreezeDelegatedAccount`.
+const FREEZE_DELEGATED_ACCOUNT_INSTRUCTION: u8 = 26;
+/// Token Metadata instruction index of `ThawDelegatedAccount`.
+const THAW_DELEGATED_ACCOUNT_INSTRUCTION: u8 = 27;
+
+/// Accounts for Token Metadata's `Transfer` (V1), which is the only way to
+/// move a programmable NFT: the token accounts stay frozen and token records
+/// plus rule sets are enforced by Token Metadata.
+pub struct TransferV1Accounts<'a, 'info> {
+    pub token_metadata_program: &'a AccountInfo<'info>,
+    pub token: &'a AccountInfo<'info>,
+    pub token_owner: &'a AccountInfo<'info>,
+    pub destination_token: &'a AccountInfo<'info>,
+    pub destination_owner: &'a AccountInfo<'info>,
+    pub mint: &'a AccountInfo<'info>,
+    pub metadata: &'a AccountInfo<'info>,
+    pub edition: Option<&'a AccountInfo<'info>>,
+    pub owner_token_record: Option<&'a AccountInfo<'info>>,
+    pub destination_token_record: Option<&'a AccountInfo<'info>>,
+    pub authority: &'a AccountInfo<'info>,
+    pub payer: &'a AccountInfo<'info>,
+    pub system_program: &'a AccountInfo<'info>,
+    pub sysvar_instructions: &'a AccountInfo<'info>,
+    pub spl_token_program: &'a AccountInfo<'info>,
+    pub spl_ata_program: &'a AccountInfo<'info>,
+    pub authorization_rules_program: Option<&'a AccountInfo<'info>>,
+    pub authorization_rules: Option<&'a AccountInfo<'info>>,
+}
+
+/// CPIs Token Metadata `Transfer` for `amount` units without authorization
+/// data. Optional accounts that are absent are passed as the Token Metadata
+/// program id, which is how the program encodes `None`.
+pub fn transfer_v1<'info>(
+    accounts: TransferV1Accounts<'_, 'info>,
+    amount: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let program = accounts.token_metadata_program;
+    let optional = |account: Option<&AccountInfo<'info>>| account.unwrap_or(program).clone();
+
+    let infos = vec![
+        accounts.token.clone(),
+        accounts.token_owner.clone(),
+        accounts.destination_token.clone(),
+        accounts.destination_owner.clone(),
+        accounts.mint.clone(),
+        accounts.metadata.clone(),
+        optional(accounts.edition),
+        optional(accounts.owner_token_record),
+        optional(accounts.destination_token_record),
+        accounts.authority.clone(),
+        accounts.payer.clone(),
+        accounts.system_program.clone(),
+        accounts.sysvar_instructions.clone(),
+        accounts.spl_token_program.clone(),
+        accounts.spl_ata_program.clone(),
+        optional(accounts.authorization_rules_program),
+        optional(accounts.authorization_rules),
+    ];
+    let keys: Vec<Pubkey> = infos.iter().map(|info| info.key()).collect();
+    let ix = transfer_v1_instruction(&keys, amount);
+    invoke_signed(&ix, &infos, signer_seeds).map_err(Into::into)
+}
+
+/// Builds Token Metadata `Transfer` for `amount` units over `keys`, given in
+/// `TransferV1Accounts` order. Absent optional accounts, passed as the Token
+/// Metadata program id, are never marked writable.
+pub fn transfer_v1_instruction(keys: &[Pubkey], amount: u64) -> Instruction {
+    let writable = [0, 2, 5, 7, 8, 10];
+    let signers = [9, 10];
+    let metas = keys
+        .iter()
+        .enumerate()
+        .map(|(i, key)| {
+            let is_signer = signers.contains(&i);
+            if writable.contains(&i) && *key != TOKEN_METADATA_PROGRAM_ID {
+                AccountMeta::new(*key, is_signer)
+            } else {
+                AccountMeta::new_readonly(*key, is_signer)
+            }
+        })
+        .collect();
+
+    let mut data = vec![TRANSFER_INSTRUCTION, TRANSFER_ARGS_V1];
+    data.extend_from_slice(&amount.to_le_bytes());
+    // authorization_data: None
+    data.push(0);
+
+    Instruction {
+        program_id: TOKEN_METADATA_PROGRAM_ID,
+        accounts: metas,
+        data,
+    }
+}
+
+/// Accounts for Token Metadata's `FreezeDelegatedAccount` and
+/// `ThawDelegatedAccount`, which let a token delegate freeze an NFT in the
+/// owner's wallet through the master edition's freeze authority.
+pub struct DelegatedFreezeAccounts<'a, 'info> {
+    pub token_metadata_program: &'a AccountInfo<'info>,
+    pub delegate: &'a AccountInfo<'info>,
+    pub token_account: &'a AccountInfo<'info>,
+    pub edition: &'a AccountInfo<'info>,
+    pub mint: &'a AccountInfo<'info>,
+    pub token_program: &'a AccountInfo<'info>,
+}
+
+/// CPIs Token Metadata `FreezeDelegatedAccount`. `delegate` must be the
+/// token account's approved delegate and signs through `signer_seeds`.
+pub fn freeze_delegated_account<'info>(
+    accounts: DelegatedFreezeAccounts<'_, 'info>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    invoke_delegated_freeze(FREEZE_DELEGATED_ACCOUNT_INSTRUCTION, accounts, signer_seeds)
+}
+
+/// CPIs Token Metadata `ThawDelegatedAccount`, undoing
+/// [`freeze_delegated_account`].
+pub fn thaw_delegated_account<'info>(
+    accounts: DelegatedFreezeAccounts<'_, 'info>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    invoke_delegated_freeze(THAW_DELEGATED_ACCOUNT_INSTRUCTION, accounts, signer_seeds)
+}
+
+fn invoke_delegated_freeze<'info>(
+    instruction: u8,
+    accounts: DelegatedFreezeAccounts<'_, 'info>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let metas = vec![
+        AccountMeta::new(accounts.delegate.key(), true),
+        AccountMeta::new(accounts.token_account.key(), false),
+        AccountMeta::new_readonly(accounts.edition.key(), false),
+        AccountMeta::new_readonly(accounts.mint.key(), false),
+        AccountMeta::new_readonly(accounts.token_program.key(), false),
+    ];
+    let infos = [
+        accounts.delegate.clone(),
+        accounts.token_account.clone(),
+        accounts.edition.clone(),
+        accounts.mint.clone(),
+        accounts.token_program.clone(),
+        accounts.token_metadata_program.clone(),
+    ];
+
+    let ix = Instruction {
+        program_id: TOKEN_METADATA_PROGRAM_ID,
+        accounts: metas,
+        data: vec![instruction],
+    };
+    invoke_signed(&ix, &infos, signer_seeds).map_err(Into::into)
+}
+
+/// One entry of a metadata account's `creators` array.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Creator {
+    pub address: Pubkey,
+    pub verified: bool,
+    pub share: u8,
+}
+
+impl Creator {
+    pub const LEN: usize = 32 + 1 + 1;
+}
+
+/// Royalty terms read from a metadata account.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Royalties {
+    pub seller_fee_basis_points: u16,
+    pub creators: Vec<Creator>,
+}
+
+/// Display fields read from a metadata account, with the null padding older
+/// accounts carry trimmed off.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Descriptor {
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+}
+
+/// Verified collection membership read from a metadata account.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Collection {
+    pub verified: bool,
+    pub key: Pubkey,
+}
+
+/// Reads the royalty terms of `mint` from its metadata PDA. A PDA that was
+/// never created means the mint has no Token Metadata and owes no royalties.
+pub fn load_royalties(metadata: &AccountInfo, mint: &Pubkey) -> Result<Royalties> {
+    if metadata.data_is_empty() {
+        return Ok(Royalties::default());
+    }
+    let data = borrow_metadata(metadata, mint)?;
+    let (royalties, _) = read_royalties(&data)?;
+    Ok(royalties)
+}
+
+/// Reads the display fields and royalty terms of `mint` from its metadata
+/// PDA.
+pub fn load_descriptor(metadata: &AccountInfo, mint: &Pubkey) -> Result<(Descriptor, Royalties)> {
+    let data = borrow_metadata(metadata, mint)?;
+    let mut offset = 1 + 32 + 32;
+    let mut fields = [String::new(), String::new(), String::new()];
+    for field in fields.iter_mut() {
+        let len = u32::from_le_bytes(read(&data, offset, 4)?.try_into().unwrap()) as usize;
+        let bytes = read(&data, offset + 4, len)?;
+        *field = std::str::from_utf8(bytes)
+            .map_err(|_| error!(AuctionError::InvalidMetadata))?
+            .trim_end_matches('\0')
+            .to_string();
+        offset += 4 + len;
+    }
+    let [name, symbol, uri] = fields;
+    let (royalties, _) = read_royalties(&data)?;
+    Ok((Descriptor { name, symbol, uri }, royalties))
+}
+
+/// Reads the collection `mint` belongs to from its metadata PDA, or `None`
+/// if the metadata names no collection.
+pub fn load_collection(metadata: &AccountInfo, mint: &Pubkey) -> Result<Option<Collection>> {
+    let data = borrow_metadata(metadata, mint)?;
+    let (_, mut offset) = read_royalties(&data)?;
+
+    // primary_sale_happened, is_mutable
+    offset += 2;
+    // edition_nonce, token_standard: Option<u8>
+    for _ in 0..2 {
+        offset += 1 + read(&data, offset, 1)?[0] as usize;
+    }
+    if read(&data, offset, 1)?[0] == 0 {
+        return Ok(None);
+    }
+    let entry = read(&data, offset + 1, 33)?;
+    Ok(Some(Collection {
+        verified: entry[0] == 1,
+        key: Pubkey::try_from(&entry[1..]).unwrap(),
+    }))
+}
+
+/// Borrows a metadata account's data after checking it is a `MetadataV1`
+/// account owned by Token Metadata that describes `mint`.
+fn borrow_metadata<'a>(metadata: &'a AccountInfo, mint: &Pubkey) -> Result<std::cell::Ref<'a, &'a mut [u8]>> {
+    require_keys_eq!(
+        *metadata.owner,
+        TOKEN_METADATA_PROGRAM_ID,
+        AuctionError::InvalidMetadata
+    );
+    let data = metadata.try_borrow_data()?;
+    require!(
+        data.first() == Some(&METADATA_V1_KEY),
+        AuctionError::InvalidMetadata
+    );
+    // key, update_authority, then the mint the account describes
+    require!(
+        read(&data, 1 + 32, 32)? == mint.as_ref(),
+        AuctionError::InvalidMetadata
+    );
+    Ok(data)
+}
+
+/// Parses the royalty terms of a metadata account and returns them with the
+/// offset of the field that follows the creators.
+fn read_royalties(data: &[u8]) -> Result<(Royalties, usize)> {
+    let mut offset = 1 + 32 + 32;
+
+    // name, symbol, uri are borsh strings (u32 length prefix)
+    for _ in 0..3 {
+        let len = u32::from_le_bytes(read(data, offset, 4)?.try_into().unwrap()) as usize;
+        offset += 4 + len;
+    }
+    let seller_fee_basis_points = u16::from_le_bytes(read(data, offset, 2)?.try_into().unwrap());
+    offset += 2;
+
+    let mut creators = Vec::new();
+    let has_creators = read(data, offset, 1)?[0] == 1;
+    offset += 1;
+    if has_creators {
+        let count = u32::from_le_bytes(read(data, offset, 4)?.try_into().unwrap());
+        offset += 4;
+        for _ in 0..count {
+            let entry = read(data, offset, 34)?;
+            creators.push(Creator {
+                address: Pubkey::try_from(&entry[..32]).unwrap(),
+                verified: entry[32] == 1,
+                share: entry[33],
+            });
+            offset += 34;
+        }
+    }
+
+    Ok((
+        Royalties {
+            seller_fee_basis_points,
+            creators,
+        },
+        offset,
+    ))
+}
+
+fn read(data: &[u8], offset: usize, len: usize) -> Result<&[u8]> {
+    data.get(offset..offset + len)
+        .ok_or_else(|| error!(AuctionError::InvalidMetadata))
+}
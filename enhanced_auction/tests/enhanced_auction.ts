@@ -1,7 +1,19 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
+import {
+  TOKEN_PROGRAM_ID,
+  createMint,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+} from "@solana/spl-token";
 import assert from "assert";
-import { AuctionSystem } from "../target/types/auction_system";
+import { EnhancedAuction } from "../target/types/enhanced_auction";
+
+const { Keypair, PublicKey, LAMPORTS_PER_SOL } = anchor.web3;
+
+const BPF_UPGRADEABLE_LOADER = new PublicKey(
+  "BPFLoaderUpgradeab1e11111111111111111111111"
+);
 
 // Utility function for creating keypairs
 const createKeypair = async (): Promise<anchor.web3.Keypair> => {
@@ -9,11 +21,245 @@ const createKeypair = async (): Promise<anchor.web3.Keypair> => {
   return keypair;
 };
 
+const sleep = (ms: number) => new Promise((resolve) => setTimeout(resolve, ms));
+
+const pda = (program: Program<EnhancedAuction>, ...seeds: Buffer[]) =>
+  PublicKey.findProgramAddressSync(seeds, program.programId)[0];
+
+// Asserts that `promise` is rejected with the named program error.
+const expectError = async (promise: Promise<unknown>, code: string) => {
+  try {
+    await promise;
+  } catch (err) {
+    assert.match(String(err), new RegExp(code));
+    return;
+  }
+  assert.fail(`expected ${code}`);
+};
+
+const fund = async (
+  provider: anchor.AnchorProvider,
+  ...wallets: anchor.web3.Keypair[]
+) => {
+  for (const wallet of wallets) {
+    const sig = await provider.connection.requestAirdrop(
+      wallet.publicKey,
+      LAMPORTS_PER_SOL * 10
+    );
+    await provider.connection.confirmTransaction(sig);
+  }
+};
+
+// Creates the program config once per validator, with the provider wallet
+// as upgrade authority and therefore admin.
+const ensureConfig = async (
+  program: Program<EnhancedAuction>,
+  provider: anchor.AnchorProvider
+) => {
+  const config = pda(program, Buffer.from("config"));
+  if (await provider.connection.getAccountInfo(config)) {
+    return config;
+  }
+  const [programData] = PublicKey.findProgramAddressSync(
+    [program.programId.toBuffer()],
+    BPF_UPGRADEABLE_LOADER
+  );
+  await program.methods
+    .initializeConfig()
+    .accountsPartial({
+      config,
+      admin: provider.wallet.publicKey,
+      program: program.programId,
+      programData,
+    })
+    .rpc();
+  return config;
+};
+
+// Mints a fresh NFT to `creator` and lists it as a native SOL auction,
+// private to the wallets under `allowlistRoot` when one is given.
+const listAuction = async (
+  program: Program<EnhancedAuction>,
+  provider: anchor.AnchorProvider,
+  creator: anchor.web3.Keypair,
+  {
+    startingBid = LAMPORTS_PER_SOL / 10,
+    increment = LAMPORTS_PER_SOL / 20,
+    duration = 4,
+    buyNowPrice = null as number | null,
+    allowlistRoot = null as number[] | null,
+  } = {}
+) => {
+  const auction = Keypair.generate();
+  const nftMint = await createMint(
+    provider.connection,
+    creator,
+    creator.publicKey,
+    null,
+    0
+  );
+  const creatorNftAccount = await getOrCreateAssociatedTokenAccount(
+    provider.connection,
+    creator,
+    nftMint,
+    creator.publicKey
+  );
+  await mintTo(
+    provider.connection,
+    creator,
+    nftMint,
+    creatorNftAccount.address,
+    creator,
+    1
+  );
+
+  const bid = new anchor.BN(startingBid);
+  const step = new anchor.BN(increment);
+  const length = new anchor.BN(duration);
+  const buyNow = buyNowPrice === null ? null : new anchor.BN(buyNowPrice);
+  const init =
+    allowlistRoot === null
+      ? program.methods.initializeAuction(bid, step, length, buyNow, null, null)
+      : program.methods.initializePrivateAuction(
+          bid,
+          step,
+          length,
+          buyNow,
+          null,
+          null,
+          allowlistRoot
+        );
+  await init
+    .accountsPartial({
+      auction: auction.publicKey,
+      creator: creator.publicKey,
+      nftMint,
+      creatorNftAccount: creatorNftAccount.address,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    })
+    .signers([auction, creator])
+    .rpc();
+
+  return { auction: auction.publicKey, nftMint };
+};
+
+// Refund escrow of `bidder`'s outbid bids on `auction`.
+const bidEscrow = (
+  program: Program<EnhancedAuction>,
+  auction: anchor.web3.PublicKey,
+  bidder: anchor.web3.PublicKey
+) => pda(program, Buffer.from("bid_escrow"), auction.toBuffer(), bidder.toBuffer());
+
+// Accounts `place_bid` needs for the standing bid: the outbid leader's
+// refund escrow and, when it is proxy-backed, the leader's proxy.
+const standingBidAccounts = async (
+  program: Program<EnhancedAuction>,
+  auction: anchor.web3.PublicKey
+) => {
+  const state = await program.account.auction.fetch(auction);
+  if (state.highestBid.isZero()) {
+    return {};
+  }
+  const leader = state.highestBidder;
+  return {
+    previousBidEscrow: bidEscrow(program, auction, leader),
+    leaderProxyBid: state.proxyBidActive
+      ? pda(program, Buffer.from("proxy_bid"), auction.toBuffer(), leader.toBuffer())
+      : null,
+  };
+};
+
+const placeBid = async (
+  program: Program<EnhancedAuction>,
+  auction: anchor.web3.PublicKey,
+  bidder: anchor.web3.Keypair,
+  amount: number
+) =>
+  program.methods
+    .placeBid(new anchor.BN(amount), [])
+    .accountsPartial({
+      auction,
+      payer: bidder.publicKey,
+      bidder: bidder.publicKey,
+      ...(await standingBidAccounts(program, auction)),
+    })
+    .signers([bidder])
+    .rpc();
+
+const claimRefund = async (
+  program: Program<EnhancedAuction>,
+  auction: anchor.web3.PublicKey,
+  bidder: anchor.web3.Keypair
+) => {
+  const escrow = bidEscrow(program, auction, bidder.publicKey);
+  const { rentPayer } = await program.account.bidEscrow.fetch(escrow);
+  await program.methods
+    .claimRefund()
+    .accountsPartial({ bidder: bidder.publicKey, bidEscrow: escrow, rentPayer })
+    .signers([bidder])
+    .rpc();
+};
+
+// Accounts `finalize_auction` needs that the client cannot resolve alone.
+const settlementAccounts = async (
+  program: Program<EnhancedAuction>,
+  auction: anchor.web3.PublicKey
+) => {
+  const state = await program.account.auction.fetch(auction);
+  const config = await program.account.config.fetch(
+    pda(program, Buffer.from("config"))
+  );
+  return {
+    auction,
+    creator: state.creator,
+    nftMint: state.nftMint,
+    platformFeeAccount: config.feeDestination,
+    closingQueue: state.closingQueue
+      ? pda(program, Buffer.from("closing_queue"), auction.toBuffer())
+      : null,
+    loserRebate:
+      state.loserRebateBps > 0
+        ? pda(program, Buffer.from("loser_rebate"), auction.toBuffer())
+        : null,
+    tokenProgram: TOKEN_PROGRAM_ID,
+  };
+};
+
+// Lamports `wallet` gained over `action`; `wallet` must not pay its fees.
+const balanceChange = async (
+  provider: anchor.AnchorProvider,
+  wallet: anchor.web3.PublicKey,
+  action: () => Promise<unknown>
+) => {
+  const before = await provider.connection.getBalance(wallet);
+  await action();
+  return (await provider.connection.getBalance(wallet)) - before;
+};
+
+// Sleeps until the validator clock has passed `unixTime`, with a margin for
+// clock drift.
+const waitUntil = async (unixTime: number) => {
+  const wait = unixTime * 1000 - Date.now() + 1500;
+  if (wait > 0) {
+    await sleep(wait);
+  }
+};
+
+// Waits out the auction's end time and moves it to `Ended`.
+const endAuction = async (
+  program: Program<EnhancedAuction>,
+  auction: anchor.web3.PublicKey
+) => {
+  const state = await program.account.auction.fetch(auction);
+  await waitUntil(state.endTime.toNumber());
+  await program.methods.endAuction().accountsPartial({ auction }).rpc();
+};
+
 describe("Auction System Tests", () => {
   const provider = anchor.AnchorProvider.env();
   anchor.setProvider(provider);
 
-  const program = anchor.workspace.AuctionSystem as Program<AuctionSystem>;
+  const program = anchor.workspace.EnhancedAuction as Program<EnhancedAuction>;
 
   // Accounts
   let auctionAccount: anchor.web3.Keypair;
@@ -52,7 +298,10 @@ describe("Auction System Tests", () => {
       .initializeAuction(
         new anchor.BN(anchor.web3.LAMPORTS_PER_SOL / 10), // Starting bid
         new anchor.BN(anchor.web3.LAMPORTS_PER_SOL / 20), // Minimum increment
-        new anchor.BN(60 * 60) // Auction duration
+        new anchor.BN(60 * 60), // Auction duration
+        null, // No buy-now price
+        null, // Single token
+        null // No required collection
       )
       .accounts({
         auction: auctionAccount.publicKey,
@@ -67,14 +316,15 @@ describe("Auction System Tests", () => {
       auctionAccount.publicKey
     );
     assert.strictEqual(auctionState.creator.toBase58(), creator.publicKey.toBase58());
-    assert.strictEqual(auctionState.status.active, true);
+    assert.strictEqual(auctionState.status.live, true);
   });
 
   it("Places a valid bid", async () => {
     await program.methods
-      .placeBid(new anchor.BN(anchor.web3.LAMPORTS_PER_SOL / 2))
+      .placeBid(new anchor.BN(anchor.web3.LAMPORTS_PER_SOL / 2), [])
       .accounts({
         auction: auctionAccount.publicKey,
+        payer: bidder.publicKey,
         bidder: bidder.publicKey,
         systemProgram: anchor.web3.SystemProgram.programId,
       })
@@ -94,9 +344,10 @@ describe("Auction System Tests", () => {
   it("Fails to place a bid lower than the current highest bid", async () => {
     try {
       await program.methods
-        .placeBid(new anchor.BN(anchor.web3.LAMPORTS_PER_SOL / 4))
+        .placeBid(new anchor.BN(anchor.web3.LAMPORTS_PER_SOL / 4), [])
         .accounts({
           auction: auctionAccount.publicKey,
+          payer: bidder.publicKey,
           bidder: bidder.publicKey,
           systemProgram: anchor.web3.SystemProgram.programId,
         })
@@ -123,7 +374,7 @@ describe("Auction System Tests", () => {
     const auctionState = await program.account.auction.fetch(
       auctionAccount.publicKey
     );
-    assert.strictEqual(auctionState.status.completed, true);
+    assert.strictEqual(auctionState.status.settled, true);
   });
 
   it("Fails to finalize an already completed auction", async () => {
@@ -167,7 +418,10 @@ describe("Auction System Tests", () => {
       .initializeAuction(
         new anchor.BN(anchor.web3.LAMPORTS_PER_SOL / 10),
         new anchor.BN(anchor.web3.LAMPORTS_PER_SOL / 20),
-        new anchor.BN(60 * 60)
+        new anchor.BN(60 * 60),
+        null,
+        null,
+        null
       )
       .accounts({
         auction: newAuction.publicKey,
@@ -192,4 +446,1446 @@ describe("Auction System Tests", () => {
     );
     assert.strictEqual(auctionState.status.cancelled, true);
   });
-});
\ No newline at end of file
+
+  it("Lets only the first of two same-slot buy-now transactions win", async () => {
+    const raceAuction = await createKeypair();
+    const buyerA = await createKeypair();
+    const buyerB = await createKeypair();
+    const buyNowPrice = new anchor.BN(anchor.web3.LAMPORTS_PER_SOL);
+
+    for (const buyer of [buyerA, buyerB]) {
+      const sig = await provider.connection.requestAirdrop(
+        buyer.publicKey,
+        anchor.web3.LAMPORTS_PER_SOL * 2
+      );
+      await provider.connection.confirmTransaction(sig);
+    }
+
+    await program.methods
+      .initializeAuction(
+        new anchor.BN(anchor.web3.LAMPORTS_PER_SOL / 10),
+        new anchor.BN(anchor.web3.LAMPORTS_PER_SOL / 20),
+        new anchor.BN(60 * 60),
+        buyNowPrice,
+        null,
+        null
+      )
+      .accounts({
+        auction: raceAuction.publicKey,
+        creator: creator.publicKey,
+        vaultNftAccount: nftVault,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([raceAuction, creator])
+      .rpc();
+
+    const balancesBefore = await Promise.all(
+      [buyerA, buyerB].map((b) => provider.connection.getBalance(b.publicKey))
+    );
+
+    // Submit both buy-now transactions without awaiting either so they land
+    // in the same slot.
+    const results = await Promise.allSettled(
+      [buyerA, buyerB].map((buyer) =>
+        program.methods
+          .buyNow()
+          .accountsPartial({
+            auction: raceAuction.publicKey,
+            buyer: buyer.publicKey,
+          })
+          .signers([buyer])
+          .rpc()
+      )
+    );
+
+    const winners = results.filter((r) => r.status === "fulfilled");
+    const losers = results.filter((r) => r.status === "rejected");
+    assert.strictEqual(winners.length, 1);
+    assert.strictEqual(losers.length, 1);
+    assert.match(
+      (losers[0] as PromiseRejectedResult).reason.message,
+      /BuyNowAlreadyExecuted|AuctionEnded/
+    );
+
+    const winnerIndex = results[0].status === "fulfilled" ? 0 : 1;
+    const winner = [buyerA, buyerB][winnerIndex];
+    const loserIndex = 1 - winnerIndex;
+
+    const auctionState = await program.account.auction.fetch(
+      raceAuction.publicKey
+    );
+    assert.strictEqual(auctionState.buyNowExecuted, true);
+    assert.strictEqual(auctionState.highestBid.toString(), buyNowPrice.toString());
+    assert.strictEqual(
+      auctionState.highestBidder.toBase58(),
+      winner.publicKey.toBase58()
+    );
+
+    // The losing buyer never moved lamports into escrow.
+    const loserBalanceAfter = await provider.connection.getBalance(
+      [buyerA, buyerB][loserIndex].publicKey
+    );
+    assert.ok(
+      balancesBefore[loserIndex] - loserBalanceAfter < buyNowPrice.toNumber()
+    );
+  });
+});
+
+describe("Settlement", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.EnhancedAuction as Program<EnhancedAuction>;
+
+  const creator = Keypair.generate();
+  const bidder = Keypair.generate();
+  const outsider = Keypair.generate();
+
+  before(async () => {
+    await fund(provider, creator, bidder, outsider);
+    await ensureConfig(program, provider);
+  });
+
+  it("Rejects finalize_auction with a substituted creator", async () => {
+    const { auction } = await listAuction(program, provider, creator);
+    await placeBid(program, auction, bidder, LAMPORTS_PER_SOL / 2);
+    await endAuction(program, auction);
+
+    const outsiderBefore = await provider.connection.getBalance(
+      outsider.publicKey
+    );
+    // The provider wallet settles as a third party, pointing the seller's
+    // proceeds at the outsider instead of the creator.
+    const accounts = await settlementAccounts(program, auction);
+    await expectError(
+      program.methods
+        .finalizeAuction()
+        .accountsPartial({ ...accounts, creator: outsider.publicKey })
+        .rpc(),
+      "Unauthorized"
+    );
+    assert.strictEqual(
+      await provider.connection.getBalance(outsider.publicKey),
+      outsiderBefore
+    );
+
+    await program.methods.finalizeAuction().accountsPartial(accounts).rpc();
+    const state = await program.account.auction.fetch(auction);
+    assert.ok(state.status.settled);
+  });
+
+  it("Rejects a winner NFT account the winner does not own", async () => {
+    const { auction, nftMint } = await listAuction(program, provider, creator);
+    await placeBid(program, auction, bidder, LAMPORTS_PER_SOL / 2);
+    await endAuction(program, auction);
+
+    const outsiderNftAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      outsider,
+      nftMint,
+      outsider.publicKey
+    );
+    const accounts = await settlementAccounts(program, auction);
+    await expectError(
+      program.methods
+        .finalizeAuction()
+        .accountsPartial({
+          ...accounts,
+          winnerNftAccount: outsiderNftAccount.address,
+        })
+        .rpc(),
+      "ConstraintTokenOwner"
+    );
+  });
+});
+
+describe("Blocklist", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.EnhancedAuction as Program<EnhancedAuction>;
+
+  const creator = Keypair.generate();
+  const blocked = Keypair.generate();
+  const bidder = Keypair.generate();
+
+  before(async () => {
+    await fund(provider, creator, blocked, bidder);
+    await ensureConfig(program, provider);
+    await program.methods
+      .blockWallet()
+      .accountsPartial({
+        admin: provider.wallet.publicKey,
+        wallet: blocked.publicKey,
+      })
+      .rpc();
+  });
+
+  it("Bars a blocked wallet from buy_now", async () => {
+    const { auction } = await listAuction(program, provider, creator, {
+      duration: 60 * 60,
+      buyNowPrice: LAMPORTS_PER_SOL,
+    });
+    await expectError(
+      program.methods
+        .buyNow()
+        .accountsPartial({ auction, buyer: blocked.publicKey })
+        .signers([blocked])
+        .rpc(),
+      "WalletOnBlocklist"
+    );
+    const state = await program.account.auction.fetch(auction);
+    assert.strictEqual(state.buyNowExecuted, false);
+  });
+
+  it("Bars a blocked wallet from place_proxy_bid", async () => {
+    const { auction } = await listAuction(program, provider, creator, {
+      duration: 60 * 60,
+    });
+    await expectError(
+      program.methods
+        .placeProxyBid(new anchor.BN(LAMPORTS_PER_SOL))
+        .accountsPartial({ auction, bidder: blocked.publicKey })
+        .signers([blocked])
+        .rpc(),
+      "WalletOnBlocklist"
+    );
+  });
+
+  it("Lets a regular bidder through the same paths", async () => {
+    const { auction } = await listAuction(program, provider, creator, {
+      duration: 60 * 60,
+    });
+    await program.methods
+      .placeProxyBid(new anchor.BN(LAMPORTS_PER_SOL))
+      .accountsPartial({ auction, bidder: bidder.publicKey })
+      .signers([bidder])
+      .rpc();
+    const state = await program.account.auction.fetch(auction);
+    assert.strictEqual(
+      state.highestBidder.toBase58(),
+      bidder.publicKey.toBase58()
+    );
+  });
+});
+
+describe("Creator bids", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.EnhancedAuction as Program<EnhancedAuction>;
+
+  const creator = Keypair.generate();
+
+  before(async () => {
+    await fund(provider, creator);
+    await ensureConfig(program, provider);
+  });
+
+  it("Bars the creator from buying their own listing", async () => {
+    const { auction } = await listAuction(program, provider, creator, {
+      duration: 60 * 60,
+      buyNowPrice: LAMPORTS_PER_SOL,
+    });
+    await expectError(
+      program.methods
+        .buyNow()
+        .accountsPartial({ auction, buyer: creator.publicKey })
+        .signers([creator])
+        .rpc(),
+      "CreatorBid"
+    );
+  });
+
+  it("Bars the creator from place_proxy_bid", async () => {
+    const { auction } = await listAuction(program, provider, creator, {
+      duration: 60 * 60,
+    });
+    await expectError(
+      program.methods
+        .placeProxyBid(new anchor.BN(LAMPORTS_PER_SOL))
+        .accountsPartial({ auction, bidder: creator.publicKey })
+        .signers([creator])
+        .rpc(),
+      "CreatorBid"
+    );
+  });
+});
+
+describe("Deposit bidding", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.EnhancedAuction as Program<EnhancedAuction>;
+
+  const creator = Keypair.generate();
+  const runnerUp = Keypair.generate();
+  const winner = Keypair.generate();
+  const settlementWindow = 6;
+
+  before(async () => {
+    await fund(provider, creator, runnerUp, winner);
+    await ensureConfig(program, provider);
+  });
+
+  const listDepositAuction = async () => {
+    const { auction } = await listAuction(program, provider, creator, {
+      duration: 10,
+    });
+    await program.methods
+      .enableDepositBidding(5_000, new anchor.BN(settlementWindow))
+      .accountsPartial({ auction, creator: creator.publicKey })
+      .signers([creator])
+      .rpc();
+    return auction;
+  };
+
+  const placeDepositBid = (
+    auction: anchor.web3.PublicKey,
+    bidder: anchor.web3.Keypair,
+    amount: number
+  ) =>
+    program.methods
+      .placeDepositBid(new anchor.BN(amount))
+      .accountsPartial({ auction, bidder: bidder.publicKey })
+      .signers([bidder])
+      .rpc();
+
+  it("Offers the lot to the runner-up once the winner forfeits", async () => {
+    const auction = await listDepositAuction();
+    await placeDepositBid(auction, runnerUp, LAMPORTS_PER_SOL / 5);
+    await placeDepositBid(auction, winner, LAMPORTS_PER_SOL / 2);
+
+    // The winner lets the settlement window lapse
+    const listed = await program.account.auction.fetch(auction);
+    await waitUntil(listed.endTime.toNumber() + settlementWindow);
+
+    const creatorBefore = await provider.connection.getBalance(
+      creator.publicKey
+    );
+    await program.methods
+      .forfeit()
+      .accountsPartial({ auction, creator: creator.publicKey })
+      .rpc();
+    assert.strictEqual(
+      (await provider.connection.getBalance(creator.publicKey)) -
+        creatorBefore,
+      LAMPORTS_PER_SOL / 4
+    );
+
+    let state = await program.account.auction.fetch(auction);
+    assert.strictEqual(
+      state.highestBidder.toBase58(),
+      runnerUp.publicKey.toBase58()
+    );
+    assert.strictEqual(state.highestBid.toNumber(), LAMPORTS_PER_SOL / 5);
+
+    // Only the promoted runner-up may now settle
+    await expectError(
+      program.methods
+        .settleWinningBid()
+        .accountsPartial({ auction, winner: winner.publicKey })
+        .signers([winner])
+        .rpc(),
+      "Unauthorized"
+    );
+    await program.methods
+      .settleWinningBid()
+      .accountsPartial({ auction, winner: runnerUp.publicKey })
+      .signers([runnerUp])
+      .rpc();
+
+    state = await program.account.auction.fetch(auction);
+    assert.ok(state.depositMode.settled);
+    assert.strictEqual(
+      state.highestBidder.toBase58(),
+      runnerUp.publicKey.toBase58()
+    );
+  });
+});
+
+describe("Deposit bid settlement", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.EnhancedAuction as Program<EnhancedAuction>;
+
+  const creator = Keypair.generate();
+  const winner = Keypair.generate();
+  const outsider = Keypair.generate();
+
+  before(async () => {
+    await fund(provider, creator, winner, outsider);
+    await ensureConfig(program, provider);
+  });
+
+  it("Escrows only the deposit and takes the balance at settlement", async () => {
+    const { auction } = await listAuction(program, provider, creator, {
+      duration: 10,
+    });
+    await program.methods
+      .enableDepositBidding(2_000, new anchor.BN(60 * 60))
+      .accountsPartial({ auction, creator: creator.publicKey })
+      .signers([creator])
+      .rpc();
+
+    const bid = LAMPORTS_PER_SOL / 2;
+    const escrow = pda(program, Buffer.from("escrow"), auction.toBuffer());
+    const deposited = await balanceChange(provider, escrow, () =>
+      program.methods
+        .placeDepositBid(new anchor.BN(bid))
+        .accountsPartial({ auction, bidder: winner.publicKey })
+        .signers([winner])
+        .rpc()
+    );
+    assert.strictEqual(deposited, bid / 5);
+
+    // The leader cannot bid against themselves
+    await expectError(
+      program.methods
+        .placeDepositBid(new anchor.BN(LAMPORTS_PER_SOL))
+        .accountsPartial({ auction, bidder: winner.publicKey })
+        .signers([winner])
+        .rpc(),
+      "AlreadyLeading"
+    );
+
+    const state = await program.account.auction.fetch(auction);
+    await waitUntil(state.endTime.toNumber());
+
+    // Nobody can forfeit the winner while their window is open
+    await expectError(
+      program.methods
+        .forfeit()
+        .accountsPartial({ auction, creator: creator.publicKey })
+        .rpc(),
+      "SettlementWindowOpen"
+    );
+    // Nor settle in their place
+    await expectError(
+      program.methods
+        .settleWinningBid()
+        .accountsPartial({ auction, winner: outsider.publicKey })
+        .signers([outsider])
+        .rpc(),
+      "Unauthorized"
+    );
+
+    const balance = await balanceChange(provider, escrow, () =>
+      program.methods
+        .settleWinningBid()
+        .accountsPartial({ auction, winner: winner.publicKey })
+        .signers([winner])
+        .rpc()
+    );
+    assert.strictEqual(balance, bid - bid / 5);
+  });
+
+  it("Rejects a forfeit that pays someone other than the creator", async () => {
+    const { auction } = await listAuction(program, provider, creator, {
+      duration: 10,
+    });
+    await program.methods
+      .enableDepositBidding(2_000, new anchor.BN(1))
+      .accountsPartial({ auction, creator: creator.publicKey })
+      .signers([creator])
+      .rpc();
+    await program.methods
+      .placeDepositBid(new anchor.BN(LAMPORTS_PER_SOL / 2))
+      .accountsPartial({ auction, bidder: winner.publicKey })
+      .signers([winner])
+      .rpc();
+
+    const state = await program.account.auction.fetch(auction);
+    await waitUntil(state.endTime.toNumber() + 1);
+    await expectError(
+      program.methods
+        .forfeit()
+        .accountsPartial({ auction, creator: outsider.publicKey })
+        .rpc(),
+      "ConstraintAddress"
+    );
+  });
+});
+
+describe("Unsold withdrawals", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.EnhancedAuction as Program<EnhancedAuction>;
+
+  const creator = Keypair.generate();
+  const outsider = Keypair.generate();
+
+  before(async () => {
+    await fund(provider, creator, outsider);
+    await ensureConfig(program, provider);
+  });
+
+  const withdrawUnsold = async (
+    auction: anchor.web3.PublicKey,
+    nftMint: anchor.web3.PublicKey,
+    signer: anchor.web3.Keypair
+  ) => {
+    const destination = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      signer,
+      nftMint,
+      signer.publicKey
+    );
+    return program.methods
+      .withdrawUnsoldNft()
+      .accountsPartial({
+        auction,
+        creator: signer.publicKey,
+        nftMint,
+        creatorNftAccount: destination.address,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([signer])
+      .rpc();
+  };
+
+  it("Returns an unsold NFT and the escrow reserve to the creator only", async () => {
+    const { auction, nftMint } = await listAuction(program, provider, creator);
+    const state = await program.account.auction.fetch(auction);
+    await waitUntil(state.endTime.toNumber());
+
+    await expectError(
+      withdrawUnsold(auction, nftMint, outsider),
+      "UnauthorizedUpdate"
+    );
+
+    await withdrawUnsold(auction, nftMint, creator);
+    const settled = await program.account.auction.fetch(auction);
+    assert.ok(settled.status.cancelled);
+  });
+});
+
+describe("Private auctions", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.EnhancedAuction as Program<EnhancedAuction>;
+
+  const creator = Keypair.generate();
+  const buyer = Keypair.generate();
+
+  before(async () => {
+    await fund(provider, creator, buyer);
+    await ensureConfig(program, provider);
+  });
+
+  it("Bars wallets that have not proven their place from buy_now", async () => {
+    const { auction } = await listAuction(program, provider, creator, {
+      duration: 60 * 60,
+      buyNowPrice: LAMPORTS_PER_SOL,
+      allowlistRoot: Array(32).fill(7),
+    });
+    await expectError(
+      program.methods
+        .buyNow()
+        .accountsPartial({ auction, buyer: buyer.publicKey })
+        .signers([buyer])
+        .rpc(),
+      "MissingAllowlistEntry"
+    );
+    const state = await program.account.auction.fetch(auction);
+    assert.strictEqual(state.buyNowExecuted, false);
+  });
+});
+
+describe("Buy-now eligibility", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.EnhancedAuction as Program<EnhancedAuction>;
+
+  const creator = Keypair.generate();
+  const buyer = Keypair.generate();
+
+  before(async () => {
+    await fund(provider, creator, buyer);
+    await ensureConfig(program, provider);
+  });
+
+  const buyNow = (auction: anchor.web3.PublicKey) =>
+    program.methods
+      .buyNow()
+      .accountsPartial({ auction, buyer: buyer.publicKey })
+      .signers([buyer])
+      .rpc();
+
+  it("Bars buy_now on token-gated auctions", async () => {
+    const { auction } = await listAuction(program, provider, creator, {
+      duration: 60 * 60,
+      buyNowPrice: LAMPORTS_PER_SOL,
+    });
+    await program.methods
+      .setTokenGate(Keypair.generate().publicKey, new anchor.BN(1))
+      .accountsPartial({ auction, creator: creator.publicKey })
+      .signers([creator])
+      .rpc();
+    await expectError(buyNow(auction), "BidGated");
+  });
+
+  it("Bars buy_now on attestation-gated auctions", async () => {
+    const { auction } = await listAuction(program, provider, creator, {
+      duration: 60 * 60,
+      buyNowPrice: LAMPORTS_PER_SOL,
+    });
+    await program.methods
+      .setAttestationGate(Keypair.generate().publicKey)
+      .accountsPartial({ auction, creator: creator.publicKey })
+      .signers([creator])
+      .rpc();
+    await expectError(buyNow(auction), "BidGated");
+  });
+
+  it("Bars unregistered wallets from buy_now", async () => {
+    const { auction } = await listAuction(program, provider, creator, {
+      duration: 60 * 60,
+      buyNowPrice: LAMPORTS_PER_SOL,
+    });
+    await program.methods
+      .setRegistrationDeposit(new anchor.BN(LAMPORTS_PER_SOL / 100))
+      .accountsPartial({ auction, creator: creator.publicKey })
+      .signers([creator])
+      .rpc();
+    await expectError(buyNow(auction), "MissingRegistration");
+  });
+});
+
+describe("Outbid refunds", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.EnhancedAuction as Program<EnhancedAuction>;
+
+  const creator = Keypair.generate();
+  const bidder = Keypair.generate();
+  const buyer = Keypair.generate();
+
+  before(async () => {
+    await fund(provider, creator, bidder, buyer);
+    await ensureConfig(program, provider);
+  });
+
+  it("Credits the outbid leader's escrow on buy_now", async () => {
+    const { auction } = await listAuction(program, provider, creator, {
+      duration: 60 * 60,
+      buyNowPrice: LAMPORTS_PER_SOL,
+    });
+    const bid = LAMPORTS_PER_SOL / 2;
+    await placeBid(program, auction, bidder, bid);
+
+    await program.methods
+      .buyNow()
+      .accountsPartial({
+        auction,
+        buyer: buyer.publicKey,
+        previousBidEscrow: bidEscrow(program, auction, bidder.publicKey),
+      })
+      .signers([buyer])
+      .rpc();
+    const escrow = await program.account.bidEscrow.fetch(
+      bidEscrow(program, auction, bidder.publicKey)
+    );
+    assert.strictEqual(escrow.refundable.toNumber(), bid);
+
+    const refunded = await balanceChange(provider, bidder.publicKey, () =>
+      claimRefund(program, auction, bidder)
+    );
+    assert.ok(refunded > bid - 10_000);
+  });
+});
+
+describe("Bid limits", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.EnhancedAuction as Program<EnhancedAuction>;
+
+  const creator = Keypair.generate();
+  const bidder = Keypair.generate();
+
+  before(async () => {
+    await fund(provider, creator, bidder);
+    await ensureConfig(program, provider);
+  });
+
+  const bidderStats = (
+    auction: anchor.web3.PublicKey,
+    wallet: anchor.web3.PublicKey
+  ) =>
+    pda(
+      program,
+      Buffer.from("bidder_stats"),
+      auction.toBuffer(),
+      wallet.toBuffer()
+    );
+
+  it("Holds queued bids to the bid cooldown", async () => {
+    const { auction } = await listAuction(program, provider, creator, {
+      duration: 10,
+    });
+    await program.methods
+      .enableClosingQueue()
+      .accountsPartial({ auction, creator: creator.publicKey })
+      .signers([creator])
+      .rpc();
+    await program.methods
+      .setBidCooldown(new anchor.BN(60))
+      .accountsPartial({ auction, creator: creator.publicKey })
+      .signers([creator])
+      .rpc();
+
+    const queueBid = (amount: number) =>
+      program.methods
+        .queueBid(new anchor.BN(amount))
+        .accountsPartial({
+          auction,
+          bidder: bidder.publicKey,
+          bidderStats: bidderStats(auction, bidder.publicKey),
+        })
+        .signers([bidder])
+        .rpc();
+
+    await queueBid(LAMPORTS_PER_SOL / 5);
+    await expectError(queueBid(LAMPORTS_PER_SOL / 2), "BidCooldown");
+  });
+
+  it("Holds pool bids to the bid cap", async () => {
+    const { auction } = await listAuction(program, provider, creator, {
+      duration: 60 * 60,
+    });
+    await program.methods
+      .setBidCap(1)
+      .accountsPartial({ auction, creator: creator.publicKey })
+      .signers([creator])
+      .rpc();
+    await program.methods
+      .createBidPool()
+      .accountsPartial({ auction, organizer: bidder.publicKey })
+      .signers([bidder])
+      .rpc();
+    const pool = pda(
+      program,
+      Buffer.from("bid_pool"),
+      auction.toBuffer(),
+      bidder.publicKey.toBuffer()
+    );
+    await program.methods
+      .contributeToPool(new anchor.BN(LAMPORTS_PER_SOL))
+      .accountsPartial({ auction, bidPool: pool, contributor: bidder.publicKey })
+      .signers([bidder])
+      .rpc();
+
+    const placePoolBid = (amount: number) =>
+      program.methods
+        .placePoolBid(new anchor.BN(amount))
+        .accountsPartial({
+          auction,
+          organizer: bidder.publicKey,
+          bidPool: pool,
+          bidderStats: bidderStats(auction, pool),
+        })
+        .signers([bidder])
+        .rpc();
+
+    await placePoolBid(LAMPORTS_PER_SOL / 5);
+    await expectError(placePoolBid(LAMPORTS_PER_SOL / 2), "BidCapReached");
+  });
+});
+
+describe("Proxy bidding", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.EnhancedAuction as Program<EnhancedAuction>;
+
+  const creator = Keypair.generate();
+  const proxyBidder = Keypair.generate();
+  const challenger = Keypair.generate();
+
+  const proxyBid = (auction: anchor.web3.PublicKey, bidder: anchor.web3.PublicKey) =>
+    pda(program, Buffer.from("proxy_bid"), auction.toBuffer(), bidder.toBuffer());
+
+  before(async () => {
+    await fund(provider, creator, proxyBidder, challenger);
+    await ensureConfig(program, provider);
+  });
+
+  it("Answers a challenger and returns the unspent reserve", async () => {
+    const { auction } = await listAuction(program, provider, creator, {
+      duration: 10,
+    });
+    await program.methods
+      .placeProxyBid(new anchor.BN(LAMPORTS_PER_SOL))
+      .accountsPartial({ auction, bidder: proxyBidder.publicKey })
+      .signers([proxyBidder])
+      .rpc();
+
+    let state = await program.account.auction.fetch(auction);
+    assert.ok(state.proxyBidActive);
+    assert.strictEqual(state.highestBid.toNumber(), LAMPORTS_PER_SOL / 10);
+
+    await placeBid(program, auction, challenger, (LAMPORTS_PER_SOL * 3) / 10);
+
+    state = await program.account.auction.fetch(auction);
+    assert.strictEqual(
+      state.highestBidder.toBase58(),
+      proxyBidder.publicKey.toBase58()
+    );
+    assert.ok(state.highestBid.toNumber() > (LAMPORTS_PER_SOL * 3) / 10);
+
+    // The leading proxy stays locked until bidding closes
+    await expectError(
+      program.methods
+        .withdrawProxyBid()
+        .accountsPartial({ auction, bidder: proxyBidder.publicKey })
+        .signers([proxyBidder])
+        .rpc(),
+      "ProxyBidLocked"
+    );
+
+    await waitUntil(state.endTime.toNumber());
+    const { reserve } = await program.account.proxyBid.fetch(
+      proxyBid(auction, proxyBidder.publicKey)
+    );
+    assert.ok(reserve.toNumber() > 0);
+    await program.methods
+      .withdrawProxyBid()
+      .accountsPartial({ auction, bidder: proxyBidder.publicKey })
+      .signers([proxyBidder])
+      .rpc();
+    assert.strictEqual(
+      await provider.connection.getAccountInfo(
+        proxyBid(auction, proxyBidder.publicKey)
+      ),
+      null
+    );
+  });
+
+  it("Rejects withdrawing another bidder's proxy", async () => {
+    const { auction } = await listAuction(program, provider, creator, {
+      duration: 60 * 60,
+    });
+    await program.methods
+      .placeProxyBid(new anchor.BN(LAMPORTS_PER_SOL))
+      .accountsPartial({ auction, bidder: proxyBidder.publicKey })
+      .signers([proxyBidder])
+      .rpc();
+
+    await expectError(
+      program.methods
+        .withdrawProxyBid()
+        .accountsPartial({
+          auction,
+          bidder: challenger.publicKey,
+          proxyBid: proxyBid(auction, proxyBidder.publicKey),
+        })
+        .signers([challenger])
+        .rpc(),
+      "ConstraintHasOne|ConstraintSeeds"
+    );
+  });
+});
+
+describe("Closing queue", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.EnhancedAuction as Program<EnhancedAuction>;
+
+  const creator = Keypair.generate();
+  const low = Keypair.generate();
+  const high = Keypair.generate();
+  const outsider = Keypair.generate();
+
+  before(async () => {
+    await fund(provider, creator, low, high, outsider);
+    await ensureConfig(program, provider);
+  });
+
+  const queueBid = (
+    auction: anchor.web3.PublicKey,
+    bidder: anchor.web3.Keypair,
+    amount: number
+  ) =>
+    program.methods
+      .queueBid(new anchor.BN(amount))
+      .accountsPartial({ auction, bidder: bidder.publicKey })
+      .signers([bidder])
+      .rpc();
+
+  const withdrawQueuedBid = (
+    auction: anchor.web3.PublicKey,
+    bidder: anchor.web3.Keypair
+  ) =>
+    program.methods
+      .withdrawQueuedBid()
+      .accountsPartial({ auction, bidder: bidder.publicKey })
+      .signers([bidder])
+      .rpc();
+
+  it("Settles on the highest queued bid and refunds the rest", async () => {
+    // Short enough that the whole auction sits inside the closing window
+    const { auction } = await listAuction(program, provider, creator, {
+      duration: 10,
+    });
+    await program.methods
+      .enableClosingQueue()
+      .accountsPartial({ auction, creator: creator.publicKey })
+      .signers([creator])
+      .rpc();
+
+    await queueBid(auction, high, LAMPORTS_PER_SOL / 2);
+    await queueBid(auction, low, (LAMPORTS_PER_SOL * 3) / 10);
+
+    // Direct bids are closed while the queue is open
+    await expectError(
+      placeBid(program, auction, outsider, LAMPORTS_PER_SOL),
+      "ClosingQueueOpen"
+    );
+    // Queued bids stay put until the queue is resolved
+    await expectError(withdrawQueuedBid(auction, low), "ClosingQueuePending");
+
+    await endAuction(program, auction);
+    await program.methods
+      .finalizeAuction()
+      .accountsPartial(await settlementAccounts(program, auction))
+      .rpc();
+
+    const state = await program.account.auction.fetch(auction);
+    assert.strictEqual(
+      state.highestBidder.toBase58(),
+      high.publicKey.toBase58()
+    );
+    assert.strictEqual(state.highestBid.toNumber(), LAMPORTS_PER_SOL / 2);
+
+    const refunded = await balanceChange(provider, low.publicKey, () =>
+      withdrawQueuedBid(auction, low)
+    );
+    // The loser signs for the withdrawal, so its fee comes off the refund
+    assert.ok(refunded > (LAMPORTS_PER_SOL * 3) / 10 - 10_000);
+
+    // Wallets that never queued have nothing to withdraw
+    await expectError(withdrawQueuedBid(auction, outsider), "NoQueuedBid");
+  });
+});
+
+describe("First refusal", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.EnhancedAuction as Program<EnhancedAuction>;
+
+  const creator = Keypair.generate();
+  const leader = Keypair.generate();
+  const challenger = Keypair.generate();
+  const window = 4;
+
+  before(async () => {
+    await fund(provider, creator, leader, challenger);
+    await ensureConfig(program, provider);
+  });
+
+  const listWithRefusal = async () => {
+    const { auction } = await listAuction(program, provider, creator, {
+      duration: 60 * 60,
+    });
+    await program.methods
+      .setFirstRefusalWindow(new anchor.BN(window))
+      .accountsPartial({ auction, creator: creator.publicKey })
+      .signers([creator])
+      .rpc();
+    await placeBid(program, auction, leader, LAMPORTS_PER_SOL / 5);
+    await placeBid(program, auction, challenger, LAMPORTS_PER_SOL / 2);
+    return auction;
+  };
+
+  const matchFirstRefusal = (
+    auction: anchor.web3.PublicKey,
+    bidder: anchor.web3.Keypair
+  ) =>
+    program.methods
+      .matchFirstRefusal()
+      .accountsPartial({ auction, bidder: bidder.publicKey })
+      .signers([bidder])
+      .rpc();
+
+  it("Lets the leader match and refunds the pending bidder", async () => {
+    const auction = await listWithRefusal();
+    let state = await program.account.auction.fetch(auction);
+    assert.strictEqual(
+      state.pendingBidder.toBase58(),
+      challenger.publicKey.toBase58()
+    );
+
+    // Only the standing leader may match
+    await expectError(matchFirstRefusal(auction, challenger), "Unauthorized");
+
+    await matchFirstRefusal(auction, leader);
+    state = await program.account.auction.fetch(auction);
+    assert.strictEqual(
+      state.highestBidder.toBase58(),
+      leader.publicKey.toBase58()
+    );
+    assert.strictEqual(state.highestBid.toNumber(), LAMPORTS_PER_SOL / 2);
+
+    const refunded = await balanceChange(provider, challenger.publicKey, () =>
+      claimRefund(program, auction, challenger)
+    );
+    assert.ok(refunded > LAMPORTS_PER_SOL / 2 - 10_000);
+  });
+
+  it("Makes the pending bid stand once the window lapses", async () => {
+    const auction = await listWithRefusal();
+    const accept = () =>
+      program.methods
+        .acceptPendingBid()
+        .accountsPartial({
+          auction,
+          payer: provider.wallet.publicKey,
+          previousBidEscrow: bidEscrow(program, auction, leader.publicKey),
+        })
+        .rpc();
+
+    await expectError(accept(), "FirstRefusalOpen");
+
+    const { pendingUntil } = await program.account.auction.fetch(auction);
+    await waitUntil(pendingUntil.toNumber());
+    await accept();
+
+    const state = await program.account.auction.fetch(auction);
+    assert.strictEqual(
+      state.highestBidder.toBase58(),
+      challenger.publicKey.toBase58()
+    );
+    const refunded = await balanceChange(provider, leader.publicKey, () =>
+      claimRefund(program, auction, leader)
+    );
+    assert.ok(refunded > LAMPORTS_PER_SOL / 5 - 10_000);
+  });
+});
+
+describe("Bid pools", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.EnhancedAuction as Program<EnhancedAuction>;
+
+  const creator = Keypair.generate();
+  const organizer = Keypair.generate();
+  const contributorA = Keypair.generate();
+  const contributorB = Keypair.generate();
+  const rival = Keypair.generate();
+
+  before(async () => {
+    await fund(provider, creator, organizer, contributorA, contributorB, rival);
+    await ensureConfig(program, provider);
+  });
+
+  const bidPool = (auction: anchor.web3.PublicKey, owner: anchor.web3.PublicKey) =>
+    pda(program, Buffer.from("bid_pool"), auction.toBuffer(), owner.toBuffer());
+
+  const contribute = (
+    auction: anchor.web3.PublicKey,
+    pool: anchor.web3.PublicKey,
+    contributor: anchor.web3.Keypair,
+    amount: number
+  ) =>
+    program.methods
+      .contributeToPool(new anchor.BN(amount))
+      .accountsPartial({ auction, bidPool: pool, contributor: contributor.publicKey })
+      .signers([contributor])
+      .rpc();
+
+  const withdrawShare = (
+    auction: anchor.web3.PublicKey,
+    pool: anchor.web3.PublicKey,
+    contributor: anchor.web3.Keypair
+  ) =>
+    program.methods
+      .withdrawPoolShare()
+      .accountsPartial({ auction, bidPool: pool, contributor: contributor.publicKey })
+      .signers([contributor])
+      .rpc();
+
+  it("Bids pooled funds and returns them pro rata after losing", async () => {
+    const { auction } = await listAuction(program, provider, creator, {
+      duration: 15,
+    });
+    await program.methods
+      .createBidPool()
+      .accountsPartial({ auction, organizer: organizer.publicKey })
+      .signers([organizer])
+      .rpc();
+    const pool = bidPool(auction, organizer.publicKey);
+    await contribute(auction, pool, contributorA, (LAMPORTS_PER_SOL * 3) / 10);
+    await contribute(auction, pool, contributorB, LAMPORTS_PER_SOL / 5);
+
+    // Only the organizer bids the pool's funds
+    await expectError(
+      program.methods
+        .placePoolBid(new anchor.BN(LAMPORTS_PER_SOL / 5))
+        .accountsPartial({
+          auction,
+          organizer: contributorA.publicKey,
+          bidPool: pool,
+        })
+        .signers([contributorA])
+        .rpc(),
+      "ConstraintHasOne|ConstraintSeeds"
+    );
+
+    await program.methods
+      .placePoolBid(new anchor.BN((LAMPORTS_PER_SOL * 2) / 5))
+      .accountsPartial({ auction, organizer: organizer.publicKey, bidPool: pool })
+      .signers([organizer])
+      .rpc();
+    let state = await program.account.auction.fetch(auction);
+    assert.strictEqual(state.highestBidder.toBase58(), pool.toBase58());
+
+    await placeBid(program, auction, rival, (LAMPORTS_PER_SOL * 3) / 5);
+    await endAuction(program, auction);
+    await program.methods
+      .finalizeAuction()
+      .accountsPartial(await settlementAccounts(program, auction))
+      .rpc();
+
+    // Shares wait until the pool's outbid bid is back in the pool
+    await expectError(
+      withdrawShare(auction, pool, contributorA),
+      "PoolRefundUnclaimed"
+    );
+    await program.methods
+      .reclaimPoolRefund()
+      .accountsPartial({
+        bidPool: pool,
+        bidEscrow: bidEscrow(program, auction, pool),
+        rentPayer: rival.publicKey,
+      })
+      .rpc();
+
+    const paid = await balanceChange(provider, contributorA.publicKey, () =>
+      withdrawShare(auction, pool, contributorA)
+    );
+    assert.ok(paid > (LAMPORTS_PER_SOL * 3) / 10 - 10_000);
+    await expectError(
+      withdrawShare(auction, pool, contributorA),
+      "PoolShareWithdrawn"
+    );
+
+    // Wallets that never contributed hold no share
+    await expectError(
+      withdrawShare(auction, pool, rival),
+      "AccountNotInitialized"
+    );
+  });
+});
+
+describe("Rebids", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.EnhancedAuction as Program<EnhancedAuction>;
+
+  const creator = Keypair.generate();
+  const rebidder = Keypair.generate();
+  const rival = Keypair.generate();
+  const outsider = Keypair.generate();
+
+  before(async () => {
+    await fund(provider, creator, rebidder, rival, outsider);
+    await ensureConfig(program, provider);
+  });
+
+  const strategy = (auction: anchor.web3.PublicKey, bidder: anchor.web3.PublicKey) =>
+    pda(program, Buffer.from("rebid_strategy"), auction.toBuffer(), bidder.toBuffer());
+
+  const executeRebid = async (
+    auction: anchor.web3.PublicKey,
+    bidder: anchor.web3.PublicKey,
+    overrides = {}
+  ) => {
+    const state = await program.account.auction.fetch(auction);
+    return program.methods
+      .executeRebid()
+      .accountsPartial({
+        auction,
+        cranker: provider.wallet.publicKey,
+        strategy: strategy(auction, bidder),
+        bidDeposit: pda(program, Buffer.from("bid_deposit"), bidder.toBuffer()),
+        previousBidEscrow: bidEscrow(program, auction, state.highestBidder),
+        ...overrides,
+      })
+      .rpc();
+  };
+
+  it("Rebids for an outbid bidder out of their deposit", async () => {
+    const { auction } = await listAuction(program, provider, creator, {
+      duration: 60 * 60,
+    });
+    await program.methods
+      .fundBidDeposit(new anchor.BN(LAMPORTS_PER_SOL))
+      .accountsPartial({ owner: rebidder.publicKey })
+      .signers([rebidder])
+      .rpc();
+    await program.methods
+      .setRebidStrategy(new anchor.BN((LAMPORTS_PER_SOL * 4) / 5), 1_000)
+      .accountsPartial({ auction, bidder: rebidder.publicKey })
+      .signers([rebidder])
+      .rpc();
+
+    await placeBid(program, auction, rival, LAMPORTS_PER_SOL / 5);
+    await executeRebid(auction, rebidder.publicKey);
+
+    const state = await program.account.auction.fetch(auction);
+    assert.strictEqual(
+      state.highestBidder.toBase58(),
+      rebidder.publicKey.toBase58()
+    );
+    // 10% of 0.2 SOL is below the minimum increment, which applies instead
+    assert.strictEqual(state.highestBid.toNumber(), LAMPORTS_PER_SOL / 4);
+
+    // A leading bidder has nothing to rebid against
+    await expectError(executeRebid(auction, rebidder.publicKey), "NotOutbid");
+  });
+
+  it("Rejects a rebid paid from another wallet's deposit", async () => {
+    const { auction } = await listAuction(program, provider, creator, {
+      duration: 60 * 60,
+    });
+    await program.methods
+      .setRebidStrategy(new anchor.BN(LAMPORTS_PER_SOL / 2), 1_000)
+      .accountsPartial({ auction, bidder: outsider.publicKey })
+      .signers([outsider])
+      .rpc();
+    await placeBid(program, auction, rival, LAMPORTS_PER_SOL / 5);
+
+    await expectError(
+      executeRebid(auction, outsider.publicKey, {
+        bidDeposit: pda(
+          program,
+          Buffer.from("bid_deposit"),
+          rebidder.publicKey.toBuffer()
+        ),
+      }),
+      "ConstraintSeeds"
+    );
+  });
+});
+
+describe("Bid transfers", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.EnhancedAuction as Program<EnhancedAuction>;
+
+  const creator = Keypair.generate();
+  const rival = Keypair.generate();
+  const outsider = Keypair.generate();
+
+  before(async () => {
+    await fund(provider, creator, rival, outsider);
+    await ensureConfig(program, provider);
+  });
+
+  it("Moves an outbid refund into a bid on another auction", async () => {
+    const { auction: from } = await listAuction(program, provider, creator, {
+      duration: 60 * 60,
+    });
+    const { auction: to } = await listAuction(program, provider, creator, {
+      duration: 60 * 60,
+    });
+    await placeBid(program, from, rival, LAMPORTS_PER_SOL / 5);
+    await placeBid(program, from, outsider, LAMPORTS_PER_SOL / 2);
+
+    const sourceEscrow = bidEscrow(program, from, rival.publicKey);
+    const { rentPayer } = await program.account.bidEscrow.fetch(sourceEscrow);
+    const transfer = (bidder: anchor.web3.Keypair) =>
+      program.methods
+        .transferBid(new anchor.BN(LAMPORTS_PER_SOL / 5), [])
+        .accountsPartial({
+          sourceEscrow,
+          rentPayer,
+          bid: {
+            auction: to,
+            payer: bidder.publicKey,
+            bidder: bidder.publicKey,
+          },
+        })
+        .signers([bidder])
+        .rpc();
+
+    // The escrow is tied to the bidder it refunds
+    await expectError(transfer(outsider), "ConstraintSeeds");
+
+    await transfer(rival);
+    const state = await program.account.auction.fetch(to);
+    assert.strictEqual(state.highestBidder.toBase58(), rival.publicKey.toBase58());
+    assert.strictEqual(
+      await provider.connection.getAccountInfo(sourceEscrow),
+      null
+    );
+  });
+});
+
+describe("Loser rebate", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.EnhancedAuction as Program<EnhancedAuction>;
+
+  const creator = Keypair.generate();
+  const loser = Keypair.generate();
+  const winner = Keypair.generate();
+  const outsider = Keypair.generate();
+
+  before(async () => {
+    await fund(provider, creator, loser, winner, outsider);
+    await ensureConfig(program, provider);
+  });
+
+  it("Pays losing bidders a rebate out of the fee", async () => {
+    const { auction } = await listAuction(program, provider, creator, {
+      duration: 10,
+    });
+    await program.methods
+      .enableLoserRebate(1_000)
+      .accountsPartial({ auction, creator: creator.publicKey })
+      .signers([creator])
+      .rpc();
+    await placeBid(program, auction, loser, LAMPORTS_PER_SOL / 5);
+    await placeBid(program, auction, winner, LAMPORTS_PER_SOL);
+    await endAuction(program, auction);
+
+    await program.methods
+      .finalizeAuction()
+      .accountsPartial(await settlementAccounts(program, auction))
+      .rpc();
+
+    const { feeBps } = await program.account.auction.fetch(auction);
+    const fee = Math.floor((LAMPORTS_PER_SOL * feeBps) / 10_000);
+    const rebate = Math.floor(fee / 10);
+
+    const claimRebate = (bidder: anchor.web3.Keypair) =>
+      program.methods
+        .claimLoserRebate()
+        .accountsPartial({
+          auction,
+          loserRebate: pda(program, Buffer.from("loser_rebate"), auction.toBuffer()),
+          bidder: bidder.publicKey,
+        })
+        .signers([bidder])
+        .rpc();
+
+    // Neither the winner nor a wallet that never bid has a claim
+    await expectError(claimRebate(winner), "NotLosingBidder");
+    await expectError(claimRebate(outsider), "NotLosingBidder");
+
+    const claimed = await balanceChange(provider, loser.publicKey, () =>
+      claimRebate(loser)
+    );
+    assert.ok(claimed > rebate - 10_000);
+    await expectError(claimRebate(loser), "LoserRebateClaimed");
+  });
+});
+
+describe("Settlement bounty", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.EnhancedAuction as Program<EnhancedAuction>;
+
+  const creator = Keypair.generate();
+  const winner = Keypair.generate();
+  const settler = Keypair.generate();
+
+  before(async () => {
+    await fund(provider, creator, winner, settler);
+    await ensureConfig(program, provider);
+  });
+
+  it("Pays the settler a bounty out of the fee", async () => {
+    const { auction } = await listAuction(program, provider, creator, {
+      duration: 10,
+    });
+    await placeBid(program, auction, winner, LAMPORTS_PER_SOL);
+    await endAuction(program, auction);
+
+    const accounts = await settlementAccounts(program, auction);
+    // A settler cannot redirect the platform fee to themselves
+    await expectError(
+      program.methods
+        .finalizeAuction()
+        .accountsPartial({
+          ...accounts,
+          settler: settler.publicKey,
+          platformFeeAccount: settler.publicKey,
+        })
+        .signers([settler])
+        .rpc(),
+      "InvalidFeeDestination"
+    );
+
+    const { feeBps } = await program.account.auction.fetch(auction);
+    const fee = Math.floor((LAMPORTS_PER_SOL * feeBps) / 10_000);
+    const bounty = Math.floor((fee * 500) / 10_000);
+
+    const earned = await balanceChange(provider, settler.publicKey, () =>
+      program.methods
+        .finalizeAuction()
+        .accountsPartial({ ...accounts, settler: settler.publicKey })
+        .signers([settler])
+        .rpc()
+    );
+    // The provider wallet pays the transaction fee, so the settler's
+    // balance moves by exactly the bounty
+    assert.strictEqual(earned, bounty);
+  });
+});
+
+describe("Auction migration", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.EnhancedAuction as Program<EnhancedAuction>;
+
+  const creator = Keypair.generate();
+
+  before(async () => {
+    await fund(provider, creator);
+    await ensureConfig(program, provider);
+  });
+
+  const migrate = (auction: anchor.web3.PublicKey) =>
+    program.methods
+      .migrateAuction()
+      .accountsPartial({ auction, payer: provider.wallet.publicKey })
+      .rpc();
+
+  it("Leaves an auction on the current layout untouched", async () => {
+    const { auction } = await listAuction(program, provider, creator, {
+      duration: 60 * 60,
+    });
+    const before = await provider.connection.getAccountInfo(auction);
+    await expectError(migrate(auction), "AuctionUpToDate");
+    const after = await provider.connection.getAccountInfo(auction);
+    assert.strictEqual(after.lamports, before.lamports);
+    assert.ok(after.data.equals(before.data));
+  });
+
+  it("Rejects program accounts that are not auctions", async () => {
+    await expectError(
+      migrate(pda(program, Buffer.from("config"))),
+      "InvalidAuctionAccount"
+    );
+  });
+
+  it("Rejects accounts the program does not own", async () => {
+    await expectError(migrate(creator.publicKey), "ConstraintOwner");
+  });
+});